@@ -97,6 +97,25 @@ fn export_url_argument() -> Arg<'static, 'static> {
         .takes_value(true)
 }
 
+fn platforms_config_argument() -> Arg<'static, 'static> {
+    Arg::with_name("platforms-config")
+        .short("c")
+        .long("platforms-config")
+        .value_name("EXPORT_API_PLATFORMS_CONFIG")
+        .env("EXPORT_API_PLATFORMS_CONFIG")
+        .help("Path to a JSON file declaring additional platform mappings (formats and specifications cannot be configured)")
+        .takes_value(true)
+}
+
+fn schemas_dir_argument() -> Arg<'static, 'static> {
+    Arg::with_name("schemas-dir")
+        .long("schemas-dir")
+        .value_name("EXPORT_API_SCHEMAS_DIR")
+        .env("EXPORT_API_SCHEMAS_DIR")
+        .help("Path to the directory of reference schemas used to validate records on request")
+        .takes_value(true)
+}
+
 fn thoth_commands() -> App<'static, 'static> {
     App::new(env!("CARGO_PKG_NAME"))
         .version(crate_version!())
@@ -130,7 +149,9 @@ fn thoth_commands() -> App<'static, 'static> {
                         .arg(host_argument("EXPORT_API_HOST"))
                         .arg(port_argument("8181", "EXPORT_API_PORT"))
                         .arg(export_url_argument())
-                        .arg(gql_endpoint_argument()),
+                        .arg(gql_endpoint_argument())
+                        .arg(platforms_config_argument())
+                        .arg(schemas_dir_argument()),
                 ),
         )
         .subcommand(
@@ -178,7 +199,14 @@ fn main() -> ThothResult<()> {
                 let port = client_matches.value_of("port").unwrap().to_owned();
                 let url = client_matches.value_of("export-url").unwrap().to_owned();
                 let gql_endpoint = client_matches.value_of("gql-endpoint").unwrap().to_owned();
-                export_server(host, port, url, gql_endpoint).map_err(|e| e.into())
+                let platforms_config = client_matches
+                    .value_of("platforms-config")
+                    .map(|path| path.to_owned());
+                let schemas_dir = client_matches
+                    .value_of("schemas-dir")
+                    .map(|path| path.to_owned());
+                export_server(host, port, url, gql_endpoint, platforms_config, schemas_dir)
+                    .map_err(|e| e.into())
             }
             _ => unreachable!(),
         },
@@ -0,0 +1,38 @@
+DROP TRIGGER IF EXISTS set_work_updated_at_with_relations ON contribution;
+DROP TRIGGER IF EXISTS set_work_updated_at_with_relations ON funding;
+DROP TRIGGER IF EXISTS set_work_updated_at_with_relations ON issue;
+DROP TRIGGER IF EXISTS set_work_updated_at_with_relations ON language;
+DROP TRIGGER IF EXISTS set_work_updated_at_with_relations ON publication;
+DROP TRIGGER IF EXISTS set_work_updated_at_with_relations ON subject;
+DROP TRIGGER IF EXISTS set_work_updated_at_with_relations ON work_relation;
+DROP TRIGGER IF EXISTS set_work_updated_at_with_relations ON affiliation;
+DROP TRIGGER IF EXISTS set_work_updated_at_with_relations ON price;
+DROP TRIGGER IF EXISTS set_work_updated_at_with_relations ON location;
+DROP FUNCTION IF EXISTS work_updated_at_with_relations();
+DROP FUNCTION IF EXISTS work_relation_updated_at_with_relations();
+DROP FUNCTION IF EXISTS contribution_updated_at_with_relations();
+DROP FUNCTION IF EXISTS publication_updated_at_with_relations();
+
+DROP TRIGGER IF EXISTS set_updated_at ON work;
+DROP FUNCTION IF EXISTS work_set_updated_at();
+SELECT diesel_manage_updated_at('work');
+
+ALTER TABLE work DROP COLUMN IF EXISTS updated_at_with_relations;
+
+DROP TABLE IF EXISTS work_relation_history;
+DROP TABLE IF EXISTS work_relation;
+DROP TYPE IF EXISTS relation_type;
+
+ALTER TABLE work
+    DROP COLUMN IF EXISTS first_page,
+    DROP COLUMN IF EXISTS last_page;
+
+DROP TRIGGER IF EXISTS record_deletion ON publication;
+DROP FUNCTION IF EXISTS publication_record_deletion();
+DROP TABLE IF EXISTS deleted_publication;
+
+DROP TRIGGER IF EXISTS record_deletion ON imprint;
+DROP FUNCTION IF EXISTS imprint_record_deletion();
+DROP TRIGGER IF EXISTS record_deletion ON work;
+DROP FUNCTION IF EXISTS work_record_deletion();
+DROP TABLE IF EXISTS deleted_work;
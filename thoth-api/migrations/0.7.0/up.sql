@@ -0,0 +1,237 @@
+-- Keep a record of deleted works, so that recipients of metadata can be notified of deletions.
+-- The publisher is not a foreign key, as its deletion must be notified too.
+CREATE TABLE deleted_work (
+    work_id             UUID PRIMARY KEY,
+    publisher_id        UUID NOT NULL,
+    publication_ids     UUID[] NOT NULL DEFAULT '{}',
+    deleted_at          TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Runs before the work is deleted, while its imprint and publications can still be looked up.
+-- When the deletion cascades from the imprint, the imprint is already gone and nothing is
+-- inserted here: the imprint's own trigger has recorded the work instead.
+CREATE OR REPLACE FUNCTION work_record_deletion() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO deleted_work(work_id, publisher_id, publication_ids)
+        SELECT OLD.work_id, imprint.publisher_id,
+            ARRAY(SELECT publication_id FROM publication WHERE publication.work_id = OLD.work_id)
+        FROM imprint WHERE imprint.imprint_id = OLD.imprint_id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_deletion BEFORE DELETE ON work
+    FOR EACH ROW EXECUTE PROCEDURE work_record_deletion();
+
+-- Runs before the imprint, whether deleted on its own or along with its publisher,
+-- cascades the deletion to its works
+CREATE OR REPLACE FUNCTION imprint_record_deletion() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO deleted_work(work_id, publisher_id, publication_ids)
+        SELECT work.work_id, OLD.publisher_id,
+            ARRAY(SELECT publication_id FROM publication WHERE publication.work_id = work.work_id)
+        FROM work WHERE work.imprint_id = OLD.imprint_id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_deletion BEFORE DELETE ON imprint
+    FOR EACH ROW EXECUTE PROCEDURE imprint_record_deletion();
+
+-- Keep a record of publications deleted from works that remain in Thoth.
+-- Publications deleted along with their work are listed in the work's deletion record instead.
+CREATE TABLE deleted_publication (
+    publication_id      UUID PRIMARY KEY,
+    work_id             UUID NOT NULL,
+    publisher_id        UUID NOT NULL,
+    deleted_at          TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- When the deletion cascades from the work, the work is already gone and nothing is inserted
+CREATE OR REPLACE FUNCTION publication_record_deletion() RETURNS trigger AS $$
+BEGIN
+    INSERT INTO deleted_publication(publication_id, work_id, publisher_id)
+        SELECT OLD.publication_id, OLD.work_id, imprint.publisher_id
+        FROM work INNER JOIN imprint ON imprint.imprint_id = work.imprint_id
+        WHERE work.work_id = OLD.work_id;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER record_deletion BEFORE DELETE ON publication
+    FOR EACH ROW EXECUTE PROCEDURE publication_record_deletion();
+
+-- Page range of a work within the work it is part of (e.g. a chapter within a book).
+-- Stored as text, as front matter may be numbered in roman numerals.
+ALTER TABLE work
+    ADD COLUMN first_page TEXT CHECK (octet_length(first_page) >= 1),
+    ADD COLUMN last_page TEXT CHECK (octet_length(last_page) >= 1);
+
+CREATE TYPE relation_type AS ENUM (
+    'replaces',
+    'has-translation',
+    'has-part',
+    'has-child',
+    -- only ever derived from has-child relations, never stored
+    'is-child-of'
+);
+
+CREATE TABLE work_relation (
+    work_relation_id    UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    relator_work_id     UUID NOT NULL REFERENCES work(work_id) ON DELETE CASCADE,
+    related_work_id     UUID NOT NULL REFERENCES work(work_id) ON DELETE CASCADE,
+    relation_type       relation_type NOT NULL,
+    relation_ordinal    INTEGER NOT NULL CHECK (relation_ordinal > 0),
+    created_at          TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at          TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT work_relation_ids_check CHECK (relator_work_id != related_work_id),
+    CONSTRAINT work_relation_type_check CHECK (relation_type != 'is-child-of'),
+    CONSTRAINT work_relation_ids_uniq UNIQUE (relator_work_id, related_work_id),
+    CONSTRAINT work_relation_ordinal_type_uniq UNIQUE (relator_work_id, relation_ordinal, relation_type)
+);
+SELECT diesel_manage_updated_at('work_relation');
+
+CREATE TABLE work_relation_history (
+    work_relation_history_id    UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    work_relation_id            UUID NOT NULL REFERENCES work_relation(work_relation_id) ON DELETE CASCADE,
+    account_id                  UUID NOT NULL REFERENCES account(account_id),
+    data                        JSONB NOT NULL,
+    timestamp                   TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Time of the last change to a work or any of its child records. Deleting a child record leaves
+-- no updated_at behind, so the column is maintained by triggers on each child table instead of
+-- being derived from the child records that remain.
+ALTER TABLE work ADD COLUMN updated_at_with_relations TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP;
+
+-- Replaces diesel_set_updated_at on work, so that changes to child records, which only touch
+-- updated_at_with_relations, are not taken as changes to the work itself
+CREATE OR REPLACE FUNCTION work_set_updated_at() RETURNS trigger AS $$
+BEGIN
+    IF (
+        NEW IS DISTINCT FROM OLD AND
+        NEW.updated_at IS NOT DISTINCT FROM OLD.updated_at AND
+        NEW.updated_at_with_relations IS NOT DISTINCT FROM OLD.updated_at_with_relations
+    ) THEN
+        NEW.updated_at := current_timestamp;
+        NEW.updated_at_with_relations := current_timestamp;
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER set_updated_at ON work;
+CREATE TRIGGER set_updated_at BEFORE UPDATE ON work
+    FOR EACH ROW EXECUTE PROCEDURE work_set_updated_at();
+
+-- Existing works were last changed along with their most recently updated child record
+UPDATE work SET updated_at_with_relations = GREATEST(
+    work.updated_at,
+    (SELECT MAX(updated_at) FROM contribution WHERE contribution.work_id = work.work_id),
+    (SELECT MAX(affiliation.updated_at) FROM affiliation
+        INNER JOIN contribution ON contribution.contribution_id = affiliation.contribution_id
+        WHERE contribution.work_id = work.work_id),
+    (SELECT MAX(updated_at) FROM funding WHERE funding.work_id = work.work_id),
+    (SELECT MAX(updated_at) FROM issue WHERE issue.work_id = work.work_id),
+    (SELECT MAX(updated_at) FROM language WHERE language.work_id = work.work_id),
+    (SELECT MAX(updated_at) FROM publication WHERE publication.work_id = work.work_id),
+    (SELECT MAX(price.updated_at) FROM price
+        INNER JOIN publication ON publication.publication_id = price.publication_id
+        WHERE publication.work_id = work.work_id),
+    (SELECT MAX(location.updated_at) FROM location
+        INNER JOIN publication ON publication.publication_id = location.publication_id
+        WHERE publication.work_id = work.work_id),
+    (SELECT MAX(updated_at) FROM subject WHERE subject.work_id = work.work_id)
+);
+
+-- Child records referencing their work directly
+CREATE OR REPLACE FUNCTION work_updated_at_with_relations() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE work SET updated_at_with_relations = current_timestamp
+            WHERE work_id = OLD.work_id;
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        UPDATE work SET updated_at_with_relations = current_timestamp
+            WHERE work_id = NEW.work_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER set_work_updated_at_with_relations AFTER INSERT OR UPDATE OR DELETE ON contribution
+    FOR EACH ROW EXECUTE PROCEDURE work_updated_at_with_relations();
+CREATE TRIGGER set_work_updated_at_with_relations AFTER INSERT OR UPDATE OR DELETE ON funding
+    FOR EACH ROW EXECUTE PROCEDURE work_updated_at_with_relations();
+CREATE TRIGGER set_work_updated_at_with_relations AFTER INSERT OR UPDATE OR DELETE ON issue
+    FOR EACH ROW EXECUTE PROCEDURE work_updated_at_with_relations();
+CREATE TRIGGER set_work_updated_at_with_relations AFTER INSERT OR UPDATE OR DELETE ON language
+    FOR EACH ROW EXECUTE PROCEDURE work_updated_at_with_relations();
+CREATE TRIGGER set_work_updated_at_with_relations AFTER INSERT OR UPDATE OR DELETE ON publication
+    FOR EACH ROW EXECUTE PROCEDURE work_updated_at_with_relations();
+CREATE TRIGGER set_work_updated_at_with_relations AFTER INSERT OR UPDATE OR DELETE ON subject
+    FOR EACH ROW EXECUTE PROCEDURE work_updated_at_with_relations();
+
+-- Relations change the records of both works, e.g. a chapter's record names its parent book
+CREATE OR REPLACE FUNCTION work_relation_updated_at_with_relations() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE work SET updated_at_with_relations = current_timestamp
+            WHERE work_id IN (OLD.relator_work_id, OLD.related_work_id);
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        UPDATE work SET updated_at_with_relations = current_timestamp
+            WHERE work_id IN (NEW.relator_work_id, NEW.related_work_id);
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER set_work_updated_at_with_relations AFTER INSERT OR UPDATE OR DELETE ON work_relation
+    FOR EACH ROW EXECUTE PROCEDURE work_relation_updated_at_with_relations();
+
+-- Child records of a contribution
+CREATE OR REPLACE FUNCTION contribution_updated_at_with_relations() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE work SET updated_at_with_relations = current_timestamp
+            FROM contribution
+            WHERE work.work_id = contribution.work_id
+                AND contribution.contribution_id = OLD.contribution_id;
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        UPDATE work SET updated_at_with_relations = current_timestamp
+            FROM contribution
+            WHERE work.work_id = contribution.work_id
+                AND contribution.contribution_id = NEW.contribution_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER set_work_updated_at_with_relations AFTER INSERT OR UPDATE OR DELETE ON affiliation
+    FOR EACH ROW EXECUTE PROCEDURE contribution_updated_at_with_relations();
+
+-- Child records of a publication
+CREATE OR REPLACE FUNCTION publication_updated_at_with_relations() RETURNS trigger AS $$
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        UPDATE work SET updated_at_with_relations = current_timestamp
+            FROM publication
+            WHERE work.work_id = publication.work_id
+                AND publication.publication_id = OLD.publication_id;
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        UPDATE work SET updated_at_with_relations = current_timestamp
+            FROM publication
+            WHERE work.work_id = publication.work_id
+                AND publication.publication_id = NEW.publication_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER set_work_updated_at_with_relations AFTER INSERT OR UPDATE OR DELETE ON price
+    FOR EACH ROW EXECUTE PROCEDURE publication_updated_at_with_relations();
+CREATE TRIGGER set_work_updated_at_with_relations AFTER INSERT OR UPDATE OR DELETE ON location
+    FOR EACH ROW EXECUTE PROCEDURE publication_updated_at_with_relations();
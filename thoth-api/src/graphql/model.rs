@@ -22,6 +22,7 @@ use crate::model::publisher::*;
 use crate::model::series::*;
 use crate::model::subject::*;
 use crate::model::work::*;
+use crate::model::work_relation::*;
 use crate::model::Convert;
 use crate::model::Crud;
 use crate::model::Doi;
@@ -102,6 +103,13 @@ pub struct FundingOrderBy {
     pub direction: Direction,
 }
 
+#[derive(juniper::GraphQLInputObject)]
+#[graphql(description = "Field and order to use when sorting work relations list")]
+pub struct WorkRelationOrderBy {
+    pub field: WorkRelationField,
+    pub direction: Direction,
+}
+
 pub struct QueryRoot;
 
 #[juniper::object(Context = Context)]
@@ -129,8 +137,14 @@ impl QueryRoot {
             default = vec![],
             description = "If set, only shows results connected to publishers with these IDs",
         ),
+        work_ids(
+            default = vec![],
+            description = "If set, only shows works with these IDs",
+        ),
         work_type(description = "A specific type to filter by"),
         work_status(description = "A specific status to filter by"),
+        updated_since(description = "If set, only shows works which were updated, or had any of their child records created, updated or deleted, after this time"),
+        after_work_id(description = "If set along with updatedSince, also shows works last updated at exactly that time whose ID is greater than this one, to page through works ordered by UPDATED_AT_WITH_RELATIONS"),
     )
   )]
     fn works(
@@ -140,24 +154,70 @@ impl QueryRoot {
         filter: String,
         order: WorkOrderBy,
         publishers: Vec<Uuid>,
+        work_ids: Vec<Uuid>,
         work_type: Option<WorkType>,
         work_status: Option<WorkStatus>,
+        updated_since: Option<Timestamp>,
+        after_work_id: Option<Uuid>,
     ) -> FieldResult<Vec<Work>> {
-        Work::all(
+        Work::all_updated_since(
             &context.db,
             limit,
             offset,
             Some(filter),
             order,
             publishers,
-            None,
+            work_ids,
             None,
             work_type,
             work_status,
+            updated_since,
+            after_work_id,
         )
         .map_err(|e| e.into())
     }
 
+    #[graphql(
+        description = "Query the list of works which have been deleted",
+        arguments(
+            publishers(
+                default = vec![],
+                description = "If set, only shows results connected to publishers with these IDs",
+            ),
+            work_ids(
+                default = vec![],
+                description = "If set, only shows deleted works with these IDs",
+            ),
+            deleted_since(description = "If set, only shows works which were deleted after this time"),
+        )
+    )]
+    fn deleted_works(
+        context: &Context,
+        publishers: Vec<Uuid>,
+        work_ids: Vec<Uuid>,
+        deleted_since: Option<Timestamp>,
+    ) -> FieldResult<Vec<DeletedWork>> {
+        DeletedWork::all(&context.db, publishers, work_ids, deleted_since).map_err(|e| e.into())
+    }
+
+    #[graphql(
+        description = "Query the list of publications which have been deleted from works that still exist",
+        arguments(
+            publishers(
+                default = vec![],
+                description = "If set, only shows results connected to publishers with these IDs",
+            ),
+            deleted_since(description = "If set, only shows publications which were deleted after this time"),
+        )
+    )]
+    fn deleted_publications(
+        context: &Context,
+        publishers: Vec<Uuid>,
+        deleted_since: Option<Timestamp>,
+    ) -> FieldResult<Vec<DeletedPublication>> {
+        DeletedPublication::all(&context.db, publishers, deleted_since).map_err(|e| e.into())
+    }
+
     #[graphql(description = "Query a single work using its id")]
     fn work(context: &Context, work_id: Uuid) -> FieldResult<Work> {
         Work::from_id(&context.db, &work_id).map_err(|e| e.into())
@@ -1058,6 +1118,66 @@ impl QueryRoot {
     fn affiliation_count(context: &Context) -> FieldResult<i32> {
         Affiliation::count(&context.db, None, vec![], None, None).map_err(|e| e.into())
     }
+
+    #[graphql(
+        description = "Query the full list of work relations",
+        arguments(
+            limit(default = 100, description = "The number of items to return"),
+            offset(default = 0, description = "The number of items to skip"),
+            order(
+                default = {
+                    WorkRelationOrderBy {
+                        field: WorkRelationField::RelationOrdinal,
+                        direction: Direction::Asc,
+                    }
+                },
+                description = "The order in which to sort the results",
+            ),
+            publishers(
+                default = vec![],
+                description = "If set, only shows results connected to publishers with these IDs",
+            ),
+            relation_type(description = "A specific type to filter by"),
+        )
+    )]
+    fn work_relations(
+        context: &Context,
+        limit: i32,
+        offset: i32,
+        order: WorkRelationOrderBy,
+        publishers: Vec<Uuid>,
+        relation_type: Option<RelationType>,
+    ) -> FieldResult<Vec<WorkRelation>> {
+        WorkRelation::all(
+            &context.db,
+            limit,
+            offset,
+            None,
+            order,
+            publishers,
+            None,
+            None,
+            relation_type,
+            None,
+        )
+        .map_err(|e| e.into())
+    }
+
+    #[graphql(description = "Query a single work relation using its id")]
+    fn work_relation(context: &Context, work_relation_id: Uuid) -> FieldResult<WorkRelation> {
+        WorkRelation::from_id(&context.db, &work_relation_id).map_err(|e| e.into())
+    }
+
+    #[graphql(
+        description = "Get the total number of work relations",
+        arguments(relation_type(description = "A specific type to filter by"),)
+    )]
+    fn work_relation_count(
+        context: &Context,
+        relation_type: Option<RelationType>,
+    ) -> FieldResult<i32> {
+        WorkRelation::count(&context.db, None, vec![], relation_type, None).map_err(|e| e.into())
+    }
 }
 
 pub struct MutationRoot;
@@ -1213,6 +1333,21 @@ impl MutationRoot {
         Affiliation::create(&context.db, &data).map_err(|e| e.into())
     }
 
+    fn create_work_relation(context: &Context, data: NewWorkRelation) -> FieldResult<WorkRelation> {
+        context.token.jwt.as_ref().ok_or(ThothError::Unauthorised)?;
+        // Both works must be editable by the user
+        context.account_access.can_edit(publisher_id_from_work_id(
+            &context.db,
+            data.relator_work_id,
+        )?)?;
+        context.account_access.can_edit(publisher_id_from_work_id(
+            &context.db,
+            data.related_work_id,
+        )?)?;
+
+        WorkRelation::create(&context.db, &data).map_err(|e| e.into())
+    }
+
     fn update_work(context: &Context, data: PatchWork, units: LengthUnit) -> FieldResult<Work> {
         context.token.jwt.as_ref().ok_or(ThothError::Unauthorised)?;
         let work = Work::from_id(&context.db, &data.work_id).unwrap();
@@ -1502,6 +1637,35 @@ impl MutationRoot {
             .map_err(|e| e.into())
     }
 
+    fn update_work_relation(
+        context: &Context,
+        data: PatchWorkRelation,
+    ) -> FieldResult<WorkRelation> {
+        context.token.jwt.as_ref().ok_or(ThothError::Unauthorised)?;
+        let work_relation = WorkRelation::from_id(&context.db, &data.work_relation_id).unwrap();
+        context
+            .account_access
+            .can_edit(work_relation.publisher_id(&context.db)?)?;
+
+        if !(data.relator_work_id == work_relation.relator_work_id) {
+            context.account_access.can_edit(publisher_id_from_work_id(
+                &context.db,
+                data.relator_work_id,
+            )?)?;
+        }
+        if !(data.related_work_id == work_relation.related_work_id) {
+            context.account_access.can_edit(publisher_id_from_work_id(
+                &context.db,
+                data.related_work_id,
+            )?)?;
+        }
+
+        let account_id = context.token.jwt.as_ref().unwrap().account_id(&context.db);
+        work_relation
+            .update(&context.db, &data, &account_id)
+            .map_err(|e| e.into())
+    }
+
     fn delete_work(context: &Context, work_id: Uuid) -> FieldResult<Work> {
         context.token.jwt.as_ref().ok_or(ThothError::Unauthorised)?;
         let work = Work::from_id(&context.db, &work_id).unwrap();
@@ -1645,6 +1809,19 @@ impl MutationRoot {
 
         affiliation.delete(&context.db).map_err(|e| e.into())
     }
+
+    fn delete_work_relation(
+        context: &Context,
+        work_relation_id: Uuid,
+    ) -> FieldResult<WorkRelation> {
+        context.token.jwt.as_ref().ok_or(ThothError::Unauthorised)?;
+        let work_relation = WorkRelation::from_id(&context.db, &work_relation_id).unwrap();
+        context
+            .account_access
+            .can_edit(work_relation.publisher_id(&context.db)?)?;
+
+        work_relation.delete(&context.db).map_err(|e| e.into())
+    }
 }
 
 #[juniper::object(Context = Context, description = "A written text that can be published")]
@@ -1800,6 +1977,20 @@ impl Work {
         self.cover_caption.as_ref()
     }
 
+    #[graphql(
+        description = "First page of the work within the work it is part of (e.g. a chapter within a book)"
+    )]
+    pub fn first_page(&self) -> Option<&String> {
+        self.first_page.as_ref()
+    }
+
+    #[graphql(
+        description = "Last page of the work within the work it is part of (e.g. a chapter within a book)"
+    )]
+    pub fn last_page(&self) -> Option<&String> {
+        self.last_page.as_ref()
+    }
+
     pub fn created_at(&self) -> Timestamp {
         self.created_at.clone()
     }
@@ -1808,6 +1999,13 @@ impl Work {
         self.updated_at.clone()
     }
 
+    #[graphql(
+        description = "Time of the last update to this work or any of its child records (contributions, affiliations, fundings, issues, languages, publications, prices, locations, relations and subjects), including their creation or deletion"
+    )]
+    pub fn updated_at_with_relations(&self) -> Timestamp {
+        self.updated_at_with_relations.clone()
+    }
+
     pub fn imprint(&self, context: &Context) -> FieldResult<Imprint> {
         Imprint::from_id(&context.db, &self.imprint_id).map_err(|e| e.into())
     }
@@ -2059,6 +2257,46 @@ impl Work {
         )
         .map_err(|e| e.into())
     }
+
+    #[graphql(
+        description = "Get other works related to this work",
+        arguments(
+            limit(default = 100, description = "The number of items to return"),
+            offset(default = 0, description = "The number of items to skip"),
+            order(
+                default = {
+                    WorkRelationOrderBy {
+                        field: WorkRelationField::RelationOrdinal,
+                        direction: Direction::Asc,
+                    }
+                },
+                description = "The order in which to sort the results",
+            ),
+            relation_type(description = "A specific type to filter by"),
+        )
+    )]
+    pub fn relations(
+        &self,
+        context: &Context,
+        limit: i32,
+        offset: i32,
+        order: WorkRelationOrderBy,
+        relation_type: Option<RelationType>,
+    ) -> FieldResult<Vec<WorkRelation>> {
+        WorkRelation::all(
+            &context.db,
+            limit,
+            offset,
+            None,
+            order,
+            vec![],
+            Some(self.work_id),
+            None,
+            relation_type,
+            None,
+        )
+        .map_err(|e| e.into())
+    }
 }
 
 #[juniper::object(Context = Context, description = "A manifestation of a written text")]
@@ -2298,6 +2536,8 @@ impl Imprint {
         ),
         work_type(description = "A specific type to filter by"),
         work_status(description = "A specific status to filter by"),
+        updated_since(description = "If set, only shows works which were updated, or had any of their child records created, updated or deleted, after this time"),
+        after_work_id(description = "If set along with updatedSince, also shows works last updated at exactly that time whose ID is greater than this one, to page through works ordered by UPDATED_AT_WITH_RELATIONS"),
     )
   )]
     pub fn works(
@@ -2308,18 +2548,22 @@ impl Imprint {
         order: WorkOrderBy,
         work_type: Option<WorkType>,
         work_status: Option<WorkStatus>,
+        updated_since: Option<Timestamp>,
+        after_work_id: Option<Uuid>,
     ) -> FieldResult<Vec<Work>> {
-        Work::all(
+        Work::all_updated_since(
             &context.db,
             limit,
             offset,
             Some(filter),
             order,
             vec![],
+            vec![],
             Some(self.imprint_id),
-            None,
             work_type,
             work_status,
+            updated_since,
+            after_work_id,
         )
         .map_err(|e| e.into())
     }
@@ -2956,6 +3200,86 @@ impl Affiliation {
     }
 }
 
+#[juniper::object(Context = Context, description = "A record of a work which has been deleted.")]
+impl DeletedWork {
+    pub fn work_id(&self) -> &Uuid {
+        &self.work_id
+    }
+
+    pub fn publisher_id(&self) -> &Uuid {
+        &self.publisher_id
+    }
+
+    pub fn publication_ids(&self) -> &Vec<Uuid> {
+        &self.publication_ids
+    }
+
+    pub fn deleted_at(&self) -> Timestamp {
+        self.deleted_at.clone()
+    }
+}
+
+#[juniper::object(Context = Context, description = "A record of a publication which has been deleted from a work that still exists.")]
+impl DeletedPublication {
+    pub fn publication_id(&self) -> &Uuid {
+        &self.publication_id
+    }
+
+    pub fn work_id(&self) -> &Uuid {
+        &self.work_id
+    }
+
+    pub fn publisher_id(&self) -> &Uuid {
+        &self.publisher_id
+    }
+
+    pub fn deleted_at(&self) -> Timestamp {
+        self.deleted_at.clone()
+    }
+}
+
+#[juniper::object(Context = Context, description = "A relationship between two works, e.g. a book and one of its chapters.")]
+impl WorkRelation {
+    pub fn work_relation_id(&self) -> &Uuid {
+        &self.work_relation_id
+    }
+
+    #[graphql(description = "The work from which the relation is declared, e.g. the book")]
+    pub fn relator_work_id(&self) -> &Uuid {
+        &self.relator_work_id
+    }
+
+    #[graphql(description = "The work the relator work is related to, e.g. the chapter")]
+    pub fn related_work_id(&self) -> &Uuid {
+        &self.related_work_id
+    }
+
+    pub fn relation_type(&self) -> &RelationType {
+        &self.relation_type
+    }
+
+    #[graphql(description = "Position of the related work among those of the same type")]
+    pub fn relation_ordinal(&self) -> &i32 {
+        &self.relation_ordinal
+    }
+
+    pub fn created_at(&self) -> Timestamp {
+        self.created_at.clone()
+    }
+
+    pub fn updated_at(&self) -> Timestamp {
+        self.updated_at.clone()
+    }
+
+    pub fn relator_work(&self, context: &Context) -> FieldResult<Work> {
+        Work::from_id(&context.db, &self.relator_work_id).map_err(|e| e.into())
+    }
+
+    pub fn related_work(&self, context: &Context) -> FieldResult<Work> {
+        Work::from_id(&context.db, &self.related_work_id).map_err(|e| e.into())
+    }
+}
+
 pub type Schema = RootNode<'static, QueryRoot, MutationRoot>;
 
 pub fn create_schema() -> Schema {
@@ -72,7 +72,7 @@ pub struct Ror(String);
     derive(DieselNewType, juniper::GraphQLScalarValue),
     graphql(description = "RFC 3339 combined date and time in UTC time zone")
 )]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, PartialOrd)]
 pub struct Timestamp(DateTime<Utc>);
 
 impl Default for LengthUnit {
@@ -262,12 +262,28 @@ impl FromStr for Ror {
     }
 }
 
+impl FromStr for Timestamp {
+    type Err = ThothError;
+
+    fn from_str(input: &str) -> ThothResult<Timestamp> {
+        DateTime::parse_from_rfc3339(input)
+            .map(|timestamp| Timestamp(timestamp.with_timezone(&Utc)))
+            .map_err(|_| ThothError::TimestampParseError(input.to_string()))
+    }
+}
+
 impl Doi {
     pub fn to_lowercase_string(&self) -> String {
         self.0.to_lowercase()
     }
 }
 
+impl Timestamp {
+    pub fn to_datetime(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
 #[cfg(feature = "backend")]
 #[allow(clippy::too_many_arguments)]
 /// Common functionality to perform basic CRUD actions on Thoth entities
@@ -761,6 +777,31 @@ fn test_ror_fromstr() {
     assert!(Ror::from_str("0abcdef12https://ror.org/").is_err());
 }
 
+#[test]
+fn test_timestamp_fromstr() {
+    assert_eq!(
+        Timestamp::from_str("1970-01-01T00:00:00Z").unwrap(),
+        Default::default()
+    );
+    assert_eq!(
+        Timestamp::from_str("1970-01-01T01:00:00+01:00").unwrap(),
+        Default::default()
+    );
+    assert!(Timestamp::from_str("1970-01-01 00:00:00").is_err());
+    assert!(Timestamp::from_str("1970-01-01").is_err());
+    assert!(Timestamp::from_str("").is_err());
+}
+
+#[test]
+fn test_timestamp_to_datetime() {
+    let stamp = Timestamp::from_str("2021-06-01T12:30:00+01:00").unwrap();
+    assert_eq!(
+        stamp.to_datetime(),
+        TimeZone::ymd(&Utc, 2021, 6, 1).and_hms(11, 30, 0)
+    );
+    assert!(Timestamp::from_str("2021-06-02T00:00:00Z").unwrap() > stamp);
+}
+
 #[test]
 // Float equality comparison is fine here because the floats
 // have already been rounded by the functions under test
@@ -852,3 +893,4 @@ pub mod publisher;
 pub mod series;
 pub mod subject;
 pub mod work;
+pub mod work_relation;
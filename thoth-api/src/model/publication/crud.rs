@@ -1,9 +1,9 @@
 use super::{
-    NewPublication, NewPublicationHistory, PatchPublication, Publication, PublicationField,
-    PublicationHistory, PublicationOrderBy, PublicationType,
+    DeletedPublication, NewPublication, NewPublicationHistory, PatchPublication, Publication,
+    PublicationField, PublicationHistory, PublicationOrderBy, PublicationType,
 };
 use crate::graphql::utils::Direction;
-use crate::model::{Crud, DbInsert, HistoryEntry};
+use crate::model::{Crud, DbInsert, HistoryEntry, Timestamp};
 use crate::schema::{publication, publication_history};
 use crate::{crud_methods, db_insert};
 use diesel::{ExpressionMethods, PgTextExpressionMethods, QueryDsl, RunQueryDsl};
@@ -172,6 +172,32 @@ impl DbInsert for NewPublicationHistory {
     db_insert!(publication_history::table);
 }
 
+impl DeletedPublication {
+    /// Query the database to obtain a list of publications that have been deleted from works that
+    /// remain, optionally restricted to those connected to the given publishers, or deleted after
+    /// a given time
+    pub fn all(
+        db: &crate::db::PgPool,
+        publishers: Vec<Uuid>,
+        deleted_since: Option<Timestamp>,
+    ) -> ThothResult<Vec<DeletedPublication>> {
+        use crate::schema::deleted_publication::dsl;
+        let connection = db.get().unwrap();
+        let mut query = dsl::deleted_publication
+            .order(dsl::deleted_at.asc())
+            .into_boxed();
+        if !publishers.is_empty() {
+            query = query.filter(dsl::publisher_id.eq_any(publishers));
+        }
+        if let Some(since) = deleted_since {
+            query = query.filter(dsl::deleted_at.gt(since));
+        }
+        query
+            .load::<DeletedPublication>(&connection)
+            .map_err(ThothError::from)
+    }
+}
+
 impl NewPublication {
     pub fn can_have_isbn(&self, db: &crate::db::PgPool) -> ThothResult<()> {
         publication_can_have_isbn(self.work_id, db)
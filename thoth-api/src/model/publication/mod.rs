@@ -107,6 +107,16 @@ pub struct PatchPublication {
     pub isbn: Option<Isbn>,
 }
 
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedPublication {
+    pub publication_id: Uuid,
+    pub work_id: Uuid,
+    pub publisher_id: Uuid,
+    pub deleted_at: Timestamp,
+}
+
 #[cfg_attr(feature = "backend", derive(Queryable))]
 pub struct PublicationHistory {
     pub publication_history_id: Uuid,
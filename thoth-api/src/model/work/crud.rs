@@ -1,9 +1,9 @@
 use super::{
-    NewWork, NewWorkHistory, PatchWork, Work, WorkField, WorkHistory, WorkOrderBy, WorkStatus,
-    WorkType,
+    DeletedWork, NewWork, NewWorkHistory, PatchWork, Work, WorkField, WorkHistory, WorkOrderBy,
+    WorkStatus, WorkType,
 };
 use crate::graphql::utils::Direction;
-use crate::model::{Convert, Crud, DbInsert, Doi, HistoryEntry, LengthUnit};
+use crate::model::{Convert, Crud, DbInsert, Doi, HistoryEntry, LengthUnit, Timestamp};
 use crate::schema::{work, work_history};
 use crate::{crud_methods, db_insert};
 use diesel::{
@@ -135,30 +135,25 @@ impl Work {
             }
         }
     }
-}
-
-impl Crud for Work {
-    type NewEntity = NewWork;
-    type PatchEntity = PatchWork;
-    type OrderByEntity = WorkOrderBy;
-    type FilterParameter1 = WorkType;
-    type FilterParameter2 = WorkStatus;
 
-    fn pk(&self) -> Uuid {
-        self.work_id
-    }
-
-    fn all(
+    /// Query the database to obtain a list of works as per `Crud::all`, optionally restricted to
+    /// those with the given IDs, or to those which were updated, or had any of their child records created, updated or deleted,
+    /// after a given time. Works last updated at exactly that time are also included if their ID
+    /// sorts after `after_work_id`, so that lists ordered by update time can be paged by keyset.
+    #[allow(clippy::too_many_arguments)]
+    pub fn all_updated_since(
         db: &crate::db::PgPool,
         limit: i32,
         offset: i32,
         filter: Option<String>,
-        order: Self::OrderByEntity,
+        order: WorkOrderBy,
         publishers: Vec<Uuid>,
+        work_ids: Vec<Uuid>,
         parent_id_1: Option<Uuid>,
-        _: Option<Uuid>,
-        work_type: Option<Self::FilterParameter1>,
-        work_status: Option<Self::FilterParameter2>,
+        work_type: Option<WorkType>,
+        work_status: Option<WorkStatus>,
+        updated_since: Option<Timestamp>,
+        after_work_id: Option<Uuid>,
     ) -> ThothResult<Vec<Work>> {
         use crate::schema::work::dsl;
         let connection = db.get().unwrap();
@@ -196,8 +191,11 @@ impl Crud for Work {
                 dsl::toc,
                 dsl::cover_url,
                 dsl::cover_caption,
+                dsl::first_page,
+                dsl::last_page,
                 dsl::created_at,
                 dsl::updated_at,
+                dsl::updated_at_with_relations,
             ))
             .into_boxed();
 
@@ -330,13 +328,22 @@ impl Crud for Work {
                 Direction::Asc => query = query.order(dsl::updated_at.asc()),
                 Direction::Desc => query = query.order(dsl::updated_at.desc()),
             },
+            WorkField::UpdatedAtWithRelations => match order.direction {
+                Direction::Asc => query = query.order(dsl::updated_at_with_relations.asc()),
+                Direction::Desc => query = query.order(dsl::updated_at_with_relations.desc()),
+            },
         }
+        // Break ties on the chosen field so that consecutive pages neither repeat nor skip works
+        query = query.then_order_by(dsl::work_id);
         // This loop must appear before any other filter statements, as it takes advantage of
         // the behaviour of `or_filter` being equal to `filter` when no other filters are present yet.
         // Result needs to be `WHERE (x = $1 [OR x = $2...]) AND ([...])` - note bracketing.
         for pub_id in publishers {
             query = query.or_filter(crate::schema::imprint::publisher_id.eq(pub_id));
         }
+        if !work_ids.is_empty() {
+            query = query.filter(dsl::work_id.eq_any(work_ids));
+        }
         if let Some(pid) = parent_id_1 {
             query = query.filter(dsl::imprint_id.eq(pid));
         }
@@ -357,6 +364,21 @@ impl Crud for Work {
                     .or(dsl::landing_page.ilike(format!("%{}%", filter))),
             );
         }
+        match (updated_since, after_work_id) {
+            (Some(since), Some(work_id)) => {
+                query = query.filter(
+                    dsl::updated_at_with_relations.gt(since.clone()).or(
+                        dsl::updated_at_with_relations
+                            .eq(since)
+                            .and(dsl::work_id.gt(work_id)),
+                    ),
+                );
+            }
+            (Some(since), None) => {
+                query = query.filter(dsl::updated_at_with_relations.gt(since));
+            }
+            _ => {}
+        }
         match query
             .limit(limit.into())
             .offset(offset.into())
@@ -366,6 +388,74 @@ impl Crud for Work {
             Err(e) => Err(ThothError::from(e)),
         }
     }
+}
+
+impl DeletedWork {
+    /// Query the database to obtain a list of works that have been deleted, optionally restricted
+    /// to those connected to the given publishers, to those with the given IDs, or to those
+    /// deleted after a given time
+    pub fn all(
+        db: &crate::db::PgPool,
+        publishers: Vec<Uuid>,
+        work_ids: Vec<Uuid>,
+        deleted_since: Option<Timestamp>,
+    ) -> ThothResult<Vec<DeletedWork>> {
+        use crate::schema::deleted_work::dsl;
+        let connection = db.get().unwrap();
+        let mut query = dsl::deleted_work.order(dsl::deleted_at.asc()).into_boxed();
+        if !publishers.is_empty() {
+            query = query.filter(dsl::publisher_id.eq_any(publishers));
+        }
+        if !work_ids.is_empty() {
+            query = query.filter(dsl::work_id.eq_any(work_ids));
+        }
+        if let Some(since) = deleted_since {
+            query = query.filter(dsl::deleted_at.gt(since));
+        }
+        query
+            .load::<DeletedWork>(&connection)
+            .map_err(ThothError::from)
+    }
+}
+
+impl Crud for Work {
+    type NewEntity = NewWork;
+    type PatchEntity = PatchWork;
+    type OrderByEntity = WorkOrderBy;
+    type FilterParameter1 = WorkType;
+    type FilterParameter2 = WorkStatus;
+
+    fn pk(&self) -> Uuid {
+        self.work_id
+    }
+
+    fn all(
+        db: &crate::db::PgPool,
+        limit: i32,
+        offset: i32,
+        filter: Option<String>,
+        order: Self::OrderByEntity,
+        publishers: Vec<Uuid>,
+        parent_id_1: Option<Uuid>,
+        _: Option<Uuid>,
+        work_type: Option<Self::FilterParameter1>,
+        work_status: Option<Self::FilterParameter2>,
+    ) -> ThothResult<Vec<Work>> {
+        Work::all_updated_since(
+            db,
+            limit,
+            offset,
+            filter,
+            order,
+            publishers,
+            vec![],
+            parent_id_1,
+            work_type,
+            work_status,
+            None,
+            None,
+        )
+    }
 
     fn count(
         db: &crate::db::PgPool,
@@ -410,8 +500,11 @@ impl Crud for Work {
                 dsl::toc,
                 dsl::cover_url,
                 dsl::cover_caption,
+                dsl::first_page,
+                dsl::last_page,
                 dsl::created_at,
                 dsl::updated_at,
+                dsl::updated_at_with_relations,
             ))
             .into_boxed();
         // This loop must appear before any other filter statements, as it takes advantage of
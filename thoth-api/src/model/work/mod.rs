@@ -112,6 +112,7 @@ pub enum WorkField {
     CoverCaption,
     CreatedAt,
     UpdatedAt,
+    UpdatedAtWithRelations,
 }
 
 #[cfg_attr(feature = "backend", derive(Queryable))]
@@ -149,8 +150,11 @@ pub struct Work {
     pub toc: Option<String>,
     pub cover_url: Option<String>,
     pub cover_caption: Option<String>,
+    pub first_page: Option<String>,
+    pub last_page: Option<String>,
     pub created_at: Timestamp,
     pub updated_at: Timestamp,
+    pub updated_at_with_relations: Timestamp,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -186,6 +190,8 @@ pub struct WorkWithRelations {
     pub toc: Option<String>,
     pub cover_url: Option<String>,
     pub cover_caption: Option<String>,
+    pub first_page: Option<String>,
+    pub last_page: Option<String>,
     pub updated_at: Timestamp,
     pub contributions: Option<Vec<Contribution>>,
     pub publications: Option<Vec<Publication>>,
@@ -232,6 +238,8 @@ pub struct NewWork {
     pub toc: Option<String>,
     pub cover_url: Option<String>,
     pub cover_caption: Option<String>,
+    pub first_page: Option<String>,
+    pub last_page: Option<String>,
 }
 
 #[cfg_attr(
@@ -272,6 +280,8 @@ pub struct PatchWork {
     pub toc: Option<String>,
     pub cover_url: Option<String>,
     pub cover_caption: Option<String>,
+    pub first_page: Option<String>,
+    pub last_page: Option<String>,
 }
 
 #[cfg_attr(feature = "backend", derive(Queryable))]
@@ -290,6 +300,16 @@ pub struct NewWorkHistory {
     pub data: serde_json::Value,
 }
 
+#[cfg_attr(feature = "backend", derive(Queryable))]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeletedWork {
+    pub work_id: Uuid,
+    pub publisher_id: Uuid,
+    pub publication_ids: Vec<Uuid>,
+    pub deleted_at: Timestamp,
+}
+
 #[cfg_attr(
     feature = "backend",
     derive(juniper::GraphQLInputObject),
@@ -380,6 +400,8 @@ impl Default for WorkWithRelations {
             toc: Default::default(),
             cover_url: Default::default(),
             cover_caption: Default::default(),
+            first_page: Default::default(),
+            last_page: Default::default(),
             updated_at: Default::default(),
             contributions: Default::default(),
             publications: Default::default(),
@@ -482,6 +504,10 @@ fn test_workfield_display() {
     assert_eq!(format!("{}", WorkField::CoverCaption), "CoverCaption");
     assert_eq!(format!("{}", WorkField::CreatedAt), "CreatedAt");
     assert_eq!(format!("{}", WorkField::UpdatedAt), "UpdatedAt");
+    assert_eq!(
+        format!("{}", WorkField::UpdatedAtWithRelations),
+        "UpdatedAtWithRelations"
+    );
 }
 
 #[test]
@@ -659,6 +685,10 @@ fn test_workfield_fromstr() {
         WorkField::from_str("UpdatedAt").unwrap(),
         WorkField::UpdatedAt
     );
+    assert_eq!(
+        WorkField::from_str("UpdatedAtWithRelations").unwrap(),
+        WorkField::UpdatedAtWithRelations
+    );
     assert!(WorkField::from_str("WorkID").is_err());
     assert!(WorkField::from_str("Contributors").is_err());
     assert!(WorkField::from_str("Publisher").is_err());
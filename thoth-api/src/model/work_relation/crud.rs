@@ -0,0 +1,225 @@
+use super::{
+    NewWorkRelation, NewWorkRelationHistory, PatchWorkRelation, RelationType, WorkRelation,
+    WorkRelationField, WorkRelationHistory,
+};
+use crate::graphql::model::WorkRelationOrderBy;
+use crate::graphql::utils::Direction;
+use crate::model::{Crud, DbInsert, HistoryEntry};
+use crate::schema::{imprint, work, work_relation, work_relation_history};
+use crate::{crud_methods, db_insert};
+use diesel::{ExpressionMethods, JoinOnDsl, QueryDsl, RunQueryDsl};
+use thoth_errors::{ThothError, ThothResult};
+use uuid::Uuid;
+
+impl Crud for WorkRelation {
+    type NewEntity = NewWorkRelation;
+    type PatchEntity = PatchWorkRelation;
+    type OrderByEntity = WorkRelationOrderBy;
+    type FilterParameter1 = RelationType;
+    type FilterParameter2 = ();
+
+    fn pk(&self) -> Uuid {
+        self.work_relation_id
+    }
+
+    fn all(
+        db: &crate::db::PgPool,
+        limit: i32,
+        offset: i32,
+        _: Option<String>,
+        order: Self::OrderByEntity,
+        publishers: Vec<Uuid>,
+        parent_id_1: Option<Uuid>,
+        _: Option<Uuid>,
+        relation_type: Option<Self::FilterParameter1>,
+        _: Option<Self::FilterParameter2>,
+    ) -> ThothResult<Vec<WorkRelation>> {
+        use crate::schema::work_relation::dsl;
+        let connection = db.get().unwrap();
+        // Relations belong to the publisher of the relator work
+        let mut query = dsl::work_relation
+            .inner_join(work::table.on(dsl::relator_work_id.eq(work::work_id)))
+            .inner_join(imprint::table.on(work::imprint_id.eq(imprint::imprint_id)))
+            .select((
+                dsl::work_relation_id,
+                dsl::relator_work_id,
+                dsl::related_work_id,
+                dsl::relation_type,
+                dsl::relation_ordinal,
+                dsl::created_at,
+                dsl::updated_at,
+            ))
+            .into_boxed();
+
+        match order.field {
+            WorkRelationField::WorkRelationId => match order.direction {
+                Direction::Asc => query = query.order(dsl::work_relation_id.asc()),
+                Direction::Desc => query = query.order(dsl::work_relation_id.desc()),
+            },
+            WorkRelationField::RelatorWorkId => match order.direction {
+                Direction::Asc => query = query.order(dsl::relator_work_id.asc()),
+                Direction::Desc => query = query.order(dsl::relator_work_id.desc()),
+            },
+            WorkRelationField::RelatedWorkId => match order.direction {
+                Direction::Asc => query = query.order(dsl::related_work_id.asc()),
+                Direction::Desc => query = query.order(dsl::related_work_id.desc()),
+            },
+            WorkRelationField::RelationType => match order.direction {
+                Direction::Asc => query = query.order(dsl::relation_type.asc()),
+                Direction::Desc => query = query.order(dsl::relation_type.desc()),
+            },
+            WorkRelationField::RelationOrdinal => match order.direction {
+                Direction::Asc => query = query.order(dsl::relation_ordinal.asc()),
+                Direction::Desc => query = query.order(dsl::relation_ordinal.desc()),
+            },
+            WorkRelationField::CreatedAt => match order.direction {
+                Direction::Asc => query = query.order(dsl::created_at.asc()),
+                Direction::Desc => query = query.order(dsl::created_at.desc()),
+            },
+            WorkRelationField::UpdatedAt => match order.direction {
+                Direction::Asc => query = query.order(dsl::updated_at.asc()),
+                Direction::Desc => query = query.order(dsl::updated_at.desc()),
+            },
+        }
+        // This loop must appear before any other filter statements, as it takes advantage of
+        // the behaviour of `or_filter` being equal to `filter` when no other filters are present yet.
+        // Result needs to be `WHERE (x = $1 [OR x = $2...]) AND ([...])` - note bracketing.
+        for pub_id in publishers {
+            query = query.or_filter(imprint::publisher_id.eq(pub_id));
+        }
+        // A work is the child of those which have it as a child, so these relations are found
+        // from the other end and returned inverted
+        let inverse = relation_type == Some(RelationType::IsChildOf);
+        if let Some(pid) = parent_id_1 {
+            if inverse {
+                query = query.filter(dsl::related_work_id.eq(pid));
+            } else {
+                query = query.filter(dsl::relator_work_id.eq(pid));
+            }
+        }
+        if let Some(rel_type) = relation_type {
+            query = query.filter(dsl::relation_type.eq(stored_type(rel_type)));
+        }
+        match query
+            .limit(limit.into())
+            .offset(offset.into())
+            .load::<WorkRelation>(&connection)
+        {
+            Ok(t) if inverse => Ok(t.into_iter().map(WorkRelation::inverted).collect()),
+            Ok(t) => Ok(t),
+            Err(e) => Err(ThothError::from(e)),
+        }
+    }
+
+    fn count(
+        db: &crate::db::PgPool,
+        _: Option<String>,
+        _: Vec<Uuid>,
+        relation_type: Option<Self::FilterParameter1>,
+        _: Option<Self::FilterParameter2>,
+    ) -> ThothResult<i32> {
+        use crate::schema::work_relation::dsl;
+        let connection = db.get().unwrap();
+        let mut query = dsl::work_relation.into_boxed();
+        if let Some(rel_type) = relation_type {
+            query = query.filter(dsl::relation_type.eq(stored_type(rel_type)));
+        }
+
+        // `SELECT COUNT(*)` in postgres returns a BIGINT, which diesel parses as i64. Juniper does
+        // not implement i64 yet, only i32. The only sensible way, albeit shameful, to solve this
+        // is converting i64 to string and then parsing it as i32. This should work until we reach
+        // 2147483647 records - if you are fixing this bug, congratulations on book number 2147483647!
+        match query.count().get_result::<i64>(&connection) {
+            Ok(t) => Ok(t.to_string().parse::<i32>().unwrap()),
+            Err(e) => Err(ThothError::from(e)),
+        }
+    }
+
+    fn publisher_id(&self, db: &crate::db::PgPool) -> ThothResult<Uuid> {
+        crate::model::work::Work::from_id(db, &self.relator_work_id)?.publisher_id(db)
+    }
+
+    crud_methods!(work_relation::table, work_relation::dsl::work_relation);
+}
+
+impl WorkRelation {
+    /// The same relation, as seen from the related work
+    fn inverted(self) -> Self {
+        WorkRelation {
+            relator_work_id: self.related_work_id,
+            related_work_id: self.relator_work_id,
+            relation_type: RelationType::IsChildOf,
+            ..self
+        }
+    }
+}
+
+/// Type of the stored relations a relation type is found in
+fn stored_type(relation_type: RelationType) -> RelationType {
+    match relation_type {
+        RelationType::IsChildOf => RelationType::HasChild,
+        relation_type => relation_type,
+    }
+}
+
+impl HistoryEntry for WorkRelation {
+    type NewHistoryEntity = NewWorkRelationHistory;
+
+    fn new_history_entry(&self, account_id: &Uuid) -> Self::NewHistoryEntity {
+        Self::NewHistoryEntity {
+            work_relation_id: self.work_relation_id,
+            account_id: *account_id,
+            data: serde_json::Value::String(serde_json::to_string(&self).unwrap()),
+        }
+    }
+}
+
+impl DbInsert for NewWorkRelationHistory {
+    type MainEntity = WorkRelationHistory;
+
+    db_insert!(work_relation_history::table);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_work_relation_pk() {
+        let work_relation: WorkRelation = Default::default();
+        assert_eq!(work_relation.pk(), work_relation.work_relation_id);
+    }
+
+    #[test]
+    fn test_work_relation_inverted() {
+        let work_relation = WorkRelation {
+            relator_work_id: Uuid::parse_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            related_work_id: Uuid::parse_str("00000000-0000-0000-AAAA-000000000002").unwrap(),
+            relation_ordinal: 3,
+            ..Default::default()
+        };
+        let inverted = work_relation.clone().inverted();
+        assert_eq!(inverted.relator_work_id, work_relation.related_work_id);
+        assert_eq!(inverted.related_work_id, work_relation.relator_work_id);
+        assert_eq!(inverted.relation_type, RelationType::IsChildOf);
+        assert_eq!(inverted.relation_ordinal, 3);
+        assert_eq!(stored_type(RelationType::IsChildOf), RelationType::HasChild);
+        assert_eq!(stored_type(RelationType::HasPart), RelationType::HasPart);
+    }
+
+    #[test]
+    fn test_new_work_relation_history_from_work_relation() {
+        let work_relation: WorkRelation = Default::default();
+        let account_id: Uuid = Default::default();
+        let new_work_relation_history = work_relation.new_history_entry(&account_id);
+        assert_eq!(
+            new_work_relation_history.work_relation_id,
+            work_relation.work_relation_id
+        );
+        assert_eq!(new_work_relation_history.account_id, account_id);
+        assert_eq!(
+            new_work_relation_history.data,
+            serde_json::Value::String(serde_json::to_string(&work_relation).unwrap())
+        );
+    }
+}
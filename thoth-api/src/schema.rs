@@ -102,6 +102,28 @@ table! {
     }
 }
 
+table! {
+    use diesel::sql_types::*;
+
+    deleted_work (work_id) {
+        work_id -> Uuid,
+        publisher_id -> Uuid,
+        publication_ids -> Array<Uuid>,
+        deleted_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+
+    deleted_publication (publication_id) {
+        publication_id -> Uuid,
+        work_id -> Uuid,
+        publisher_id -> Uuid,
+        deleted_at -> Timestamptz,
+    }
+}
+
 table! {
     use diesel::sql_types::*;
 
@@ -446,8 +468,11 @@ table! {
         toc -> Nullable<Text>,
         cover_url -> Nullable<Text>,
         cover_caption -> Nullable<Text>,
+        first_page -> Nullable<Text>,
+        last_page -> Nullable<Text>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        updated_at_with_relations -> Timestamptz,
     }
 }
 
@@ -463,6 +488,33 @@ table! {
     }
 }
 
+table! {
+    use diesel::sql_types::*;
+    use crate::model::work_relation::Relation_type;
+
+    work_relation (work_relation_id) {
+        work_relation_id -> Uuid,
+        relator_work_id -> Uuid,
+        related_work_id -> Uuid,
+        relation_type -> Relation_type,
+        relation_ordinal -> Int4,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+table! {
+    use diesel::sql_types::*;
+
+    work_relation_history (work_relation_history_id) {
+        work_relation_history_id -> Uuid,
+        work_relation_id -> Uuid,
+        account_id -> Uuid,
+        data -> Jsonb,
+        timestamp -> Timestamptz,
+    }
+}
+
 joinable!(affiliation -> contribution (contribution_id));
 joinable!(affiliation -> institution (institution_id));
 joinable!(affiliation_history -> account (account_id));
@@ -511,6 +563,8 @@ joinable!(subject_history -> subject (subject_id));
 joinable!(work -> imprint (imprint_id));
 joinable!(work_history -> account (account_id));
 joinable!(work_history -> work (work_id));
+joinable!(work_relation_history -> account (account_id));
+joinable!(work_relation_history -> work_relation (work_relation_id));
 
 allow_tables_to_appear_in_same_query!(
     account,
@@ -520,6 +574,8 @@ allow_tables_to_appear_in_same_query!(
     contribution_history,
     contributor,
     contributor_history,
+    deleted_publication,
+    deleted_work,
     funding,
     funding_history,
     imprint,
@@ -545,4 +601,6 @@ allow_tables_to_appear_in_same_query!(
     subject_history,
     work,
     work_history,
+    work_relation,
+    work_relation_history,
 );
@@ -122,6 +122,8 @@ pub enum Msg {
     ChangeLengthUnit(LengthUnit),
     ChangePageCount(String),
     ChangePageBreakdown(String),
+    ChangeFirstPage(String),
+    ChangeLastPage(String),
     ChangeImageCount(String),
     ChangeTableCount(String),
     ChangeAudioCount(String),
@@ -329,6 +331,8 @@ impl Component for WorkComponent {
                         toc: self.work.toc.clone(),
                         cover_url: self.work.cover_url.clone(),
                         cover_caption: self.work.cover_caption.clone(),
+                        first_page: self.work.first_page.clone(),
+                        last_page: self.work.last_page.clone(),
                         units: self.props.units_selection.clone(),
                     },
                     ..Default::default()
@@ -462,6 +466,8 @@ impl Component for WorkComponent {
             Msg::ChangePageBreakdown(value) => {
                 self.work.page_breakdown.neq_assign(value.to_opt_string())
             }
+            Msg::ChangeFirstPage(value) => self.work.first_page.neq_assign(value.to_opt_string()),
+            Msg::ChangeLastPage(value) => self.work.last_page.neq_assign(value.to_opt_string()),
             Msg::ChangeImageCount(value) => self.work.image_count.neq_assign(value.to_opt_int()),
             Msg::ChangeTableCount(value) => self.work.table_count.neq_assign(value.to_opt_int()),
             Msg::ChangeAudioCount(value) => self.work.audio_count.neq_assign(value.to_opt_int()),
@@ -736,6 +742,16 @@ impl Component for WorkComponent {
                                         value=self.work.page_breakdown.clone()
                                         oninput=self.link.callback(|e: InputData| Msg::ChangePageBreakdown(e.value))
                                     />
+                                    <FormTextInput
+                                        label = "First Page"
+                                        value=self.work.first_page.clone()
+                                        oninput=self.link.callback(|e: InputData| Msg::ChangeFirstPage(e.value))
+                                    />
+                                    <FormTextInput
+                                        label = "Last Page"
+                                        value=self.work.last_page.clone()
+                                        oninput=self.link.callback(|e: InputData| Msg::ChangeLastPage(e.value))
+                                    />
                                 </div>
                             </div>
                             <div class="field is-horizontal">
@@ -40,7 +40,9 @@ const UPDATE_WORK_MUTATION: &str = "
         $generalNote: String,
         $toc: String,
         $coverUrl: String,
-        $coverCaption: String
+        $coverCaption: String,
+        $firstPage: String,
+        $lastPage: String
     ) {
         updateWork(units: $units,
             data: {
@@ -75,6 +77,8 @@ const UPDATE_WORK_MUTATION: &str = "
             toc: $toc
             coverUrl: $coverUrl
             coverCaption: $coverCaption
+            firstPage: $firstPage
+            lastPage: $lastPage
         }){
             workId
             workType
@@ -136,6 +140,8 @@ pub struct Variables {
     pub toc: Option<String>,
     pub cover_url: Option<String>,
     pub cover_caption: Option<String>,
+    pub first_page: Option<String>,
+    pub last_page: Option<String>,
     pub imprint_id: Uuid,
     pub units: LengthUnit,
 }
@@ -42,6 +42,8 @@ pub const WORK_QUERY: &str = "
             toc
             coverUrl
             coverCaption
+            firstPage
+            lastPage
             updatedAt
             contributions {
                 contributionId
@@ -5,14 +5,59 @@ use graphql_client::GraphQLQuery;
 use graphql_client::Response;
 use serde::Serialize;
 use std::future::Future;
+use thoth_api::model::Timestamp;
 use thoth_errors::{ThothError, ThothResult};
 use uuid::Uuid;
 
+pub use crate::queries::deleted_publications_query::DeletedPublicationsQueryDeletedPublications as DeletedPublication;
+pub use crate::queries::deleted_works_query::DeletedWorksQueryDeletedWorks as DeletedWork;
+pub use crate::queries::publishers_query::{
+    PublishersQueryPublishers as Publisher, PublishersQueryPublishersImprints as PublisherImprints,
+};
 pub use crate::queries::work_query::*;
-use crate::queries::{work_query, works_query, WorkQuery, WorksQuery};
+use crate::queries::{
+    deleted_publications_query, deleted_works_query, imprint_works_query, publishers_query,
+    series_works_query, updated_imprint_works_query, updated_works_query, work_query,
+    works_by_id_query, works_query, works_summary_query, DeletedPublicationsQuery,
+    DeletedWorksQuery, ImprintWorksQuery, PublishersQuery, SeriesWorksQuery,
+    UpdatedImprintWorksQuery, UpdatedWorksQuery, WorkQuery, WorksByIdQuery, WorksQuery,
+    WorksSummaryQuery,
+};
 
 type HttpFuture = Result<reqwest::Response, reqwest::Error>;
 
+/// Number of works requested at a time when listing works
+pub const WORKS_PAGE_SIZE: i64 = 100;
+
+/// Maximum number of works that can be requested at once by ID
+pub const MAX_WORK_IDS: usize = WORKS_PAGE_SIZE as usize;
+
+/// Number of works of a list, and time of the last update to any of them
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorksSummary {
+    /// Number of works, regardless of the time of their last update
+    pub work_count: i64,
+    /// Time of the last update to any work, or to its child records, if any was updated
+    pub last_updated: Option<Timestamp>,
+}
+
+/// Request every page of a list of works, given the request for the page at an offset
+async fn all_pages<F, P>(get_page: F) -> ThothResult<Vec<Work>>
+where
+    F: Fn(i64) -> P,
+    P: Future<Output = ThothResult<Vec<Work>>>,
+{
+    let mut works = vec![];
+    loop {
+        let page = get_page(works.len() as i64).await?;
+        let is_last_page = (page.len() as i64) < WORKS_PAGE_SIZE;
+        works.extend(page);
+        if is_last_page {
+            return Ok(works);
+        }
+    }
+}
+
 /// A GraphQL `ThothClient` to query metadata
 pub struct ThothClient {
     graphql_endpoint: String,
@@ -69,7 +114,9 @@ impl ThothClient {
         }
     }
 
-    /// Get a list of `Work`s from Thoth
+    /// Get a list of `Work`s from Thoth, optionally restricted to those updated since a given time
+    ///
+    /// Works are requested in pages of `WORKS_PAGE_SIZE`, to keep each response small.
     ///
     /// # Errors
     ///
@@ -85,12 +132,109 @@ impl ThothClient {
     /// # async fn run() -> ThothResult<Vec<Work>> {
     /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
     /// let publisher_id = Uuid::parse_str("00000000-0000-0000-AAAA-000000000001")?;
-    /// let works = thoth_client.get_works(Some(vec![publisher_id])).await?;
+    /// let works = thoth_client.get_works(Some(vec![publisher_id]), None).await?;
     /// # Ok(works)
     /// # }
     /// ```
-    pub async fn get_works(&self, publishers: Option<Vec<Uuid>>) -> ThothResult<Vec<Work>> {
-        let request_body = WorksQuery::build_query(works_query::Variables { publishers });
+    pub async fn get_works(
+        &self,
+        publishers: Option<Vec<Uuid>>,
+        updated_since: Option<Timestamp>,
+    ) -> ThothResult<Vec<Work>> {
+        all_pages(|offset| {
+            self.get_works_page(
+                publishers.clone(),
+                updated_since.clone(),
+                WORKS_PAGE_SIZE,
+                offset,
+            )
+        })
+        .await
+    }
+
+    /// Get the number of works of the given publishers, and the time of the last update to any of
+    /// them updated since a given time, without requesting the works themselves
+    ///
+    /// # Errors
+    ///
+    /// This method fails if there was an error while sending the request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use thoth_errors::ThothResult;
+    /// # use thoth_client::{ThothClient, WorksSummary};
+    /// # use uuid::Uuid;
+    ///
+    /// # async fn run() -> ThothResult<WorksSummary> {
+    /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
+    /// let publisher_id = Uuid::parse_str("00000000-0000-0000-AAAA-000000000001")?;
+    /// let summary = thoth_client
+    ///     .get_works_summary(Some(vec![publisher_id]), None)
+    ///     .await?;
+    /// # Ok(summary)
+    /// # }
+    /// ```
+    pub async fn get_works_summary(
+        &self,
+        publishers: Option<Vec<Uuid>>,
+        updated_since: Option<Timestamp>,
+    ) -> ThothResult<WorksSummary> {
+        let request_body = WorksSummaryQuery::build_query(works_summary_query::Variables {
+            publishers,
+            updated_since,
+        });
+        let res = self.post_request(&request_body).await.await?;
+        let response_body: Response<works_summary_query::ResponseData> = res.json().await?;
+        match response_body.data {
+            Some(data) => Ok(WorksSummary {
+                work_count: data.work_count,
+                last_updated: data
+                    .works
+                    .into_iter()
+                    .next()
+                    .map(|work| work.updated_at_with_relations),
+            }),
+            None => Err(ThothError::EntityNotFound),
+        }
+    }
+
+    /// Get a page of the list of `Work`s from Thoth, optionally restricted to those updated since
+    /// a given time. A page shorter than `limit` is the last one.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if there was an error while sending the request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use thoth_errors::ThothResult;
+    /// # use thoth_client::{ThothClient, Work, WORKS_PAGE_SIZE};
+    /// # use uuid::Uuid;
+    ///
+    /// # async fn run() -> ThothResult<Vec<Work>> {
+    /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
+    /// let publisher_id = Uuid::parse_str("00000000-0000-0000-AAAA-000000000001")?;
+    /// let second_page = thoth_client
+    ///     .get_works_page(Some(vec![publisher_id]), None, WORKS_PAGE_SIZE, WORKS_PAGE_SIZE)
+    ///     .await?;
+    /// # Ok(second_page)
+    /// # }
+    /// ```
+    pub async fn get_works_page(
+        &self,
+        publishers: Option<Vec<Uuid>>,
+        updated_since: Option<Timestamp>,
+        limit: i64,
+        offset: i64,
+    ) -> ThothResult<Vec<Work>> {
+        let request_body = WorksQuery::build_query(works_query::Variables {
+            publishers,
+            updated_since,
+            limit,
+            offset,
+        });
         let res = self.post_request(&request_body).await.await?;
         let response_body: Response<works_query::ResponseData> = res.json().await?;
         match response_body.data {
@@ -98,4 +242,428 @@ impl ThothClient {
             None => Err(ThothError::EntityNotFound),
         }
     }
+
+    /// Get a page of the list of `Work`s from Thoth in the order they were last updated, then by
+    /// ID, starting after a given update time. Works updated at exactly that time are included
+    /// if their ID is greater than `after_work_id`, so that the update time and ID of the last
+    /// work of a page resume the list without repeating or skipping any work.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if there was an error while sending the request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use thoth_errors::ThothResult;
+    /// # use thoth_client::{ThothClient, Work, WORKS_PAGE_SIZE};
+    /// # use uuid::Uuid;
+    ///
+    /// # async fn run() -> ThothResult<Vec<Work>> {
+    /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
+    /// let first_page = thoth_client
+    ///     .get_updated_works_page(None, None, None, WORKS_PAGE_SIZE)
+    ///     .await?;
+    /// let next_page = match first_page.last() {
+    ///     Some(last) => {
+    ///         thoth_client
+    ///             .get_updated_works_page(
+    ///                 None,
+    ///                 Some(last.updated_at_with_relations.clone()),
+    ///                 Some(last.work_id),
+    ///                 WORKS_PAGE_SIZE,
+    ///             )
+    ///             .await?
+    ///     }
+    ///     None => vec![],
+    /// };
+    /// # Ok(next_page)
+    /// # }
+    /// ```
+    pub async fn get_updated_works_page(
+        &self,
+        publishers: Option<Vec<Uuid>>,
+        updated_since: Option<Timestamp>,
+        after_work_id: Option<Uuid>,
+        limit: i64,
+    ) -> ThothResult<Vec<Work>> {
+        let request_body = UpdatedWorksQuery::build_query(updated_works_query::Variables {
+            publishers,
+            updated_since,
+            after_work_id,
+            limit,
+        });
+        let res = self.post_request(&request_body).await.await?;
+        let response_body: Response<updated_works_query::ResponseData> = res.json().await?;
+        match response_body.data {
+            Some(data) => Ok(data.works.iter().map(|w| w.work.clone().into()).collect()),
+            None => Err(ThothError::EntityNotFound),
+        }
+    }
+
+    /// Get a page of the list of `Work`s published under an imprint, in the order they were last
+    /// updated, then by ID, as per `get_updated_works_page`
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the `imprint_id` was not found
+    /// or if there was an error while sending the request
+    pub async fn get_updated_imprint_works_page(
+        &self,
+        imprint_id: Uuid,
+        updated_since: Option<Timestamp>,
+        after_work_id: Option<Uuid>,
+        limit: i64,
+    ) -> ThothResult<Vec<Work>> {
+        let request_body =
+            UpdatedImprintWorksQuery::build_query(updated_imprint_works_query::Variables {
+                imprint_id,
+                updated_since,
+                after_work_id,
+                limit,
+            });
+        let res = self.post_request(&request_body).await.await?;
+        let response_body: Response<updated_imprint_works_query::ResponseData> = res.json().await?;
+        match response_body.data {
+            Some(data) => Ok(data
+                .imprint
+                .works
+                .iter()
+                .map(|w| w.work.clone().into())
+                .collect()),
+            None => Err(ThothError::EntityNotFound),
+        }
+    }
+
+    /// Get the list of `Work`s deleted from Thoth, optionally restricted to those deleted since a
+    /// given time
+    ///
+    /// # Errors
+    ///
+    /// This method fails if there was an error while sending the request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use thoth_errors::ThothResult;
+    /// # use thoth_client::{DeletedWork, ThothClient};
+    /// # use uuid::Uuid;
+    ///
+    /// # async fn run() -> ThothResult<Vec<DeletedWork>> {
+    /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
+    /// let publisher_id = Uuid::parse_str("00000000-0000-0000-AAAA-000000000001")?;
+    /// let deleted_works = thoth_client
+    ///     .get_deleted_works(Some(vec![publisher_id]), None)
+    ///     .await?;
+    /// # Ok(deleted_works)
+    /// # }
+    /// ```
+    pub async fn get_deleted_works(
+        &self,
+        publishers: Option<Vec<Uuid>>,
+        deleted_since: Option<Timestamp>,
+    ) -> ThothResult<Vec<DeletedWork>> {
+        let request_body = DeletedWorksQuery::build_query(deleted_works_query::Variables {
+            publishers,
+            work_ids: None,
+            deleted_since,
+        });
+        let res = self.post_request(&request_body).await.await?;
+        let response_body: Response<deleted_works_query::ResponseData> = res.json().await?;
+        match response_body.data {
+            Some(data) => Ok(data.deleted_works),
+            None => Err(ThothError::EntityNotFound),
+        }
+    }
+
+    /// Get the record of a `Work` deleted from Thoth given its `work_id`
+    ///
+    /// # Errors
+    ///
+    /// This method fails if no work with the `work_id` was deleted, or if there was an error
+    /// while sending the request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use thoth_errors::ThothResult;
+    /// # use thoth_client::{DeletedWork, ThothClient};
+    /// # use uuid::Uuid;
+    ///
+    /// # async fn run() -> ThothResult<DeletedWork> {
+    /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
+    /// let work_id = Uuid::parse_str("00000000-0000-0000-AAAA-000000000001")?;
+    /// let deleted_work = thoth_client.get_deleted_work(work_id).await?;
+    /// # Ok(deleted_work)
+    /// # }
+    /// ```
+    pub async fn get_deleted_work(&self, work_id: Uuid) -> ThothResult<DeletedWork> {
+        let request_body = DeletedWorksQuery::build_query(deleted_works_query::Variables {
+            publishers: None,
+            work_ids: Some(vec![work_id]),
+            deleted_since: None,
+        });
+        let res = self.post_request(&request_body).await.await?;
+        let response_body: Response<deleted_works_query::ResponseData> = res.json().await?;
+        response_body
+            .data
+            .and_then(|data| data.deleted_works.into_iter().last())
+            .ok_or(ThothError::EntityNotFound)
+    }
+
+    /// Get the list of `DeletedPublication`s: publications deleted from works that still exist,
+    /// optionally restricted to those of the given publishers, or deleted since a given time
+    ///
+    /// # Errors
+    ///
+    /// This method fails if there was an error while sending the request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use thoth_errors::ThothResult;
+    /// # use thoth_client::{DeletedPublication, ThothClient};
+    /// # use uuid::Uuid;
+    ///
+    /// # async fn run() -> ThothResult<Vec<DeletedPublication>> {
+    /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
+    /// let publisher_id = Uuid::parse_str("00000000-0000-0000-AAAA-000000000001")?;
+    /// let deleted_publications = thoth_client
+    ///     .get_deleted_publications(Some(vec![publisher_id]), None)
+    ///     .await?;
+    /// # Ok(deleted_publications)
+    /// # }
+    /// ```
+    pub async fn get_deleted_publications(
+        &self,
+        publishers: Option<Vec<Uuid>>,
+        deleted_since: Option<Timestamp>,
+    ) -> ThothResult<Vec<DeletedPublication>> {
+        let request_body =
+            DeletedPublicationsQuery::build_query(deleted_publications_query::Variables {
+                publishers,
+                deleted_since,
+            });
+        let res = self.post_request(&request_body).await.await?;
+        let response_body: Response<deleted_publications_query::ResponseData> = res.json().await?;
+        match response_body.data {
+            Some(data) => Ok(data.deleted_publications),
+            None => Err(ThothError::EntityNotFound),
+        }
+    }
+
+    /// Get the list of `Work`s published under an imprint
+    ///
+    /// Works are requested in pages of `WORKS_PAGE_SIZE`, to keep each response small.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the `imprint_id` was not found
+    /// or if there was an error while sending the request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use thoth_errors::ThothResult;
+    /// # use thoth_client::{ThothClient, Work};
+    /// # use uuid::Uuid;
+    ///
+    /// # async fn run() -> ThothResult<Vec<Work>> {
+    /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
+    /// let imprint_id = Uuid::parse_str("00000000-0000-0000-AAAA-000000000001")?;
+    /// let works = thoth_client.get_imprint_works(imprint_id).await?;
+    /// # Ok(works)
+    /// # }
+    /// ```
+    pub async fn get_imprint_works(&self, imprint_id: Uuid) -> ThothResult<Vec<Work>> {
+        all_pages(|offset| self.get_imprint_works_page(imprint_id, WORKS_PAGE_SIZE, offset)).await
+    }
+
+    /// Get a page of the list of `Work`s published under an imprint, ordered by title.
+    /// A page shorter than `limit` is the last one.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the `imprint_id` was not found
+    /// or if there was an error while sending the request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use thoth_errors::ThothResult;
+    /// # use thoth_client::{ThothClient, Work, WORKS_PAGE_SIZE};
+    /// # use uuid::Uuid;
+    ///
+    /// # async fn run() -> ThothResult<Vec<Work>> {
+    /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
+    /// let imprint_id = Uuid::parse_str("00000000-0000-0000-AAAA-000000000001")?;
+    /// let first_page = thoth_client
+    ///     .get_imprint_works_page(imprint_id, WORKS_PAGE_SIZE, 0)
+    ///     .await?;
+    /// # Ok(first_page)
+    /// # }
+    /// ```
+    pub async fn get_imprint_works_page(
+        &self,
+        imprint_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> ThothResult<Vec<Work>> {
+        let request_body = ImprintWorksQuery::build_query(imprint_works_query::Variables {
+            imprint_id,
+            limit,
+            offset,
+        });
+        let res = self.post_request(&request_body).await.await?;
+        let response_body: Response<imprint_works_query::ResponseData> = res.json().await?;
+        match response_body.data {
+            Some(data) => Ok(data
+                .imprint
+                .works
+                .iter()
+                .map(|w| w.work.clone().into())
+                .collect()),
+            None => Err(ThothError::EntityNotFound),
+        }
+    }
+
+    /// Get the list of `Work`s issued in a series, in issue order
+    ///
+    /// Works are requested in pages of `WORKS_PAGE_SIZE`, to keep each response small.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the `series_id` was not found
+    /// or if there was an error while sending the request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use thoth_errors::ThothResult;
+    /// # use thoth_client::{ThothClient, Work};
+    /// # use uuid::Uuid;
+    ///
+    /// # async fn run() -> ThothResult<Vec<Work>> {
+    /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
+    /// let series_id = Uuid::parse_str("00000000-0000-0000-AAAA-000000000001")?;
+    /// let works = thoth_client.get_series_works(series_id).await?;
+    /// # Ok(works)
+    /// # }
+    /// ```
+    pub async fn get_series_works(&self, series_id: Uuid) -> ThothResult<Vec<Work>> {
+        all_pages(|offset| self.get_series_works_page(series_id, WORKS_PAGE_SIZE, offset)).await
+    }
+
+    /// Get a page of the list of `Work`s issued in a series, in issue order.
+    /// A page shorter than `limit` is the last one.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the `series_id` was not found
+    /// or if there was an error while sending the request
+    async fn get_series_works_page(
+        &self,
+        series_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> ThothResult<Vec<Work>> {
+        let request_body = SeriesWorksQuery::build_query(series_works_query::Variables {
+            series_id,
+            limit,
+            offset,
+        });
+        let res = self.post_request(&request_body).await.await?;
+        let response_body: Response<series_works_query::ResponseData> = res.json().await?;
+        match response_body.data {
+            Some(data) => Ok(data
+                .series
+                .issues
+                .iter()
+                .map(|i| i.work.work.clone().into())
+                .collect()),
+            None => Err(ThothError::EntityNotFound),
+        }
+    }
+
+    /// Get a list of `Work`s from Thoth given their `work_id`s, in the order requested
+    ///
+    /// # Errors
+    ///
+    /// This method fails if more than `MAX_WORK_IDS` works are requested,
+    /// if any `work_id` was not found or if there was an error while sending the request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use thoth_errors::ThothResult;
+    /// # use thoth_client::{ThothClient, Work};
+    /// # use uuid::Uuid;
+    ///
+    /// # async fn run() -> ThothResult<Vec<Work>> {
+    /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
+    /// let work_ids = vec![
+    ///     Uuid::parse_str("00000000-0000-0000-AAAA-000000000001")?,
+    ///     Uuid::parse_str("00000000-0000-0000-AAAA-000000000002")?,
+    /// ];
+    /// let works = thoth_client.get_works_by_id(work_ids).await?;
+    /// # Ok(works)
+    /// # }
+    /// ```
+    pub async fn get_works_by_id(&self, work_ids: Vec<Uuid>) -> ThothResult<Vec<Work>> {
+        if work_ids.len() > MAX_WORK_IDS {
+            return Err(ThothError::TooManyWorks(MAX_WORK_IDS));
+        }
+        if work_ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let request_body = WorksByIdQuery::build_query(works_by_id_query::Variables {
+            work_ids: Some(work_ids.clone()),
+            limit: work_ids.len() as i64,
+        });
+        let res = self.post_request(&request_body).await.await?;
+        let response_body: Response<works_by_id_query::ResponseData> = res.json().await?;
+        let works: Vec<Work> = match response_body.data {
+            Some(data) => data.works.into_iter().map(|w| w.work.into()).collect(),
+            None => return Err(ThothError::EntityNotFound),
+        };
+        // The API returns works in its own order, and leaves out any it can't find
+        work_ids
+            .iter()
+            .map(|work_id| {
+                works
+                    .iter()
+                    .find(|w| w.work_id.eq(work_id))
+                    .cloned()
+                    .ok_or(ThothError::EntityNotFound)
+            })
+            .collect()
+    }
+
+    /// Get the list of all `Publisher`s in Thoth, along with their imprints
+    ///
+    /// # Errors
+    ///
+    /// This method fails if there was an error while sending the request
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use thoth_errors::ThothResult;
+    /// # use thoth_client::{Publisher, ThothClient};
+    ///
+    /// # async fn run() -> ThothResult<Vec<Publisher>> {
+    /// let thoth_client = ThothClient::new("https://api.thoth.pub/graphql".to_string());
+    /// let publishers = thoth_client.get_publishers().await?;
+    /// # Ok(publishers)
+    /// # }
+    /// ```
+    pub async fn get_publishers(&self) -> ThothResult<Vec<Publisher>> {
+        let request_body = PublishersQuery::build_query(publishers_query::Variables);
+        let res = self.post_request(&request_body).await.await?;
+        let response_body: Response<publishers_query::ResponseData> = res.json().await?;
+        match response_body.data {
+            Some(data) => Ok(data.publishers),
+            None => Err(ThothError::EntityNotFound),
+        }
+    }
 }
@@ -6,6 +6,7 @@ use thoth_api::model::Doi;
 use thoth_api::model::Isbn;
 use thoth_api::model::Orcid;
 use thoth_api::model::Ror;
+use thoth_api::model::Timestamp;
 use uuid::Uuid;
 
 #[derive(GraphQLQuery)]
@@ -30,7 +31,79 @@ impl fmt::Display for work_query::LanguageCode {
 )]
 pub struct WorksQuery;
 
-// Needed to set work_query::Work as the canonical struct for the shared fragment in the two queries
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "assets/schema.json",
+    query_path = "assets/queries.graphql",
+    response_derives = "Debug,Clone,Deserialize,Serialize"
+)]
+pub struct WorksByIdQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "assets/schema.json",
+    query_path = "assets/queries.graphql",
+    response_derives = "Debug,Clone,Deserialize,Serialize"
+)]
+pub struct ImprintWorksQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "assets/schema.json",
+    query_path = "assets/queries.graphql",
+    response_derives = "Debug,Clone,Deserialize,Serialize"
+)]
+pub struct SeriesWorksQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "assets/schema.json",
+    query_path = "assets/queries.graphql",
+    response_derives = "Debug,Clone,Deserialize,Serialize"
+)]
+pub struct DeletedWorksQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "assets/schema.json",
+    query_path = "assets/queries.graphql",
+    response_derives = "Debug,Clone,Deserialize,Serialize"
+)]
+pub struct WorksSummaryQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "assets/schema.json",
+    query_path = "assets/queries.graphql",
+    response_derives = "Debug,Clone,Deserialize,Serialize"
+)]
+pub struct UpdatedWorksQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "assets/schema.json",
+    query_path = "assets/queries.graphql",
+    response_derives = "Debug,Clone,Deserialize,Serialize"
+)]
+pub struct UpdatedImprintWorksQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "assets/schema.json",
+    query_path = "assets/queries.graphql",
+    response_derives = "Debug,Clone,Deserialize,Serialize"
+)]
+pub struct DeletedPublicationsQuery;
+
+#[derive(GraphQLQuery)]
+#[graphql(
+    schema_path = "assets/schema.json",
+    query_path = "assets/queries.graphql",
+    response_derives = "Debug,Clone,Deserialize,Serialize"
+)]
+pub struct PublishersQuery;
+
+// Needed to set work_query::Work as the canonical struct for the shared fragment in the queries
 // until https://github.com/graphql-rust/graphql-client/issues/312 gets fixed
 impl From<works_query::Work> for work_query::Work {
     fn from(w: works_query::Work) -> Self {
@@ -38,3 +111,47 @@ impl From<works_query::Work> for work_query::Work {
         serde_json::from_str(&se).unwrap()
     }
 }
+
+impl From<works_by_id_query::Work> for work_query::Work {
+    fn from(w: works_by_id_query::Work) -> Self {
+        let se = serde_json::to_string(&w).unwrap();
+        serde_json::from_str(&se).unwrap()
+    }
+}
+
+impl From<imprint_works_query::Work> for work_query::Work {
+    fn from(w: imprint_works_query::Work) -> Self {
+        let se = serde_json::to_string(&w).unwrap();
+        serde_json::from_str(&se).unwrap()
+    }
+}
+
+impl From<updated_works_query::Work> for work_query::Work {
+    fn from(w: updated_works_query::Work) -> Self {
+        let se = serde_json::to_string(&w).unwrap();
+        serde_json::from_str(&se).unwrap()
+    }
+}
+
+impl From<updated_imprint_works_query::Work> for work_query::Work {
+    fn from(w: updated_imprint_works_query::Work) -> Self {
+        let se = serde_json::to_string(&w).unwrap();
+        serde_json::from_str(&se).unwrap()
+    }
+}
+
+impl From<series_works_query::Work> for work_query::Work {
+    fn from(w: series_works_query::Work) -> Self {
+        let se = serde_json::to_string(&w).unwrap();
+        serde_json::from_str(&se).unwrap()
+    }
+}
+
+// Chapters share the contribution fields of the main fragment, so that they can be written
+// out by the same code that handles the contributions of the parent work
+impl From<work_query::WorkRelationsRelatedWorkContributions> for work_query::WorkContributions {
+    fn from(c: work_query::WorkRelationsRelatedWorkContributions) -> Self {
+        let se = serde_json::to_string(&c).unwrap();
+        serde_json::from_str(&se).unwrap()
+    }
+}
@@ -52,6 +52,14 @@ pub enum ThothError {
         _0
     )]
     RorParseError(String),
+    #[fail(display = "{} is not a valid RFC 3339 timestamp", _0)]
+    TimestampParseError(String),
+    #[fail(display = "Metadata cannot be represented as {}", _0)]
+    NotAcceptable(String),
+    #[fail(display = "Could not validate {}: {}", _0, _1)]
+    SchemaValidationError(String, String),
+    #[fail(display = "At most {} works can be requested at once", _0)]
+    TooManyWorks(usize),
     #[fail(display = "Cannot parse ORCID: no value provided")]
     OrcidEmptyError,
     #[fail(display = "Cannot parse DOI: no value provided")]
@@ -106,9 +114,14 @@ impl actix_web::error::ResponseError for ThothError {
                 HttpResponse::Unauthorized().json(self.to_string())
             }
             ThothError::EntityNotFound => HttpResponse::NotFound().json(self.to_string()),
-            ThothError::InvalidMetadataSpecification(_) | ThothError::InvalidUuid => {
-                HttpResponse::BadRequest().json(self.to_string())
+            ThothError::NotAcceptable(_) => HttpResponse::NotAcceptable().json(self.to_string()),
+            ThothError::SchemaValidationError(..) => {
+                HttpResponse::UnprocessableEntity().json(self.to_string())
             }
+            ThothError::InvalidMetadataSpecification(_)
+            | ThothError::InvalidUuid
+            | ThothError::TimestampParseError(_)
+            | ThothError::TooManyWorks(_) => HttpResponse::BadRequest().json(self.to_string()),
             ThothError::DatabaseError { .. } => {
                 HttpResponse::InternalServerError().json("DB error")
             }
@@ -164,6 +177,12 @@ impl From<xml::writer::Error> for ThothError {
     }
 }
 
+impl From<serde_json::Error> for ThothError {
+    fn from(error: serde_json::Error) -> ThothError {
+        ThothError::InternalError(error.to_string())
+    }
+}
+
 impl From<failure::Error> for ThothError {
     fn from(error: failure::Error) -> ThothError {
         if error.downcast_ref::<ThothError>().is_some() {
@@ -173,6 +192,13 @@ impl From<failure::Error> for ThothError {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+impl From<zip::result::ZipError> for ThothError {
+    fn from(error: zip::result::ZipError) -> ThothError {
+        ThothError::InternalError(error.to_string())
+    }
+}
+
 impl From<uuid::parser::ParseError> for ThothError {
     fn from(_: uuid::parser::ParseError) -> ThothError {
         ThothError::InvalidUuid
@@ -196,6 +222,14 @@ mod tests {
         assert!(matches!(ThothError::from(err), ThothError::CsvError { .. }));
     }
 
+    #[test]
+    fn test_zip_error() {
+        assert!(matches!(
+            ThothError::from(zip::result::ZipError::FileNotFound),
+            ThothError::InternalError { .. }
+        ));
+    }
+
     #[test]
     fn test_uuid_error() {
         assert_eq!(
@@ -203,4 +237,13 @@ mod tests {
             ThothError::InvalidUuid
         );
     }
+
+    #[test]
+    fn test_serde_json_error() {
+        let err = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert!(matches!(
+            ThothError::from(err),
+            ThothError::InternalError { .. }
+        ));
+    }
 }
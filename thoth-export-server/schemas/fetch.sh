@@ -0,0 +1,79 @@
+#!/usr/bin/env bash
+# Vendor the reference schemas of the export server's XML specifications into this directory.
+#
+# Each schema is downloaded along with every schema it imports or includes, and locations that
+# point to remote schemas are rewritten to local paths, so that validation can run offline
+# (`xmllint --nonet`).
+#
+# EDItEUR only distributes the ONIX schemas within ZIP archives, listed on the pages in README.md,
+# whose paths or URLs must be passed to this script, e.g.
+#   ONIX_30_ZIP=~/Downloads/ONIX_BookProduct_XSD_schema+codes_Issue_65.zip \
+#   ONIX_21_ZIP=~/Downloads/ONIX_BookProduct_Release2.1_XSD_schema.zip \
+#   ./fetch.sh
+set -euo pipefail
+cd "$(dirname "$0")"
+
+visited=$(mktemp)
+archive=$(mktemp)
+trap 'rm -f "$visited" "$archive"' EXIT
+
+# Download the schema at `$1` to `$2`, then each schema it refers to, next to it
+fetch() {
+    local url=$1 dest
+    dest=$(realpath -m --relative-to=. "$2")
+    if grep -qxF "$dest" "$visited"; then
+        return
+    fi
+    echo "$dest" >>"$visited"
+    echo "Fetching $url"
+    mkdir -p "$(dirname "$dest")"
+    curl -fsSL "$url" -o "$dest"
+    local location local_path dependency
+    for location in $(grep -o '[[:space:]]schemaLocation="[^"]*"' "$dest" | sed 's/.*="\(.*\)"/\1/' | sort -u); do
+        case $location in
+            http://* | https://*)
+                local_path=$(basename "$location")
+                dependency=$location
+                sed -i "s|schemaLocation=\"$location\"|schemaLocation=\"$local_path\"|" "$dest"
+                ;;
+            *)
+                local_path=$location
+                dependency="${url%/*}/$location"
+                ;;
+        esac
+        fetch "$dependency" "$(dirname "$dest")/$local_path"
+    done
+}
+
+# Extract the XSD files of an EDItEUR archive, given by path or URL, into `$2`
+unpack_onix() {
+    local source=$1 dest=$2
+    case $source in
+        http://* | https://*)
+            echo "Fetching $source"
+            curl -fsSL "$source" -o "$archive"
+            ;;
+        *)
+            cp "$source" "$archive"
+            ;;
+    esac
+    mkdir -p "$dest"
+    unzip -o -j "$archive" '*.xsd' -d "$dest"
+}
+
+fetch https://www.loc.gov/standards/marcxml/schema/MARC21slim.xsd marc21xml/MARC21slim.xsd
+fetch https://www.crossref.org/schemas/crossref5.3.1.xsd crossref/crossref5.3.1.xsd
+fetch https://schema.datacite.org/meta/kernel-4.4/metadata.xsd datacite/kernel-4.4/metadata.xsd
+fetch http://www.openarchives.org/OAI/2.0/oai_dc.xsd oai_dc/oai_dc.xsd
+fetch https://www.loc.gov/standards/mods/v3/mods-3-7.xsd mods/mods-3-7.xsd
+
+if [ -n "${ONIX_30_ZIP:-}" ]; then
+    unpack_onix "$ONIX_30_ZIP" onix_3.0
+else
+    echo "ONIX_30_ZIP is not set: skipping the ONIX 3.0 schema" >&2
+fi
+if [ -n "${ONIX_21_ZIP:-}" ]; then
+    unpack_onix "$ONIX_21_ZIP" onix_2.1
+else
+    echo "ONIX_21_ZIP is not set: skipping the ONIX 2.1 schema" >&2
+fi
@@ -0,0 +1,177 @@
+use chrono::SecondsFormat;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::record::GeneratedRecord;
+use crate::specification::model::SpecificationQuery;
+
+/// In-process store of generated metadata records, keyed by specification and entity, so that
+/// repeated requests within the time to live neither query Thoth nor regenerate the output.
+/// The cache is bounded by the total size of the records it holds.
+pub(crate) struct RecordCache {
+    ttl: Duration,
+    max_bytes: usize,
+    entries: Mutex<HashMap<String, (Instant, GeneratedRecord)>>,
+}
+
+impl RecordCache {
+    pub(crate) fn new(ttl: Duration, max_bytes: usize) -> Self {
+        RecordCache {
+            ttl,
+            max_bytes,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Identify a record by its specification, the entity it describes (e.g. `publisher/{id}`)
+    /// and the query parameters that alter its contents. Equivalent `since` timestamps, e.g. in
+    /// different time zones, share the same key.
+    pub(crate) fn key(specification_id: &str, entity: &str, query: &SpecificationQuery) -> String {
+        let since = match query.updated_since() {
+            Ok(Some(since)) => since
+                .to_datetime()
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            // Invalid timestamps are rejected before any record is cached
+            _ => query.since.clone().unwrap_or_default(),
+        };
+        format!(
+            "{}/{}?platform={}&since={}",
+            specification_id,
+            entity,
+            query.platform.as_deref().unwrap_or_default(),
+            since
+        )
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<GeneratedRecord> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(key)
+            .filter(|(cached_at, _)| cached_at.elapsed() < self.ttl)
+            .map(|(_, record)| record.clone())
+    }
+
+    /// Store a record, dropping expired entries and, while there is not enough room left for it,
+    /// the oldest ones. Records larger than the whole cache are not stored.
+    pub(crate) fn insert(&self, key: String, record: GeneratedRecord) {
+        if record.body.len() > self.max_bytes {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        let ttl = self.ttl;
+        entries.retain(|_, (cached_at, _)| cached_at.elapsed() < ttl);
+        entries.remove(&key);
+        let mut total_bytes: usize = entries.values().map(|(_, record)| record.body.len()).sum();
+        while total_bytes + record.body.len() > self.max_bytes {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, (cached_at, _))| *cached_at)
+                .map(|(key, _)| key.clone());
+            match oldest.and_then(|oldest| entries.remove(&oldest)) {
+                Some((_, removed)) => total_bytes -= removed.body.len(),
+                None => break,
+            }
+        }
+        entries.insert(key, (Instant::now(), record));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_record(id: &str) -> GeneratedRecord {
+        GeneratedRecord {
+            body: id.as_bytes().to_vec().into(),
+            content_type: "text/plain; charset=utf-8",
+            content_disposition: format!("attachment; filename=\"{}.txt\"", id),
+            etag: format!("\"{}\"", id),
+            last_modified: None,
+        }
+    }
+
+    fn test_query(platform: Option<&str>) -> SpecificationQuery {
+        SpecificationQuery {
+            platform: platform.map(ToString::to_string),
+            since: None,
+            validate: None,
+        }
+    }
+
+    fn test_since_query(since: &str) -> SpecificationQuery {
+        SpecificationQuery {
+            since: Some(since.to_string()),
+            ..test_query(None)
+        }
+    }
+
+    #[test]
+    fn test_record_cache_key() {
+        assert_eq!(
+            RecordCache::key("kbart::niso", "publisher/some_id", &test_query(None)),
+            "kbart::niso/publisher/some_id?platform=&since="
+        );
+        assert_ne!(
+            RecordCache::key("kbart::niso", "publisher/some_id", &test_query(None)),
+            RecordCache::key(
+                "kbart::niso",
+                "publisher/some_id",
+                &test_query(Some("jisc_kb"))
+            )
+        );
+        assert_eq!(
+            RecordCache::key(
+                "kbart::niso",
+                "publisher/some_id",
+                &test_since_query("2021-01-01T01:00:00+01:00")
+            ),
+            "kbart::niso/publisher/some_id?platform=&since=2021-01-01T00:00:00Z"
+        );
+        assert_eq!(
+            RecordCache::key(
+                "kbart::niso",
+                "publisher/some_id",
+                &test_since_query("2021-01-01T00:00:00.000Z")
+            ),
+            RecordCache::key(
+                "kbart::niso",
+                "publisher/some_id",
+                &test_since_query("2021-01-01T00:00:00Z")
+            )
+        );
+    }
+
+    #[test]
+    fn test_record_cache_get() {
+        let cache = RecordCache::new(Duration::from_secs(60), 10);
+        assert!(cache.get("some_key").is_none());
+        cache.insert("some_key".to_string(), test_record("some_id"));
+        assert!(cache.get("some_key").is_some());
+        assert!(cache.get("other_key").is_none());
+    }
+
+    #[test]
+    fn test_record_cache_ttl() {
+        let cache = RecordCache::new(Duration::from_secs(0), 10);
+        cache.insert("some_key".to_string(), test_record("some_id"));
+        assert!(cache.get("some_key").is_none());
+    }
+
+    #[test]
+    fn test_record_cache_max_bytes() {
+        // Room for two of the five-byte test records
+        let cache = RecordCache::new(Duration::from_secs(60), 12);
+        cache.insert("first".to_string(), test_record("first"));
+        cache.insert("second".to_string(), test_record("secnd"));
+        cache.insert("third".to_string(), test_record("third"));
+        // The oldest entry makes way for the newest
+        assert!(cache.get("first").is_none());
+        assert!(cache.get("second").is_some());
+        assert!(cache.get("third").is_some());
+        // Records larger than the cache are never stored
+        cache.insert("large".to_string(), test_record("larger_than_the_cache"));
+        assert!(cache.get("large").is_none());
+        assert!(cache.get("third").is_some());
+    }
+}
@@ -0,0 +1,443 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::Write;
+use thoth_client::{ContributionType, SubjectType, Work, WorkType};
+use thoth_errors::{ThothError, ThothResult};
+use unicode_normalization::UnicodeNormalization;
+
+use super::{contributor_names, CitationSpecification};
+use crate::record::RequiredFields;
+
+pub(crate) struct BibtexThoth;
+
+#[derive(Debug, PartialEq)]
+struct BibtexEntry {
+    entry_type: &'static str,
+    key: String,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl CitationSpecification for BibtexThoth {
+    fn handle_event<W: Write>(w: &mut W, works: &[Work]) -> ThothResult<()> {
+        match works.len() {
+            0 => Err(ThothError::IncompleteMetadataRecord(
+                "bibtex::thoth".to_string(),
+                "Not enough data".to_string(),
+            )),
+            1 => {
+                let entry = BibtexEntry::try_from(works.first().unwrap())?;
+                write!(w, "{}", entry).map_err(|e| e.into())
+            }
+            _ => {
+                let mut entries: Vec<(&Work, BibtexEntry)> = works
+                    .iter()
+                    .filter_map(|work| BibtexEntry::try_from(work).ok().map(|e| (work, e)))
+                    .collect();
+                // Keys must be unique within a file. Those which clash are all disambiguated
+                // using their work ID, rather than keeping the plain key for whichever comes
+                // first. A work's key may therefore differ from that in its own export.
+                let mut key_count: HashMap<String, usize> = HashMap::new();
+                for (_, entry) in &entries {
+                    *key_count.entry(entry.key.clone()).or_default() += 1;
+                }
+                for (work, entry) in entries.iter_mut() {
+                    if key_count[&entry.key] > 1 {
+                        entry.key = format!("{}_{}", entry.key, &work.work_id.to_string()[..8]);
+                    }
+                }
+                for (_, entry) in entries {
+                    write!(w, "{}", entry)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl RequiredFields for BibtexThoth {
+    const SPECIFICATION_ID: &'static str = "bibtex::thoth";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        // year is mandatory in BibTeX but optional in Thoth
+        if work.publication_date.is_none() {
+            missing.push("Missing Publication Date");
+        }
+        missing
+    }
+}
+
+impl TryFrom<&Work> for BibtexEntry {
+    type Error = ThothError;
+
+    fn try_from(work: &Work) -> ThothResult<Self> {
+        BibtexThoth::check_required_fields(work)?;
+        // year is mandatory in BibTeX but optional in Thoth
+        let publication_date = work.publication_date.ok_or_else(|| {
+            ThothError::IncompleteMetadataRecord(
+                "bibtex::thoth".to_string(),
+                "Missing Publication Date".to_string(),
+            )
+        })?;
+        let year = publication_date.format("%Y").to_string();
+        let authors = contributor_names(work, ContributionType::AUTHOR);
+        let editors = contributor_names(work, ContributionType::EDITOR);
+
+        let mut fields = vec![("title", escape(&work.full_title))];
+        if !authors.is_empty() {
+            fields.push(("author", escape(&authors.join(" and "))));
+        }
+        if !editors.is_empty() {
+            fields.push(("editor", escape(&editors.join(" and "))));
+        }
+        fields.push(("year", year.clone()));
+        fields.push((
+            "month",
+            publication_date.format("%b").to_string().to_lowercase(),
+        ));
+        fields.push(("publisher", escape(&work.imprint.publisher.publisher_name)));
+        if let Some(place) = &work.place {
+            fields.push(("address", escape(place)));
+        }
+        if work.edition > 1 {
+            fields.push(("edition", work.edition.to_string()));
+        }
+        // Only one series can be listed in BibTeX, so we select the first one found (if any)
+        if let Some(issue) = work.issues.first() {
+            fields.push(("series", escape(&issue.series.series_name)));
+            fields.push(("number", issue.issue_ordinal.to_string()));
+        }
+        // DOIs and URLs are typeset verbatim, so they are not escaped
+        if let Some(doi) = &work.doi {
+            fields.push(("doi", doi.to_string()));
+        }
+        let isbns: Vec<String> = work
+            .publications
+            .iter()
+            .filter_map(|p| p.isbn.as_ref().map(|isbn| isbn.to_string()))
+            .collect();
+        if !isbns.is_empty() {
+            fields.push(("isbn", isbns.join(", ")));
+        }
+        if let Some(landing_page) = &work.landing_page {
+            fields.push(("url", landing_page.clone()));
+        }
+        if let Some(labstract) = work.long_abstract.as_ref().or(work.short_abstract.as_ref()) {
+            fields.push(("abstract", escape(labstract)));
+        }
+        let keywords: Vec<&str> = work
+            .subjects
+            .iter()
+            .filter(|s| s.subject_type == SubjectType::KEYWORD)
+            .map(|s| s.subject_code.as_str())
+            .collect();
+        if !keywords.is_empty() {
+            fields.push(("keywords", escape(&keywords.join(", "))));
+        }
+
+        // Authors take precedence over editors in the key, as they do in citations
+        let key_name = work
+            .contributions
+            .iter()
+            .filter(|c| {
+                c.contribution_type == ContributionType::AUTHOR
+                    || c.contribution_type == ContributionType::EDITOR
+            })
+            .min_by_key(|c| {
+                (
+                    c.contribution_type != ContributionType::AUTHOR,
+                    c.contribution_ordinal,
+                )
+            })
+            .map(|c| key_part(&c.last_name))
+            .unwrap_or_else(|| "anon".to_string());
+        let key_title = work
+            .title
+            .split_whitespace()
+            .map(key_part)
+            .find(|word| !word.is_empty() && !["a", "an", "the"].contains(&word.as_str()))
+            .unwrap_or_default();
+
+        Ok(BibtexEntry {
+            entry_type: match work.work_type {
+                WorkType::BOOK_CHAPTER => "incollection",
+                _ => "book",
+            },
+            key: format!("{}{}{}", key_name, year, key_title),
+            fields,
+        })
+    }
+}
+
+impl fmt::Display for BibtexEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "@{}{{{},", self.entry_type, self.key)?;
+        for (name, value) in &self.fields {
+            writeln!(f, "  {} = {{{}}},", name, value)?;
+        }
+        writeln!(f, "}}")?;
+        writeln!(f)
+    }
+}
+
+/// Reduce a string to lowercase ASCII letters and digits, for use in citation keys
+fn key_part(value: &str) -> String {
+    value
+        .nfd()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// Escape characters with a special meaning in (La)TeX
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '{' | '}' | '&' | '%' | '$' | '#' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            // Line breaks would be read as paragraph breaks
+            '\n' | '\r' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use thoth_api::model::{Doi, Isbn};
+    use thoth_client::{
+        PublicationType, SeriesType, WorkContributions, WorkContributionsContributor, WorkImprint,
+        WorkImprintPublisher, WorkIssues, WorkIssuesSeries, WorkPublications, WorkStatus,
+        WorkSubjects,
+    };
+    use uuid::Uuid;
+
+    fn test_contribution(
+        contribution_type: ContributionType,
+        last_name: &str,
+        contribution_ordinal: i64,
+    ) -> WorkContributions {
+        WorkContributions {
+            contribution_type,
+            first_name: Some("Joan".to_string()),
+            last_name: last_name.to_string(),
+            full_name: format!("Joan {}", last_name),
+            main_contribution: true,
+            biography: None,
+            contribution_ordinal,
+            contributor: WorkContributionsContributor { orcid: None },
+            affiliations: vec![],
+        }
+    }
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "The Book Title: Book Subtitle".to_string(),
+            title: "The Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 2,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: None,
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: Some("Lorem ipsum".to_string()),
+            long_abstract: Some("Lorem ipsum dolor sit amet\nconsectetur 100% & more".to_string()),
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: None,
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![WorkIssues {
+                issue_ordinal: 3,
+                series: WorkIssuesSeries {
+                    series_type: SeriesType::BOOK_SERIES,
+                    series_name: "Name of series".to_string(),
+                    issn_print: "1234-5678".to_string(),
+                    issn_digital: "8765-4321".to_string(),
+                    series_url: None,
+                },
+            }],
+            contributions: vec![
+                test_contribution(ContributionType::EDITOR, "Editor", 3),
+                test_contribution(ContributionType::AUTHOR, "Ñúñez", 2),
+                test_contribution(ContributionType::AUTHOR, "Álvarez", 1),
+            ],
+            languages: vec![],
+            publications: vec![
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000002").unwrap(),
+                    publication_type: PublicationType::PAPERBACK,
+                    isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                    prices: vec![],
+                    locations: vec![],
+                },
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000003").unwrap(),
+                    publication_type: PublicationType::PDF,
+                    isbn: Some(Isbn::from_str("978-1-56619-909-4").unwrap()),
+                    prices: vec![],
+                    locations: vec![],
+                },
+            ],
+            subjects: vec![
+                WorkSubjects {
+                    subject_code: "AAA".to_string(),
+                    subject_type: SubjectType::BIC,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "keyword1".to_string(),
+                    subject_type: SubjectType::KEYWORD,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "key_word2".to_string(),
+                    subject_type: SubjectType::KEYWORD,
+                    subject_ordinal: 2,
+                },
+            ],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    const TEST_RESULT: &str = r#"@book{alvarez1999book,
+  title = {The Book Title: Book Subtitle},
+  author = {Álvarez, Joan and Ñúñez, Joan},
+  editor = {Editor, Joan},
+  year = {1999},
+  month = {dec},
+  publisher = {OA Editions},
+  address = {León, Spain},
+  edition = {2},
+  series = {Name of series},
+  number = {3},
+  doi = {10.00001/BOOK.0001},
+  isbn = {978-3-16-148410-0, 978-1-56619-909-4},
+  url = {https://www.book.com},
+  abstract = {Lorem ipsum dolor sit amet consectetur 100\% \& more},
+  keywords = {keyword1, key\_word2},
+}
+
+"#;
+
+    #[test]
+    fn test_bibtex_thoth() {
+        assert_eq!(
+            BibtexThoth {}.generate(&[test_work()]).unwrap(),
+            TEST_RESULT.to_string()
+        );
+    }
+
+    #[test]
+    fn test_bibtex_thoth_minimal() {
+        let mut work = test_work();
+        work.work_type = WorkType::BOOK_CHAPTER;
+        work.edition = 1;
+        work.doi = None;
+        work.short_abstract = None;
+        work.long_abstract = None;
+        work.place = None;
+        work.landing_page = None;
+        work.issues.clear();
+        work.publications.clear();
+        work.subjects.clear();
+        work.contributions
+            .retain(|c| c.contribution_type == ContributionType::EDITOR);
+        assert_eq!(
+            BibtexThoth {}.generate(&[work.clone()]).unwrap(),
+            "@incollection{editor1999book,\n  title = {The Book Title: Book Subtitle},\n  editor = {Editor, Joan},\n  year = {1999},\n  month = {dec},\n  publisher = {OA Editions},\n}\n\n"
+        );
+        work.contributions.clear();
+        work.title = "A {Title}".to_string();
+        assert!(BibtexThoth {}
+            .generate(&[work])
+            .unwrap()
+            .starts_with("@incollection{anon1999title,\n"));
+    }
+
+    #[test]
+    fn test_bibtex_thoth_keys() {
+        let mut other_work = test_work();
+        other_work.work_id = Uuid::from_str("11111111-0000-0000-AAAA-000000000001").unwrap();
+        let mut unrelated_work = test_work();
+        unrelated_work.title = "Another Title".to_string();
+        let mut incomplete_work = test_work();
+        incomplete_work.publication_date = None;
+        let output = BibtexThoth {}
+            .generate(&[test_work(), other_work, unrelated_work, incomplete_work])
+            .unwrap();
+        assert_eq!(output.matches("\n}\n").count(), 3);
+        assert!(output.contains("@book{alvarez1999book_00000000,\n"));
+        assert!(output.contains("@book{alvarez1999book_11111111,\n"));
+        assert!(output.contains("@book{alvarez1999another,\n"));
+    }
+
+    #[test]
+    fn test_bibtex_thoth_errors() {
+        let mut work = test_work();
+        work.publication_date = None;
+        assert_eq!(
+            BibtexThoth {}.generate(&[work]).unwrap_err().to_string(),
+            "Could not generate bibtex::thoth: Missing Publication Date".to_string()
+        );
+        assert_eq!(
+            BibtexThoth {}.generate(&[]).unwrap_err().to_string(),
+            "Could not generate bibtex::thoth: Not enough data".to_string()
+        );
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("a_b & c"), "a\\_b \\& c");
+        assert_eq!(escape("{x}"), "\\{x\\}");
+        assert_eq!(
+            escape("~^\\"),
+            "\\textasciitilde{}\\textasciicircum{}\\textbackslash{}"
+        );
+        assert_eq!(key_part("Ñúñez-García"), "nunezgarcia");
+    }
+}
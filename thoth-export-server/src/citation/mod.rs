@@ -0,0 +1,74 @@
+use std::io::Write;
+use std::marker::PhantomData;
+use thoth_client::{ContributionType, DeletedWork, Work, WorkContributions};
+use thoth_errors::{ThothError, ThothResult};
+
+use crate::record::RecordWriter;
+
+pub(crate) trait CitationSpecification {
+    fn generate(&self, works: &[Work]) -> ThothResult<String> {
+        let mut buffer = Vec::new();
+        Self::handle_event(&mut buffer, works).and_then(|_| {
+            String::from_utf8(buffer)
+                .map_err(|_| ThothError::InternalError("Could not parse citation".to_string()))
+        })
+    }
+
+    fn handle_event<W: Write>(w: &mut W, works: &[Work]) -> ThothResult<()>;
+}
+
+/// Specifications whose documents are a sequence of independent entries, with no header or
+/// footer, and can therefore be written one page of works at a time
+pub(crate) trait CitationStreamSpecification: CitationSpecification {
+    fn write_entry<W: Write>(w: &mut W, work: &Work) -> ThothResult<()>;
+}
+
+/// Writes a document of a streamable citation specification one page of works at a time
+pub(crate) struct CitationStreamWriter<T: CitationStreamSpecification> {
+    specification: PhantomData<T>,
+}
+
+impl<T: CitationStreamSpecification> CitationStreamWriter<T> {
+    pub(crate) fn new() -> Self {
+        CitationStreamWriter {
+            specification: PhantomData,
+        }
+    }
+}
+
+impl<T: CitationStreamSpecification> RecordWriter for CitationStreamWriter<T> {
+    fn write_page(&mut self, works: &[Work]) -> ThothResult<Vec<u8>> {
+        let mut output = Vec::new();
+        for work in works.iter() {
+            // As in multi-work documents generated in one go, works that fail are left out
+            T::write_entry(&mut output, work).ok();
+        }
+        Ok(output)
+    }
+
+    fn finish(&mut self, _: &[DeletedWork]) -> ThothResult<Vec<u8>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Obtain a work's contributions of the given type, in order, formatted as "Surname, Forename"
+fn contributor_names(work: &Work, contribution_type: ContributionType) -> Vec<String> {
+    let mut contributions: Vec<&WorkContributions> = work
+        .contributions
+        .iter()
+        .filter(|c| c.contribution_type == contribution_type)
+        .collect();
+    contributions.sort_by_key(|c| c.contribution_ordinal);
+    contributions
+        .iter()
+        .map(|c| match &c.first_name {
+            Some(first_name) => format!("{}, {}", c.last_name, first_name),
+            None => c.full_name.clone(),
+        })
+        .collect()
+}
+
+mod bibtex_thoth;
+pub(crate) use bibtex_thoth::BibtexThoth;
+mod ris_thoth;
+pub(crate) use ris_thoth::RisThoth;
@@ -0,0 +1,320 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::Write;
+use thoth_client::{ContributionType, SubjectType, Work, WorkType};
+use thoth_errors::{ThothError, ThothResult};
+
+use super::{contributor_names, CitationSpecification, CitationStreamSpecification};
+use crate::record::RequiredFields;
+
+pub(crate) struct RisThoth;
+
+#[derive(Debug, PartialEq)]
+struct RisEntry {
+    fields: Vec<(&'static str, String)>,
+}
+
+impl CitationSpecification for RisThoth {
+    fn handle_event<W: Write>(w: &mut W, works: &[Work]) -> ThothResult<()> {
+        match works.len() {
+            0 => Err(ThothError::IncompleteMetadataRecord(
+                "ris::thoth".to_string(),
+                "Not enough data".to_string(),
+            )),
+            1 => Self::write_entry(w, works.first().unwrap()),
+            _ => {
+                for work in works.iter() {
+                    if let Ok(entry) = RisEntry::try_from(work) {
+                        write!(w, "{}", entry)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl CitationStreamSpecification for RisThoth {
+    fn write_entry<W: Write>(w: &mut W, work: &Work) -> ThothResult<()> {
+        let entry = RisEntry::try_from(work)?;
+        write!(w, "{}", entry).map_err(|e| e.into())
+    }
+}
+
+impl RequiredFields for RisThoth {
+    const SPECIFICATION_ID: &'static str = "ris::thoth";
+}
+
+impl TryFrom<&Work> for RisEntry {
+    type Error = ThothError;
+
+    fn try_from(work: &Work) -> ThothResult<Self> {
+        let is_chapter = work.work_type == WorkType::BOOK_CHAPTER;
+        let mut fields = vec![("TY", if is_chapter { "CHAP" } else { "BOOK" }.to_string())];
+        fields.push(("TI", work.full_title.clone()));
+        for author in contributor_names(work, ContributionType::AUTHOR) {
+            fields.push(("AU", author));
+        }
+        for editor in contributor_names(work, ContributionType::EDITOR) {
+            fields.push(("ED", editor));
+        }
+        if let Some(publication_date) = work.publication_date {
+            fields.push(("PY", publication_date.format("%Y").to_string()));
+            fields.push(("DA", publication_date.format("%Y/%m/%d").to_string()));
+        }
+        fields.push(("PB", work.imprint.publisher.publisher_name.clone()));
+        if let Some(place) = &work.place {
+            fields.push(("CY", place.clone()));
+        }
+        if work.edition > 1 {
+            fields.push(("ET", work.edition.to_string()));
+        }
+        // The series title is the secondary title of a book, but the tertiary title of a chapter.
+        // Only one series can be listed, so we select the first one found (if any).
+        if let Some(issue) = work.issues.first() {
+            fields.push((
+                if is_chapter { "T3" } else { "T2" },
+                issue.series.series_name.clone(),
+            ));
+            fields.push(("VL", issue.issue_ordinal.to_string()));
+        }
+        for isbn in work.publications.iter().filter_map(|p| p.isbn.as_ref()) {
+            fields.push(("SN", isbn.to_string()));
+        }
+        if let Some(doi) = &work.doi {
+            fields.push(("DO", doi.to_string()));
+        }
+        if let Some(landing_page) = &work.landing_page {
+            fields.push(("UR", landing_page.clone()));
+        }
+        if let Some(labstract) = work.long_abstract.as_ref().or(work.short_abstract.as_ref()) {
+            fields.push(("AB", labstract.clone()));
+        }
+        for subject in work
+            .subjects
+            .iter()
+            .filter(|s| s.subject_type == SubjectType::KEYWORD)
+        {
+            fields.push(("KW", subject.subject_code.clone()));
+        }
+        Ok(RisEntry { fields })
+    }
+}
+
+impl fmt::Display for RisEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Each tag occupies a single line, so line breaks within values are replaced
+        for (tag, value) in &self.fields {
+            write!(
+                f,
+                "{}  - {}\r\n",
+                tag,
+                value.replace(&['\r', '\n'][..], " ")
+            )?;
+        }
+        write!(f, "ER  - \r\n\r\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::citation::CitationStreamWriter;
+    use crate::record::RecordWriter;
+    use std::str::FromStr;
+    use thoth_api::model::{Doi, Isbn};
+    use thoth_client::{
+        PublicationType, SeriesType, WorkContributions, WorkContributionsContributor, WorkImprint,
+        WorkImprintPublisher, WorkIssues, WorkIssuesSeries, WorkPublications, WorkStatus,
+        WorkSubjects,
+    };
+    use uuid::Uuid;
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 2,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: None,
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: None,
+            long_abstract: Some("Lorem ipsum dolor sit amet\nconsectetur".to_string()),
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: None,
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![WorkIssues {
+                issue_ordinal: 3,
+                series: WorkIssuesSeries {
+                    series_type: SeriesType::BOOK_SERIES,
+                    series_name: "Name of series".to_string(),
+                    issn_print: "1234-5678".to_string(),
+                    issn_digital: "8765-4321".to_string(),
+                    series_url: None,
+                },
+            }],
+            contributions: vec![
+                WorkContributions {
+                    contribution_type: ContributionType::AUTHOR,
+                    first_name: None,
+                    last_name: "2".to_string(),
+                    full_name: "Author 2".to_string(),
+                    main_contribution: true,
+                    biography: None,
+                    contribution_ordinal: 2,
+                    contributor: WorkContributionsContributor { orcid: None },
+                    affiliations: vec![],
+                },
+                WorkContributions {
+                    contribution_type: ContributionType::AUTHOR,
+                    first_name: Some("Author".to_string()),
+                    last_name: "1".to_string(),
+                    full_name: "Author 1".to_string(),
+                    main_contribution: true,
+                    biography: None,
+                    contribution_ordinal: 1,
+                    contributor: WorkContributionsContributor { orcid: None },
+                    affiliations: vec![],
+                },
+                WorkContributions {
+                    contribution_type: ContributionType::EDITOR,
+                    first_name: Some("Editor".to_string()),
+                    last_name: "1".to_string(),
+                    full_name: "Editor 1".to_string(),
+                    main_contribution: false,
+                    biography: None,
+                    contribution_ordinal: 3,
+                    contributor: WorkContributionsContributor { orcid: None },
+                    affiliations: vec![],
+                },
+            ],
+            languages: vec![],
+            publications: vec![
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000002").unwrap(),
+                    publication_type: PublicationType::PAPERBACK,
+                    isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                    prices: vec![],
+                    locations: vec![],
+                },
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000003").unwrap(),
+                    publication_type: PublicationType::HTML,
+                    isbn: None,
+                    prices: vec![],
+                    locations: vec![],
+                },
+            ],
+            subjects: vec![
+                WorkSubjects {
+                    subject_code: "AAA".to_string(),
+                    subject_type: SubjectType::BIC,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "keyword1".to_string(),
+                    subject_type: SubjectType::KEYWORD,
+                    subject_ordinal: 1,
+                },
+            ],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    #[test]
+    fn test_ris_thoth() {
+        assert_eq!(
+            RisThoth {}.generate(&[test_work()]).unwrap(),
+            "TY  - BOOK\r\n\
+             TI  - Book Title: Book Subtitle\r\n\
+             AU  - 1, Author\r\n\
+             AU  - Author 2\r\n\
+             ED  - 1, Editor\r\n\
+             PY  - 1999\r\n\
+             DA  - 1999/12/31\r\n\
+             PB  - OA Editions\r\n\
+             CY  - León, Spain\r\n\
+             ET  - 2\r\n\
+             T2  - Name of series\r\n\
+             VL  - 3\r\n\
+             SN  - 978-3-16-148410-0\r\n\
+             DO  - 10.00001/BOOK.0001\r\n\
+             UR  - https://www.book.com\r\n\
+             AB  - Lorem ipsum dolor sit amet consectetur\r\n\
+             KW  - keyword1\r\n\
+             ER  - \r\n\r\n"
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn test_streamed_ris_thoth_matches_generated() {
+        let mut writer = CitationStreamWriter::<RisThoth>::new();
+        let mut output = writer.write_page(&[test_work()]).unwrap();
+        output.extend(writer.write_page(&[test_work()]).unwrap());
+        output.extend(writer.finish(&[]).unwrap());
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            RisThoth {}.generate(&[test_work(), test_work()]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ris_thoth_chapter() {
+        let mut work = test_work();
+        work.work_type = WorkType::BOOK_CHAPTER;
+        work.publication_date = None;
+        let output = RisThoth {}.generate(&[work.clone(), work]).unwrap();
+        assert_eq!(output.matches("TY  - CHAP\r\n").count(), 2);
+        assert_eq!(output.matches("ER  - \r\n").count(), 2);
+        assert!(output.contains("T3  - Name of series\r\n"));
+        assert!(!output.contains("T2  - "));
+        assert!(!output.contains("PY  - "));
+    }
+
+    #[test]
+    fn test_ris_thoth_errors() {
+        assert_eq!(
+            RisThoth {}.generate(&[]).unwrap_err().to_string(),
+            "Could not generate ris::thoth: Not enough data".to_string()
+        );
+    }
+}
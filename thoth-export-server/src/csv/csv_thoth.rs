@@ -9,6 +9,7 @@ use thoth_client::{
 use thoth_errors::ThothResult;
 
 use super::{CsvCell, CsvRow, CsvSpecification};
+use crate::record::RequiredFields;
 
 pub(crate) struct CsvThoth;
 
@@ -41,6 +42,8 @@ struct CsvThothRow {
     height_in: Option<f64>,
     page_count: Option<i64>,
     page_breakdown: Option<String>,
+    first_page: Option<String>,
+    last_page: Option<String>,
     image_count: Option<i64>,
     table_count: Option<i64>,
     audio_count: Option<i64>,
@@ -81,10 +84,12 @@ struct CsvThothRow {
         rename = "funding [(institution, institution_doi, ror, country, program, project, grant, jurisdiction)]"
     )]
     funding: String,
+    #[serde(rename = "parent_work (title, doi, ordinal)")]
+    parent_work: String,
 }
 
 impl CsvSpecification for CsvThoth {
-    fn handle_event<W: Write>(w: &mut Writer<W>, works: &[Work]) -> ThothResult<()> {
+    fn handle_event<W: Write>(&self, w: &mut Writer<W>, works: &[Work]) -> ThothResult<()> {
         for work in works.iter() {
             CsvRow::<CsvThoth>::csv_row(work, w)?;
         }
@@ -92,6 +97,10 @@ impl CsvSpecification for CsvThoth {
     }
 }
 
+impl RequiredFields for CsvThoth {
+    const SPECIFICATION_ID: &'static str = "csv::thoth";
+}
+
 impl CsvRow<CsvThoth> for Work {
     fn csv_row<W: Write>(&self, w: &mut Writer<W>) -> ThothResult<()> {
         w.serialize(CsvThothRow::from(self.clone()))
@@ -125,6 +134,8 @@ impl From<Work> for CsvThothRow {
             height_in: work.height_in,
             page_count: work.page_count,
             page_breakdown: work.page_breakdown,
+            first_page: work.first_page,
+            last_page: work.last_page,
             image_count: work.image_count,
             table_count: work.table_count,
             audio_count: work.audio_count,
@@ -214,6 +225,24 @@ impl From<Work> for CsvThothRow {
                     .map(|f| CsvCell::<CsvThoth>::csv_cell(f))
                     .collect::<Vec<String>>(),
             ),
+            // Chapters are listed with the book they belong to, if any
+            parent_work: work
+                .parents
+                .first()
+                .map(|parent| {
+                    format!(
+                        "(\"{}\", \"{}\", \"{}\")",
+                        parent.related_work.full_title,
+                        parent
+                            .related_work
+                            .doi
+                            .as_ref()
+                            .map(|d| d.to_string())
+                            .unwrap_or_default(),
+                        parent.relation_ordinal,
+                    )
+                })
+                .unwrap_or_default(),
         }
     }
 }
@@ -371,7 +400,8 @@ impl CsvCell<CsvThoth> for WorkFundings {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::record::DELIMITER_COMMA;
+    use crate::csv::CsvStreamWriter;
+    use crate::record::{RecordWriter, DELIMITER_COMMA};
     use csv::QuoteStyle;
     use lazy_static::lazy_static;
     use std::str::FromStr;
@@ -383,8 +413,9 @@ mod tests {
         ContributionType, CountryCode, CurrencyCode, LanguageCode, LanguageRelation,
         LocationPlatform, PublicationType, SeriesType, WorkContributionsAffiliations,
         WorkContributionsAffiliationsInstitution, WorkContributionsContributor,
-        WorkFundingsInstitution, WorkImprint, WorkImprintPublisher, WorkIssuesSeries,
-        WorkPublicationsLocations, WorkPublicationsPrices, WorkStatus, WorkType,
+        WorkFundingsInstitution, WorkImprint, WorkImprintPublisher, WorkIssuesSeries, WorkParents,
+        WorkParentsRelatedWork, WorkPublicationsLocations, WorkPublicationsPrices, WorkRelations,
+        WorkRelationsRelatedWork, WorkStatus, WorkType,
     };
     use uuid::Uuid;
 
@@ -413,6 +444,8 @@ mod tests {
             height_in: Some(9.21),
             page_count: Some(334),
             page_breakdown: Some("x+334".to_string()),
+            first_page: None,
+            last_page: None,
             image_count: Some(15),
             table_count: Some(20),
             audio_count: Some(25),
@@ -423,9 +456,14 @@ mod tests {
             oclc: Some("987654321".to_string()),
             cover_url: Some("https://www.book.com/cover".to_string()),
             cover_caption: Some("This is a cover caption".to_string()),
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
             imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
                 imprint_name: "OA Editions Imprint".to_string(),
                 publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
                     publisher_name: "OA Editions".to_string(),
                     publisher_url: None,
                 },
@@ -458,6 +496,7 @@ mod tests {
                             affiliation_ordinal: 1,
                             institution: WorkContributionsAffiliationsInstitution {
                                 institution_name: "University of Life".to_string(),
+                                ror: None,
                             },
                         },
                     ],
@@ -629,11 +668,13 @@ mod tests {
                     country_code: Some(CountryCode::MDA),
                 },
             }],
+            relations: vec![],
+            parents: vec![],
         };
     }
 
-    const TEST_RESULT: &str = r#""publisher","imprint","work_type","work_status","title","subtitle","edition","doi","publication_date","publication_place","license","copyright_holder","landing_page","width (mm)","width (cm)","width (in)","height (mm)","height (cm)","height (in)","page_count","page_breakdown","image_count","table_count","audio_count","video_count","lccn","oclc","short_abstract","long_abstract","general_note","toc","cover_url","cover_caption","contributions [(type, first_name, last_name, full_name, orcid, [(position, ordinal, institution)])]","publications [(type, isbn, [(ISO_4217_currency, price)], [(landing_page, full_text, platform, is_canonical)])]","series [(type, name, issn_print, issn_digital, url, issue)]","languages [(relation, ISO_639-3/B_language, is_main)]","BIC [code]","THEMA [code]","BISAC [code]","LCC [code]","custom_categories [category]","keywords [keyword]","funding [(institution, institution_doi, ror, country, program, project, grant, jurisdiction)]"
-"OA Editions","OA Editions Imprint","MONOGRAPH","ACTIVE","Book Title","Book Subtitle","1","10.00001/BOOK.0001","1999-12-31","León, Spain","http://creativecommons.org/licenses/by/4.0/","Author 1; Author 2","https://www.book.com","156.0","15.6","6.14","234.0","23.4","9.21","334","x+334","15","20","25","30","123456789","987654321","Lorem ipsum dolor sit amet, consectetur adipiscing elit. Vestibulum vel libero eleifend, ultrices purus vitae, suscipit ligula. Aliquam ornare quam et nulla vestibulum, id euismod tellus malesuada. Orci varius natoque penatibus et magnis dis parturient montes, nascetur ridiculus mus.","Lorem ipsum dolor sit amet, consectetur adipiscing elit. Vestibulum vel libero eleifend, ultrices purus vitae, suscipit ligula. Aliquam ornare quam et nulla vestibulum, id euismod tellus malesuada. Orci varius natoque penatibus et magnis dis parturient montes, nascetur ridiculus mus. Nullam ornare bibendum ex nec dapibus. Proin porta risus elementum odio feugiat tempus. Etiam eu felis ac metus viverra ornare. In consectetur neque sed feugiat ornare. Mauris at purus fringilla orci tincidunt pulvinar sed a massa. Nullam vestibulum posuere augue, sit amet tincidunt nisl pulvinar ac.","This is a general note","1. Chapter 1","https://www.book.com/cover","This is a cover caption","[(""AUTHOR"", ""Author"", ""1"", ""Author 1"", ""0000-0002-0000-0001"", [(""Manager"", ""1"", ""University of Life"")]),(""AUTHOR"", ""Author"", ""2"", ""Author 2"", """", )]","[(""PAPERBACK"", ""978-3-16-148410-0"", [(""EUR"", ""25.95""),(""GBP"", ""22.95""),(""USD"", ""31.95"")], [(""https://www.book.com/paperback"", """", ""OTHER"", ""true""),(""https://www.jstor.com/paperback"", """", ""JSTOR"", ""false"")]),(""HARDBACK"", ""978-1-4028-9462-6"", [(""EUR"", ""36.95""),(""GBP"", ""32.95""),(""USD"", ""40.95"")], ),(""PDF"", ""978-1-56619-909-4"", , [(""https://www.book.com/pdf_landing"", ""https://www.book.com/pdf_fulltext"", ""OTHER"", ""true"")]),(""HTML"", """", , [(""https://www.book.com/html_landing"", ""https://www.book.com/html_fulltext"", ""OTHER"", ""true"")]),(""XML"", ""978-92-95055-02-5"", , )]","[(""JOURNAL"", ""Name of series"", ""1234-5678"", ""8765-4321"", ""https://www.series.com"", ""1"")]","[(""ORIGINAL"", ""SPA"", ""true"")]","[""AAA"",""AAB""]","[""JWA""]","[""AAA000000"",""AAA000001""]","[""JA85""]","[""Category1""]","[""keyword1"",""keyword2""]","[(""Name of institution"", ""10.00001/INSTITUTION.0001"", ""0aaaaaa00"", ""MDA"", ""Name of program"", ""Name of project"", ""Number of grant"", ""Funding jurisdiction"")]"
+    const TEST_RESULT: &str = r#""publisher","imprint","work_type","work_status","title","subtitle","edition","doi","publication_date","publication_place","license","copyright_holder","landing_page","width (mm)","width (cm)","width (in)","height (mm)","height (cm)","height (in)","page_count","page_breakdown","first_page","last_page","image_count","table_count","audio_count","video_count","lccn","oclc","short_abstract","long_abstract","general_note","toc","cover_url","cover_caption","contributions [(type, first_name, last_name, full_name, orcid, [(position, ordinal, institution)])]","publications [(type, isbn, [(ISO_4217_currency, price)], [(landing_page, full_text, platform, is_canonical)])]","series [(type, name, issn_print, issn_digital, url, issue)]","languages [(relation, ISO_639-3/B_language, is_main)]","BIC [code]","THEMA [code]","BISAC [code]","LCC [code]","custom_categories [category]","keywords [keyword]","funding [(institution, institution_doi, ror, country, program, project, grant, jurisdiction)]","parent_work (title, doi, ordinal)"
+"OA Editions","OA Editions Imprint","MONOGRAPH","ACTIVE","Book Title","Book Subtitle","1","10.00001/BOOK.0001","1999-12-31","León, Spain","http://creativecommons.org/licenses/by/4.0/","Author 1; Author 2","https://www.book.com","156.0","15.6","6.14","234.0","23.4","9.21","334","x+334","","","15","20","25","30","123456789","987654321","Lorem ipsum dolor sit amet, consectetur adipiscing elit. Vestibulum vel libero eleifend, ultrices purus vitae, suscipit ligula. Aliquam ornare quam et nulla vestibulum, id euismod tellus malesuada. Orci varius natoque penatibus et magnis dis parturient montes, nascetur ridiculus mus.","Lorem ipsum dolor sit amet, consectetur adipiscing elit. Vestibulum vel libero eleifend, ultrices purus vitae, suscipit ligula. Aliquam ornare quam et nulla vestibulum, id euismod tellus malesuada. Orci varius natoque penatibus et magnis dis parturient montes, nascetur ridiculus mus. Nullam ornare bibendum ex nec dapibus. Proin porta risus elementum odio feugiat tempus. Etiam eu felis ac metus viverra ornare. In consectetur neque sed feugiat ornare. Mauris at purus fringilla orci tincidunt pulvinar sed a massa. Nullam vestibulum posuere augue, sit amet tincidunt nisl pulvinar ac.","This is a general note","1. Chapter 1","https://www.book.com/cover","This is a cover caption","[(""AUTHOR"", ""Author"", ""1"", ""Author 1"", ""0000-0002-0000-0001"", [(""Manager"", ""1"", ""University of Life"")]),(""AUTHOR"", ""Author"", ""2"", ""Author 2"", """", )]","[(""PAPERBACK"", ""978-3-16-148410-0"", [(""EUR"", ""25.95""),(""GBP"", ""22.95""),(""USD"", ""31.95"")], [(""https://www.book.com/paperback"", """", ""OTHER"", ""true""),(""https://www.jstor.com/paperback"", """", ""JSTOR"", ""false"")]),(""HARDBACK"", ""978-1-4028-9462-6"", [(""EUR"", ""36.95""),(""GBP"", ""32.95""),(""USD"", ""40.95"")], ),(""PDF"", ""978-1-56619-909-4"", , [(""https://www.book.com/pdf_landing"", ""https://www.book.com/pdf_fulltext"", ""OTHER"", ""true"")]),(""HTML"", """", , [(""https://www.book.com/html_landing"", ""https://www.book.com/html_fulltext"", ""OTHER"", ""true"")]),(""XML"", ""978-92-95055-02-5"", , )]","[(""JOURNAL"", ""Name of series"", ""1234-5678"", ""8765-4321"", ""https://www.series.com"", ""1"")]","[(""ORIGINAL"", ""SPA"", ""true"")]","[""AAA"",""AAB""]","[""JWA""]","[""AAA000000"",""AAA000001""]","[""JA85""]","[""Category1""]","[""keyword1"",""keyword2""]","[(""Name of institution"", ""10.00001/INSTITUTION.0001"", ""0aaaaaa00"", ""MDA"", ""Name of program"", ""Name of project"", ""Number of grant"", ""Funding jurisdiction"")]",""
 "#;
 
     #[test]
@@ -643,6 +684,72 @@ mod tests {
         assert_eq!(to_test, Ok(TEST_RESULT.to_string()))
     }
 
+    #[test]
+    fn test_csv_thoth_stream() {
+        let mut writer = CsvStreamWriter::<CsvThoth>::new(QuoteStyle::Always, DELIMITER_COMMA);
+        let mut to_test = writer.write_page(&[TEST_WORK.clone()]).unwrap();
+        to_test.extend(writer.write_page(&[TEST_WORK.clone()]).unwrap());
+        to_test.extend(writer.finish(&[]).unwrap());
+        // Headers are only written before the first row
+        assert_eq!(
+            String::from_utf8(to_test).unwrap(),
+            format!("{}{}\n", TEST_RESULT, TEST_RESULT.lines().nth(1).unwrap())
+        );
+    }
+
+    #[test]
+    fn test_csv_thoth_chapters() {
+        let mut book = TEST_WORK.clone();
+        book.relations = vec![WorkRelations {
+            relation_ordinal: 1,
+            related_work: WorkRelationsRelatedWork {
+                work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000002").unwrap(),
+                work_status: WorkStatus::ACTIVE,
+                full_title: "Chapter Title".to_string(),
+                title: "Chapter Title".to_string(),
+                subtitle: None,
+                work_type: WorkType::BOOK_CHAPTER,
+                edition: 1,
+                doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001.01").unwrap()),
+                publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+                license: Some("http://creativecommons.org/licenses/by/4.0/".to_string()),
+                copyright_holder: "Author 1".to_string(),
+                short_abstract: None,
+                long_abstract: Some("Chapter abstract".to_string()),
+                page_count: Some(10),
+                first_page: Some("1".to_string()),
+                last_page: Some("10".to_string()),
+                landing_page: Some("https://www.book.com/chapter1".to_string()),
+                updated_at_with_relations: Default::default(),
+                contributions: vec![],
+            },
+        }];
+        let mut chapter = TEST_WORK.clone();
+        chapter.work_id = Uuid::from_str("00000000-0000-0000-AAAA-000000000002").unwrap();
+        chapter.work_type = WorkType::BOOK_CHAPTER;
+        chapter.parents = vec![WorkParents {
+            relation_ordinal: 1,
+            related_work: WorkParentsRelatedWork {
+                work_id: TEST_WORK.work_id,
+                full_title: "Book Title: Book Subtitle".to_string(),
+                doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+                landing_page: None,
+            },
+        }];
+        let to_test = CsvThoth
+            .generate(&[book, chapter], QuoteStyle::Always, DELIMITER_COMMA)
+            .unwrap();
+        // Each work is listed once, and the chapter refers to its book
+        let mut rows = to_test.lines();
+        assert_eq!(rows.next(), TEST_RESULT.lines().next());
+        assert_eq!(rows.next(), TEST_RESULT.lines().nth(1));
+        let chapter_row = rows.next().unwrap();
+        assert!(chapter_row.starts_with(r#""OA Editions","OA Editions Imprint","BOOK_CHAPTER","#));
+        assert!(chapter_row
+            .ends_with(r#","(""Book Title: Book Subtitle"", ""10.00001/BOOK.0001"", ""1"")""#));
+        assert_eq!(rows.next(), None);
+    }
+
     #[test]
     fn test_csv_thoth_cell() {
         assert_eq!(CsvCell::<CsvThoth>::csv_cell(&vec![]), "".to_string());
@@ -720,6 +827,7 @@ mod tests {
             affiliation_ordinal: 1,
             institution: WorkContributionsAffiliationsInstitution {
                 institution_name: "University of Life".to_string(),
+                ror: None,
             },
         };
         assert_eq!(
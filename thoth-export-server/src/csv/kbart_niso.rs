@@ -0,0 +1,573 @@
+use chrono::NaiveDate;
+use csv::Writer;
+use serde::Serialize;
+use std::io::Write;
+use thoth_client::{
+    ContributionType, PublicationType, SeriesType, Work, WorkIssuesSeries, WorkType,
+};
+use thoth_errors::{ThothError, ThothResult};
+
+use super::CsvSpecification;
+use crate::record::RequiredFields;
+
+/// Plain NISO KBART Phase II, with the values left to the content provider's discretion
+/// (`access_type` and `coverage_depth`) configurable per knowledge base
+#[derive(Debug, PartialEq)]
+pub(crate) struct KbartNiso {
+    access_type: Option<&'static str>,
+    coverage_depth: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct KbartNisoRow {
+    publication_title: String,
+    print_identifier: Option<String>,
+    online_identifier: Option<String>,
+    date_first_issue_online: Option<String>,
+    num_first_vol_online: Option<i64>,
+    num_first_issue_online: Option<i64>,
+    date_last_issue_online: Option<String>,
+    num_last_vol_online: Option<i64>,
+    num_last_issue_online: Option<i64>,
+    title_url: String,
+    first_author: Option<String>,
+    title_id: String,
+    embargo_info: Option<String>,
+    coverage_depth: String,
+    notes: Option<String>,
+    publisher_name: Option<String>,
+    publication_type: String,
+    date_monograph_published_print: Option<String>,
+    date_monograph_published_online: Option<String>,
+    monograph_volume: Option<i64>,
+    monograph_edition: Option<i64>,
+    first_editor: Option<String>,
+    parent_publication_title_id: Option<String>,
+    preceding_publication_title_id: Option<String>,
+    access_type: String,
+}
+
+impl Default for KbartNiso {
+    fn default() -> Self {
+        // Access type is derived from each title's licence unless a platform overrides it
+        KbartNiso {
+            access_type: None,
+            coverage_depth: "fulltext",
+        }
+    }
+}
+
+impl KbartNiso {
+    /// Settings expected by the knowledge base with the given platform ID
+    pub(crate) fn for_platform(platform_id: &str) -> ThothResult<Self> {
+        match platform_id {
+            // These knowledge bases list all of our titles as free to read, as in `kbart::oclc`
+            "oclc_kb" | "proquest_kb" | "proquest_exlibris" => Ok(KbartNiso {
+                access_type: Some("F"),
+                coverage_depth: "fulltext",
+            }),
+            "ebsco_kb" | "jisc_kb" => Ok(KbartNiso::default()),
+            _ => Err(ThothError::EntityNotFound),
+        }
+    }
+
+    fn access_type(&self, open_access: bool) -> String {
+        match (self.access_type, open_access) {
+            (Some(access_type), _) => access_type.to_string(),
+            (None, true) => "F".to_string(),
+            (None, false) => "P".to_string(),
+        }
+    }
+
+    fn monograph_row(&self, work: &Work) -> ThothResult<KbartNisoRow> {
+        // title_url is mandatory in KBART but optional in Thoth
+        let title_url = work.landing_page.as_ref().ok_or_else(|| {
+            ThothError::IncompleteMetadataRecord(
+                "kbart::niso".to_string(),
+                "Missing Landing Page".to_string(),
+            )
+        })?;
+        let publication_date = work.publication_date.ok_or_else(|| {
+            ThothError::IncompleteMetadataRecord(
+                "kbart::niso".to_string(),
+                "Missing Publication Date".to_string(),
+            )
+        })?;
+        let isbn = |publication_types: &[PublicationType]| {
+            publication_types.iter().find_map(|publication_type| {
+                work.publications
+                    .iter()
+                    .filter(|p| p.publication_type.eq(publication_type))
+                    .find_map(|p| p.isbn.as_ref().map(|i| i.to_string()))
+            })
+        };
+        let print_edition_exists = work.publications.iter().any(|p| {
+            p.publication_type.eq(&PublicationType::PAPERBACK)
+                || p.publication_type.eq(&PublicationType::HARDBACK)
+        });
+        let mut contributions: Vec<_> = work
+            .contributions
+            .iter()
+            .filter(|c| c.main_contribution)
+            .collect();
+        contributions.sort_by_key(|c| c.contribution_ordinal);
+        // KBART lists either a first author or a first editor, by surname only
+        let first_contributor = |contribution_type: ContributionType| {
+            contributions
+                .iter()
+                .find(|c| c.contribution_type == contribution_type)
+                .map(|c| kbart_text(&c.last_name))
+        };
+        let (first_author, first_editor) = match work.work_type {
+            WorkType::EDITED_BOOK => (None, first_contributor(ContributionType::EDITOR)),
+            _ => (first_contributor(ContributionType::AUTHOR), None),
+        };
+        let date_published = publication_date.format("%Y-%m-%d").to_string();
+        Ok(KbartNisoRow {
+            publication_title: kbart_text(&work.full_title),
+            print_identifier: isbn(&[PublicationType::PAPERBACK, PublicationType::HARDBACK]),
+            online_identifier: isbn(&[
+                PublicationType::PDF,
+                PublicationType::EPUB,
+                PublicationType::HTML,
+                PublicationType::XML,
+                PublicationType::MOBI,
+            ]),
+            date_first_issue_online: None,
+            num_first_vol_online: None,
+            num_first_issue_online: None,
+            date_last_issue_online: None,
+            num_last_vol_online: None,
+            num_last_issue_online: None,
+            title_url: title_url.to_string(),
+            first_author,
+            // KBART title IDs must be unique and stable: fall back to Thoth's own ID
+            title_id: work
+                .doi
+                .as_ref()
+                .map(|d| d.to_string())
+                .unwrap_or_else(|| work.work_id.to_string()),
+            embargo_info: None,
+            coverage_depth: self.coverage_depth.to_string(),
+            notes: None,
+            publisher_name: Some(kbart_text(&work.imprint.publisher.publisher_name)),
+            publication_type: "Monograph".to_string(),
+            date_monograph_published_print: match print_edition_exists {
+                true => Some(date_published.clone()),
+                false => None,
+            },
+            date_monograph_published_online: Some(date_published),
+            // Only one series can be listed in KBART, so we select the first one found (if any)
+            monograph_volume: work.issues.first().map(|i| i.issue_ordinal),
+            monograph_edition: Some(work.edition),
+            first_editor,
+            parent_publication_title_id: work
+                .issues
+                .first()
+                .map(|i| i.series.issn_digital.to_string()),
+            preceding_publication_title_id: None,
+            access_type: self.access_type(work.license.is_some()),
+        })
+    }
+
+    fn serial_row(
+        &self,
+        series: &WorkIssuesSeries,
+        issues: &[(&Work, i64)],
+    ) -> ThothResult<KbartNisoRow> {
+        let title_url = series.series_url.as_ref().ok_or_else(|| {
+            ThothError::IncompleteMetadataRecord(
+                "kbart::niso".to_string(),
+                "Missing Series URL".to_string(),
+            )
+        })?;
+        // Coverage spans the earliest to the latest published issue
+        let mut dated: Vec<(NaiveDate, i64)> = issues
+            .iter()
+            .filter_map(|(work, ordinal)| work.publication_date.map(|date| (date, *ordinal)))
+            .collect();
+        dated.sort();
+        let (first, last) = match (dated.first(), dated.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => {
+                return Err(ThothError::IncompleteMetadataRecord(
+                    "kbart::niso".to_string(),
+                    "Missing Publication Date".to_string(),
+                ))
+            }
+        };
+        Ok(KbartNisoRow {
+            publication_title: kbart_text(&series.series_name),
+            print_identifier: Some(series.issn_print.to_string()),
+            online_identifier: Some(series.issn_digital.to_string()),
+            date_first_issue_online: Some(first.0.format("%Y-%m-%d").to_string()),
+            num_first_vol_online: None,
+            num_first_issue_online: Some(first.1),
+            date_last_issue_online: Some(last.0.format("%Y-%m-%d").to_string()),
+            num_last_vol_online: None,
+            num_last_issue_online: Some(last.1),
+            title_url: title_url.to_string(),
+            first_author: None,
+            // This matches the `parent_publication_title_id` of the series' monographs
+            title_id: series.issn_digital.to_string(),
+            embargo_info: None,
+            coverage_depth: self.coverage_depth.to_string(),
+            notes: None,
+            publisher_name: issues
+                .first()
+                .map(|(work, _)| kbart_text(&work.imprint.publisher.publisher_name)),
+            publication_type: "Serial".to_string(),
+            date_monograph_published_print: None,
+            date_monograph_published_online: None,
+            monograph_volume: None,
+            monograph_edition: None,
+            first_editor: None,
+            parent_publication_title_id: None,
+            preceding_publication_title_id: None,
+            access_type: self.access_type(issues.iter().all(|(work, _)| work.license.is_some())),
+        })
+    }
+}
+
+impl RequiredFields for KbartNiso {
+    const SPECIFICATION_ID: &'static str = "kbart::niso";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        // Journal issues are listed as part of their journal rather than on their own
+        let journals: Vec<_> = work
+            .issues
+            .iter()
+            .filter(|i| i.series.series_type == SeriesType::JOURNAL)
+            .collect();
+        if journals.is_empty() {
+            // title_url is mandatory in KBART but optional in Thoth
+            if work.landing_page.is_none() {
+                missing.push("Missing Landing Page");
+            }
+        } else if journals.iter().any(|i| i.series.series_url.is_none()) {
+            missing.push("Missing Series URL");
+        }
+        if work.publication_date.is_none() {
+            missing.push("Missing Publication Date");
+        }
+        missing
+    }
+}
+
+impl CsvSpecification for KbartNiso {
+    fn handle_event<W: Write>(&self, w: &mut Writer<W>, works: &[Work]) -> ThothResult<()> {
+        match works {
+            [] => {
+                return Err(ThothError::IncompleteMetadataRecord(
+                    "kbart::niso".to_string(),
+                    "Not enough data".to_string(),
+                ))
+            }
+            // Works in a list are checked as part of their row, as a journal's issues share one
+            [work] => KbartNiso::check_required_fields(work)?,
+            _ => {}
+        }
+        // Works that are issues of a journal are listed once per journal, as serials
+        let mut journals: Vec<(&WorkIssuesSeries, Vec<(&Work, i64)>)> = Vec::new();
+        let mut rows: Vec<ThothResult<KbartNisoRow>> = Vec::new();
+        for work in works {
+            let mut is_journal_issue = false;
+            for issue in work
+                .issues
+                .iter()
+                .filter(|i| i.series.series_type == SeriesType::JOURNAL)
+            {
+                is_journal_issue = true;
+                match journals
+                    .iter_mut()
+                    .find(|(series, _)| series.issn_digital == issue.series.issn_digital)
+                {
+                    Some((_, issues)) => issues.push((work, issue.issue_ordinal)),
+                    None => journals.push((&issue.series, vec![(work, issue.issue_ordinal)])),
+                }
+            }
+            if !is_journal_issue {
+                rows.push(self.monograph_row(work));
+            }
+        }
+        for (series, issues) in &journals {
+            rows.push(self.serial_row(series, issues));
+        }
+        for row in rows {
+            match row {
+                Ok(row) => w.serialize(row)?,
+                // A single work's errors are reported, but one bad work shouldn't spoil a list
+                Err(e) if works.len() == 1 => return Err(e),
+                Err(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// KBART fields must not contain tabs or line breaks
+fn kbart_text(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Registry, RegistryConfig};
+    use crate::record::DELIMITER_TAB;
+    use csv::QuoteStyle;
+    use std::str::FromStr;
+    use thoth_api::model::{Doi, Isbn};
+    use thoth_client::{
+        WorkContributions, WorkContributionsContributor, WorkImprint, WorkImprintPublisher,
+        WorkIssues, WorkPublications, WorkStatus,
+    };
+    use uuid::Uuid;
+
+    const TEST_HEADERS: &str = "publication_title\tprint_identifier\tonline_identifier\tdate_first_issue_online\tnum_first_vol_online\tnum_first_issue_online\tdate_last_issue_online\tnum_last_vol_online\tnum_last_issue_online\ttitle_url\tfirst_author\ttitle_id\tembargo_info\tcoverage_depth\tnotes\tpublisher_name\tpublication_type\tdate_monograph_published_print\tdate_monograph_published_online\tmonograph_volume\tmonograph_edition\tfirst_editor\tparent_publication_title_id\tpreceding_publication_title_id\taccess_type\n";
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title:\tBook\nSubtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 2,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("http://creativecommons.org/licenses/by/4.0/".to_string()),
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: None,
+            long_abstract: None,
+            general_note: None,
+            place: None,
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: None,
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![WorkIssues {
+                issue_ordinal: 20,
+                series: WorkIssuesSeries {
+                    series_type: SeriesType::BOOK_SERIES,
+                    series_name: "Name of series".to_string(),
+                    issn_print: "1234-5678".to_string(),
+                    issn_digital: "8765-4321".to_string(),
+                    series_url: None,
+                },
+            }],
+            contributions: vec![WorkContributions {
+                contribution_type: ContributionType::AUTHOR,
+                first_name: Some("Author".to_string()),
+                last_name: "First".to_string(),
+                full_name: "Author First".to_string(),
+                main_contribution: true,
+                biography: None,
+                contribution_ordinal: 1,
+                contributor: WorkContributionsContributor { orcid: None },
+                affiliations: vec![],
+            }],
+            languages: vec![],
+            publications: vec![
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-EEEE-000000000005").unwrap(),
+                    publication_type: PublicationType::EPUB,
+                    isbn: Some(Isbn::from_str("978-1-56619-909-4").unwrap()),
+                    prices: vec![],
+                    locations: vec![],
+                },
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000003").unwrap(),
+                    publication_type: PublicationType::HARDBACK,
+                    isbn: Some(Isbn::from_str("978-1-4028-9462-6").unwrap()),
+                    prices: vec![],
+                    locations: vec![],
+                },
+            ],
+            subjects: vec![],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    fn test_journal_issue(work_id: &str, issue_ordinal: i64, year: i32) -> Work {
+        let mut work = test_work();
+        work.work_id = Uuid::from_str(work_id).unwrap();
+        work.work_type = WorkType::JOURNAL_ISSUE;
+        work.publication_date = Some(chrono::NaiveDate::from_ymd(year, 6, 1));
+        work.issues = vec![WorkIssues {
+            issue_ordinal,
+            series: WorkIssuesSeries {
+                series_type: SeriesType::JOURNAL,
+                series_name: "Name of journal".to_string(),
+                issn_print: "1111-2222".to_string(),
+                issn_digital: "3333-4444".to_string(),
+                series_url: Some("https://www.journal.com".to_string()),
+            },
+        }];
+        work
+    }
+
+    #[test]
+    fn test_kbart_niso_monograph() {
+        let mut test_work = test_work();
+        let to_test = KbartNiso::default().generate(
+            &[test_work.clone()],
+            QuoteStyle::Necessary,
+            DELIMITER_TAB,
+        );
+        // Whitespace within fields is collapsed, and print ISBN falls back to the hardback's
+        assert_eq!(
+            to_test,
+            Ok(format!(
+                "{}Book Title: Book Subtitle\t978-1-4028-9462-6\t978-1-56619-909-4\t\t\t\t\t\t\thttps://www.book.com\tFirst\t10.00001/BOOK.0001\t\tfulltext\t\tOA Editions\tMonograph\t1999-12-31\t1999-12-31\t20\t2\t\t8765-4321\t\tF\n",
+                TEST_HEADERS
+            ))
+        );
+
+        // Without a DOI or licence, the title ID is Thoth's own and the title is not free to read
+        test_work.doi = None;
+        test_work.license = None;
+        test_work.issues.clear();
+        test_work.publications.remove(1);
+        test_work.work_type = WorkType::EDITED_BOOK;
+        test_work.contributions[0].contribution_type = ContributionType::EDITOR;
+        let to_test = KbartNiso::default().generate(
+            &[test_work.clone()],
+            QuoteStyle::Necessary,
+            DELIMITER_TAB,
+        );
+        assert_eq!(
+            to_test,
+            Ok(format!(
+                "{}Book Title: Book Subtitle\t\t978-1-56619-909-4\t\t\t\t\t\t\thttps://www.book.com\t\t00000000-0000-0000-aaaa-000000000001\t\tfulltext\t\tOA Editions\tMonograph\t\t1999-12-31\t\t2\tFirst\t\t\tP\n",
+                TEST_HEADERS
+            ))
+        );
+
+        // Platform settings take precedence over the licence
+        let to_test = KbartNiso::for_platform("oclc_kb").unwrap().generate(
+            &[test_work.clone()],
+            QuoteStyle::Necessary,
+            DELIMITER_TAB,
+        );
+        assert!(to_test.unwrap().ends_with("\tF\n"));
+        assert_eq!(KbartNiso::for_platform("jisc_kb"), Ok(KbartNiso::default()));
+        assert_eq!(
+            KbartNiso::for_platform("project_muse"),
+            Err(ThothError::EntityNotFound)
+        );
+
+        // Remove landing page: KBART fails to generate
+        test_work.landing_page = None;
+        let to_test = KbartNiso::default().generate(
+            &[test_work.clone()],
+            QuoteStyle::Necessary,
+            DELIMITER_TAB,
+        );
+        assert_eq!(
+            to_test,
+            Err(ThothError::IncompleteMetadataRecord(
+                "kbart::niso".to_string(),
+                "Missing Landing Page".to_string(),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_kbart_niso_serial() {
+        let works = vec![
+            test_journal_issue("00000000-0000-0000-AAAA-000000000002", 2, 2001),
+            test_work(),
+            test_journal_issue("00000000-0000-0000-AAAA-000000000003", 1, 2000),
+            test_journal_issue("00000000-0000-0000-AAAA-000000000004", 3, 2002),
+        ];
+        let to_test = KbartNiso::default()
+            .generate(&works, QuoteStyle::Necessary, DELIMITER_TAB)
+            .unwrap();
+        let rows: Vec<&str> = to_test.lines().collect();
+        // Header, one monograph and one row for all issues of the journal
+        assert_eq!(rows.len(), 3);
+        assert_eq!(
+            rows[2],
+            "Name of journal\t1111-2222\t3333-4444\t2000-06-01\t\t1\t2002-06-01\t\t3\thttps://www.journal.com\t\t3333-4444\t\tfulltext\t\tOA Editions\tSerial\t\t\t\t\t\t\t\tF"
+        );
+
+        // Journals without a URL are skipped in multi-work exports, but reported for single works
+        let mut works = works;
+        for work in works.iter_mut() {
+            for issue in work.issues.iter_mut() {
+                issue.series.series_url = None;
+            }
+        }
+        let to_test = KbartNiso::default()
+            .generate(&works, QuoteStyle::Necessary, DELIMITER_TAB)
+            .unwrap();
+        assert_eq!(to_test.lines().count(), 2);
+        let to_test =
+            KbartNiso::default().generate(&works[..1], QuoteStyle::Necessary, DELIMITER_TAB);
+        assert_eq!(
+            to_test,
+            Err(ThothError::IncompleteMetadataRecord(
+                "kbart::niso".to_string(),
+                "Missing Series URL".to_string(),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_kbart_niso_platforms() {
+        // Every knowledge base that accepts NISO KBART must have settings
+        let registry = Registry::new("http://localhost:8181", &RegistryConfig::default()).unwrap();
+        for platform in registry.platforms().iter().filter(|p| {
+            p.accepts
+                .iter()
+                .any(|s| s.ends_with("/specifications/kbart::niso"))
+        }) {
+            assert!(KbartNiso::for_platform(&platform.id).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_kbart_niso_errors() {
+        let to_test = KbartNiso::default().generate(&[], QuoteStyle::Necessary, DELIMITER_TAB);
+        assert_eq!(
+            to_test,
+            Err(ThothError::IncompleteMetadataRecord(
+                "kbart::niso".to_string(),
+                "Not enough data".to_string(),
+            ))
+        );
+    }
+}
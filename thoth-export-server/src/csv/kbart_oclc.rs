@@ -6,6 +6,7 @@ use thoth_client::{ContributionType, PublicationType, Work, WorkType};
 use thoth_errors::{ThothError, ThothResult};
 
 use super::{CsvRow, CsvSpecification};
+use crate::record::RequiredFields;
 
 pub(crate) struct KbartOclc;
 
@@ -39,10 +40,10 @@ struct KbartOclcRow {
 }
 
 impl CsvSpecification for KbartOclc {
-    fn handle_event<W: Write>(w: &mut Writer<W>, works: &[Work]) -> ThothResult<()> {
+    fn handle_event<W: Write>(&self, w: &mut Writer<W>, works: &[Work]) -> ThothResult<()> {
         match works.len() {
             0 => Err(ThothError::IncompleteMetadataRecord(
-                "onix_3.0::project_muse".to_string(),
+                "kbart::oclc".to_string(),
                 "Not enough data".to_string(),
             )),
             1 => CsvRow::<KbartOclc>::csv_row(works.first().unwrap(), w),
@@ -56,6 +57,23 @@ impl CsvSpecification for KbartOclc {
     }
 }
 
+impl RequiredFields for KbartOclc {
+    const SPECIFICATION_ID: &'static str = "kbart::oclc";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        // title_url is mandatory in KBART but optional in Thoth
+        if work.landing_page.is_none() {
+            missing.push("Missing Landing Page");
+        }
+        // Don't output works with no publication date (mandatory in KBART)
+        if work.publication_date.is_none() {
+            missing.push("Missing Publication Date");
+        }
+        missing
+    }
+}
+
 impl CsvRow<KbartOclc> for Work {
     fn csv_row<W: Write>(&self, w: &mut Writer<W>) -> ThothResult<()> {
         w.serialize(KbartOclcRow::try_from(self.clone())?)
@@ -67,106 +85,91 @@ impl TryFrom<Work> for KbartOclcRow {
     type Error = ThothError;
 
     fn try_from(work: Work) -> ThothResult<Self> {
-        // title_url is mandatory in KBART but optional in Thoth
-        if work.landing_page.is_none() {
-            Err(ThothError::IncompleteMetadataRecord(
-                "kbart::oclc".to_string(),
-                "Missing Landing Page".to_string(),
-            ))
-        // Don't output works with no publication date (mandatory in KBART)
-        } else if work.publication_date.is_none() {
-            Err(ThothError::IncompleteMetadataRecord(
-                "kbart::oclc".to_string(),
-                "Missing Publication Date".to_string(),
-            ))
-        } else {
-            let mut print_identifier = None;
-            let mut online_identifier = None;
-            let mut print_edition_exists = false;
-            for publication in work.publications {
-                if publication.publication_type == PublicationType::PDF
-                    && publication.isbn.is_some()
-                {
-                    online_identifier = publication.isbn.as_ref().map(|i| i.to_string());
-                }
-                if publication.publication_type == PublicationType::PAPERBACK {
-                    print_edition_exists = true;
-                    if publication.isbn.is_some() {
-                        print_identifier = publication.isbn.as_ref().map(|i| i.to_string());
-                    }
-                }
-                if publication.publication_type == PublicationType::HARDBACK {
-                    print_edition_exists = true;
+        KbartOclc::check_required_fields(&work)?;
+        let mut print_identifier = None;
+        let mut online_identifier = None;
+        let mut print_edition_exists = false;
+        for publication in work.publications {
+            if publication.publication_type == PublicationType::PDF && publication.isbn.is_some() {
+                online_identifier = publication.isbn.as_ref().map(|i| i.to_string());
+            }
+            if publication.publication_type == PublicationType::PAPERBACK {
+                print_edition_exists = true;
+                if publication.isbn.is_some() {
+                    print_identifier = publication.isbn.as_ref().map(|i| i.to_string());
                 }
             }
-            let mut first_author = None;
-            let mut first_editor = None;
-            let mut contributions = work.contributions;
-            // The first author/editor will usually be the contributor with contribution_ordinal 1,
-            // but this is not guaranteed, so we select the highest-ranked contributor of the
-            // appropriate contribution type who is listed as a "main" contributor.
-            contributions.sort_by(|a, b| a.contribution_ordinal.cmp(&b.contribution_ordinal));
-            for contribution in contributions {
-                if contribution.main_contribution {
-                    if work.work_type == WorkType::EDITED_BOOK {
-                        if contribution.contribution_type == ContributionType::EDITOR {
-                            first_editor = Some(contribution.last_name);
-                            break;
-                        }
-                    } else if contribution.contribution_type == ContributionType::AUTHOR {
-                        first_author = Some(contribution.last_name);
+            if publication.publication_type == PublicationType::HARDBACK {
+                print_edition_exists = true;
+            }
+        }
+        let mut first_author = None;
+        let mut first_editor = None;
+        let mut contributions = work.contributions;
+        // The first author/editor will usually be the contributor with contribution_ordinal 1,
+        // but this is not guaranteed, so we select the highest-ranked contributor of the
+        // appropriate contribution type who is listed as a "main" contributor.
+        contributions.sort_by(|a, b| a.contribution_ordinal.cmp(&b.contribution_ordinal));
+        for contribution in contributions {
+            if contribution.main_contribution {
+                if work.work_type == WorkType::EDITED_BOOK {
+                    if contribution.contribution_type == ContributionType::EDITOR {
+                        first_editor = Some(contribution.last_name);
                         break;
                     }
+                } else if contribution.contribution_type == ContributionType::AUTHOR {
+                    first_author = Some(contribution.last_name);
+                    break;
                 }
             }
-            let date_monograph_published_online = work
-                .publication_date
-                .map(|date| chrono::Datelike::year(&date).into())
-                .unwrap();
-            let date_monograph_published_print = match print_edition_exists {
-                true => Some(date_monograph_published_online),
-                false => None,
-            };
-            Ok(KbartOclcRow {
-                publication_title: match work.subtitle {
-                    Some(subtitle) => format!("{}: {}", work.title, subtitle),
-                    None => work.full_title,
-                },
-                print_identifier,
-                online_identifier,
-                date_first_issue_online: None,
-                num_first_vol_online: None,
-                num_first_issue_online: None,
-                date_last_issue_online: None,
-                num_last_vol_online: None,
-                num_last_issue_online: None,
-                title_url: work.landing_page.unwrap(),
-                first_author,
-                title_id: work.doi.map(|d| d.to_string()),
-                embargo_info: None,
-                coverage_depth: "fulltext".to_string(),
-                notes: None,
-                publisher_name: Some(work.imprint.publisher.publisher_name),
-                publication_type: match work.work_type {
-                    WorkType::BOOK_SET => "Serial".to_string(),
-                    _ => "Monograph".to_string(),
-                },
-                date_monograph_published_print,
-                date_monograph_published_online,
-                // Note that it is possible for a work to belong to more than one series.
-                // Only one series can be listed in KBART, so we select the first one found (if any).
-                monograph_volume: work.issues.first().map(|i| i.issue_ordinal),
-                monograph_edition: Some(work.edition),
-                first_editor,
-                // This should match the series' `title_id` if also provided in the KBART.
-                parent_publication_title_id: work
-                    .issues
-                    .first()
-                    .map(|i| i.series.issn_digital.to_string()),
-                preceding_publication_title_id: None,
-                access_type: "F".to_string(),
-            })
         }
+        let date_monograph_published_online = work
+            .publication_date
+            .map(|date| chrono::Datelike::year(&date).into())
+            .unwrap();
+        let date_monograph_published_print = match print_edition_exists {
+            true => Some(date_monograph_published_online),
+            false => None,
+        };
+        Ok(KbartOclcRow {
+            publication_title: match work.subtitle {
+                Some(subtitle) => format!("{}: {}", work.title, subtitle),
+                None => work.full_title,
+            },
+            print_identifier,
+            online_identifier,
+            date_first_issue_online: None,
+            num_first_vol_online: None,
+            num_first_issue_online: None,
+            date_last_issue_online: None,
+            num_last_vol_online: None,
+            num_last_issue_online: None,
+            title_url: work.landing_page.unwrap(),
+            first_author,
+            title_id: work.doi.map(|d| d.to_string()),
+            embargo_info: None,
+            coverage_depth: "fulltext".to_string(),
+            notes: None,
+            publisher_name: Some(work.imprint.publisher.publisher_name),
+            publication_type: match work.work_type {
+                WorkType::BOOK_SET => "Serial".to_string(),
+                _ => "Monograph".to_string(),
+            },
+            date_monograph_published_print,
+            date_monograph_published_online,
+            // Note that it is possible for a work to belong to more than one series.
+            // Only one series can be listed in KBART, so we select the first one found (if any).
+            monograph_volume: work.issues.first().map(|i| i.issue_ordinal),
+            monograph_edition: Some(work.edition),
+            first_editor,
+            // This should match the series' `title_id` if also provided in the KBART.
+            parent_publication_title_id: work
+                .issues
+                .first()
+                .map(|i| i.series.issn_digital.to_string()),
+            preceding_publication_title_id: None,
+            access_type: "F".to_string(),
+        })
     }
 }
 
@@ -256,6 +259,8 @@ mod tests {
             height_in: Some(9.21),
             page_count: Some(334),
             page_breakdown: Some("x+334".to_string()),
+            first_page: None,
+            last_page: None,
             image_count: Some(15),
             table_count: None,
             audio_count: None,
@@ -266,9 +271,14 @@ mod tests {
             oclc: None,
             cover_url: Some("https://www.book.com/cover".to_string()),
             cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
             imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
                 imprint_name: "OA Editions Imprint".to_string(),
                 publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
                     publisher_name: "OA Editions".to_string(),
                     publisher_url: None,
                 },
@@ -374,6 +384,8 @@ mod tests {
             ],
             subjects: vec![],
             fundings: vec![],
+            relations: vec![],
+            parents: vec![],
         };
         let mut test_result = TestResult {
             headers: "publication_title\tprint_identifier\tonline_identifier\tdate_first_issue_online\tnum_first_vol_online\tnum_first_issue_online\tdate_last_issue_online\tnum_last_vol_online\tnum_last_issue_online\ttitle_url\tfirst_author\ttitle_id\tembargo_info\tcoverage_depth\tnotes\tpublisher_name\tpublication_type\tdate_monograph_published_print\tdate_monograph_published_online\tmonograph_volume\tmonograph_edition\tfirst_editor\tparent_publication_title_id\tpreceding_publication_title_id\taccess_type\n".to_string(),
@@ -458,5 +470,15 @@ mod tests {
                 "Missing Publication Date".to_string(),
             ))
         );
+
+        // No works supplied: error refers to this specification
+        let to_test = KbartOclc.generate(&[], QuoteStyle::Necessary, DELIMITER_TAB);
+        assert_eq!(
+            to_test,
+            Err(ThothError::IncompleteMetadataRecord(
+                "kbart::oclc".to_string(),
+                "Not enough data".to_string(),
+            ))
+        );
     }
 }
@@ -1,8 +1,11 @@
 use csv::{QuoteStyle, Writer, WriterBuilder};
 use std::io::Write;
-use thoth_client::Work;
+use std::marker::PhantomData;
+use thoth_client::{DeletedWork, Work};
 use thoth_errors::{ThothError, ThothResult};
 
+use crate::record::RecordWriter;
+
 pub(crate) trait CsvSpecification {
     fn generate(
         &self,
@@ -14,7 +17,7 @@ pub(crate) trait CsvSpecification {
             .quote_style(quote_style)
             .delimiter(delimiter)
             .from_writer(Vec::new());
-        Self::handle_event(&mut writer, works)
+        self.handle_event(&mut writer, works)
             .map(|_| writer.into_inner().map_err(|e| e.error().into()))
             .and_then(|val| val)
             .and_then(|csv| {
@@ -23,7 +26,7 @@ pub(crate) trait CsvSpecification {
             })
     }
 
-    fn handle_event<W: Write>(w: &mut Writer<W>, works: &[Work]) -> ThothResult<()>;
+    fn handle_event<W: Write>(&self, w: &mut Writer<W>, works: &[Work]) -> ThothResult<()>;
 }
 
 pub(crate) trait CsvRow<T: CsvSpecification> {
@@ -34,7 +37,53 @@ pub(crate) trait CsvCell<T: CsvSpecification> {
     fn csv_cell(&self) -> String;
 }
 
+/// Writes a CSV specification one page of works at a time, with its headers before the first row
+pub(crate) struct CsvStreamWriter<T: CsvSpecification> {
+    quote_style: QuoteStyle,
+    delimiter: u8,
+    headers_written: bool,
+    specification: PhantomData<T>,
+}
+
+impl<T: CsvSpecification> CsvStreamWriter<T> {
+    pub(crate) fn new(quote_style: QuoteStyle, delimiter: u8) -> Self {
+        CsvStreamWriter {
+            quote_style,
+            delimiter,
+            headers_written: false,
+            specification: PhantomData,
+        }
+    }
+}
+
+impl<T: CsvSpecification> RecordWriter for CsvStreamWriter<T>
+where
+    Work: CsvRow<T>,
+{
+    fn write_page(&mut self, works: &[Work]) -> ThothResult<Vec<u8>> {
+        let mut writer = WriterBuilder::new()
+            .quote_style(self.quote_style)
+            .delimiter(self.delimiter)
+            .has_headers(!self.headers_written)
+            .from_writer(Vec::new());
+        for work in works.iter() {
+            CsvRow::<T>::csv_row(work, &mut writer).ok();
+        }
+        let output = writer
+            .into_inner()
+            .map_err(|e| ThothError::from(e.error()))?;
+        self.headers_written |= !output.is_empty();
+        Ok(output)
+    }
+
+    fn finish(&mut self, _: &[DeletedWork]) -> ThothResult<Vec<u8>> {
+        Ok(Vec::new())
+    }
+}
+
 mod csv_thoth;
 pub(crate) use csv_thoth::CsvThoth;
 mod kbart_oclc;
 pub(crate) use kbart_oclc::KbartOclc;
+mod kbart_niso;
+pub(crate) use kbart_niso::KbartNiso;
@@ -1,229 +1,344 @@
-use lazy_static::lazy_static;
+use serde::Deserialize;
+use std::fs;
 use thoth_errors::{ThothError, ThothResult};
+use uuid::Uuid;
 
 use crate::format::model::Format;
 use crate::platform::model::Platform;
 use crate::specification::model::Specification;
 
-lazy_static! {
-    pub(crate) static ref ALL_SPECIFICATIONS: Vec<Specification<'static>> = vec![
-        Specification {
-            id: "onix_3.0::project_muse",
-            name: "Project MUSE ONIX 3.0",
-            format: concat!(env!("THOTH_EXPORT_API"), "/formats/onix_3.0"),
-            accepted_by: vec![
-                concat!(env!("THOTH_EXPORT_API"), "/platforms/project_muse"),
-                concat!(env!("THOTH_EXPORT_API"), "/platforms/jstor"),
-            ],
-        },
-        Specification {
-            id: "onix_3.0::oapen",
-            name: "OAPEN ONIX 3.0",
-            format: concat!(env!("THOTH_EXPORT_API"), "/formats/onix_3.0"),
-            accepted_by: vec![
-                concat!(env!("THOTH_EXPORT_API"), "/platforms/oapen"),
-                concat!(env!("THOTH_EXPORT_API"), "/platforms/doab"),
-            ],
-        },
-        Specification {
-            id: "onix_3.0::jstor",
-            name: "JSTOR ONIX 3.0",
-            format: concat!(env!("THOTH_EXPORT_API"), "/formats/onix_3.0"),
-            accepted_by: vec![concat!(env!("THOTH_EXPORT_API"), "/platforms/jstor"),],
-        },
-        Specification {
-            id: "onix_2.1::ebsco_host",
-            name: "EBSCO Host ONIX 2.1",
-            format: concat!(env!("THOTH_EXPORT_API"), "/formats/onix_2.1"),
-            accepted_by: vec![concat!(env!("THOTH_EXPORT_API"), "/platforms/ebsco_host"),],
-        },
-        Specification {
-            id: "csv::thoth",
-            name: "Thoth CSV",
-            format: concat!(env!("THOTH_EXPORT_API"), "/formats/csv"),
-            accepted_by: vec![concat!(env!("THOTH_EXPORT_API"), "/platforms/thoth"),],
-        },
-        Specification {
-            id: "kbart::oclc",
-            name: "OCLC KBART",
-            format: concat!(env!("THOTH_EXPORT_API"), "/formats/kbart"),
-            accepted_by: vec![
-                concat!(env!("THOTH_EXPORT_API"), "/platforms/oclc_kb"),
-                concat!(env!("THOTH_EXPORT_API"), "/platforms/proquest_kb"),
-                concat!(env!("THOTH_EXPORT_API"), "/platforms/proquest_exlibris"),
-                concat!(env!("THOTH_EXPORT_API"), "/platforms/ebsco_kb"),
-                concat!(env!("THOTH_EXPORT_API"), "/platforms/jisc_kb"),
-            ],
-        },
-    ];
-    pub(crate) static ref ALL_PLATFORMS: Vec<Platform<'static>> = vec![
-        Platform {
-            id: "thoth",
-            name: "Thoth",
-            accepts: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/csv::thoth"
-            ),],
-        },
-        Platform {
-            id: "project_muse",
-            name: "Project MUSE",
-            accepts: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/onix_3.0::project_muse"
-            ),],
-        },
-        Platform {
-            id: "oapen",
-            name: "OAPEN",
-            accepts: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/onix_3.0::oapen"
-            ),],
-        },
-        Platform {
-            id: "doab",
-            name: "DOAB",
-            accepts: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/onix_3.0::oapen"
-            ),],
-        },
-        Platform {
-            id: "jstor",
-            name: "JSTOR",
-            accepts: vec![
-                concat!(env!("THOTH_EXPORT_API"), "/specifications/onix_3.0::jstor"),
-                concat!(
-                    env!("THOTH_EXPORT_API"),
-                    "/specifications/onix_3.0::project_muse"
-                ),
-            ],
-        },
-        Platform {
-            id: "ebsco_host",
-            name: "EBSCO Host",
-            accepts: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/onix_2.1::ebsco_host"
-            ),],
-        },
-        Platform {
-            id: "oclc_kb",
-            name: "OCLC KB",
-            accepts: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/kbart::oclc"
-            ),],
-        },
-        Platform {
-            id: "proquest_kb",
-            name: "ProQuest KB",
-            accepts: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/kbart::oclc"
-            ),],
-        },
-        Platform {
-            id: "proquest_exlibris",
-            name: "ProQuest ExLibris",
-            accepts: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/kbart::oclc"
-            ),],
-        },
-        Platform {
-            id: "ebsco_kb",
-            name: "EBSCO KB",
-            accepts: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/kbart::oclc"
-            ),],
-        },
-        Platform {
-            id: "jisc_kb",
-            name: "JISC KB",
-            accepts: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/kbart::oclc"
-            ),],
-        },
-    ];
-    pub(crate) static ref ALL_FORMATS: Vec<Format<'static>> = vec![
-        Format {
-            id: "onix_3.0",
-            name: "ONIX",
-            version: Some("3.0"),
-            specifications: vec![
-                concat!(
-                    env!("THOTH_EXPORT_API"),
-                    "/specifications/onix_3.0::project_muse"
-                ),
-                concat!(env!("THOTH_EXPORT_API"), "/specifications/onix_3.0::oapen"),
-                concat!(env!("THOTH_EXPORT_API"), "/specifications/onix_3.0::jstor"),
-            ],
-        },
-        Format {
-            id: "onix_2.1",
-            name: "ONIX",
-            version: Some("2.1"),
-            specifications: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/onix_2.1::ebsco_host"
-            ),],
-        },
-        Format {
-            id: "csv",
-            name: "CSV",
-            version: None,
-            specifications: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/csv::thoth"
-            ),],
-        },
-        Format {
-            id: "kbart",
-            name: "KBART",
-            version: None,
-            specifications: vec![concat!(
-                env!("THOTH_EXPORT_API"),
-                "/specifications/kbart::oclc"
-            ),],
-        },
-    ];
+/// Built-in specifications: ID, name, format ID and IDs of the platforms that accept it
+const SPECIFICATIONS: &[(&str, &str, &str, &[&str])] = &[
+    (
+        "onix_3.0::project_muse",
+        "Project MUSE ONIX 3.0",
+        "onix_3.0",
+        &["project_muse", "jstor"],
+    ),
+    (
+        "onix_3.0::oapen",
+        "OAPEN ONIX 3.0",
+        "onix_3.0",
+        &["oapen", "doab"],
+    ),
+    ("onix_3.0::jstor", "JSTOR ONIX 3.0", "onix_3.0", &["jstor"]),
+    ("onix_3.0::thoth", "Thoth ONIX 3.0", "onix_3.0", &["thoth"]),
+    (
+        "onix_3.0::google_books",
+        "Google Books ONIX 3.0",
+        "onix_3.0",
+        &["google_books"],
+    ),
+    (
+        "onix_3.0::proquest_ebrary",
+        "ProQuest Ebrary ONIX 3.0",
+        "onix_3.0",
+        &["proquest_ebrary"],
+    ),
+    (
+        "onix_2.1::ebsco_host",
+        "EBSCO Host ONIX 2.1",
+        "onix_2.1",
+        &["ebsco_host"],
+    ),
+    ("csv::thoth", "Thoth CSV", "csv", &["thoth"]),
+    (
+        "kbart::oclc",
+        "OCLC KBART",
+        "kbart",
+        &[
+            "oclc_kb",
+            "proquest_kb",
+            "proquest_exlibris",
+            "ebsco_kb",
+            "jisc_kb",
+        ],
+    ),
+    // Accepted by the same knowledge bases as `kbart::oclc`, each with its own settings
+    (
+        "kbart::niso",
+        "NISO KBART Phase II",
+        "kbart",
+        &[
+            "oclc_kb",
+            "proquest_kb",
+            "proquest_exlibris",
+            "ebsco_kb",
+            "jisc_kb",
+        ],
+    ),
+    (
+        "marc21xml::thoth",
+        "Thoth MARC 21 XML",
+        "marc21xml",
+        &["thoth"],
+    ),
+    ("marc21::thoth", "Thoth MARC 21", "marc21", &["thoth"]),
+    (
+        "crossref::thoth",
+        "Crossref DOI deposit",
+        "crossref",
+        &["crossref"],
+    ),
+    ("datacite::thoth", "DataCite XML", "datacite", &["datacite"]),
+    (
+        "datacite::thoth_json",
+        "DataCite JSON",
+        "datacite",
+        &["datacite"],
+    ),
+    ("bibtex::thoth", "Thoth BibTeX", "bibtex", &["thoth"]),
+    ("ris::thoth", "Thoth RIS", "ris", &["thoth"]),
+    ("csl_json::thoth", "Thoth CSL-JSON", "csl_json", &["thoth"]),
+    ("oai_dc::thoth", "Thoth Dublin Core", "oai_dc", &["thoth"]),
+    ("mods::thoth", "Thoth MODS", "mods", &["thoth"]),
+    (
+        "jsonld::schema_org",
+        "Schema.org JSON-LD",
+        "jsonld",
+        &["thoth"],
+    ),
+];
+
+/// Built-in platforms: ID and name
+const PLATFORMS: &[(&str, &str)] = &[
+    ("thoth", "Thoth"),
+    ("project_muse", "Project MUSE"),
+    ("oapen", "OAPEN"),
+    ("doab", "DOAB"),
+    ("jstor", "JSTOR"),
+    ("google_books", "Google Books"),
+    ("proquest_ebrary", "ProQuest Ebrary"),
+    ("ebsco_host", "EBSCO Host"),
+    ("oclc_kb", "OCLC KB"),
+    ("proquest_kb", "ProQuest KB"),
+    ("proquest_exlibris", "ProQuest ExLibris"),
+    ("ebsco_kb", "EBSCO KB"),
+    ("jisc_kb", "JISC KB"),
+    ("crossref", "Crossref"),
+    ("datacite", "DataCite"),
+];
+
+/// Built-in formats: slug, name and version. A format's ID is its slug followed by its version.
+const FORMATS: &[(&str, &str, Option<&str>)] = &[
+    ("onix", "ONIX", Some("3.0")),
+    ("onix", "ONIX", Some("2.1")),
+    ("csv", "CSV", None),
+    ("kbart", "KBART", None),
+    ("marc21xml", "MARC 21 XML", None),
+    ("marc21", "MARC 21", None),
+    ("crossref", "Crossref", None),
+    ("datacite", "DataCite", None),
+    ("bibtex", "BibTeX", None),
+    ("ris", "RIS", None),
+    ("csl_json", "CSL-JSON", None),
+    ("oai_dc", "OAI Dublin Core", None),
+    ("mods", "MODS", None),
+    ("jsonld", "JSON-LD", None),
+];
+
+/// ID of a built-in format, from its slug and version
+fn format_id(slug: &str, version: Option<&str>) -> String {
+    match version {
+        Some(version) => format!("{}_{}", slug, version),
+        None => slug.to_string(),
+    }
 }
 
-pub(crate) fn find_format(format_id: String) -> ThothResult<Format<'static>> {
-    ALL_FORMATS
-        .iter()
-        .find(|f| f.id == format_id)
-        .cloned()
-        .ok_or(ThothError::EntityNotFound)
+/// Platform mapping declared in a configuration file, either adding a new platform or
+/// extending the specifications accepted by a built-in one
+#[derive(Debug, Deserialize)]
+pub(crate) struct PlatformConfig {
+    pub(crate) id: String,
+    /// Required for platforms not already supported
+    pub(crate) name: Option<String>,
+    /// IDs of the specifications accepted by the platform
+    #[serde(default)]
+    pub(crate) accepts: Vec<String>,
 }
 
-pub(crate) fn find_platform(platform_id: String) -> ThothResult<Platform<'static>> {
-    ALL_PLATFORMS
-        .iter()
-        .find(|p| p.id == platform_id)
-        .cloned()
-        .ok_or(ThothError::EntityNotFound)
+/// Export API configuration file.
+///
+/// Only platform mappings can be configured: formats and specifications are built in, as each
+/// specification needs its own generator.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct RegistryConfig {
+    #[serde(default)]
+    pub(crate) platforms: Vec<PlatformConfig>,
+}
+
+impl RegistryConfig {
+    pub(crate) fn from_file(path: &str) -> ThothResult<Self> {
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(|e| e.into())
+    }
 }
 
-pub(crate) fn find_specification(specification_id: String) -> ThothResult<Specification<'static>> {
-    ALL_SPECIFICATIONS
-        .iter()
-        .find(|s| s.id == specification_id)
-        .cloned()
-        .ok_or(ThothError::InvalidMetadataSpecification(specification_id))
+/// Formats, platforms and specifications supported by the export API, with their URLs
+/// built from the public facing root URL of the running instance
+#[derive(Clone)]
+pub(crate) struct Registry {
+    root: String,
+    formats: Vec<Format>,
+    platforms: Vec<Platform>,
+    specifications: Vec<Specification>,
+}
+
+impl Registry {
+    pub(crate) fn new(public_url: &str, config: &RegistryConfig) -> ThothResult<Self> {
+        let root = public_url.trim_end_matches('/');
+        let url = |kind: &str, id: &str| format!("{}/{}/{}", root, kind, id);
+
+        // specification ID -> IDs of the platforms accepting it
+        let mut accepted_by: Vec<(&str, Vec<String>)> = SPECIFICATIONS
+            .iter()
+            .map(|(id, _, _, platforms)| (*id, platforms.iter().map(|p| p.to_string()).collect()))
+            .collect();
+        let mut platforms: Vec<(String, String)> = PLATFORMS
+            .iter()
+            .map(|(id, name)| (id.to_string(), name.to_string()))
+            .collect();
+
+        for platform_config in &config.platforms {
+            if !platforms.iter().any(|(id, _)| id == &platform_config.id) {
+                let name = platform_config.name.clone().ok_or_else(|| {
+                    ThothError::InternalError(format!(
+                        "Platform {} must have a name",
+                        platform_config.id
+                    ))
+                })?;
+                platforms.push((platform_config.id.clone(), name));
+            } else if let Some(name) = &platform_config.name {
+                if let Some(platform) = platforms
+                    .iter_mut()
+                    .find(|(id, _)| id == &platform_config.id)
+                {
+                    platform.1 = name.clone();
+                }
+            }
+            for specification_id in &platform_config.accepts {
+                let (_, platform_ids) = accepted_by
+                    .iter_mut()
+                    .find(|(id, _)| id == specification_id)
+                    .ok_or_else(|| {
+                        ThothError::InvalidMetadataSpecification(specification_id.clone())
+                    })?;
+                if !platform_ids.contains(&platform_config.id) {
+                    platform_ids.push(platform_config.id.clone());
+                }
+            }
+        }
+
+        let specifications = SPECIFICATIONS
+            .iter()
+            .zip(accepted_by.iter())
+            .map(
+                |((id, name, format_id, _), (_, platform_ids))| Specification {
+                    id: id.to_string(),
+                    name: name.to_string(),
+                    format: url("formats", format_id),
+                    accepted_by: platform_ids
+                        .iter()
+                        .map(|platform_id| url("platforms", platform_id))
+                        .collect(),
+                },
+            )
+            .collect();
+        let platforms = platforms
+            .into_iter()
+            .map(|(platform_id, name)| Platform {
+                accepts: accepted_by
+                    .iter()
+                    .filter(|(_, platform_ids)| platform_ids.contains(&platform_id))
+                    .map(|(specification_id, _)| url("specifications", specification_id))
+                    .collect(),
+                id: platform_id,
+                name,
+            })
+            .collect();
+        let formats = FORMATS
+            .iter()
+            .map(|(slug, name, version)| {
+                let id = format_id(slug, *version);
+                Format {
+                    specifications: SPECIFICATIONS
+                        .iter()
+                        .filter(|(_, _, specification_format, _)| specification_format == &id)
+                        .map(|(specification_id, _, _, _)| url("specifications", specification_id))
+                        .collect(),
+                    id,
+                    name: name.to_string(),
+                    version: version.map(|v| v.to_string()),
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            root: root.to_string(),
+            formats,
+            platforms,
+            specifications,
+        })
+    }
+
+    /// Canonical URL of a work, at which its metadata is served in any negotiable format
+    pub(crate) fn work_url(&self, work_id: &Uuid) -> String {
+        format!("{}/works/{}", self.root, work_id)
+    }
+
+    pub(crate) fn formats(&self) -> &[Format] {
+        &self.formats
+    }
+
+    pub(crate) fn platforms(&self) -> &[Platform] {
+        &self.platforms
+    }
+
+    pub(crate) fn specifications(&self) -> &[Specification] {
+        &self.specifications
+    }
+
+    pub(crate) fn find_format(&self, format_id: String) -> ThothResult<Format> {
+        self.formats
+            .iter()
+            .find(|f| f.id == format_id)
+            .cloned()
+            .ok_or(ThothError::EntityNotFound)
+    }
+
+    pub(crate) fn find_platform(&self, platform_id: String) -> ThothResult<Platform> {
+        self.platforms
+            .iter()
+            .find(|p| p.id == platform_id)
+            .cloned()
+            .ok_or(ThothError::EntityNotFound)
+    }
+
+    pub(crate) fn find_specification(
+        &self,
+        specification_id: String,
+    ) -> ThothResult<Specification> {
+        self.specifications
+            .iter()
+            .find(|s| s.id == specification_id)
+            .cloned()
+            .ok_or(ThothError::InvalidMetadataSpecification(specification_id))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::csv::KbartNiso;
 
-    const FORMATS_PREFIX: &str = concat!(env!("THOTH_EXPORT_API"), "/formats/");
-    const PLATFORMS_PREFIX: &str = concat!(env!("THOTH_EXPORT_API"), "/platforms/");
-    const SPECIFICATIONS_PREFIX: &str = concat!(env!("THOTH_EXPORT_API"), "/specifications/");
+    const PUBLIC_URL: &str = "https://export.thoth.pub";
+    const FORMATS_PREFIX: &str = "https://export.thoth.pub/formats/";
+    const PLATFORMS_PREFIX: &str = "https://export.thoth.pub/platforms/";
+    const SPECIFICATIONS_PREFIX: &str = "https://export.thoth.pub/specifications/";
+
+    fn registry() -> Registry {
+        Registry::new(PUBLIC_URL, &RegistryConfig::default()).unwrap()
+    }
 
     fn format_id_from_url(url: &str) -> String {
         url.replace(FORMATS_PREFIX, "")
@@ -239,74 +354,173 @@ mod tests {
 
     #[test]
     fn test_specification_format_in_all_formats() {
-        for s in ALL_SPECIFICATIONS.iter() {
-            let format_id = format_id_from_url(s.format);
-            assert!(find_format(format_id).is_ok())
+        let registry = registry();
+        for s in registry.specifications() {
+            let format_id = format_id_from_url(&s.format);
+            assert!(registry.find_format(format_id).is_ok())
         }
     }
 
     #[test]
     fn test_specification_platforms_in_all_platforms() {
-        for s in ALL_SPECIFICATIONS.iter() {
+        let registry = registry();
+        for s in registry.specifications() {
             for accepted_platform in &s.accepted_by {
                 let platform_id = platform_id_from_url(accepted_platform);
-                assert!(find_platform(platform_id).is_ok())
+                assert!(registry.find_platform(platform_id).is_ok())
             }
         }
     }
 
     #[test]
     fn test_specification_id_begins_with_format_id() {
-        for s in ALL_SPECIFICATIONS.iter() {
-            let format_id = format_id_from_url(s.format);
+        for s in registry().specifications() {
+            let format_id = format_id_from_url(&s.format);
             assert!(s.id.starts_with(&format_id));
         }
     }
 
     #[test]
     fn test_all_specifications_listed_in_formats() {
-        for s in ALL_SPECIFICATIONS.iter() {
-            let specification_id = s.id;
-            let format_id = format_id_from_url(s.format);
-            let format = find_format(format_id).unwrap();
+        let registry = registry();
+        for s in registry.specifications() {
+            let format_id = format_id_from_url(&s.format);
+            let format = registry.find_format(format_id).unwrap();
             assert!(format
                 .specifications
                 .iter()
-                .find(|specification| specification_id_from_url(specification) == specification_id)
-                .cloned()
-                .ok_or(ThothError::EntityNotFound)
-                .is_ok())
+                .any(|specification| specification_id_from_url(specification) == s.id))
         }
     }
 
     #[test]
     fn test_platform_specifications_in_all_specifications() {
-        for p in ALL_PLATFORMS.iter() {
+        let registry = registry();
+        for p in registry.platforms() {
             for s in &p.accepts {
                 let specification_id = specification_id_from_url(s);
-                assert!(find_specification(specification_id).is_ok())
+                assert!(registry.find_specification(specification_id).is_ok())
             }
         }
     }
 
     #[test]
     fn test_format_specifications_in_all_specifications() {
-        for f in ALL_FORMATS.iter() {
+        let registry = registry();
+        for f in registry.formats() {
             for s in &f.specifications {
                 let specification_id = specification_id_from_url(s);
-                assert!(find_specification(specification_id).is_ok())
+                assert!(registry.find_specification(specification_id).is_ok())
             }
         }
     }
 
     #[test]
-    fn test_format_id_derives_from_name_and_version() {
-        for f in ALL_FORMATS.iter() {
-            let id_should_be = match f.version {
-                Some(version) => format!("{}_{}", f.name.to_lowercase(), version),
-                None => f.name.to_lowercase().to_string(),
+    fn test_format_id_derives_from_slug_and_version() {
+        for (f, (slug, name, _)) in registry().formats().iter().zip(FORMATS.iter()) {
+            let id_should_be = match &f.version {
+                Some(version) => format!("{}_{}", slug, version),
+                None => slug.to_string(),
             };
-            assert_eq!(String::from(f.id), id_should_be)
+            assert_eq!(f.id, id_should_be);
+            assert_eq!(&f.name, name)
+        }
+    }
+
+    #[test]
+    fn test_kbart_niso_platforms_have_settings() {
+        let specification = registry()
+            .find_specification("kbart::niso".to_string())
+            .unwrap();
+        for accepted_platform in &specification.accepted_by {
+            let platform_id = platform_id_from_url(accepted_platform);
+            assert!(KbartNiso::for_platform(&platform_id).is_ok())
         }
     }
+
+    #[test]
+    fn test_urls_use_public_url() {
+        let registry = Registry::new("http://localhost:8181/", &RegistryConfig::default()).unwrap();
+        let specification = registry
+            .find_specification("onix_3.0::oapen".to_string())
+            .unwrap();
+        assert_eq!(
+            specification.format,
+            "http://localhost:8181/formats/onix_3.0"
+        );
+        assert_eq!(
+            specification.accepted_by,
+            vec![
+                "http://localhost:8181/platforms/oapen".to_string(),
+                "http://localhost:8181/platforms/doab".to_string(),
+            ]
+        );
+        assert_eq!(
+            registry.work_url(&Uuid::nil()),
+            "http://localhost:8181/works/00000000-0000-0000-0000-000000000000"
+        );
+    }
+
+    #[test]
+    fn test_registry_config_adds_platform() {
+        let config: RegistryConfig = serde_json::from_str(
+            r#"{"platforms": [{"id": "internet_archive", "name": "Internet Archive", "accepts": ["marc21xml::thoth", "oai_dc::thoth"]}]}"#,
+        )
+        .unwrap();
+        let registry = Registry::new(PUBLIC_URL, &config).unwrap();
+        let platform = registry
+            .find_platform("internet_archive".to_string())
+            .unwrap();
+        assert_eq!(platform.name, "Internet Archive");
+        assert_eq!(
+            platform.accepts,
+            vec![
+                "https://export.thoth.pub/specifications/marc21xml::thoth".to_string(),
+                "https://export.thoth.pub/specifications/oai_dc::thoth".to_string(),
+            ]
+        );
+        assert!(registry
+            .find_specification("oai_dc::thoth".to_string())
+            .unwrap()
+            .accepted_by
+            .contains(&"https://export.thoth.pub/platforms/internet_archive".to_string()));
+    }
+
+    #[test]
+    fn test_registry_config_extends_platform() {
+        let config: RegistryConfig = serde_json::from_str(
+            r#"{"platforms": [{"id": "oapen", "accepts": ["marc21xml::thoth"]}]}"#,
+        )
+        .unwrap();
+        let registry = Registry::new(PUBLIC_URL, &config).unwrap();
+        let platform = registry.find_platform("oapen".to_string()).unwrap();
+        assert_eq!(platform.name, "OAPEN");
+        assert_eq!(
+            platform.accepts,
+            vec![
+                "https://export.thoth.pub/specifications/onix_3.0::oapen".to_string(),
+                "https://export.thoth.pub/specifications/marc21xml::thoth".to_string(),
+            ]
+        );
+        assert_eq!(registry.platforms().len(), PLATFORMS.len());
+    }
+
+    #[test]
+    fn test_registry_config_errors() {
+        let unnamed: RegistryConfig =
+            serde_json::from_str(r#"{"platforms": [{"id": "new_platform", "accepts": []}]}"#)
+                .unwrap();
+        assert!(matches!(
+            Registry::new(PUBLIC_URL, &unnamed),
+            Err(ThothError::InternalError(_))
+        ));
+        let unknown_specification: RegistryConfig = serde_json::from_str(
+            r#"{"platforms": [{"id": "oapen", "accepts": ["onix_3.0::unknown"]}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            Registry::new(PUBLIC_URL, &unknown_specification),
+            Err(ThothError::InvalidMetadataSpecification(id)) if id == "onix_3.0::unknown"
+        ));
+    }
 }
@@ -0,0 +1,698 @@
+use serde::Serialize;
+use thoth_api::model::{DOI_DOMAIN, ORCID_DOMAIN, ROR_DOMAIN};
+use thoth_client::{
+    ContributionType, SubjectType, Work, WorkContributions, WorkFundings, WorkType,
+};
+use thoth_errors::{ThothError, ThothResult};
+
+pub(crate) const DATACITE_SCHEMA_VERSION: &str = "http://datacite.org/schema/kernel-4";
+
+/// A DataCite metadata kernel 4.4 record, independently of its serialisation
+///
+/// Field names follow DataCite's JSON representation, from which the XML names can be derived.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteRecord {
+    pub(crate) doi: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) url: Option<String>,
+    pub(crate) types: DataCiteTypes,
+    pub(crate) creators: Vec<DataCiteName>,
+    pub(crate) titles: Vec<DataCiteTitle>,
+    pub(crate) publisher: String,
+    pub(crate) publication_year: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) subjects: Vec<DataCiteSubject>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) contributors: Vec<DataCiteName>,
+    pub(crate) dates: Vec<DataCiteDate>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) alternate_identifiers: Vec<DataCiteAlternateIdentifier>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) rights_list: Vec<DataCiteRights>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) descriptions: Vec<DataCiteDescription>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) funding_references: Vec<DataCiteFundingReference>,
+    pub(crate) schema_version: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteTypes {
+    pub(crate) resource_type_general: &'static str,
+    pub(crate) resource_type: &'static str,
+}
+
+/// A creator or contributor
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteName {
+    pub(crate) name: String,
+    pub(crate) name_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) given_name: Option<String>,
+    pub(crate) family_name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) name_identifiers: Vec<DataCiteNameIdentifier>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) affiliation: Vec<DataCiteAffiliation>,
+    /// Only present for contributors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) contributor_type: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteNameIdentifier {
+    pub(crate) name_identifier: String,
+    pub(crate) name_identifier_scheme: &'static str,
+    pub(crate) scheme_uri: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteAffiliation {
+    pub(crate) name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) affiliation_identifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) affiliation_identifier_scheme: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) scheme_uri: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteTitle {
+    pub(crate) title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) title_type: Option<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteSubject {
+    pub(crate) subject: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) subject_scheme: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) scheme_uri: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) classification_code: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteDate {
+    pub(crate) date: String,
+    pub(crate) date_type: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteAlternateIdentifier {
+    pub(crate) alternate_identifier: String,
+    pub(crate) alternate_identifier_type: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteRights {
+    pub(crate) rights: String,
+    pub(crate) rights_uri: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteDescription {
+    pub(crate) description: String,
+    pub(crate) description_type: &'static str,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DataCiteFundingReference {
+    pub(crate) funder_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) funder_identifier: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) funder_identifier_type: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) award_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) award_title: Option<String>,
+}
+
+/// Fields that DataCite requires and Thoth does not, as reported by `DataCiteRecord::from_work`
+pub(crate) fn missing_fields(work: &Work) -> Vec<&'static str> {
+    let mut missing = Vec::new();
+    if work.doi.is_none() {
+        missing.push("Missing DOI");
+    }
+    if work.publication_date.is_none() {
+        missing.push("Missing Publication Date");
+    }
+    // Edited books without authors are credited to their editors
+    if !work.contributions.iter().any(|c| {
+        c.contribution_type == ContributionType::AUTHOR
+            || c.contribution_type == ContributionType::EDITOR
+    }) {
+        missing.push("Missing Creator");
+    }
+    missing
+}
+
+impl DataCiteRecord {
+    /// Map a work to DataCite properties, reporting any missing data against the given specification
+    pub(crate) fn from_work(work: &Work, specification_id: &str) -> ThothResult<Self> {
+        let doi = work.doi.as_ref().ok_or_else(|| {
+            ThothError::IncompleteMetadataRecord(
+                specification_id.to_string(),
+                "Missing DOI".to_string(),
+            )
+        })?;
+        let publication_date = work.publication_date.ok_or_else(|| {
+            ThothError::IncompleteMetadataRecord(
+                specification_id.to_string(),
+                "Missing Publication Date".to_string(),
+            )
+        })?;
+
+        let mut contributions = work.contributions.clone();
+        contributions.sort_by_key(|c| c.contribution_ordinal);
+        // Creators are mandatory: edited books without authors are credited to their editors
+        let creator_type = if contributions
+            .iter()
+            .any(|c| c.contribution_type == ContributionType::AUTHOR)
+        {
+            ContributionType::AUTHOR
+        } else {
+            ContributionType::EDITOR
+        };
+        let creators: Vec<DataCiteName> = contributions
+            .iter()
+            .filter(|c| c.contribution_type == creator_type)
+            .map(|c| DataCiteName::from_contribution(c, None))
+            .collect();
+        if creators.is_empty() {
+            return Err(ThothError::IncompleteMetadataRecord(
+                specification_id.to_string(),
+                "Missing Creator".to_string(),
+            ));
+        }
+        let contributors = contributions
+            .iter()
+            .filter(|c| c.contribution_type != creator_type)
+            .map(|c| {
+                DataCiteName::from_contribution(c, Some(contributor_type(&c.contribution_type)))
+            })
+            .collect();
+
+        let mut titles = vec![DataCiteTitle {
+            title: work.title.clone(),
+            title_type: None,
+        }];
+        if let Some(subtitle) = &work.subtitle {
+            titles.push(DataCiteTitle {
+                title: subtitle.clone(),
+                title_type: Some("Subtitle"),
+            });
+        }
+
+        let mut subjects = work.subjects.clone();
+        subjects.sort_by_key(|s| s.subject_ordinal);
+        let subjects = subjects
+            .iter()
+            .map(|s| match &s.subject_type {
+                SubjectType::KEYWORD | SubjectType::CUSTOM => DataCiteSubject {
+                    subject: s.subject_code.clone(),
+                    subject_scheme: None,
+                    scheme_uri: None,
+                    classification_code: None,
+                },
+                subject_type => {
+                    let (subject_scheme, scheme_uri) = subject_scheme(subject_type);
+                    DataCiteSubject {
+                        subject: s.subject_code.clone(),
+                        subject_scheme: Some(subject_scheme),
+                        scheme_uri,
+                        classification_code: Some(s.subject_code.clone()),
+                    }
+                }
+            })
+            .collect();
+
+        let alternate_identifiers = work
+            .publications
+            .iter()
+            .filter_map(|p| p.isbn.as_ref())
+            .map(|isbn| DataCiteAlternateIdentifier {
+                alternate_identifier: isbn.to_string(),
+                alternate_identifier_type: "ISBN",
+            })
+            .collect();
+
+        let rights_list = work
+            .license
+            .iter()
+            .map(|license| DataCiteRights {
+                rights: license.clone(),
+                rights_uri: license.clone(),
+            })
+            .collect();
+
+        let descriptions = work
+            .long_abstract
+            .as_ref()
+            .or(work.short_abstract.as_ref())
+            .map(|labstract| DataCiteDescription {
+                description: labstract.clone(),
+                description_type: "Abstract",
+            })
+            .into_iter()
+            .collect();
+
+        Ok(DataCiteRecord {
+            doi: doi.to_string(),
+            url: work.landing_page.clone(),
+            types: DataCiteTypes {
+                resource_type_general: match work.work_type {
+                    WorkType::BOOK_CHAPTER => "BookChapter",
+                    _ => "Book",
+                },
+                resource_type: resource_type(&work.work_type),
+            },
+            creators,
+            titles,
+            publisher: work.imprint.publisher.publisher_name.clone(),
+            publication_year: publication_date.format("%Y").to_string(),
+            subjects,
+            contributors,
+            dates: vec![DataCiteDate {
+                date: publication_date.format("%Y-%m-%d").to_string(),
+                date_type: "Issued",
+            }],
+            alternate_identifiers,
+            rights_list,
+            descriptions,
+            funding_references: work.fundings.iter().map(From::from).collect(),
+            schema_version: DATACITE_SCHEMA_VERSION,
+        })
+    }
+}
+
+impl DataCiteName {
+    fn from_contribution(
+        contribution: &WorkContributions,
+        contributor_type: Option<&'static str>,
+    ) -> Self {
+        let name = match &contribution.first_name {
+            Some(first_name) => format!("{}, {}", contribution.last_name, first_name),
+            None => contribution.full_name.clone(),
+        };
+        let mut affiliations = contribution.affiliations.clone();
+        affiliations.sort_by_key(|a| a.affiliation_ordinal);
+        DataCiteName {
+            name,
+            name_type: "Personal",
+            given_name: contribution.first_name.clone(),
+            family_name: contribution.last_name.clone(),
+            name_identifiers: contribution
+                .contributor
+                .orcid
+                .iter()
+                .map(|orcid| DataCiteNameIdentifier {
+                    name_identifier: format!("{}{}", ORCID_DOMAIN, orcid),
+                    name_identifier_scheme: "ORCID",
+                    scheme_uri: "https://orcid.org",
+                })
+                .collect(),
+            affiliation: affiliations
+                .iter()
+                .map(|a| DataCiteAffiliation {
+                    name: a.institution.institution_name.clone(),
+                    affiliation_identifier: a
+                        .institution
+                        .ror
+                        .as_ref()
+                        .map(|ror| format!("{}{}", ROR_DOMAIN, ror)),
+                    affiliation_identifier_scheme: a.institution.ror.as_ref().map(|_| "ROR"),
+                    scheme_uri: a.institution.ror.as_ref().map(|_| "https://ror.org"),
+                })
+                .collect(),
+            contributor_type,
+        }
+    }
+}
+
+impl From<&WorkFundings> for DataCiteFundingReference {
+    fn from(funding: &WorkFundings) -> Self {
+        // Funder Registry DOIs are preferred, and ROR IDs accepted in their absence
+        let (funder_identifier, funder_identifier_type) = match (
+            &funding.institution.institution_doi,
+            &funding.institution.ror,
+        ) {
+            (Some(doi), _) => (
+                Some(format!("{}{}", DOI_DOMAIN, doi)),
+                Some("Crossref Funder ID"),
+            ),
+            (None, Some(ror)) => (Some(format!("{}{}", ROR_DOMAIN, ror)), Some("ROR")),
+            (None, None) => (None, None),
+        };
+        DataCiteFundingReference {
+            funder_name: funding.institution.institution_name.clone(),
+            funder_identifier,
+            funder_identifier_type,
+            award_number: funding.grant_number.clone(),
+            award_title: funding.project_name.clone(),
+        }
+    }
+}
+
+fn contributor_type(contribution_type: &ContributionType) -> &'static str {
+    match contribution_type {
+        ContributionType::EDITOR | ContributionType::MUSIC_EDITOR => "Editor",
+        _ => "Other",
+    }
+}
+
+fn resource_type(work_type: &WorkType) -> &'static str {
+    match work_type {
+        WorkType::BOOK_CHAPTER => "Book chapter",
+        WorkType::MONOGRAPH => "Monograph",
+        WorkType::EDITED_BOOK => "Edited book",
+        WorkType::TEXTBOOK => "Textbook",
+        WorkType::JOURNAL_ISSUE => "Journal issue",
+        WorkType::BOOK_SET => "Book set",
+        WorkType::Other(_) => unreachable!(),
+    }
+}
+
+fn subject_scheme(subject_type: &SubjectType) -> (&'static str, Option<&'static str>) {
+    match subject_type {
+        SubjectType::THEMA => ("Thema", Some("https://ns.editeur.org/thema/")),
+        SubjectType::BIC => ("BIC", None),
+        SubjectType::BISAC => ("BISAC", None),
+        SubjectType::LCC => ("LCC", Some("https://id.loc.gov/authorities/classification")),
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use thoth_api::model::{Doi, Isbn, Orcid, Ror};
+    use thoth_client::{
+        PublicationType, WorkContributionsAffiliations, WorkContributionsAffiliationsInstitution,
+        WorkContributionsContributor, WorkFundingsInstitution, WorkImprint, WorkImprintPublisher,
+        WorkPublications, WorkStatus, WorkSubjects,
+    };
+    use uuid::Uuid;
+
+    fn test_contribution(
+        contribution_type: ContributionType,
+        first_name: Option<&str>,
+        last_name: &str,
+        contribution_ordinal: i64,
+    ) -> WorkContributions {
+        WorkContributions {
+            contribution_type,
+            first_name: first_name.map(|n| n.to_string()),
+            last_name: last_name.to_string(),
+            full_name: match first_name {
+                Some(first_name) => format!("{} {}", first_name, last_name),
+                None => last_name.to_string(),
+            },
+            main_contribution: true,
+            biography: None,
+            contribution_ordinal,
+            contributor: WorkContributionsContributor { orcid: None },
+            affiliations: vec![],
+        }
+    }
+
+    fn test_work() -> Work {
+        let mut author = test_contribution(ContributionType::AUTHOR, Some("Author"), "1", 1);
+        author.contributor.orcid =
+            Some(Orcid::from_str("https://orcid.org/0000-0002-0000-0001").unwrap());
+        author.affiliations = vec![WorkContributionsAffiliations {
+            position: None,
+            affiliation_ordinal: 1,
+            institution: WorkContributionsAffiliationsInstitution {
+                institution_name: "University of Life".to_string(),
+                ror: Some(Ror::from_str("https://ror.org/0abcdef12").unwrap()),
+            },
+        }];
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: Some("Lorem ipsum".to_string()),
+            long_abstract: Some("Lorem ipsum dolor sit amet".to_string()),
+            general_note: None,
+            place: None,
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: None,
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![],
+            contributions: vec![
+                test_contribution(ContributionType::TRANSLATOR, None, "Translator", 3),
+                test_contribution(ContributionType::EDITOR, Some("Editor"), "1", 2),
+                author,
+            ],
+            languages: vec![],
+            publications: vec![
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-DDDD-000000000004").unwrap(),
+                    publication_type: PublicationType::PDF,
+                    isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                    prices: vec![],
+                    locations: vec![],
+                },
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-DDDD-000000000005").unwrap(),
+                    publication_type: PublicationType::HTML,
+                    isbn: None,
+                    prices: vec![],
+                    locations: vec![],
+                },
+            ],
+            subjects: vec![
+                WorkSubjects {
+                    subject_code: "keyword1".to_string(),
+                    subject_type: SubjectType::KEYWORD,
+                    subject_ordinal: 2,
+                },
+                WorkSubjects {
+                    subject_code: "AAB".to_string(),
+                    subject_type: SubjectType::THEMA,
+                    subject_ordinal: 1,
+                },
+            ],
+            fundings: vec![WorkFundings {
+                program: None,
+                project_name: Some("Name of project".to_string()),
+                project_shortname: None,
+                grant_number: Some("Number of grant".to_string()),
+                jurisdiction: None,
+                institution: WorkFundingsInstitution {
+                    institution_name: "Name of institution".to_string(),
+                    institution_doi: None,
+                    ror: Some(Ror::from_str("https://ror.org/0aaaaaa11").unwrap()),
+                    country_code: None,
+                },
+            }],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    #[test]
+    fn test_datacite_record() {
+        let record = DataCiteRecord::from_work(&test_work(), "datacite::thoth").unwrap();
+        assert_eq!(record.doi, "10.00001/BOOK.0001");
+        assert_eq!(record.url, Some("https://www.book.com".to_string()));
+        assert_eq!(
+            record.types,
+            DataCiteTypes {
+                resource_type_general: "Book",
+                resource_type: "Monograph",
+            }
+        );
+        // Authors are creators, and everyone else a contributor
+        assert_eq!(
+            record.creators,
+            vec![DataCiteName {
+                name: "1, Author".to_string(),
+                name_type: "Personal",
+                given_name: Some("Author".to_string()),
+                family_name: "1".to_string(),
+                name_identifiers: vec![DataCiteNameIdentifier {
+                    name_identifier: "https://orcid.org/0000-0002-0000-0001".to_string(),
+                    name_identifier_scheme: "ORCID",
+                    scheme_uri: "https://orcid.org",
+                }],
+                affiliation: vec![DataCiteAffiliation {
+                    name: "University of Life".to_string(),
+                    affiliation_identifier: Some("https://ror.org/0abcdef12".to_string()),
+                    affiliation_identifier_scheme: Some("ROR"),
+                    scheme_uri: Some("https://ror.org"),
+                }],
+                contributor_type: None,
+            }]
+        );
+        assert_eq!(record.contributors.len(), 2);
+        assert_eq!(record.contributors[0].name, "1, Editor");
+        assert_eq!(record.contributors[0].contributor_type, Some("Editor"));
+        assert_eq!(record.contributors[1].name, "Translator");
+        assert_eq!(record.contributors[1].given_name, None);
+        assert_eq!(record.contributors[1].contributor_type, Some("Other"));
+        assert_eq!(record.titles.len(), 2);
+        assert_eq!(record.titles[1].title_type, Some("Subtitle"));
+        assert_eq!(record.publisher, "OA Editions");
+        assert_eq!(record.publication_year, "1999");
+        assert_eq!(
+            record.subjects,
+            vec![
+                DataCiteSubject {
+                    subject: "AAB".to_string(),
+                    subject_scheme: Some("Thema"),
+                    scheme_uri: Some("https://ns.editeur.org/thema/"),
+                    classification_code: Some("AAB".to_string()),
+                },
+                DataCiteSubject {
+                    subject: "keyword1".to_string(),
+                    subject_scheme: None,
+                    scheme_uri: None,
+                    classification_code: None,
+                },
+            ]
+        );
+        assert_eq!(record.dates[0].date, "1999-12-31");
+        // Only publications with an ISBN are listed
+        assert_eq!(
+            record.alternate_identifiers,
+            vec![DataCiteAlternateIdentifier {
+                alternate_identifier: "978-3-16-148410-0".to_string(),
+                alternate_identifier_type: "ISBN",
+            }]
+        );
+        assert_eq!(
+            record.rights_list[0].rights_uri,
+            "https://creativecommons.org/licenses/by/4.0/"
+        );
+        // The long abstract is preferred
+        assert_eq!(
+            record.descriptions,
+            vec![DataCiteDescription {
+                description: "Lorem ipsum dolor sit amet".to_string(),
+                description_type: "Abstract",
+            }]
+        );
+        assert_eq!(
+            record.funding_references,
+            vec![DataCiteFundingReference {
+                funder_name: "Name of institution".to_string(),
+                funder_identifier: Some("https://ror.org/0aaaaaa11".to_string()),
+                funder_identifier_type: Some("ROR"),
+                award_number: Some("Number of grant".to_string()),
+                award_title: Some("Name of project".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_datacite_record_creators() {
+        // Books without authors are credited to their editors
+        let mut work = test_work();
+        work.work_type = WorkType::EDITED_BOOK;
+        work.contributions
+            .retain(|c| c.contribution_type != ContributionType::AUTHOR);
+        let record = DataCiteRecord::from_work(&work, "datacite::thoth").unwrap();
+        assert_eq!(record.types.resource_type, "Edited book");
+        assert_eq!(record.creators.len(), 1);
+        assert_eq!(record.creators[0].name, "1, Editor");
+        assert_eq!(record.contributors.len(), 1);
+        assert_eq!(record.contributors[0].name, "Translator");
+
+        work.contributions
+            .retain(|c| c.contribution_type != ContributionType::EDITOR);
+        assert_eq!(
+            DataCiteRecord::from_work(&work, "datacite::thoth"),
+            Err(ThothError::IncompleteMetadataRecord(
+                "datacite::thoth".to_string(),
+                "Missing Creator".to_string(),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_datacite_record_mandatory_fields() {
+        let mut work = test_work();
+        work.work_type = WorkType::BOOK_CHAPTER;
+        let record = DataCiteRecord::from_work(&work, "datacite::thoth_json").unwrap();
+        assert_eq!(
+            record.types,
+            DataCiteTypes {
+                resource_type_general: "BookChapter",
+                resource_type: "Book chapter",
+            }
+        );
+
+        work.publication_date = None;
+        assert_eq!(
+            DataCiteRecord::from_work(&work, "datacite::thoth_json"),
+            Err(ThothError::IncompleteMetadataRecord(
+                "datacite::thoth_json".to_string(),
+                "Missing Publication Date".to_string(),
+            ))
+        );
+        work.doi = None;
+        assert_eq!(
+            DataCiteRecord::from_work(&work, "datacite::thoth"),
+            Err(ThothError::IncompleteMetadataRecord(
+                "datacite::thoth".to_string(),
+                "Missing DOI".to_string(),
+            ))
+        );
+    }
+}
@@ -5,15 +5,15 @@ use paperclip::actix::{
 };
 
 use super::model::Format;
-use crate::data::{find_format, ALL_FORMATS};
+use crate::data::Registry;
 
 #[api_v2_operation(
     summary = "List supported formats",
     description = "Full list of metadata formats that can be output by Thoth",
     tags(Formats)
 )]
-pub(crate) async fn get_all() -> Json<Vec<Format<'static>>> {
-    Json(ALL_FORMATS.clone())
+pub(crate) async fn get_all(registry: web::Data<Registry>) -> Json<Vec<Format>> {
+    Json(registry.formats().to_vec())
 }
 
 #[api_v2_operation(
@@ -23,6 +23,10 @@ pub(crate) async fn get_all() -> Json<Vec<Format<'static>>> {
 )]
 pub(crate) async fn get_one(
     web::Path(format_id): web::Path<String>,
-) -> Result<Json<Format<'static>>, Error> {
-    find_format(format_id).map(Json).map_err(|e| e.into())
+    registry: web::Data<Registry>,
+) -> Result<Json<Format>, Error> {
+    registry
+        .find_format(format_id)
+        .map(Json)
+        .map_err(|e| e.into())
 }
@@ -3,9 +3,9 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Serialize, Deserialize, Apiv2Schema)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct Format<'a> {
-    pub(crate) id: &'a str,
-    pub(crate) name: &'a str,
-    pub(crate) version: Option<&'a str>,
-    pub(crate) specifications: Vec<&'a str>,
+pub(crate) struct Format {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) version: Option<String>,
+    pub(crate) specifications: Vec<String>,
 }
@@ -0,0 +1,347 @@
+use chrono::Datelike;
+use serde::Serialize;
+use serde_json::Value;
+use thoth_client::{ContributionType, Work, WorkContributions, WorkType};
+
+use super::{JsonObject, JsonSpecification};
+use crate::record::RequiredFields;
+use thoth_errors::{ThothError, ThothResult};
+
+pub struct CslJsonThoth {}
+
+/// A CSL-JSON item, as consumed by citeproc processors and reference managers
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+struct CslItem {
+    id: String,
+    #[serde(rename = "type")]
+    item_type: &'static str,
+    title: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    author: Vec<CslName>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    editor: Vec<CslName>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    translator: Vec<CslName>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issued: Option<CslDate>,
+    #[serde(rename = "DOI", skip_serializing_if = "Option::is_none")]
+    doi: Option<String>,
+    #[serde(rename = "ISBN", skip_serializing_if = "Option::is_none")]
+    isbn: Option<String>,
+    #[serde(rename = "URL", skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+    publisher: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    publisher_place: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    edition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collection_title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    collection_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    number_of_pages: Option<String>,
+    #[serde(rename = "abstract", skip_serializing_if = "Option::is_none")]
+    labstract: Option<String>,
+}
+
+/// A personal name, split into parts where possible
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+enum CslName {
+    Personal { family: String, given: String },
+    Literal { literal: String },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct CslDate {
+    #[serde(rename = "date-parts")]
+    date_parts: Vec<Vec<i32>>,
+}
+
+impl JsonSpecification for CslJsonThoth {
+    fn handle_event(works: &[Work]) -> ThothResult<Value> {
+        // CSL-JSON documents are always an array of items, even if only one work is requested
+        match works.len() {
+            0 => Err(ThothError::IncompleteMetadataRecord(
+                "csl_json::thoth".to_string(),
+                "Not enough data".to_string(),
+            )),
+            1 => JsonObject::<CslJsonThoth>::json_object(works.first().unwrap())
+                .map(|item| Value::Array(vec![item])),
+            _ => Ok(Value::Array(
+                works
+                    .iter()
+                    .filter_map(|work| JsonObject::<CslJsonThoth>::json_object(work).ok())
+                    .collect(),
+            )),
+        }
+    }
+}
+
+impl RequiredFields for CslJsonThoth {
+    const SPECIFICATION_ID: &'static str = "csl_json::thoth";
+}
+
+impl JsonObject<CslJsonThoth> for Work {
+    fn json_object(&self) -> ThothResult<Value> {
+        serde_json::to_value(CslItem::from(self)).map_err(|e| e.into())
+    }
+}
+
+impl From<&Work> for CslItem {
+    fn from(work: &Work) -> Self {
+        // Only one series can be listed, so we select the first one found (if any)
+        let issue = work.issues.first();
+        CslItem {
+            id: work.work_id.to_string(),
+            item_type: match work.work_type {
+                WorkType::BOOK_CHAPTER => "chapter",
+                WorkType::JOURNAL_ISSUE => "periodical",
+                WorkType::MONOGRAPH
+                | WorkType::EDITED_BOOK
+                | WorkType::TEXTBOOK
+                | WorkType::BOOK_SET => "book",
+                WorkType::Other(_) => unreachable!(),
+            },
+            title: work.full_title.clone(),
+            author: csl_names(work, ContributionType::AUTHOR),
+            editor: csl_names(work, ContributionType::EDITOR),
+            translator: csl_names(work, ContributionType::TRANSLATOR),
+            issued: work.publication_date.map(|date| CslDate {
+                date_parts: vec![vec![date.year(), date.month() as i32, date.day() as i32]],
+            }),
+            doi: work.doi.as_ref().map(|doi| doi.to_string()),
+            // CSL only allows one ISBN, so we select the first one found (if any)
+            isbn: work
+                .publications
+                .iter()
+                .find_map(|p| p.isbn.as_ref().map(|isbn| isbn.to_string())),
+            url: work.landing_page.clone(),
+            publisher: work.imprint.publisher.publisher_name.clone(),
+            publisher_place: work.place.clone(),
+            edition: match work.edition {
+                1 => None,
+                edition => Some(edition.to_string()),
+            },
+            collection_title: issue.map(|i| i.series.series_name.clone()),
+            collection_number: issue.map(|i| i.issue_ordinal.to_string()),
+            number_of_pages: work.page_count.map(|count| count.to_string()),
+            labstract: work
+                .long_abstract
+                .as_ref()
+                .or(work.short_abstract.as_ref())
+                .cloned(),
+        }
+    }
+}
+
+fn csl_names(work: &Work, contribution_type: ContributionType) -> Vec<CslName> {
+    let mut contributions: Vec<&WorkContributions> = work
+        .contributions
+        .iter()
+        .filter(|c| c.contribution_type == contribution_type)
+        .collect();
+    contributions.sort_by_key(|c| c.contribution_ordinal);
+    contributions
+        .iter()
+        .map(|c| match &c.first_name {
+            Some(first_name) => CslName::Personal {
+                family: c.last_name.clone(),
+                given: first_name.clone(),
+            },
+            None => CslName::Literal {
+                literal: c.full_name.clone(),
+            },
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::str::FromStr;
+    use thoth_api::model::{Doi, Isbn};
+    use thoth_client::{
+        PublicationType, SeriesType, WorkContributionsContributor, WorkImprint,
+        WorkImprintPublisher, WorkIssues, WorkIssuesSeries, WorkPublications, WorkStatus,
+    };
+    use uuid::Uuid;
+
+    fn test_contribution(
+        contribution_type: ContributionType,
+        first_name: Option<&str>,
+        last_name: &str,
+        contribution_ordinal: i64,
+    ) -> WorkContributions {
+        WorkContributions {
+            contribution_type,
+            first_name: first_name.map(|n| n.to_string()),
+            last_name: last_name.to_string(),
+            full_name: format!("{} {}", first_name.unwrap_or("Mx"), last_name),
+            main_contribution: true,
+            biography: None,
+            contribution_ordinal,
+            contributor: WorkContributionsContributor { orcid: None },
+            affiliations: vec![],
+        }
+    }
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::EDITED_BOOK,
+            edition: 2,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: None,
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: Some("Lorem ipsum".to_string()),
+            long_abstract: None,
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: Some(334),
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![WorkIssues {
+                issue_ordinal: 3,
+                series: WorkIssuesSeries {
+                    series_type: SeriesType::BOOK_SERIES,
+                    series_name: "Name of series".to_string(),
+                    issn_print: "1234-5678".to_string(),
+                    issn_digital: "8765-4321".to_string(),
+                    series_url: None,
+                },
+            }],
+            contributions: vec![
+                test_contribution(ContributionType::EDITOR, None, "Editor 2", 2),
+                test_contribution(ContributionType::EDITOR, Some("Editor"), "1", 1),
+                test_contribution(ContributionType::TRANSLATOR, Some("Translator"), "1", 3),
+                test_contribution(ContributionType::FOREWORD_BY, Some("Foreword"), "1", 4),
+            ],
+            languages: vec![],
+            publications: vec![
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000002").unwrap(),
+                    publication_type: PublicationType::HTML,
+                    isbn: None,
+                    prices: vec![],
+                    locations: vec![],
+                },
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000003").unwrap(),
+                    publication_type: PublicationType::PAPERBACK,
+                    isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                    prices: vec![],
+                    locations: vec![],
+                },
+            ],
+            subjects: vec![],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    #[test]
+    fn test_csl_json_thoth() {
+        let output = CslJsonThoth::handle_event(&[test_work()]).unwrap();
+        assert_eq!(
+            output,
+            json!([{
+                "id": "00000000-0000-0000-aaaa-000000000001",
+                "type": "book",
+                "title": "Book Title: Book Subtitle",
+                "editor": [
+                    {"family": "1", "given": "Editor"},
+                    {"literal": "Mx Editor 2"}
+                ],
+                "translator": [{"family": "1", "given": "Translator"}],
+                "issued": {"date-parts": [[1999, 12, 31]]},
+                "DOI": "10.00001/BOOK.0001",
+                "ISBN": "978-3-16-148410-0",
+                "URL": "https://www.book.com",
+                "publisher": "OA Editions",
+                "publisher-place": "León, Spain",
+                "edition": "2",
+                "collection-title": "Name of series",
+                "collection-number": "3",
+                "number-of-pages": "334",
+                "abstract": "Lorem ipsum"
+            }])
+        );
+    }
+
+    #[test]
+    fn test_csl_json_thoth_minimal() {
+        let mut work = test_work();
+        work.work_type = WorkType::BOOK_CHAPTER;
+        work.edition = 1;
+        work.doi = None;
+        work.publication_date = None;
+        work.short_abstract = None;
+        work.place = None;
+        work.page_count = None;
+        work.landing_page = None;
+        work.issues.clear();
+        work.publications.clear();
+        work.contributions = vec![test_contribution(
+            ContributionType::AUTHOR,
+            Some("Author"),
+            "1",
+            1,
+        )];
+        let output = CslJsonThoth::handle_event(&[work.clone(), work]).unwrap();
+        let item = json!({
+            "id": "00000000-0000-0000-aaaa-000000000001",
+            "type": "chapter",
+            "title": "Book Title: Book Subtitle",
+            "author": [{"family": "1", "given": "Author"}],
+            "publisher": "OA Editions"
+        });
+        assert_eq!(output, json!([item.clone(), item]));
+    }
+
+    #[test]
+    fn test_csl_json_thoth_errors() {
+        assert_eq!(
+            CslJsonThoth {}.generate(&[]).unwrap_err().to_string(),
+            "Could not generate csl_json::thoth: Not enough data".to_string()
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use serde_json::Value;
+use thoth_client::Work;
+
+use super::{JsonObject, JsonSpecification};
+use crate::datacite::{self, DataCiteRecord};
+use crate::record::RequiredFields;
+use thoth_errors::{ThothError, ThothResult};
+
+pub struct DataCiteThothJson {}
+
+impl JsonSpecification for DataCiteThothJson {
+    fn handle_event(works: &[Work]) -> ThothResult<Value> {
+        match works.len() {
+            0 => Err(ThothError::IncompleteMetadataRecord(
+                "datacite::thoth_json".to_string(),
+                "Not enough data".to_string(),
+            )),
+            1 => JsonObject::<DataCiteThothJson>::json_object(works.first().unwrap()),
+            _ => Ok(Value::Array(
+                works
+                    .iter()
+                    .filter_map(|work| JsonObject::<DataCiteThothJson>::json_object(work).ok())
+                    .collect(),
+            )),
+        }
+    }
+}
+
+impl RequiredFields for DataCiteThothJson {
+    const SPECIFICATION_ID: &'static str = "datacite::thoth_json";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        datacite::missing_fields(work)
+    }
+}
+
+impl JsonObject<DataCiteThothJson> for Work {
+    fn json_object(&self) -> ThothResult<Value> {
+        DataCiteThothJson::check_required_fields(self)?;
+        let record = DataCiteRecord::from_work(self, "datacite::thoth_json")?;
+        serde_json::to_value(record).map_err(|e| e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::str::FromStr;
+    use thoth_api::model::Doi;
+    use thoth_client::{
+        ContributionType, WorkContributions, WorkContributionsContributor, WorkImprint,
+        WorkImprintPublisher, WorkStatus, WorkType,
+    };
+    use uuid::Uuid;
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: None,
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: None,
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: None,
+            long_abstract: None,
+            general_note: None,
+            place: None,
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: None,
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![],
+            contributions: vec![WorkContributions {
+                contribution_type: ContributionType::AUTHOR,
+                first_name: Some("Author".to_string()),
+                last_name: "1".to_string(),
+                full_name: "Author 1".to_string(),
+                main_contribution: true,
+                biography: None,
+                contribution_ordinal: 1,
+                contributor: WorkContributionsContributor { orcid: None },
+                affiliations: vec![],
+            }],
+            languages: vec![],
+            publications: vec![],
+            subjects: vec![],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    #[test]
+    fn test_datacite_thoth_json_works() {
+        let expected = json!({
+            "doi": "10.00001/BOOK.0001",
+            "url": "https://www.book.com",
+            "types": {
+                "resourceTypeGeneral": "Book",
+                "resourceType": "Monograph"
+            },
+            "creators": [{
+                "name": "1, Author",
+                "nameType": "Personal",
+                "givenName": "Author",
+                "familyName": "1"
+            }],
+            "titles": [{ "title": "Book Title" }],
+            "publisher": "OA Editions",
+            "publicationYear": "1999",
+            "dates": [{ "date": "1999-12-31", "dateType": "Issued" }],
+            "schemaVersion": "http://datacite.org/schema/kernel-4"
+        });
+        let output = DataCiteThothJson {}.generate(&[test_work()]).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&output).unwrap(),
+            expected.clone()
+        );
+
+        // Several works are output as a list, skipping those which are incomplete
+        let mut incomplete_work = test_work();
+        incomplete_work.doi = None;
+        let output = DataCiteThothJson {}
+            .generate(&[test_work(), incomplete_work.clone()])
+            .unwrap();
+        assert_eq!(
+            serde_json::from_str::<Value>(&output).unwrap(),
+            json!([expected])
+        );
+
+        assert_eq!(
+            DataCiteThothJson {}
+                .generate(&[incomplete_work])
+                .unwrap_err()
+                .to_string(),
+            "Could not generate datacite::thoth_json: Missing DOI".to_string()
+        );
+        assert_eq!(
+            DataCiteThothJson {}.generate(&[]).unwrap_err().to_string(),
+            "Could not generate datacite::thoth_json: Not enough data".to_string()
+        );
+    }
+}
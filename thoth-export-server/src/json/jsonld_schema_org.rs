@@ -0,0 +1,804 @@
+use serde_json::{json, Map, Value};
+use thoth_api::model::{DOI_DOMAIN, ORCID_DOMAIN, ROR_DOMAIN};
+use thoth_client::{
+    ContributionType, LanguageRelation, PublicationType, SubjectType, Work, WorkContributions,
+    WorkFundings, WorkPublications, WorkType,
+};
+
+use super::{JsonObject, JsonSpecification};
+use crate::record::RequiredFields;
+use thoth_errors::{ThothError, ThothResult};
+
+pub struct JsonLdSchemaOrg {}
+
+const SCHEMA_ORG_CONTEXT: &str = "https://schema.org";
+/// ISO 639-2/B codes of the languages which also have an ISO 639-1 code, the one BCP 47 requires
+const ISO_639_1_CODES: &[(&str, &str)] = &[
+    ("aar", "aa"),
+    ("abk", "ab"),
+    ("afr", "af"),
+    ("aka", "ak"),
+    ("alb", "sq"),
+    ("amh", "am"),
+    ("ara", "ar"),
+    ("arg", "an"),
+    ("arm", "hy"),
+    ("asm", "as"),
+    ("ava", "av"),
+    ("ave", "ae"),
+    ("aym", "ay"),
+    ("aze", "az"),
+    ("bak", "ba"),
+    ("bam", "bm"),
+    ("baq", "eu"),
+    ("bel", "be"),
+    ("ben", "bn"),
+    ("bis", "bi"),
+    ("bos", "bs"),
+    ("bre", "br"),
+    ("bul", "bg"),
+    ("bur", "my"),
+    ("cat", "ca"),
+    ("cha", "ch"),
+    ("che", "ce"),
+    ("chi", "zh"),
+    ("chu", "cu"),
+    ("chv", "cv"),
+    ("cor", "kw"),
+    ("cos", "co"),
+    ("cre", "cr"),
+    ("cze", "cs"),
+    ("dan", "da"),
+    ("div", "dv"),
+    ("dut", "nl"),
+    ("dzo", "dz"),
+    ("eng", "en"),
+    ("epo", "eo"),
+    ("est", "et"),
+    ("ewe", "ee"),
+    ("fao", "fo"),
+    ("fij", "fj"),
+    ("fin", "fi"),
+    ("fre", "fr"),
+    ("fry", "fy"),
+    ("ful", "ff"),
+    ("geo", "ka"),
+    ("ger", "de"),
+    ("gla", "gd"),
+    ("gle", "ga"),
+    ("glg", "gl"),
+    ("glv", "gv"),
+    ("gre", "el"),
+    ("grn", "gn"),
+    ("guj", "gu"),
+    ("hat", "ht"),
+    ("hau", "ha"),
+    ("heb", "he"),
+    ("her", "hz"),
+    ("hin", "hi"),
+    ("hmo", "ho"),
+    ("hrv", "hr"),
+    ("hun", "hu"),
+    ("ibo", "ig"),
+    ("ice", "is"),
+    ("ido", "io"),
+    ("iii", "ii"),
+    ("iku", "iu"),
+    ("ile", "ie"),
+    ("ina", "ia"),
+    ("ind", "id"),
+    ("ipk", "ik"),
+    ("ita", "it"),
+    ("jav", "jv"),
+    ("jpn", "ja"),
+    ("kal", "kl"),
+    ("kan", "kn"),
+    ("kas", "ks"),
+    ("kau", "kr"),
+    ("kaz", "kk"),
+    ("khm", "km"),
+    ("kik", "ki"),
+    ("kin", "rw"),
+    ("kir", "ky"),
+    ("kom", "kv"),
+    ("kon", "kg"),
+    ("kor", "ko"),
+    ("kua", "kj"),
+    ("kur", "ku"),
+    ("lao", "lo"),
+    ("lat", "la"),
+    ("lav", "lv"),
+    ("lim", "li"),
+    ("lin", "ln"),
+    ("lit", "lt"),
+    ("ltz", "lb"),
+    ("lub", "lu"),
+    ("lug", "lg"),
+    ("mac", "mk"),
+    ("mah", "mh"),
+    ("mal", "ml"),
+    ("mao", "mi"),
+    ("mar", "mr"),
+    ("may", "ms"),
+    ("mlg", "mg"),
+    ("mlt", "mt"),
+    ("mon", "mn"),
+    ("nau", "na"),
+    ("nav", "nv"),
+    ("nbl", "nr"),
+    ("nde", "nd"),
+    ("ndo", "ng"),
+    ("nep", "ne"),
+    ("nno", "nn"),
+    ("nob", "nb"),
+    ("nor", "no"),
+    ("nya", "ny"),
+    ("oci", "oc"),
+    ("oji", "oj"),
+    ("ori", "or"),
+    ("orm", "om"),
+    ("oss", "os"),
+    ("pan", "pa"),
+    ("per", "fa"),
+    ("pli", "pi"),
+    ("pol", "pl"),
+    ("por", "pt"),
+    ("pus", "ps"),
+    ("que", "qu"),
+    ("roh", "rm"),
+    ("rum", "ro"),
+    ("run", "rn"),
+    ("rus", "ru"),
+    ("sag", "sg"),
+    ("san", "sa"),
+    ("sin", "si"),
+    ("slo", "sk"),
+    ("slv", "sl"),
+    ("sme", "se"),
+    ("smo", "sm"),
+    ("sna", "sn"),
+    ("snd", "sd"),
+    ("som", "so"),
+    ("sot", "st"),
+    ("spa", "es"),
+    ("srd", "sc"),
+    ("srp", "sr"),
+    ("ssw", "ss"),
+    ("sun", "su"),
+    ("swa", "sw"),
+    ("swe", "sv"),
+    ("tah", "ty"),
+    ("tam", "ta"),
+    ("tat", "tt"),
+    ("tel", "te"),
+    ("tgk", "tg"),
+    ("tgl", "tl"),
+    ("tha", "th"),
+    ("tib", "bo"),
+    ("tir", "ti"),
+    ("ton", "to"),
+    ("tsn", "tn"),
+    ("tso", "ts"),
+    ("tuk", "tk"),
+    ("tur", "tr"),
+    ("twi", "tw"),
+    ("uig", "ug"),
+    ("ukr", "uk"),
+    ("urd", "ur"),
+    ("uzb", "uz"),
+    ("ven", "ve"),
+    ("vie", "vi"),
+    ("vol", "vo"),
+    ("wel", "cy"),
+    ("wln", "wa"),
+    ("wol", "wo"),
+    ("xho", "xh"),
+    ("yid", "yi"),
+    ("yor", "yo"),
+    ("zha", "za"),
+    ("zul", "zu"),
+];
+
+impl JsonSpecification for JsonLdSchemaOrg {
+    fn handle_event(works: &[Work]) -> ThothResult<Value> {
+        match works.len() {
+            0 => Err(ThothError::IncompleteMetadataRecord(
+                "jsonld::schema_org".to_string(),
+                "Not enough data".to_string(),
+            )),
+            1 => JsonObject::<JsonLdSchemaOrg>::json_object(works.first().unwrap()).map(|node| {
+                let mut document = Map::new();
+                document.insert("@context".to_string(), json!(SCHEMA_ORG_CONTEXT));
+                if let Value::Object(node) = node {
+                    document.extend(node);
+                }
+                Value::Object(document)
+            }),
+            // Several works share a single context, and are listed as nodes of the same graph
+            _ => Ok(json!({
+                "@context": SCHEMA_ORG_CONTEXT,
+                "@graph": works
+                    .iter()
+                    .filter_map(|work| JsonObject::<JsonLdSchemaOrg>::json_object(work).ok())
+                    .collect::<Vec<Value>>(),
+            })),
+        }
+    }
+}
+
+impl RequiredFields for JsonLdSchemaOrg {
+    const SPECIFICATION_ID: &'static str = "jsonld::schema_org";
+}
+
+impl JsonObject<JsonLdSchemaOrg> for Work {
+    fn json_object(&self) -> ThothResult<Value> {
+        let item_type = match self.work_type {
+            WorkType::BOOK_CHAPTER => "Chapter",
+            WorkType::JOURNAL_ISSUE => "PublicationIssue",
+            WorkType::MONOGRAPH
+            | WorkType::EDITED_BOOK
+            | WorkType::TEXTBOOK
+            | WorkType::BOOK_SET => "Book",
+            WorkType::Other(_) => unreachable!(),
+        };
+        let id = match &self.doi {
+            Some(doi) => format!("{}{}", DOI_DOMAIN, doi),
+            None => format!("urn:uuid:{}", self.work_id),
+        };
+
+        let mut node = Map::new();
+        node.insert("@type".to_string(), json!(item_type));
+        node.insert("@id".to_string(), json!(id));
+        node.insert("name".to_string(), json!(self.full_title));
+        for (property, contribution_type) in [
+            ("author", ContributionType::AUTHOR),
+            ("editor", ContributionType::EDITOR),
+            ("translator", ContributionType::TRANSLATOR),
+        ] {
+            let people = persons(self, contribution_type);
+            if !people.is_empty() {
+                node.insert(property.to_string(), Value::Array(people));
+            }
+        }
+        if let Some(date) = self.publication_date {
+            node.insert(
+                "datePublished".to_string(),
+                json!(date.format("%Y-%m-%d").to_string()),
+            );
+        }
+        node.insert(
+            "publisher".to_string(),
+            json!({
+                "@type": "Organization",
+                "name": self.imprint.publisher.publisher_name,
+            }),
+        );
+        if self.edition > 1 && self.work_type != WorkType::BOOK_CHAPTER {
+            node.insert("bookEdition".to_string(), json!(self.edition.to_string()));
+        }
+        if let Some(page_count) = self.page_count {
+            node.insert("numberOfPages".to_string(), json!(page_count));
+        }
+        if let Some(labstract) = self.long_abstract.as_ref().or(self.short_abstract.as_ref()) {
+            node.insert("description".to_string(), json!(labstract));
+        }
+        let keywords: Vec<&str> = self
+            .subjects
+            .iter()
+            .filter(|s| s.subject_type == SubjectType::KEYWORD)
+            .map(|s| s.subject_code.as_str())
+            .collect();
+        if !keywords.is_empty() {
+            node.insert("keywords".to_string(), json!(keywords.join(", ")));
+        }
+        // Languages translated from are those of the original work, not of this one
+        let languages: Vec<String> = self
+            .languages
+            .iter()
+            .filter(|l| l.language_relation != LanguageRelation::TRANSLATED_FROM)
+            .map(|l| bcp47_language(&l.language_code.to_string().to_lowercase()))
+            .collect();
+        match languages.len() {
+            0 => {}
+            1 => {
+                node.insert("inLanguage".to_string(), json!(languages[0]));
+            }
+            _ => {
+                node.insert("inLanguage".to_string(), json!(languages));
+            }
+        }
+        if let Some(landing_page) = &self.landing_page {
+            node.insert("url".to_string(), json!(landing_page));
+        }
+        if let Some(license) = &self.license {
+            node.insert("license".to_string(), json!(license));
+        }
+        if !self.fundings.is_empty() {
+            node.insert(
+                "funder".to_string(),
+                Value::Array(self.fundings.iter().map(funder).collect()),
+            );
+        }
+        // A chapter is part of its book, and any other work of its series. Only one series can be
+        // listed as the parent, so we select the first one found (if any)
+        let parent = self
+            .parents
+            .first()
+            .filter(|_| self.work_type == WorkType::BOOK_CHAPTER);
+        if let Some(parent) = parent {
+            let book = &parent.related_work;
+            let mut part_of = Map::new();
+            part_of.insert("@type".to_string(), json!("Book"));
+            part_of.insert(
+                "@id".to_string(),
+                json!(match &book.doi {
+                    Some(doi) => format!("{}{}", DOI_DOMAIN, doi),
+                    None => format!("urn:uuid:{}", book.work_id),
+                }),
+            );
+            part_of.insert("name".to_string(), json!(book.full_title));
+            if let Some(landing_page) = &book.landing_page {
+                part_of.insert("url".to_string(), json!(landing_page));
+            }
+            node.insert("isPartOf".to_string(), Value::Object(part_of));
+            node.insert("position".to_string(), json!(parent.relation_ordinal));
+        } else if let Some(issue) = self.issues.first() {
+            node.insert(
+                "isPartOf".to_string(),
+                json!({
+                    "@type": "BookSeries",
+                    "name": issue.series.series_name,
+                    "issn": [issue.series.issn_print, issue.series.issn_digital],
+                }),
+            );
+            node.insert("position".to_string(), json!(issue.issue_ordinal));
+        }
+        if !self.publications.is_empty() {
+            node.insert(
+                "workExample".to_string(),
+                Value::Array(
+                    self.publications
+                        .iter()
+                        .map(|p| work_example(p, item_type))
+                        .collect(),
+                ),
+            );
+        }
+        Ok(Value::Object(node))
+    }
+}
+
+/// BCP 47 tag of a language, given its ISO 639-2/B code
+fn bcp47_language(code: &str) -> String {
+    ISO_639_1_CODES
+        .iter()
+        .find(|(iso_639_2, _)| *iso_639_2 == code)
+        .map(|(_, iso_639_1)| iso_639_1.to_string())
+        .unwrap_or_else(|| code.to_string())
+}
+
+fn persons(work: &Work, contribution_type: ContributionType) -> Vec<Value> {
+    let mut contributions: Vec<&WorkContributions> = work
+        .contributions
+        .iter()
+        .filter(|c| c.contribution_type == contribution_type)
+        .collect();
+    contributions.sort_by_key(|c| c.contribution_ordinal);
+    contributions
+        .iter()
+        .map(|c| {
+            let mut person = Map::new();
+            person.insert("@type".to_string(), json!("Person"));
+            person.insert("name".to_string(), json!(c.full_name));
+            if let Some(first_name) = &c.first_name {
+                person.insert("givenName".to_string(), json!(first_name));
+                person.insert("familyName".to_string(), json!(c.last_name));
+            }
+            if let Some(orcid) = &c.contributor.orcid {
+                person.insert(
+                    "sameAs".to_string(),
+                    json!(format!("{}{}", ORCID_DOMAIN, orcid)),
+                );
+            }
+            Value::Object(person)
+        })
+        .collect()
+}
+
+fn funder(funding: &WorkFundings) -> Value {
+    let mut organization = Map::new();
+    organization.insert("@type".to_string(), json!("Organization"));
+    organization.insert(
+        "name".to_string(),
+        json!(funding.institution.institution_name),
+    );
+    // Funder Registry DOIs are preferred, and ROR IDs accepted in their absence
+    let identifier = match (
+        &funding.institution.institution_doi,
+        &funding.institution.ror,
+    ) {
+        (Some(doi), _) => Some(format!("{}{}", DOI_DOMAIN, doi)),
+        (None, Some(ror)) => Some(format!("{}{}", ROR_DOMAIN, ror)),
+        (None, None) => None,
+    };
+    if let Some(identifier) = identifier {
+        organization.insert("sameAs".to_string(), json!(identifier));
+    }
+    Value::Object(organization)
+}
+
+fn work_example(publication: &WorkPublications, item_type: &str) -> Value {
+    let (book_format, encoding_format) = match publication.publication_type {
+        PublicationType::PAPERBACK => ("Paperback", None),
+        PublicationType::HARDBACK => ("Hardcover", None),
+        PublicationType::PDF => ("EBook", Some("application/pdf")),
+        PublicationType::HTML => ("EBook", Some("text/html")),
+        PublicationType::XML => ("EBook", Some("application/xml")),
+        PublicationType::EPUB => ("EBook", Some("application/epub+zip")),
+        PublicationType::MOBI => ("EBook", Some("application/x-mobipocket-ebook")),
+        PublicationType::Other(_) => unreachable!(),
+    };
+    let mut example = Map::new();
+    example.insert("@type".to_string(), json!(item_type));
+    example.insert(
+        "bookFormat".to_string(),
+        json!(format!("{}/{}", SCHEMA_ORG_CONTEXT, book_format)),
+    );
+    if let Some(encoding_format) = encoding_format {
+        example.insert("encodingFormat".to_string(), json!(encoding_format));
+    }
+    if let Some(isbn) = &publication.isbn {
+        example.insert("isbn".to_string(), json!(isbn.to_string()));
+    }
+    if let Some(landing_page) = publication
+        .locations
+        .iter()
+        .find(|l| l.canonical)
+        .and_then(|l| l.landing_page.as_ref())
+    {
+        example.insert("url".to_string(), json!(landing_page));
+    }
+    if !publication.prices.is_empty() {
+        example.insert(
+            "offers".to_string(),
+            Value::Array(
+                publication
+                    .prices
+                    .iter()
+                    .map(|price| {
+                        json!({
+                            "@type": "Offer",
+                            "price": format!("{:.2}", price.unit_price),
+                            "priceCurrency": format!("{:?}", price.currency_code),
+                        })
+                    })
+                    .collect(),
+            ),
+        );
+    }
+    Value::Object(example)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use thoth_api::model::{Doi, Isbn, Orcid, Ror};
+    use thoth_client::{
+        CurrencyCode, LanguageCode, LocationPlatform, SeriesType, WorkContributionsContributor,
+        WorkFundingsInstitution, WorkImprint, WorkImprintPublisher, WorkIssues, WorkIssuesSeries,
+        WorkLanguages, WorkParents, WorkParentsRelatedWork, WorkPublicationsLocations,
+        WorkPublicationsPrices, WorkStatus, WorkSubjects,
+    };
+    use uuid::Uuid;
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 2,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: None,
+            long_abstract: Some("Lorem ipsum dolor sit amet".to_string()),
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: Some(334),
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![WorkIssues {
+                issue_ordinal: 3,
+                series: WorkIssuesSeries {
+                    series_type: SeriesType::BOOK_SERIES,
+                    series_name: "Name of series".to_string(),
+                    issn_print: "1234-5678".to_string(),
+                    issn_digital: "8765-4321".to_string(),
+                    series_url: None,
+                },
+            }],
+            contributions: vec![
+                WorkContributions {
+                    contribution_type: ContributionType::AUTHOR,
+                    first_name: Some("Author".to_string()),
+                    last_name: "1".to_string(),
+                    full_name: "Author 1".to_string(),
+                    main_contribution: true,
+                    biography: None,
+                    contribution_ordinal: 1,
+                    contributor: WorkContributionsContributor {
+                        orcid: Some(
+                            Orcid::from_str("https://orcid.org/0000-0002-0000-0001").unwrap(),
+                        ),
+                    },
+                    affiliations: vec![],
+                },
+                WorkContributions {
+                    contribution_type: ContributionType::EDITOR,
+                    first_name: None,
+                    last_name: "Editor".to_string(),
+                    full_name: "Editor".to_string(),
+                    main_contribution: false,
+                    biography: None,
+                    contribution_ordinal: 2,
+                    contributor: WorkContributionsContributor { orcid: None },
+                    affiliations: vec![],
+                },
+            ],
+            languages: vec![
+                WorkLanguages {
+                    language_code: LanguageCode::ENG,
+                    language_relation: LanguageRelation::TRANSLATED_INTO,
+                    main_language: true,
+                },
+                WorkLanguages {
+                    language_code: LanguageCode::SPA,
+                    language_relation: LanguageRelation::TRANSLATED_FROM,
+                    main_language: false,
+                },
+            ],
+            publications: vec![
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000002").unwrap(),
+                    publication_type: PublicationType::PAPERBACK,
+                    isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                    prices: vec![
+                        WorkPublicationsPrices {
+                            currency_code: CurrencyCode::EUR,
+                            unit_price: 25.95,
+                        },
+                        WorkPublicationsPrices {
+                            currency_code: CurrencyCode::GBP,
+                            unit_price: 22.0,
+                        },
+                    ],
+                    locations: vec![],
+                },
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000003").unwrap(),
+                    publication_type: PublicationType::PDF,
+                    isbn: None,
+                    prices: vec![],
+                    locations: vec![
+                        WorkPublicationsLocations {
+                            landing_page: Some("https://www.jstor.com/pdf".to_string()),
+                            full_text_url: None,
+                            location_platform: LocationPlatform::JSTOR,
+                            canonical: false,
+                        },
+                        WorkPublicationsLocations {
+                            landing_page: Some("https://www.book.com/pdf".to_string()),
+                            full_text_url: None,
+                            location_platform: LocationPlatform::OTHER,
+                            canonical: true,
+                        },
+                    ],
+                },
+            ],
+            subjects: vec![
+                WorkSubjects {
+                    subject_code: "AAA".to_string(),
+                    subject_type: SubjectType::BIC,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "keyword1".to_string(),
+                    subject_type: SubjectType::KEYWORD,
+                    subject_ordinal: 1,
+                },
+            ],
+            fundings: vec![WorkFundings {
+                program: None,
+                project_name: Some("Name of project".to_string()),
+                project_shortname: None,
+                grant_number: Some("Number of grant".to_string()),
+                jurisdiction: None,
+                institution: WorkFundingsInstitution {
+                    institution_name: "Name of institution".to_string(),
+                    institution_doi: None,
+                    ror: Some(Ror::from_str("https://ror.org/0aaaaaa11").unwrap()),
+                    country_code: None,
+                },
+            }],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    #[test]
+    fn test_jsonld_schema_org() {
+        let output = JsonLdSchemaOrg::handle_event(&[test_work()]).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "@context": "https://schema.org",
+                "@type": "Book",
+                "@id": "https://doi.org/10.00001/BOOK.0001",
+                "name": "Book Title: Book Subtitle",
+                "author": [{
+                    "@type": "Person",
+                    "name": "Author 1",
+                    "givenName": "Author",
+                    "familyName": "1",
+                    "sameAs": "https://orcid.org/0000-0002-0000-0001"
+                }],
+                "editor": [{"@type": "Person", "name": "Editor"}],
+                "datePublished": "1999-12-31",
+                "publisher": {"@type": "Organization", "name": "OA Editions"},
+                "bookEdition": "2",
+                "numberOfPages": 334,
+                "description": "Lorem ipsum dolor sit amet",
+                "keywords": "keyword1",
+                "inLanguage": "en",
+                "url": "https://www.book.com",
+                "license": "https://creativecommons.org/licenses/by/4.0/",
+                "funder": [{
+                    "@type": "Organization",
+                    "name": "Name of institution",
+                    "sameAs": "https://ror.org/0aaaaaa11"
+                }],
+                "isPartOf": {
+                    "@type": "BookSeries",
+                    "name": "Name of series",
+                    "issn": ["1234-5678", "8765-4321"]
+                },
+                "position": 3,
+                "workExample": [
+                    {
+                        "@type": "Book",
+                        "bookFormat": "https://schema.org/Paperback",
+                        "isbn": "978-3-16-148410-0",
+                        "offers": [
+                            {"@type": "Offer", "price": "25.95", "priceCurrency": "EUR"},
+                            {"@type": "Offer", "price": "22.00", "priceCurrency": "GBP"}
+                        ]
+                    },
+                    {
+                        "@type": "Book",
+                        "bookFormat": "https://schema.org/EBook",
+                        "encodingFormat": "application/pdf",
+                        "url": "https://www.book.com/pdf"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_jsonld_schema_org_minimal() {
+        let mut work = test_work();
+        work.work_type = WorkType::BOOK_CHAPTER;
+        work.doi = None;
+        work.publication_date = None;
+        work.license = None;
+        work.long_abstract = None;
+        work.page_count = None;
+        work.landing_page = None;
+        work.issues.clear();
+        work.contributions.clear();
+        work.languages.clear();
+        work.publications.clear();
+        work.subjects.clear();
+        work.fundings.clear();
+        let node = json!({
+            "@type": "Chapter",
+            "@id": "urn:uuid:00000000-0000-0000-aaaa-000000000001",
+            "name": "Book Title: Book Subtitle",
+            "publisher": {"@type": "Organization", "name": "OA Editions"}
+        });
+        let mut document = node.clone();
+        document["@context"] = json!("https://schema.org");
+        assert_eq!(
+            JsonLdSchemaOrg::handle_event(&[work.clone()]).unwrap(),
+            document
+        );
+        // Several works are nodes of a single graph
+        assert_eq!(
+            JsonLdSchemaOrg::handle_event(&[work.clone(), work]).unwrap(),
+            json!({
+                "@context": "https://schema.org",
+                "@graph": [node.clone(), node]
+            })
+        );
+    }
+
+    #[test]
+    fn test_jsonld_schema_org_chapter() {
+        let mut work = test_work();
+        work.work_type = WorkType::BOOK_CHAPTER;
+        work.parents = vec![WorkParents {
+            relation_ordinal: 4,
+            related_work: WorkParentsRelatedWork {
+                work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000002").unwrap(),
+                full_title: "Parent Book".to_string(),
+                doi: None,
+                landing_page: Some("https://www.book.com/parent".to_string()),
+            },
+        }];
+        let output = JsonLdSchemaOrg::handle_event(&[work]).unwrap();
+        assert_eq!(output["@type"], json!("Chapter"));
+        // The book takes the place of the series
+        assert_eq!(
+            output["isPartOf"],
+            json!({
+                "@type": "Book",
+                "@id": "urn:uuid:00000000-0000-0000-aaaa-000000000002",
+                "name": "Parent Book",
+                "url": "https://www.book.com/parent"
+            })
+        );
+        assert_eq!(output["position"], json!(4));
+    }
+
+    #[test]
+    fn test_bcp47_language() {
+        assert_eq!(bcp47_language("eng"), "en");
+        // Bibliographic codes are mapped as well as terminology ones
+        assert_eq!(bcp47_language("wel"), "cy");
+        assert_eq!(bcp47_language("ger"), "de");
+        // Languages without a two-letter code keep their three-letter one
+        assert_eq!(bcp47_language("ast"), "ast");
+    }
+
+    #[test]
+    fn test_jsonld_schema_org_errors() {
+        assert_eq!(
+            JsonLdSchemaOrg {}.generate(&[]).unwrap_err().to_string(),
+            "Could not generate jsonld::schema_org: Not enough data".to_string()
+        );
+    }
+}
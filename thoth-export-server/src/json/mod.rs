@@ -0,0 +1,23 @@
+use serde_json::Value;
+use thoth_client::Work;
+use thoth_errors::ThothResult;
+
+pub(crate) trait JsonSpecification {
+    fn generate(&self, works: &[Work]) -> ThothResult<String> {
+        Self::handle_event(works)
+            .and_then(|json| serde_json::to_string_pretty(&json).map_err(|e| e.into()))
+    }
+
+    fn handle_event(works: &[Work]) -> ThothResult<Value>;
+}
+
+pub(crate) trait JsonObject<T: JsonSpecification> {
+    fn json_object(&self) -> ThothResult<Value>;
+}
+
+mod csl_json_thoth;
+pub(crate) use csl_json_thoth::CslJsonThoth;
+mod datacite_thoth_json;
+pub(crate) use datacite_thoth_json::DataCiteThothJson;
+mod jsonld_schema_org;
+pub(crate) use jsonld_schema_org::JsonLdSchemaOrg;
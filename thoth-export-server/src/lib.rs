@@ -1,4 +1,5 @@
 use std::io;
+use std::time::Duration;
 
 use actix_cors::Cors;
 use actix_web::{middleware::Logger, App, HttpServer};
@@ -6,16 +7,32 @@ use paperclip::actix::{web, web::HttpResponse, OpenApiExt};
 use paperclip::v2::models::{Contact, DefaultApiRaw, Info, License, Tag};
 use thoth_client::ThothClient;
 
+mod cache;
+mod citation;
 mod csv;
 mod data;
+mod datacite;
 mod format;
+mod json;
+mod marc21;
+mod oai;
 mod platform;
 mod rapidoc;
 mod record;
 mod specification;
+mod work;
 mod xml;
 
+use crate::cache::RecordCache;
+use crate::data::{Registry, RegistryConfig};
+use crate::oai::model::Repository;
 use crate::rapidoc::rapidoc_source;
+use crate::xml::{SchemaValidator, SCHEMAS_DIR};
+
+/// Time to live of generated records in the in-process cache
+const RECORD_CACHE_TTL: Duration = Duration::from_secs(15 * 60);
+/// Maximum total size, in bytes, of the generated records held in the in-process cache
+const RECORD_CACHE_MAX_BYTES: usize = 256 * 1024 * 1024;
 
 struct ApiConfig {
     api_schema: String,
@@ -42,9 +59,26 @@ pub async fn start_server(
     port: String,
     public_url: String,
     gql_endpoint: String,
+    platforms_config: Option<String>,
+    schemas_dir: Option<String>,
 ) -> io::Result<()> {
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
     log::info!("Setting Thoth GraphQL endpoint to {}", gql_endpoint);
+    let config = match platforms_config {
+        Some(path) => {
+            log::info!("Loading platforms configuration from {}", path);
+            RegistryConfig::from_file(&path)
+        }
+        None => Ok(RegistryConfig::default()),
+    };
+    let registry = config
+        .and_then(|config| Registry::new(&public_url, &config))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+    let schemas_dir = schemas_dir.unwrap_or_else(|| SCHEMAS_DIR.to_string());
+    log::info!("Validating records against schemas in {}", schemas_dir);
+    let schemas = SchemaValidator::new(schemas_dir);
+    // Shared by all workers, unlike data registered within the application factory
+    let record_cache = web::Data::new(RecordCache::new(RECORD_CACHE_TTL, RECORD_CACHE_MAX_BYTES));
 
     HttpServer::new(move || {
         let spec = DefaultApiRaw {
@@ -65,6 +99,16 @@ pub async fn start_server(
                     description: None,
                     external_docs: None,
                 },
+                Tag {
+                    name: "Works".to_string(),
+                    description: None,
+                    external_docs: None,
+                },
+                Tag {
+                    name: "OAI-PMH".to_string(),
+                    description: None,
+                    external_docs: None,
+                },
             ],
             info: Info {
                 version: env!("CARGO_PKG_VERSION").parse().unwrap(),
@@ -88,14 +132,20 @@ pub async fn start_server(
 
         App::new()
             .wrap(Logger::default())
-            .wrap(Cors::default().allowed_methods(vec!["GET", "OPTIONS"]))
+            .wrap(Cors::default().allowed_methods(vec!["GET", "POST", "OPTIONS"]))
             .data(ThothClient::new(gql_endpoint.clone()))
             .data(ApiConfig::new(public_url.clone()))
+            .data(registry.clone())
+            .data(Repository::new(&public_url))
+            .data(schemas.clone())
+            .app_data(record_cache.clone())
             .service(actix_web::web::resource("/").route(actix_web::web::get().to(index)))
             .wrap_api_with_spec(spec)
             .configure(format::route)
             .configure(platform::route)
             .configure(specification::route)
+            .configure(work::route)
+            .configure(oai::route)
             .with_json_spec_at("/swagger.json")
             .build()
     })
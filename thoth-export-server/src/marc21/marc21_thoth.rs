@@ -0,0 +1,194 @@
+use std::io::Write;
+use thoth_client::Work;
+
+use super::{Marc21Entry, Marc21Specification, MarcRecord};
+use crate::record::RequiredFields;
+use thoth_errors::{ThothError, ThothResult};
+
+pub struct Marc21Thoth {}
+
+impl Marc21Specification for Marc21Thoth {
+    fn handle_event<W: Write>(w: &mut W, works: &[Work]) -> ThothResult<()> {
+        match works.len() {
+            0 => Err(ThothError::IncompleteMetadataRecord(
+                "marc21::thoth".to_string(),
+                "Not enough data".to_string(),
+            )),
+            1 => Marc21Entry::<Marc21Thoth>::marc21_entry(works.first().unwrap(), w),
+            _ => {
+                for work in works.iter() {
+                    Marc21Entry::<Marc21Thoth>::marc21_entry(work, w).ok();
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl RequiredFields for Marc21Thoth {
+    const SPECIFICATION_ID: &'static str = "marc21::thoth";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        super::missing_fields(work)
+    }
+}
+
+impl Marc21Entry<Marc21Thoth> for Work {
+    fn marc21_entry<W: Write>(&self, w: &mut W) -> ThothResult<()> {
+        Marc21Thoth::check_required_fields(self)?;
+        let record = MarcRecord::from_work(self, "marc21::thoth")?.to_iso2709()?;
+        w.write_all(&record).map_err(|e| e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::marc21::Marc21StreamWriter;
+    use crate::record::RecordWriter;
+    use std::str::FromStr;
+    use thoth_api::model::Doi;
+    use thoth_client::{
+        ContributionType, WorkContributions, WorkContributionsContributor, WorkImprint,
+        WorkImprintPublisher, WorkStatus, WorkType,
+    };
+    use uuid::Uuid;
+
+    const FIELD_TERMINATOR: char = '\u{1e}';
+    const RECORD_TERMINATOR: char = '\u{1d}';
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: None,
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: None,
+            long_abstract: None,
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: None,
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: None,
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![],
+            contributions: vec![WorkContributions {
+                contribution_type: ContributionType::AUTHOR,
+                first_name: Some("Author".to_string()),
+                last_name: "1".to_string(),
+                full_name: "Author 1".to_string(),
+                main_contribution: true,
+                biography: None,
+                contribution_ordinal: 1,
+                contributor: WorkContributionsContributor { orcid: None },
+                affiliations: vec![],
+            }],
+            languages: vec![],
+            publications: vec![],
+            subjects: vec![],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    #[test]
+    fn test_streamed_marc21_thoth_matches_generated() {
+        let mut writer = Marc21StreamWriter::<Marc21Thoth>::new();
+        let mut output = writer.write_page(&[test_work()]).unwrap();
+        output.extend(writer.write_page(&[test_work()]).unwrap());
+        output.extend(writer.finish(&[]).unwrap());
+        assert_eq!(
+            output,
+            Marc21Thoth {}
+                .generate(&[test_work(), test_work()])
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_marc21_thoth_works() {
+        let output = Marc21Thoth {}.generate(&[test_work()]).unwrap();
+        let record = String::from_utf8(output.clone()).unwrap();
+
+        // Leader: record length and base address of data are given in bytes
+        assert_eq!(&record[..5], format!("{:05}", output.len()));
+        assert_eq!(&record[5..12], "nam a22");
+        assert_eq!(&record[17..24], " c 4500");
+        let base_address: usize = record[12..17].parse().unwrap();
+        assert_eq!(record.as_bytes()[base_address - 1], FIELD_TERMINATOR as u8);
+        assert!(record.ends_with(RECORD_TERMINATOR));
+
+        // Every directory entry points to a field ending in a field terminator
+        let directory = &record[24..base_address - 1];
+        assert_eq!(directory.len() % 12, 0);
+        assert!(directory.starts_with("001003700000"));
+        for entry in directory.as_bytes().chunks(12) {
+            let entry = std::str::from_utf8(entry).unwrap();
+            let length: usize = entry[3..7].parse().unwrap();
+            let start: usize = entry[7..12].parse().unwrap();
+            let end = base_address + start + length;
+            assert_eq!(output[end - 1], FIELD_TERMINATOR as u8);
+        }
+        assert!(record.contains("\u{1e}00000000-0000-0000-aaaa-000000000001\u{1e}"));
+        assert!(record.contains("\u{1e}10\u{1f}aBook Title\u{1f}bBook Subtitle\u{1e}"));
+        assert!(record.contains("\u{1e} 1\u{1f}aLeón, Spain\u{1f}bOA Editions\u{1f}c1999\u{1e}"));
+
+        // Records are concatenated, and incomplete works are skipped
+        let mut incomplete_work = test_work();
+        incomplete_work.publication_date = None;
+        let output = Marc21Thoth {}
+            .generate(&[test_work(), incomplete_work.clone(), test_work()])
+            .unwrap();
+        let records = String::from_utf8(output).unwrap();
+        assert_eq!(records.matches(RECORD_TERMINATOR).count(), 2);
+
+        // A single incomplete work fails to generate
+        assert_eq!(
+            Marc21Thoth {}
+                .generate(&[incomplete_work])
+                .unwrap_err()
+                .to_string(),
+            "Could not generate marc21::thoth: Missing Publication Date".to_string()
+        );
+        assert_eq!(
+            Marc21Thoth {}.generate(&[]).unwrap_err().to_string(),
+            "Could not generate marc21::thoth: Not enough data".to_string()
+        );
+    }
+}
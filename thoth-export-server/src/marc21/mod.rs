@@ -0,0 +1,899 @@
+use std::io::Write;
+use std::marker::PhantomData;
+use thoth_api::model::{DOI_DOMAIN, ORCID_DOMAIN};
+use thoth_client::{
+    ContributionType, DeletedWork, LanguageRelation, PublicationType, SubjectType, Work,
+    WorkContributions, WorkIssues, WorkType,
+};
+use thoth_errors::{ThothError, ThothResult};
+
+use crate::record::RecordWriter;
+
+pub(crate) trait Marc21Specification {
+    fn generate(&self, works: &[Work]) -> ThothResult<Vec<u8>> {
+        let mut buffer = Vec::new();
+        Self::handle_event(&mut buffer, works).map(|_| buffer)
+    }
+
+    fn handle_event<W: Write>(w: &mut W, works: &[Work]) -> ThothResult<()>;
+}
+
+pub(crate) trait Marc21Entry<T: Marc21Specification> {
+    fn marc21_entry<W: Write>(&self, w: &mut W) -> ThothResult<()>;
+}
+
+/// Writes binary MARC records one page of works at a time. Files of ISO 2709 records have no
+/// header or footer, so each page is simply the concatenation of its records.
+pub(crate) struct Marc21StreamWriter<T: Marc21Specification> {
+    specification: PhantomData<T>,
+}
+
+impl<T: Marc21Specification> Marc21StreamWriter<T> {
+    pub(crate) fn new() -> Self {
+        Marc21StreamWriter {
+            specification: PhantomData,
+        }
+    }
+}
+
+impl<T: Marc21Specification> RecordWriter for Marc21StreamWriter<T>
+where
+    Work: Marc21Entry<T>,
+{
+    fn write_page(&mut self, works: &[Work]) -> ThothResult<Vec<u8>> {
+        let mut output = Vec::new();
+        for work in works.iter() {
+            // Records are serialised in full before being written, so failures leave no output
+            Marc21Entry::<T>::marc21_entry(work, &mut output).ok();
+        }
+        Ok(output)
+    }
+
+    /// MARC records cannot express deletions, so there is nothing left to write
+    fn finish(&mut self, _: &[DeletedWork]) -> ThothResult<Vec<u8>> {
+        Ok(Vec::new())
+    }
+}
+
+mod marc21_thoth;
+pub(crate) use marc21_thoth::Marc21Thoth;
+
+const LEADER_LENGTH: usize = 24;
+const SUBFIELD_DELIMITER: u8 = 0x1F;
+const FIELD_TERMINATOR: u8 = 0x1E;
+const RECORD_TERMINATOR: u8 = 0x1D;
+
+/// A bibliographic record expressed as MARC 21 fields, independently of its serialisation
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MarcRecord {
+    pub(crate) bibliographic_level: char,
+    pub(crate) control_fields: Vec<MarcControlField>,
+    pub(crate) data_fields: Vec<MarcDataField>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MarcControlField {
+    pub(crate) tag: &'static str,
+    pub(crate) value: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MarcDataField {
+    pub(crate) tag: &'static str,
+    pub(crate) ind1: char,
+    pub(crate) ind2: char,
+    pub(crate) subfields: Vec<MarcSubfield>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MarcSubfield {
+    pub(crate) code: char,
+    pub(crate) value: String,
+}
+
+impl MarcRecord {
+    /// Obtain the 24 character leader given the record length and base address of data
+    pub(crate) fn leader(&self, record_length: usize, base_address: usize) -> String {
+        // n New, a Language material, <level>, # No specified type of control, a UCS/Unicode,
+        // 2 indicators, 2 subfield code characters, # Full level, c ISBD punctuation omitted,
+        // # Not specified multipart level, 4500 directory entry map
+        format!(
+            "{:05}na{} a22{:05} c 4500",
+            record_length, self.bibliographic_level, base_address
+        )
+    }
+
+    /// Serialise the record in ISO 2709 exchange format, as used by binary MARC (.mrc) files
+    pub(crate) fn to_iso2709(&self) -> ThothResult<Vec<u8>> {
+        let fields = self
+            .control_fields
+            .iter()
+            .map(|f| (f.tag, f.value.as_bytes().to_vec()))
+            .chain(self.data_fields.iter().map(|f| (f.tag, f.to_iso2709())));
+        let mut directory: Vec<u8> = vec![];
+        let mut data: Vec<u8> = vec![];
+        for (tag, mut field) in fields {
+            field.push(FIELD_TERMINATOR);
+            // Each directory entry is made of tag (3), field length (4) and starting position (5)
+            if field.len() > 9999 {
+                return Err(ThothError::InternalError(format!(
+                    "MARC field {} exceeds the maximum length of 9999 bytes",
+                    tag
+                )));
+            }
+            directory.extend(format!("{}{:04}{:05}", tag, field.len(), data.len()).as_bytes());
+            data.extend(field);
+        }
+        directory.push(FIELD_TERMINATOR);
+
+        let base_address = LEADER_LENGTH + directory.len();
+        let record_length = base_address + data.len() + 1;
+        if record_length > 99999 {
+            return Err(ThothError::InternalError(
+                "MARC record exceeds the maximum length of 99999 bytes".to_string(),
+            ));
+        }
+        let mut record = self.leader(record_length, base_address).into_bytes();
+        record.extend(directory);
+        record.extend(data);
+        record.push(RECORD_TERMINATOR);
+        Ok(record)
+    }
+}
+
+impl MarcDataField {
+    fn new(tag: &'static str, ind1: char, ind2: char) -> Self {
+        MarcDataField {
+            tag,
+            ind1,
+            ind2,
+            subfields: vec![],
+        }
+    }
+
+    fn subfield(mut self, code: char, value: &str) -> Self {
+        self.subfields.push(MarcSubfield {
+            code,
+            value: value.to_string(),
+        });
+        self
+    }
+
+    fn optional_subfield(self, code: char, value: Option<&str>) -> Self {
+        match value {
+            Some(value) => self.subfield(code, value),
+            None => self,
+        }
+    }
+
+    /// Indicators followed by each subfield, excluding the field terminator
+    fn to_iso2709(&self) -> Vec<u8> {
+        let mut field = format!("{}{}", self.ind1, self.ind2).into_bytes();
+        for subfield in &self.subfields {
+            field.push(SUBFIELD_DELIMITER);
+            field.extend(subfield.code.to_string().as_bytes());
+            field.extend(subfield.value.as_bytes());
+        }
+        field
+    }
+}
+
+/// Fields that MARC records require and Thoth does not, as reported by `MarcRecord::from_work`
+pub(crate) fn missing_fields(work: &Work) -> Vec<&'static str> {
+    let mut missing = Vec::new();
+    if work.publication_date.is_none() {
+        missing.push("Missing Publication Date");
+    }
+    missing
+}
+
+impl MarcRecord {
+    /// Map a work to MARC 21 fields, reporting any missing data against the given specification
+    pub(crate) fn from_work(work: &Work, specification_id: &str) -> ThothResult<Self> {
+        // 008/07-10 and 264 $c are expected by most catalogues, and Thoth's is optional
+        let publication_date = work.publication_date.ok_or_else(|| {
+            ThothError::IncompleteMetadataRecord(
+                specification_id.to_string(),
+                "Missing Publication Date".to_string(),
+            )
+        })?;
+        let year = publication_date.format("%Y").to_string();
+        let main_language = work
+            .languages
+            .iter()
+            .find(|l| l.main_language)
+            .or_else(|| work.languages.first())
+            .map(|l| l.language_code.to_string().to_lowercase())
+            .unwrap_or_else(|| "und".to_string());
+        let bibliographic_level = match work.work_type {
+            // a Monographic component part
+            WorkType::BOOK_CHAPTER => 'a',
+            // m Monograph/Item
+            _ => 'm',
+        };
+
+        let mut contributions = work.contributions.clone();
+        contributions.sort_by_key(|c| c.contribution_ordinal);
+        // The main entry (100) is the first main author. Editors are always added entries (700).
+        let main_entry = contributions
+            .iter()
+            .position(|c| c.main_contribution && c.contribution_type == ContributionType::AUTHOR);
+
+        let control_fields = vec![
+            MarcControlField {
+                tag: "001",
+                value: work.work_id.to_string(),
+            },
+            MarcControlField {
+                tag: "005",
+                value: work
+                    .updated_at
+                    .to_datetime()
+                    .format("%Y%m%d%H%M%S.0")
+                    .to_string(),
+            },
+            // Electronic resource, remote
+            MarcControlField {
+                tag: "007",
+                value: "cr |n|||||||||".to_string(),
+            },
+            MarcControlField {
+                tag: "008",
+                // 00-05 date entered, 06 s single known date, 07-10 date 1, 15-17 place
+                // (xx unknown), 23 o online, 35-37 language, 39 d other cataloguing source
+                value: format!(
+                    "{}s{}    xx      o     000 0 {} d",
+                    work.created_at.to_datetime().format("%y%m%d"),
+                    year,
+                    main_language
+                ),
+            },
+        ];
+
+        let mut data_fields = vec![];
+        for publication in &work.publications {
+            if let Some(isbn) = &publication.isbn {
+                data_fields.push(
+                    MarcDataField::new("020", ' ', ' ')
+                        .subfield('a', &isbn.to_string().replace("-", ""))
+                        .subfield('q', publication_qualifier(&publication.publication_type)),
+                );
+            }
+        }
+        if let Some(doi) = &work.doi {
+            // 7 Source specified in $2
+            data_fields.push(
+                MarcDataField::new("024", '7', ' ')
+                    .subfield('a', &doi.to_string())
+                    .subfield('2', "doi"),
+            );
+        }
+        let translated_from: Vec<String> = work
+            .languages
+            .iter()
+            .filter(|l| l.language_relation == LanguageRelation::TRANSLATED_FROM)
+            .map(|l| l.language_code.to_string().to_lowercase())
+            .collect();
+        if work.languages.len() > 1 || !translated_from.is_empty() {
+            let mut field = MarcDataField::new(
+                "041",
+                if translated_from.is_empty() { '0' } else { '1' },
+                ' ',
+            );
+            for language in work
+                .languages
+                .iter()
+                .filter(|l| l.language_relation != LanguageRelation::TRANSLATED_FROM)
+            {
+                field = field.subfield('a', &language.language_code.to_string().to_lowercase());
+            }
+            for language in &translated_from {
+                field = field.subfield('h', language);
+            }
+            data_fields.push(field);
+        }
+        for subject in work
+            .subjects
+            .iter()
+            .filter(|s| s.subject_type == SubjectType::LCC)
+        {
+            // # No information provided, 4 Assigned by agency other than LC
+            data_fields
+                .push(MarcDataField::new("050", ' ', '4').subfield('a', &subject.subject_code));
+        }
+        if let Some(position) = main_entry {
+            data_fields.push(contribution_field("100", &contributions[position]));
+        }
+        data_fields.push(
+            MarcDataField::new("245", if main_entry.is_some() { '1' } else { '0' }, '0')
+                .subfield('a', &work.title)
+                .optional_subfield('b', work.subtitle.as_deref()),
+        );
+        if work.edition > 1 {
+            data_fields.push(
+                MarcDataField::new("250", ' ', ' ')
+                    .subfield('a', &format!("{} edition", ordinal(work.edition))),
+            );
+        }
+        // # Not applicable/No information provided, 1 Publication
+        data_fields.push(
+            MarcDataField::new("264", ' ', '1')
+                .optional_subfield('a', work.place.as_deref())
+                .subfield('b', &work.imprint.publisher.publisher_name)
+                .subfield('c', &year),
+        );
+        let extent = match (&work.page_breakdown, work.page_count) {
+            (Some(breakdown), _) => format!("1 online resource ({} pages)", breakdown),
+            (None, Some(count)) => format!("1 online resource ({} pages)", count),
+            (None, None) => "1 online resource".to_string(),
+        };
+        data_fields.push(
+            MarcDataField::new("300", ' ', ' ')
+                .subfield('a', &extent)
+                .optional_subfield(
+                    'b',
+                    work.image_count
+                        .filter(|count| *count > 0)
+                        .map(|_| "illustrations"),
+                ),
+        );
+        data_fields.push(
+            MarcDataField::new("336", ' ', ' ')
+                .subfield('a', "text")
+                .subfield('b', "txt")
+                .subfield('2', "rdacontent"),
+        );
+        data_fields.push(
+            MarcDataField::new("337", ' ', ' ')
+                .subfield('a', "computer")
+                .subfield('b', "c")
+                .subfield('2', "rdamedia"),
+        );
+        data_fields.push(
+            MarcDataField::new("338", ' ', ' ')
+                .subfield('a', "online resource")
+                .subfield('b', "cr")
+                .subfield('2', "rdacarrier"),
+        );
+        for issue in &work.issues {
+            // 1 Series traced in 8XX
+            data_fields.push(series_field("490", '1', ' ', issue));
+        }
+        if let Some(labstract) = work.long_abstract.as_ref().or(work.short_abstract.as_ref()) {
+            // 3 Abstract
+            data_fields.push(MarcDataField::new("520", '3', ' ').subfield('a', labstract));
+        }
+        if let Some(license) = &work.license {
+            let field = MarcDataField::new("540", ' ', ' ');
+            let field = match creative_commons_license(license) {
+                Some(name) => field.subfield('f', &name).subfield('2', "cc"),
+                None => field,
+            };
+            data_fields.push(field.subfield('u', license));
+        }
+        for subject in work.subjects.iter().filter(|s| {
+            matches!(
+                s.subject_type,
+                SubjectType::BIC | SubjectType::BISAC | SubjectType::THEMA
+            )
+        }) {
+            // # No information provided, 7 Source specified in $2
+            data_fields.push(
+                MarcDataField::new("650", ' ', '7')
+                    .subfield('a', &subject.subject_code)
+                    .subfield('2', subject_source(&subject.subject_type)),
+            );
+        }
+        for subject in work
+            .subjects
+            .iter()
+            .filter(|s| s.subject_type == SubjectType::KEYWORD)
+        {
+            // # No information provided, 0 Topical term
+            data_fields
+                .push(MarcDataField::new("653", ' ', '0').subfield('a', &subject.subject_code));
+        }
+        for (position, contribution) in contributions.iter().enumerate() {
+            if Some(position) != main_entry {
+                data_fields.push(contribution_field("700", contribution));
+            }
+        }
+        for issue in &work.issues {
+            // # Undefined, 0 Number of nonfiling characters
+            data_fields.push(series_field("830", ' ', '0', issue));
+        }
+        if let Some(doi) = &work.doi {
+            // 4 HTTP, 0 Resource
+            data_fields.push(
+                MarcDataField::new("856", '4', '0')
+                    .subfield('u', &format!("{}{}", DOI_DOMAIN, doi)),
+            );
+        }
+        if let Some(landing_page) = &work.landing_page {
+            data_fields.push(MarcDataField::new("856", '4', '0').subfield('u', landing_page));
+        }
+
+        Ok(MarcRecord {
+            bibliographic_level,
+            control_fields,
+            data_fields,
+        })
+    }
+}
+
+fn contribution_field(tag: &'static str, contribution: &WorkContributions) -> MarcDataField {
+    let (term, code) = relator(&contribution.contribution_type);
+    let field = match &contribution.first_name {
+        // 1 Surname
+        Some(first_name) => MarcDataField::new(tag, '1', ' ')
+            .subfield('a', &format!("{}, {}", contribution.last_name, first_name)),
+        // 0 Forename (direct order)
+        None => MarcDataField::new(tag, '0', ' ').subfield('a', &contribution.full_name),
+    };
+    let mut field = field.subfield('e', term).subfield('4', code);
+    let mut affiliations = contribution.affiliations.clone();
+    affiliations.sort_by_key(|a| a.affiliation_ordinal);
+    if let Some(affiliation) = affiliations.first() {
+        field = field.subfield('u', &affiliation.institution.institution_name);
+    }
+    if let Some(orcid) = &contribution.contributor.orcid {
+        field = field.subfield('1', &format!("{}{}", ORCID_DOMAIN, orcid));
+    }
+    field
+}
+
+fn series_field(tag: &'static str, ind1: char, ind2: char, issue: &WorkIssues) -> MarcDataField {
+    MarcDataField::new(tag, ind1, ind2)
+        .subfield('a', &issue.series.series_name)
+        .subfield('v', &issue.issue_ordinal.to_string())
+        .subfield('x', &issue.series.issn_digital)
+}
+
+/// MARC relator term and code corresponding to a contribution type
+pub(crate) fn relator(contribution_type: &ContributionType) -> (&'static str, &'static str) {
+    match contribution_type {
+        ContributionType::AUTHOR => ("author", "aut"),
+        ContributionType::EDITOR | ContributionType::MUSIC_EDITOR => ("editor", "edt"),
+        ContributionType::TRANSLATOR => ("translator", "trl"),
+        ContributionType::PHOTOGRAPHER => ("photographer", "pht"),
+        ContributionType::ILUSTRATOR => ("illustrator", "ill"),
+        ContributionType::FOREWORD_BY
+        | ContributionType::INTRODUCTION_BY
+        | ContributionType::PREFACE_BY => ("author of introduction, etc.", "aui"),
+        ContributionType::AFTERWORD_BY => ("author of afterword, colophon, etc.", "aft"),
+        ContributionType::Other(_) => unreachable!(),
+    }
+}
+
+fn publication_qualifier(publication_type: &PublicationType) -> &'static str {
+    match publication_type {
+        PublicationType::PAPERBACK => "paperback",
+        PublicationType::HARDBACK => "hardback",
+        PublicationType::PDF => "PDF",
+        PublicationType::HTML => "HTML",
+        PublicationType::XML => "XML",
+        PublicationType::EPUB => "EPUB",
+        PublicationType::MOBI => "MOBI",
+        PublicationType::Other(_) => unreachable!(),
+    }
+}
+
+fn subject_source(subject_type: &SubjectType) -> &'static str {
+    match subject_type {
+        SubjectType::BIC => "bicssc",
+        SubjectType::BISAC => "bisacsh",
+        SubjectType::THEMA => "thema",
+        _ => unreachable!(),
+    }
+}
+
+fn ordinal(number: i64) -> String {
+    let suffix = match (number % 10, number % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", number, suffix)
+}
+
+/// Derive a short licence name (e.g. "CC BY-NC 4.0") from a Creative Commons licence URL
+fn creative_commons_license(license: &str) -> Option<String> {
+    let path = license
+        .trim_end_matches('/')
+        .split("creativecommons.org/")
+        .nth(1)?;
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.as_slice() {
+        ["licenses", licence, version, ..] => {
+            Some(format!("CC {} {}", licence.to_uppercase(), version))
+        }
+        ["publicdomain", "zero", version, ..] => Some(format!("CC0 {}", version)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    use thoth_api::model::Doi;
+    use thoth_api::model::Isbn;
+    use thoth_api::model::Orcid;
+    use thoth_api::model::Timestamp;
+    use thoth_client::{
+        LanguageCode, SeriesType, WorkContributionsAffiliations,
+        WorkContributionsAffiliationsInstitution, WorkContributionsContributor, WorkImprint,
+        WorkImprintPublisher, WorkIssuesSeries, WorkLanguages, WorkPublications, WorkStatus,
+        WorkSubjects,
+    };
+    use uuid::Uuid;
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("https://creativecommons.org/licenses/by-nc/4.0/".to_string()),
+            copyright_holder: "Author 1; Author 2".to_string(),
+            short_abstract: None,
+            long_abstract: Some("Lorem ipsum dolor sit amet".to_string()),
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: Some(334),
+            page_breakdown: Some("x+334".to_string()),
+            first_page: None,
+            last_page: None,
+            image_count: Some(15),
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Timestamp::from_str("2020-03-14T09:26:53.589793Z").unwrap(),
+            updated_at: Timestamp::from_str("2021-06-01T12:30:00.123456Z").unwrap(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![WorkIssues {
+                issue_ordinal: 20,
+                series: WorkIssuesSeries {
+                    series_type: SeriesType::BOOK_SERIES,
+                    series_name: "Name of series".to_string(),
+                    issn_print: "1234-5678".to_string(),
+                    issn_digital: "8765-4321".to_string(),
+                    series_url: None,
+                },
+            }],
+            contributions: vec![
+                WorkContributions {
+                    contribution_type: ContributionType::EDITOR,
+                    first_name: None,
+                    last_name: "Editor".to_string(),
+                    full_name: "Sole Editor".to_string(),
+                    main_contribution: true,
+                    biography: None,
+                    contribution_ordinal: 2,
+                    contributor: WorkContributionsContributor { orcid: None },
+                    affiliations: vec![],
+                },
+                WorkContributions {
+                    contribution_type: ContributionType::AUTHOR,
+                    first_name: Some("Author".to_string()),
+                    last_name: "First".to_string(),
+                    full_name: "Author First".to_string(),
+                    main_contribution: true,
+                    biography: None,
+                    contribution_ordinal: 1,
+                    contributor: WorkContributionsContributor {
+                        orcid: Some(
+                            Orcid::from_str("https://orcid.org/0000-0002-0000-0001").unwrap(),
+                        ),
+                    },
+                    affiliations: vec![WorkContributionsAffiliations {
+                        position: None,
+                        affiliation_ordinal: 1,
+                        institution: WorkContributionsAffiliationsInstitution {
+                            institution_name: "University of Life".to_string(),
+                            ror: None,
+                        },
+                    }],
+                },
+            ],
+            languages: vec![WorkLanguages {
+                language_code: LanguageCode::SPA,
+                language_relation: LanguageRelation::ORIGINAL,
+                main_language: true,
+            }],
+            publications: vec![
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000002").unwrap(),
+                    publication_type: PublicationType::PAPERBACK,
+                    isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                    prices: vec![],
+                    locations: vec![],
+                },
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000003").unwrap(),
+                    publication_type: PublicationType::HTML,
+                    isbn: None,
+                    prices: vec![],
+                    locations: vec![],
+                },
+            ],
+            subjects: vec![
+                WorkSubjects {
+                    subject_code: "JWA".to_string(),
+                    subject_type: SubjectType::THEMA,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "keyword1".to_string(),
+                    subject_type: SubjectType::KEYWORD,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "Category1".to_string(),
+                    subject_type: SubjectType::CUSTOM,
+                    subject_ordinal: 1,
+                },
+            ],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    fn find_fields<'a>(record: &'a MarcRecord, tag: &str) -> Vec<&'a MarcDataField> {
+        record.data_fields.iter().filter(|f| f.tag == tag).collect()
+    }
+
+    fn subfields(field: &MarcDataField) -> Vec<(char, &str)> {
+        field
+            .subfields
+            .iter()
+            .map(|s| (s.code, s.value.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_marc21_record() {
+        let record = MarcRecord::from_work(&test_work(), "marc21xml::thoth").unwrap();
+        assert_eq!(record.leader(0, 0), "00000nam a2200000 c 4500");
+        assert_eq!(record.control_fields[0].tag, "001");
+        assert_eq!(
+            record.control_fields[0].value,
+            "00000000-0000-0000-aaaa-000000000001"
+        );
+        // The record's dates only change along with the work
+        assert_eq!(record.control_fields[1].value, "20210601123000.0");
+        let fixed_length = &record.control_fields[3].value;
+        assert_eq!(fixed_length.len(), 40);
+        assert_eq!(&fixed_length[0..6], "200314");
+        assert_eq!(&fixed_length[6..11], "s1999");
+        assert_eq!(&fixed_length[35..38], "spa");
+
+        // Data fields are output in ascending tag order
+        let tags: Vec<&str> = record.data_fields.iter().map(|f| f.tag).collect();
+        let mut sorted_tags = tags.clone();
+        sorted_tags.sort_unstable();
+        assert_eq!(tags, sorted_tags);
+
+        // Only publications with an ISBN are output
+        let isbns = find_fields(&record, "020");
+        assert_eq!(isbns.len(), 1);
+        assert_eq!(
+            subfields(isbns[0]),
+            vec![('a', "9783161484100"), ('q', "paperback")]
+        );
+        assert_eq!(
+            subfields(find_fields(&record, "024")[0]),
+            vec![('a', "10.00001/BOOK.0001"), ('2', "doi")]
+        );
+        // Single original language does not need a 041
+        assert!(find_fields(&record, "041").is_empty());
+
+        // The first author is the main entry, regardless of the order of contributions
+        let main_entry = find_fields(&record, "100");
+        assert_eq!(main_entry.len(), 1);
+        assert_eq!(main_entry[0].ind1, '1');
+        assert_eq!(
+            subfields(main_entry[0]),
+            vec![
+                ('a', "First, Author"),
+                ('e', "author"),
+                ('4', "aut"),
+                ('u', "University of Life"),
+                ('1', "https://orcid.org/0000-0002-0000-0001"),
+            ]
+        );
+        let added_entries = find_fields(&record, "700");
+        assert_eq!(added_entries.len(), 1);
+        assert_eq!(added_entries[0].ind1, '0');
+        assert_eq!(
+            subfields(added_entries[0]),
+            vec![('a', "Sole Editor"), ('e', "editor"), ('4', "edt")]
+        );
+
+        let title = find_fields(&record, "245")[0];
+        assert_eq!((title.ind1, title.ind2), ('1', '0'));
+        assert_eq!(
+            subfields(title),
+            vec![('a', "Book Title"), ('b', "Book Subtitle")]
+        );
+        assert!(find_fields(&record, "250").is_empty());
+        assert_eq!(
+            subfields(find_fields(&record, "264")[0]),
+            vec![('a', "León, Spain"), ('b', "OA Editions"), ('c', "1999")]
+        );
+        assert_eq!(
+            subfields(find_fields(&record, "300")[0]),
+            vec![
+                ('a', "1 online resource (x+334 pages)"),
+                ('b', "illustrations")
+            ]
+        );
+        assert_eq!(
+            subfields(find_fields(&record, "490")[0]),
+            vec![('a', "Name of series"), ('v', "20"), ('x', "8765-4321")]
+        );
+        assert_eq!(
+            subfields(find_fields(&record, "830")[0]),
+            vec![('a', "Name of series"), ('v', "20"), ('x', "8765-4321")]
+        );
+        assert_eq!(
+            subfields(find_fields(&record, "520")[0]),
+            vec![('a', "Lorem ipsum dolor sit amet")]
+        );
+        assert_eq!(
+            subfields(find_fields(&record, "540")[0]),
+            vec![
+                ('f', "CC BY-NC 4.0"),
+                ('2', "cc"),
+                ('u', "https://creativecommons.org/licenses/by-nc/4.0/")
+            ]
+        );
+        // Custom categories are not output
+        let subjects = find_fields(&record, "650");
+        assert_eq!(subjects.len(), 1);
+        assert_eq!(subfields(subjects[0]), vec![('a', "JWA"), ('2', "thema")]);
+        assert_eq!(
+            subfields(find_fields(&record, "653")[0]),
+            vec![('a', "keyword1")]
+        );
+        assert_eq!(find_fields(&record, "856").len(), 2);
+    }
+
+    #[test]
+    fn test_marc21_record_optional_fields() {
+        let mut work = test_work();
+        work.work_type = WorkType::BOOK_CHAPTER;
+        work.edition = 2;
+        work.license = Some("https://www.publisher.com/licence".to_string());
+        work.contributions.remove(1);
+        work.languages.push(WorkLanguages {
+            language_code: LanguageCode::ENG,
+            language_relation: LanguageRelation::TRANSLATED_FROM,
+            main_language: false,
+        });
+        work.page_breakdown = None;
+        work.image_count = None;
+        let record = MarcRecord::from_work(&work, "marc21xml::thoth").unwrap();
+        assert_eq!(record.leader(0, 0), "00000naa a2200000 c 4500");
+        // No author: title is the main entry
+        assert!(find_fields(&record, "100").is_empty());
+        assert_eq!(find_fields(&record, "245")[0].ind1, '0');
+        assert_eq!(
+            subfields(find_fields(&record, "250")[0]),
+            vec![('a', "2nd edition")]
+        );
+        let languages = find_fields(&record, "041")[0];
+        assert_eq!(languages.ind1, '1');
+        assert_eq!(subfields(languages), vec![('a', "spa"), ('h', "eng")]);
+        assert_eq!(
+            subfields(find_fields(&record, "300")[0]),
+            vec![('a', "1 online resource (334 pages)")]
+        );
+        // Licences other than Creative Commons are only output as a URI
+        assert_eq!(
+            subfields(find_fields(&record, "540")[0]),
+            vec![('u', "https://www.publisher.com/licence")]
+        );
+
+        work.publication_date = None;
+        assert_eq!(
+            MarcRecord::from_work(&work, "marc21::thoth"),
+            Err(ThothError::IncompleteMetadataRecord(
+                "marc21::thoth".to_string(),
+                "Missing Publication Date".to_string(),
+            ))
+        );
+    }
+
+    #[test]
+    fn test_marc21_iso2709() {
+        let record = MarcRecord {
+            bibliographic_level: 'm',
+            control_fields: vec![MarcControlField {
+                tag: "001",
+                value: "id".to_string(),
+            }],
+            data_fields: vec![MarcDataField::new("245", '0', '0')
+                .subfield('a', "Títle")
+                .subfield('b', "Subtitle")],
+        };
+        let output = record.to_iso2709().unwrap();
+        // Lengths are counted in bytes, so the accented character takes up two positions
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "00074nam a2200049 c 4500001000300000245002100003\u{1e}id\u{1e}00\u{1f}aTítle\u{1f}bSubtitle\u{1e}\u{1d}"
+        );
+
+        let record = MarcRecord {
+            bibliographic_level: 'm',
+            control_fields: vec![],
+            data_fields: vec![MarcDataField::new("520", '3', ' ').subfield('a', &"a".repeat(9999))],
+        };
+        assert_eq!(
+            record.to_iso2709(),
+            Err(ThothError::InternalError(
+                "MARC field 520 exceeds the maximum length of 9999 bytes".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_marc21_ordinal() {
+        assert_eq!(ordinal(1), "1st");
+        assert_eq!(ordinal(2), "2nd");
+        assert_eq!(ordinal(3), "3rd");
+        assert_eq!(ordinal(4), "4th");
+        assert_eq!(ordinal(11), "11th");
+        assert_eq!(ordinal(12), "12th");
+        assert_eq!(ordinal(22), "22nd");
+    }
+
+    #[test]
+    fn test_marc21_creative_commons_license() {
+        assert_eq!(
+            creative_commons_license("https://creativecommons.org/licenses/by/4.0/"),
+            Some("CC BY 4.0".to_string())
+        );
+        assert_eq!(
+            creative_commons_license("http://creativecommons.org/licenses/by-nc-nd/3.0"),
+            Some("CC BY-NC-ND 3.0".to_string())
+        );
+        assert_eq!(
+            creative_commons_license("https://creativecommons.org/publicdomain/zero/1.0/"),
+            Some("CC0 1.0".to_string())
+        );
+        assert_eq!(
+            creative_commons_license("https://www.publisher.com/licence"),
+            None
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use actix_web::{Error, HttpRequest};
+use chrono::Utc;
+use paperclip::actix::{api_v2_operation, web};
+use thoth_client::{DeletedWork, ThothClient, Work, WORKS_PAGE_SIZE};
+use thoth_errors::ThothError;
+
+use super::model::{Cursor, Harvest, MetadataFormat, OaiError, OaiRequest, Repository, Set};
+use super::response::{Item, OaiResponse, Page, Payload, Record};
+
+#[api_v2_operation(
+    summary = "OAI-PMH data provider",
+    description = "Harvest works' metadata using the Open Archives Initiative Protocol for Metadata Harvesting, version 2.0. Records are available in Dublin Core (oai_dc), MARC 21 XML (marc21), MODS (mods) and DataCite (datacite), and grouped in a set per publisher and per imprint. Records are listed in the order they last changed, and deleted works are reported transiently, with headers marked as deleted.",
+    produces = "text/xml",
+    tags(OAI-PMH)
+)]
+pub(crate) async fn get_oai(
+    req: HttpRequest,
+    thoth_client: web::Data<ThothClient>,
+    repository: web::Data<Repository>,
+) -> Result<OaiResponse, Error> {
+    respond(req.query_string(), &thoth_client, &repository).await
+}
+
+#[api_v2_operation(
+    summary = "OAI-PMH data provider",
+    description = "Harvest works' metadata using the Open Archives Initiative Protocol for Metadata Harvesting, version 2.0, with the request's arguments encoded in its body.",
+    consumes = "application/x-www-form-urlencoded",
+    produces = "text/xml",
+    tags(OAI-PMH)
+)]
+pub(crate) async fn post_oai(
+    body: web::Bytes,
+    thoth_client: web::Data<ThothClient>,
+    repository: web::Data<Repository>,
+) -> Result<OaiResponse, Error> {
+    respond(&String::from_utf8_lossy(&body), &thoth_client, &repository).await
+}
+
+async fn respond(
+    arguments: &str,
+    thoth_client: &ThothClient,
+    repository: &Repository,
+) -> Result<OaiResponse, Error> {
+    let response_date = Utc::now();
+    let (arguments, outcome) = match serde_urlencoded::from_str::<Vec<(String, String)>>(arguments)
+    {
+        Ok(arguments) => {
+            let outcome = match OaiRequest::parse(&arguments) {
+                Ok(request) => payload(request, thoth_client, repository).await,
+                Err(e) => Err(e),
+            };
+            (arguments, outcome)
+        }
+        Err(_) => (
+            vec![],
+            Err(OaiError::BadArgument(
+                "Arguments are not URL encoded".to_string(),
+            )),
+        ),
+    };
+    match outcome {
+        Err(OaiError::Internal(e)) => Err(e.into()),
+        outcome => Ok(OaiResponse::new(
+            repository,
+            response_date,
+            &arguments,
+            &outcome,
+        )?),
+    }
+}
+
+async fn payload(
+    request: OaiRequest,
+    thoth_client: &ThothClient,
+    repository: &Repository,
+) -> Result<Payload, OaiError> {
+    match request {
+        OaiRequest::Identify => Ok(Payload::Identify),
+        OaiRequest::ListMetadataFormats { identifier } => {
+            // Every format is available for any work, as long as it exists or once existed
+            if let Some(identifier) = identifier {
+                find_item(&identifier, thoth_client, repository).await?;
+            }
+            Ok(Payload::ListMetadataFormats)
+        }
+        OaiRequest::ListSets => Ok(Payload::ListSets(thoth_client.get_publishers().await?)),
+        OaiRequest::GetRecord { identifier, format } => {
+            find_item(&identifier, thoth_client, repository)
+                .await?
+                .into_item(format)
+                .map(Payload::GetRecord)
+                .ok_or_else(|| OaiError::CannotDisseminateFormat(format.prefix.to_string()))
+        }
+        OaiRequest::ListIdentifiers(harvest) => Ok(Payload::ListIdentifiers(
+            harvest_page(&harvest, thoth_client).await?,
+        )),
+        OaiRequest::ListRecords(harvest) => Ok(Payload::ListRecords(
+            harvest_page(&harvest, thoth_client).await?,
+        )),
+    }
+}
+
+/// The latest change to a work: its last update, along with the work, or its deletion
+enum Change {
+    Updated(Box<Work>),
+    Deleted(DeletedWork),
+}
+
+impl Change {
+    fn cursor(&self) -> Cursor {
+        match self {
+            Change::Updated(work) => Cursor::of_work(work),
+            Change::Deleted(deleted_work) => Cursor::of_deleted_work(deleted_work),
+        }
+    }
+
+    /// The item to list for this change, unless the work cannot be represented in the format
+    fn into_item(self, format: &MetadataFormat) -> Option<Item> {
+        match self {
+            Change::Updated(work) => format.disseminate(&work).ok().map(|metadata| {
+                Item::Record(Box::new(Record {
+                    work: *work,
+                    metadata,
+                }))
+            }),
+            Change::Deleted(deleted_work) => Some(Item::Deleted(deleted_work)),
+        }
+    }
+}
+
+/// Find the latest change to the work an item identifier refers to
+async fn find_item(
+    identifier: &str,
+    thoth_client: &ThothClient,
+    repository: &Repository,
+) -> Result<Change, OaiError> {
+    let does_not_exist = || OaiError::IdDoesNotExist(identifier.to_string());
+    let work_id = repository.work_id(identifier).ok_or_else(does_not_exist)?;
+    match thoth_client.get_work(work_id).await {
+        Ok(work) => Ok(Change::Updated(Box::new(work))),
+        Err(ThothError::EntityNotFound) => match thoth_client.get_deleted_work(work_id).await {
+            Ok(deleted_work) => Ok(Change::Deleted(deleted_work)),
+            Err(ThothError::EntityNotFound) => Err(does_not_exist()),
+            Err(e) => Err(e.into()),
+        },
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Collect the next page of items in the harvest, in the order they last changed, skipping pages
+/// of works that were all filtered out, or that cannot be represented in the format requested.
+///
+/// Works are fetched one page at a time, after the harvest's cursor. Deletions are fetched in
+/// full, as they are few and only kept transiently, and are interleaved with the works.
+async fn harvest_page(harvest: &Harvest, thoth_client: &ThothClient) -> Result<Page, OaiError> {
+    let deleted_works = fetch_deleted_works(harvest, thoth_client).await?;
+    let mut cursor = harvest.cursor.clone();
+    loop {
+        let works = fetch_works(harvest, &cursor, thoth_client).await?;
+        let has_more_works = works.len() as i64 == WORKS_PAGE_SIZE;
+        // Deletions after the last work fetched may come after works not fetched yet
+        let last_work = works.last().map(Cursor::of_work).filter(|_| has_more_works);
+        let mut changes: Vec<Change> = works
+            .into_iter()
+            .map(|work| Change::Updated(Box::new(work)))
+            .chain(
+                deleted_works
+                    .iter()
+                    .filter(|deleted_work| {
+                        let position = Cursor::of_deleted_work(deleted_work);
+                        !matches!(&cursor, Some(cursor) if &position <= cursor)
+                            && !matches!(&last_work, Some(last_work) if &position > last_work)
+                    })
+                    .cloned()
+                    .map(Change::Deleted),
+            )
+            .collect();
+        changes.sort_by_key(Change::cursor);
+        let has_more_changes = has_more_works || changes.len() as i64 > WORKS_PAGE_SIZE;
+        changes.truncate(WORKS_PAGE_SIZE as usize);
+        let next_cursor = changes.last().map(Change::cursor);
+        // Changes are listed in the order they happened, so none after `until` can match
+        let has_next_page = has_more_changes
+            && !matches!(&next_cursor, Some(next_cursor) if harvest.is_past(next_cursor));
+        let items: Vec<Item> = changes
+            .into_iter()
+            .filter(|change| harvest.includes(&change.cursor()))
+            .filter_map(|change| change.into_item(harvest.format))
+            .collect();
+        if items.is_empty() && has_next_page {
+            cursor = next_cursor;
+            continue;
+        }
+        if items.is_empty() && harvest.cursor.is_none() {
+            return Err(OaiError::NoRecordsMatch);
+        }
+        let resumption_token = match next_cursor {
+            Some(next_cursor) if has_next_page => Some(harvest.resumption_token(&next_cursor)),
+            // Tell harvesters that resumed the list that it is now complete
+            _ if harvest.cursor.is_some() => Some(String::new()),
+            _ => None,
+        };
+        return Ok(Page {
+            items,
+            resumption_token,
+        });
+    }
+}
+
+/// The page of works that changed after `cursor`, or since the start of the harvest
+async fn fetch_works(
+    harvest: &Harvest,
+    cursor: &Option<Cursor>,
+    thoth_client: &ThothClient,
+) -> Result<Vec<Work>, OaiError> {
+    let (updated_since, after_work_id) = match cursor {
+        Some(cursor) => (Some(cursor.changed_at()?), Some(cursor.work_id)),
+        None => (harvest.updated_since()?, None),
+    };
+    match harvest.set {
+        Some(Set::Imprint(imprint_id)) => match thoth_client
+            .get_updated_imprint_works_page(
+                imprint_id,
+                updated_since,
+                after_work_id,
+                WORKS_PAGE_SIZE,
+            )
+            .await
+        {
+            Err(ThothError::EntityNotFound) => Ok(vec![]),
+            result => result.map_err(OaiError::from),
+        },
+        ref set => {
+            let publishers = match set {
+                Some(Set::Publisher(publisher_id)) => Some(vec![*publisher_id]),
+                _ => None,
+            };
+            Ok(thoth_client
+                .get_updated_works_page(publishers, updated_since, after_work_id, WORKS_PAGE_SIZE)
+                .await?)
+        }
+    }
+}
+
+/// Deletions since the start of the harvest. Deletions only record the publisher a work belonged
+/// to, so imprint sets list none.
+async fn fetch_deleted_works(
+    harvest: &Harvest,
+    thoth_client: &ThothClient,
+) -> Result<Vec<DeletedWork>, OaiError> {
+    let publishers = match harvest.set {
+        Some(Set::Imprint(_)) => return Ok(vec![]),
+        Some(Set::Publisher(publisher_id)) => Some(vec![publisher_id]),
+        None => None,
+    };
+    Ok(thoth_client
+        .get_deleted_works(publishers, harvest.updated_since()?)
+        .await?)
+}
@@ -0,0 +1,14 @@
+mod handler;
+pub(crate) mod model;
+mod response;
+
+use self::handler::{get_oai, post_oai};
+use paperclip::actix::web;
+
+pub(crate) fn route(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/oai")
+            .route(web::get().to(get_oai))
+            .route(web::post().to(post_oai)),
+    );
+}
@@ -0,0 +1,727 @@
+use chrono::{DateTime, Duration, NaiveDate, SecondsFormat, TimeZone, Utc};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use thoth_api::model::Timestamp;
+use thoth_client::{DeletedWork, Work, WorkImprint};
+use thoth_errors::{ThothError, ThothResult};
+use uuid::Uuid;
+
+use crate::record::MetadataSpecification;
+
+/// Format of datestamps, at the finest granularity supported by the repository
+pub(crate) const DATESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+const DAY_FORMAT: &str = "%Y-%m-%d";
+const TOKEN_SEPARATOR: char = '!';
+
+/// Identity of the OAI-PMH data provider, derived from the public facing URL of the export API
+pub(crate) struct Repository {
+    base_url: String,
+    identifier: String,
+}
+
+impl Repository {
+    pub(crate) fn new(public_url: &str) -> Self {
+        let root = public_url.trim_end_matches('/');
+        let host = root
+            .split("://")
+            .last()
+            .and_then(|authority| authority.split(&['/', ':'][..]).next())
+            .unwrap_or_default();
+        Repository {
+            base_url: format!("{}/oai", root),
+            identifier: host.to_string(),
+        }
+    }
+
+    pub(crate) fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// OAI identifier of a work, e.g. `oai:export.thoth.pub:<work_id>`
+    pub(crate) fn item_identifier(&self, work_id: &Uuid) -> String {
+        format!("oai:{}:{}", self.identifier, work_id)
+    }
+
+    pub(crate) fn work_id(&self, item_identifier: &str) -> Option<Uuid> {
+        item_identifier
+            .strip_prefix("oai:")?
+            .strip_prefix(self.identifier.as_str())?
+            .strip_prefix(':')?
+            .parse()
+            .ok()
+    }
+}
+
+/// A metadata format that records can be disseminated in
+#[derive(Debug, PartialEq)]
+pub(crate) struct MetadataFormat {
+    pub(crate) prefix: &'static str,
+    pub(crate) schema: &'static str,
+    pub(crate) namespace: &'static str,
+    specification_id: &'static str,
+}
+
+/// XML specifications offered to harvesters. ONIX schemas are only distributed as downloads,
+/// without a URL that harvesters could resolve, so ONIX is not offered.
+pub(crate) const METADATA_FORMATS: &[MetadataFormat] = &[
+    MetadataFormat {
+        prefix: "oai_dc",
+        schema: "http://www.openarchives.org/OAI/2.0/oai_dc.xsd",
+        namespace: "http://www.openarchives.org/OAI/2.0/oai_dc/",
+        specification_id: "oai_dc::thoth",
+    },
+    MetadataFormat {
+        prefix: "marc21",
+        schema: "http://www.loc.gov/standards/marcxml/schema/MARC21slim.xsd",
+        namespace: "http://www.loc.gov/MARC21/slim",
+        specification_id: "marc21xml::thoth",
+    },
+    MetadataFormat {
+        prefix: "mods",
+        schema: "http://www.loc.gov/standards/mods/v3/mods-3-7.xsd",
+        namespace: "http://www.loc.gov/mods/v3",
+        specification_id: "mods::thoth",
+    },
+    MetadataFormat {
+        prefix: "datacite",
+        schema: "http://schema.datacite.org/meta/kernel-4.4/metadata.xsd",
+        namespace: "http://datacite.org/schema/kernel-4",
+        specification_id: "datacite::thoth",
+    },
+];
+
+impl MetadataFormat {
+    pub(crate) fn find(prefix: &str) -> Result<&'static Self, OaiError> {
+        METADATA_FORMATS
+            .iter()
+            .find(|format| format.prefix == prefix)
+            .ok_or_else(|| OaiError::CannotDisseminateFormat(prefix.to_string()))
+    }
+
+    /// A work's metadata in this format, as a standalone XML document
+    pub(crate) fn disseminate(&self, work: &Work) -> ThothResult<Vec<u8>> {
+        MetadataSpecification::from_str(self.specification_id)?
+            .generate(std::slice::from_ref(work), &[])
+    }
+}
+
+/// Sets that works are grouped in: every work belongs to the set of its publisher and to that of
+/// its imprint. Set specs follow the form `publisher_<publisher_id>`.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Set {
+    Publisher(Uuid),
+    Imprint(Uuid),
+}
+
+impl Set {
+    pub(crate) fn of_imprint(imprint: &WorkImprint) -> Vec<Set> {
+        vec![
+            Set::Publisher(imprint.publisher.publisher_id),
+            Set::Imprint(imprint.imprint_id),
+        ]
+    }
+
+    /// Deletions only record the publisher a work belonged to, so deleted works are only listed
+    /// in the set of their publisher
+    pub(crate) fn of_deleted_work(deleted_work: &DeletedWork) -> Vec<Set> {
+        vec![Set::Publisher(deleted_work.publisher_id)]
+    }
+
+    pub(crate) fn spec(&self) -> String {
+        match self {
+            Set::Publisher(publisher_id) => format!("publisher_{}", publisher_id),
+            Set::Imprint(imprint_id) => format!("imprint_{}", imprint_id),
+        }
+    }
+
+    fn from_spec(spec: &str) -> Option<Self> {
+        if let Some(publisher_id) = spec.strip_prefix("publisher_") {
+            publisher_id.parse().ok().map(Set::Publisher)
+        } else if let Some(imprint_id) = spec.strip_prefix("imprint_") {
+            imprint_id.parse().ok().map(Set::Imprint)
+        } else {
+            None
+        }
+    }
+}
+
+/// An OAI-PMH error condition, reported to the harvester within a successful response
+#[derive(Debug, PartialEq)]
+pub(crate) enum OaiError {
+    BadArgument(String),
+    BadResumptionToken(String),
+    BadVerb(String),
+    CannotDisseminateFormat(String),
+    IdDoesNotExist(String),
+    NoRecordsMatch,
+    /// Failure to obtain the data requested, answered with an HTTP error instead
+    Internal(ThothError),
+}
+
+impl OaiError {
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            OaiError::BadArgument(_) => "badArgument",
+            OaiError::BadResumptionToken(_) => "badResumptionToken",
+            OaiError::BadVerb(_) => "badVerb",
+            OaiError::CannotDisseminateFormat(_) => "cannotDisseminateFormat",
+            OaiError::IdDoesNotExist(_) => "idDoesNotExist",
+            OaiError::NoRecordsMatch => "noRecordsMatch",
+            OaiError::Internal(_) => "",
+        }
+    }
+
+    pub(crate) fn message(&self) -> String {
+        match self {
+            OaiError::BadArgument(message) | OaiError::BadVerb(message) => message.clone(),
+            OaiError::BadResumptionToken(token) => {
+                format!("{} is not a valid resumption token", token)
+            }
+            OaiError::CannotDisseminateFormat(prefix) => {
+                format!(
+                    "Records are not available in the {} metadata format",
+                    prefix
+                )
+            }
+            OaiError::IdDoesNotExist(identifier) => format!("{} is not a known item", identifier),
+            OaiError::NoRecordsMatch => "No records match the arguments given".to_string(),
+            OaiError::Internal(e) => e.to_string(),
+        }
+    }
+
+    /// Whether the request was so malformed that its arguments must not be echoed back
+    pub(crate) fn is_illegal_request(&self) -> bool {
+        matches!(self, OaiError::BadArgument(_) | OaiError::BadVerb(_))
+    }
+}
+
+impl From<ThothError> for OaiError {
+    fn from(error: ThothError) -> Self {
+        OaiError::Internal(error)
+    }
+}
+
+/// Position of an item in a list ordered by the time of its last change, at full precision, and
+/// then by work ID. Unlike an offset, it stays valid as works are added, updated or deleted.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Cursor {
+    pub(crate) changed_at: DateTime<Utc>,
+    pub(crate) work_id: Uuid,
+}
+
+impl Cursor {
+    pub(crate) fn of_work(work: &Work) -> Self {
+        Cursor {
+            changed_at: work.updated_at_with_relations.to_datetime(),
+            work_id: work.work_id,
+        }
+    }
+
+    pub(crate) fn of_deleted_work(deleted_work: &DeletedWork) -> Self {
+        Cursor {
+            changed_at: deleted_work.deleted_at.to_datetime(),
+            work_id: deleted_work.work_id,
+        }
+    }
+
+    /// Datestamp of the item at this position, truncated to the second
+    pub(crate) fn datestamp(&self) -> DateTime<Utc> {
+        Utc.timestamp(self.changed_at.timestamp(), 0)
+    }
+
+    pub(crate) fn changed_at(&self) -> ThothResult<Timestamp> {
+        Timestamp::from_str(&self.changed_at.to_rfc3339())
+    }
+}
+
+/// A request to list identifiers or records, resumed after the last item of a previous page if
+/// there is a `cursor`
+#[derive(Debug, PartialEq)]
+pub(crate) struct Harvest {
+    pub(crate) format: &'static MetadataFormat,
+    pub(crate) from: Option<DateTime<Utc>>,
+    pub(crate) until: Option<DateTime<Utc>>,
+    pub(crate) set: Option<Set>,
+    pub(crate) cursor: Option<Cursor>,
+}
+
+impl Harvest {
+    fn new(arguments: &BTreeMap<&str, &str>) -> Result<Self, OaiError> {
+        let prefix = arguments
+            .get("metadataPrefix")
+            .ok_or_else(|| OaiError::BadArgument("metadataPrefix is required".to_string()))?;
+        let format = MetadataFormat::find(prefix)?;
+        let from = arguments
+            .get("from")
+            .map(|from| parse_datestamp(from, false));
+        let until = arguments
+            .get("until")
+            .map(|until| parse_datestamp(until, true));
+        if let (Some(from), Some(until)) = (arguments.get("from"), arguments.get("until")) {
+            if is_day(from) != is_day(until) {
+                return Err(OaiError::BadArgument(
+                    "from and until must have the same granularity".to_string(),
+                ));
+            }
+        }
+        let from = from.transpose()?;
+        let until = until.transpose()?;
+        if let (Some(from), Some(until)) = (from, until) {
+            if from > until {
+                return Err(OaiError::BadArgument(
+                    "from must not be later than until".to_string(),
+                ));
+            }
+        }
+        let set = arguments
+            .get("set")
+            .map(|spec| {
+                Set::from_spec(spec)
+                    .ok_or_else(|| OaiError::BadArgument(format!("{} is not a valid set", spec)))
+            })
+            .transpose()?;
+        Ok(Harvest {
+            format,
+            from,
+            until,
+            set,
+            cursor: None,
+        })
+    }
+
+    fn from_token(token: &str) -> Result<Self, OaiError> {
+        let bad_token = || OaiError::BadResumptionToken(token.to_string());
+        let parts: Vec<&str> = token.split(TOKEN_SEPARATOR).collect();
+        if parts.len() != 6 {
+            return Err(bad_token());
+        }
+        let datestamp = |value: &str| match value {
+            "" => Ok(None),
+            value => Utc
+                .datetime_from_str(value, DATESTAMP_FORMAT)
+                .map(Some)
+                .map_err(|_| bad_token()),
+        };
+        Ok(Harvest {
+            format: MetadataFormat::find(parts[0]).map_err(|_| bad_token())?,
+            from: datestamp(parts[1])?,
+            until: datestamp(parts[2])?,
+            set: match parts[3] {
+                "" => None,
+                spec => Some(Set::from_spec(spec).ok_or_else(bad_token)?),
+            },
+            cursor: Some(Cursor {
+                changed_at: DateTime::parse_from_rfc3339(parts[4])
+                    .map_err(|_| bad_token())?
+                    .with_timezone(&Utc),
+                work_id: parts[5].parse().map_err(|_| bad_token())?,
+            }),
+        })
+    }
+
+    /// Token with which to request the list after the item at `cursor`
+    pub(crate) fn resumption_token(&self, cursor: &Cursor) -> String {
+        let datestamp = |value: Option<DateTime<Utc>>| {
+            value
+                .map(|value| value.format(DATESTAMP_FORMAT).to_string())
+                .unwrap_or_default()
+        };
+        [
+            self.format.prefix.to_string(),
+            datestamp(self.from),
+            datestamp(self.until),
+            self.set.as_ref().map(Set::spec).unwrap_or_default(),
+            cursor
+                .changed_at
+                .to_rfc3339_opts(SecondsFormat::Micros, true),
+            cursor.work_id.to_string(),
+        ]
+        .join(&TOKEN_SEPARATOR.to_string())
+    }
+
+    /// Lower bound to query works by, slightly earlier than `from`: works updated within the same
+    /// second are only told apart by their datestamps, once truncated
+    pub(crate) fn updated_since(&self) -> ThothResult<Option<Timestamp>> {
+        self.from
+            .map(|from| Timestamp::from_str(&(from - Duration::seconds(1)).to_rfc3339()))
+            .transpose()
+    }
+
+    pub(crate) fn includes(&self, cursor: &Cursor) -> bool {
+        let datestamp = cursor.datestamp();
+        !matches!(self.from, Some(from) if datestamp < from) && !self.is_past(cursor)
+    }
+
+    /// Whether the item at `cursor`, and therefore any after it, changed later than `until`
+    pub(crate) fn is_past(&self, cursor: &Cursor) -> bool {
+        matches!(self.until, Some(until) if cursor.datestamp() > until)
+    }
+}
+
+fn is_day(datestamp: &str) -> bool {
+    !datestamp.contains('T')
+}
+
+/// Parse a datestamp at either granularity. Days stand for their first second, or for their last
+/// one at the end of a range.
+fn parse_datestamp(value: &str, end_of_range: bool) -> Result<DateTime<Utc>, OaiError> {
+    let bad_datestamp = || OaiError::BadArgument(format!("{} is not a valid datestamp", value));
+    if is_day(value) {
+        let day = NaiveDate::parse_from_str(value, DAY_FORMAT).map_err(|_| bad_datestamp())?;
+        let time = if end_of_range {
+            day.and_hms(23, 59, 59)
+        } else {
+            day.and_hms(0, 0, 0)
+        };
+        Ok(Utc.from_utc_datetime(&time))
+    } else {
+        Utc.datetime_from_str(value, DATESTAMP_FORMAT)
+            .map_err(|_| bad_datestamp())
+    }
+}
+
+/// A request to the data provider, with its arguments checked against those its verb takes
+#[derive(Debug, PartialEq)]
+pub(crate) enum OaiRequest {
+    Identify,
+    ListMetadataFormats {
+        identifier: Option<String>,
+    },
+    ListSets,
+    ListIdentifiers(Harvest),
+    ListRecords(Harvest),
+    GetRecord {
+        identifier: String,
+        format: &'static MetadataFormat,
+    },
+}
+
+impl OaiRequest {
+    pub(crate) fn parse(arguments: &[(String, String)]) -> Result<Self, OaiError> {
+        let mut args: BTreeMap<&str, &str> = BTreeMap::new();
+        for (name, value) in arguments {
+            if args.insert(name, value).is_some() {
+                return Err(OaiError::BadArgument(format!("{} is repeated", name)));
+            }
+        }
+        let verb = args
+            .remove("verb")
+            .ok_or_else(|| OaiError::BadVerb("verb is required".to_string()))?;
+        let legal_arguments: &[&str] = match verb {
+            "Identify" => &[],
+            "ListMetadataFormats" => &["identifier"],
+            "ListSets" => &["resumptionToken"],
+            "ListIdentifiers" | "ListRecords" => {
+                &["metadataPrefix", "from", "until", "set", "resumptionToken"]
+            }
+            "GetRecord" => &["identifier", "metadataPrefix"],
+            _ => {
+                return Err(OaiError::BadVerb(format!(
+                    "{} is not a legal OAI-PMH verb",
+                    verb
+                )))
+            }
+        };
+        if let Some(name) = args.keys().find(|name| !legal_arguments.contains(name)) {
+            return Err(OaiError::BadArgument(format!(
+                "{} is not a legal argument of {}",
+                name, verb
+            )));
+        }
+        let required = |name: &str| {
+            args.get(name)
+                .map(|value| value.to_string())
+                .ok_or_else(|| OaiError::BadArgument(format!("{} is required", name)))
+        };
+        match verb {
+            "Identify" => Ok(OaiRequest::Identify),
+            "ListMetadataFormats" => Ok(OaiRequest::ListMetadataFormats {
+                identifier: args.get("identifier").map(|value| value.to_string()),
+            }),
+            // Sets are listed in full, so there is never a token to resume from
+            "ListSets" => match args.get("resumptionToken") {
+                Some(token) => Err(OaiError::BadResumptionToken(token.to_string())),
+                None => Ok(OaiRequest::ListSets),
+            },
+            "GetRecord" => {
+                let identifier = required("identifier")?;
+                let format = MetadataFormat::find(&required("metadataPrefix")?)?;
+                Ok(OaiRequest::GetRecord { identifier, format })
+            }
+            _ => {
+                let harvest = match args.get("resumptionToken") {
+                    Some(_) if args.len() > 1 => {
+                        return Err(OaiError::BadArgument(
+                            "resumptionToken is an exclusive argument".to_string(),
+                        ))
+                    }
+                    Some(token) => Harvest::from_token(token)?,
+                    None => Harvest::new(&args)?,
+                };
+                if verb == "ListIdentifiers" {
+                    Ok(OaiRequest::ListIdentifiers(harvest))
+                } else {
+                    Ok(OaiRequest::ListRecords(harvest))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(arguments: &[(&str, &str)]) -> Result<OaiRequest, OaiError> {
+        let arguments: Vec<(String, String)> = arguments
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        OaiRequest::parse(&arguments)
+    }
+
+    #[test]
+    fn test_repository_identifiers() {
+        let repository = Repository::new("https://export.thoth.pub/");
+        let work_id = Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap();
+        assert_eq!(repository.base_url(), "https://export.thoth.pub/oai");
+        let identifier = repository.item_identifier(&work_id);
+        assert_eq!(
+            identifier,
+            "oai:export.thoth.pub:00000000-0000-0000-aaaa-000000000001"
+        );
+        assert_eq!(repository.work_id(&identifier), Some(work_id));
+        assert_eq!(
+            repository.work_id("oai:thoth.pub:00000000-0000-0000-aaaa-000000000001"),
+            None
+        );
+        assert_eq!(repository.work_id("oai:export.thoth.pub:1"), None);
+        assert_eq!(
+            Repository::new("http://localhost:8181").item_identifier(&work_id),
+            "oai:localhost:00000000-0000-0000-aaaa-000000000001"
+        );
+    }
+
+    #[test]
+    fn test_metadata_formats_use_known_specifications() {
+        for format in METADATA_FORMATS {
+            assert!(MetadataSpecification::from_str(format.specification_id).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_set_specs() {
+        let set = Set::Imprint(Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap());
+        assert_eq!(set.spec(), "imprint_00000000-0000-0000-bbbb-000000000001");
+        assert_eq!(Set::from_spec(&set.spec()), Some(set));
+        assert_eq!(
+            Set::from_spec("series_00000000-0000-0000-bbbb-000000000001"),
+            None
+        );
+        assert_eq!(Set::from_spec("publisher_1"), None);
+    }
+
+    #[test]
+    fn test_harvest_cursors() {
+        let harvest = Harvest {
+            format: &METADATA_FORMATS[0],
+            from: Some(Utc.ymd(2021, 6, 1).and_hms(0, 0, 0)),
+            until: Some(Utc.ymd(2021, 6, 1).and_hms(23, 59, 59)),
+            set: None,
+            cursor: None,
+        };
+        let cursor = |changed_at: DateTime<Utc>, work_id: &str| Cursor {
+            changed_at,
+            work_id: Uuid::from_str(work_id).unwrap(),
+        };
+        let first = cursor(
+            Utc.ymd(2021, 6, 1).and_hms_micro(12, 30, 0, 100),
+            "00000000-0000-0000-AAAA-000000000002",
+        );
+        let second = cursor(
+            Utc.ymd(2021, 6, 1).and_hms_micro(12, 30, 0, 200),
+            "00000000-0000-0000-AAAA-000000000001",
+        );
+        let third = cursor(
+            Utc.ymd(2021, 6, 1).and_hms_micro(12, 30, 0, 200),
+            "00000000-0000-0000-AAAA-000000000003",
+        );
+        // Items are ordered by the time they changed, then by work ID
+        assert!(first < second && second < third);
+        assert_eq!(first.datestamp(), third.datestamp());
+        assert!(harvest.includes(&first));
+        // Datestamps are truncated to the second when compared with the harvest's range
+        let last_second = cursor(
+            Utc.ymd(2021, 6, 1).and_hms_micro(23, 59, 59, 999_999),
+            "00000000-0000-0000-AAAA-000000000001",
+        );
+        assert!(harvest.includes(&last_second));
+        let next_day = cursor(
+            Utc.ymd(2021, 6, 2).and_hms(0, 0, 0),
+            "00000000-0000-0000-AAAA-000000000001",
+        );
+        assert!(!harvest.includes(&next_day));
+        assert!(harvest.is_past(&next_day));
+        let previous_day = cursor(
+            Utc.ymd(2021, 5, 31).and_hms(23, 59, 59),
+            "00000000-0000-0000-AAAA-000000000001",
+        );
+        assert!(!harvest.includes(&previous_day));
+        assert!(!harvest.is_past(&previous_day));
+    }
+
+    #[test]
+    fn test_parse_verbs() {
+        assert_eq!(parse(&[("verb", "Identify")]), Ok(OaiRequest::Identify));
+        assert_eq!(
+            parse(&[("verb", "ListMetadataFormats")]),
+            Ok(OaiRequest::ListMetadataFormats { identifier: None })
+        );
+        assert_eq!(parse(&[("verb", "ListSets")]), Ok(OaiRequest::ListSets));
+        assert_eq!(
+            parse(&[
+                ("verb", "GetRecord"),
+                ("identifier", "oai:thoth.pub:1"),
+                ("metadataPrefix", "oai_dc")
+            ]),
+            Ok(OaiRequest::GetRecord {
+                identifier: "oai:thoth.pub:1".to_string(),
+                format: &METADATA_FORMATS[0]
+            })
+        );
+        assert_eq!(
+            parse(&[("verb", "ListRecords"), ("metadataPrefix", "marc21")]),
+            Ok(OaiRequest::ListRecords(Harvest {
+                format: &METADATA_FORMATS[1],
+                from: None,
+                until: None,
+                set: None,
+                cursor: None
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(parse(&[]), Err(OaiError::BadVerb(_))));
+        assert!(matches!(
+            parse(&[("verb", "ListThings")]),
+            Err(OaiError::BadVerb(_))
+        ));
+        assert!(matches!(
+            parse(&[("verb", "Identify"), ("verb", "Identify")]),
+            Err(OaiError::BadArgument(_))
+        ));
+        assert!(matches!(
+            parse(&[("verb", "Identify"), ("set", "publisher_1")]),
+            Err(OaiError::BadArgument(_))
+        ));
+        assert!(matches!(
+            parse(&[("verb", "GetRecord"), ("identifier", "oai:thoth.pub:1")]),
+            Err(OaiError::BadArgument(_))
+        ));
+        assert_eq!(
+            parse(&[("verb", "ListIdentifiers"), ("metadataPrefix", "onix")]),
+            Err(OaiError::CannotDisseminateFormat("onix".to_string()))
+        );
+        assert!(matches!(
+            parse(&[("verb", "ListIdentifiers")]),
+            Err(OaiError::BadArgument(_))
+        ));
+        assert!(matches!(
+            parse(&[
+                ("verb", "ListIdentifiers"),
+                ("metadataPrefix", "oai_dc"),
+                ("set", "series_1")
+            ]),
+            Err(OaiError::BadArgument(_))
+        ));
+        assert!(matches!(
+            parse(&[("verb", "ListSets"), ("resumptionToken", "1")]),
+            Err(OaiError::BadResumptionToken(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_datestamps() {
+        let harvest = |from: &str, until: &str| {
+            parse(&[
+                ("verb", "ListIdentifiers"),
+                ("metadataPrefix", "oai_dc"),
+                ("from", from),
+                ("until", until),
+            ])
+        };
+        match harvest("2021-06-01", "2021-06-01") {
+            Ok(OaiRequest::ListIdentifiers(harvest)) => {
+                assert_eq!(harvest.from, Some(Utc.ymd(2021, 6, 1).and_hms(0, 0, 0)));
+                assert_eq!(harvest.until, Some(Utc.ymd(2021, 6, 1).and_hms(23, 59, 59)));
+            }
+            other => panic!("Unexpected request {:?}", other),
+        }
+        assert!(harvest("2021-06-01T12:00:00Z", "2021-06-01T12:00:00Z").is_ok());
+        assert!(matches!(
+            harvest("2021-06-01", "2021-06-02T12:00:00Z"),
+            Err(OaiError::BadArgument(_))
+        ));
+        assert!(matches!(
+            harvest("2021-06-02", "2021-06-01"),
+            Err(OaiError::BadArgument(_))
+        ));
+        assert!(matches!(
+            harvest("2021-06-01T12:00:00+01:00", "2021-06-01T13:00:00+01:00"),
+            Err(OaiError::BadArgument(_))
+        ));
+    }
+
+    #[test]
+    fn test_resumption_tokens() {
+        let harvest = Harvest {
+            format: &METADATA_FORMATS[2],
+            from: Some(Utc.ymd(2021, 6, 1).and_hms(0, 0, 0)),
+            until: None,
+            set: Some(Set::Publisher(
+                Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+            )),
+            cursor: None,
+        };
+        let cursor = Cursor {
+            changed_at: Utc.ymd(2021, 6, 1).and_hms_micro(12, 30, 0, 123_456),
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+        };
+        let token = harvest.resumption_token(&cursor);
+        assert_eq!(
+            token,
+            "mods!2021-06-01T00:00:00Z!!publisher_00000000-0000-0000-cccc-000000000001!2021-06-01T12:30:00.123456Z!00000000-0000-0000-aaaa-000000000001"
+        );
+        assert_eq!(
+            parse(&[("verb", "ListRecords"), ("resumptionToken", &token)]),
+            Ok(OaiRequest::ListRecords(Harvest {
+                cursor: Some(cursor),
+                ..harvest
+            }))
+        );
+        assert!(matches!(
+            parse(&[
+                ("verb", "ListRecords"),
+                ("resumptionToken", &token),
+                ("metadataPrefix", "mods")
+            ]),
+            Err(OaiError::BadArgument(_))
+        ));
+        for token in [
+            "",
+            "mods!!!!!",
+            "mods!!!!100",
+            "mods!!!!2021-06-01T12:30:00Z!1",
+            "mods!!!!yesterday!00000000-0000-0000-aaaa-000000000001",
+            "onix!!!!2021-06-01T12:30:00Z!00000000-0000-0000-aaaa-000000000001",
+            "mods!yesterday!!!2021-06-01T12:30:00Z!00000000-0000-0000-aaaa-000000000001",
+        ] {
+            assert_eq!(
+                parse(&[("verb", "ListRecords"), ("resumptionToken", token)]),
+                Err(OaiError::BadResumptionToken(token.to_string()))
+            );
+        }
+    }
+}
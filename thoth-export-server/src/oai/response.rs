@@ -0,0 +1,546 @@
+use actix_web::http::StatusCode;
+use actix_web::{HttpRequest, Responder};
+use chrono::{DateTime, Utc};
+use paperclip::actix::web::HttpResponse;
+use paperclip::actix::OperationModifier;
+use paperclip::util::{ready, Ready};
+use paperclip::v2::models::{DefaultOperationRaw, Either, Response};
+use paperclip::v2::schema::Apiv2Schema;
+use std::collections::HashMap;
+use std::io::Write;
+use thoth_client::{DeletedWork, Publisher, Work};
+use thoth_errors::{ThothError, ThothResult};
+use xml::reader::{ParserConfig, XmlEvent as ReaderEvent};
+use xml::writer::{EmitterConfig, EventWriter, XmlEvent as WriterEvent};
+
+use super::model::{Cursor, OaiError, Repository, Set, DATESTAMP_FORMAT, METADATA_FORMATS};
+use crate::record::XML_DECLARATION;
+use crate::xml::{
+    write_attributed_element, write_element_block, write_full_element_block, write_start_element,
+};
+
+const OAI_NS: &str = "http://www.openarchives.org/OAI/2.0/";
+const OAI_SCHEMA_LOCATION: &str =
+    "http://www.openarchives.org/OAI/2.0/ http://www.openarchives.org/OAI/2.0/OAI-PMH.xsd";
+/// No work can have been updated before Thoth was first released, in 2020
+const EARLIEST_DATESTAMP: &str = "2020-01-01T00:00:00Z";
+const ADMIN_EMAIL: &str = "support@thoth.pub";
+
+/// A work along with its metadata in the format requested
+pub(crate) struct Record {
+    pub(crate) work: Work,
+    pub(crate) metadata: Vec<u8>,
+}
+
+/// An item of the repository: the record of a work, or the notice that a work was deleted
+pub(crate) enum Item {
+    Record(Box<Record>),
+    Deleted(DeletedWork),
+}
+
+impl Item {
+    /// Position of the item in lists, by the time it last changed
+    pub(crate) fn cursor(&self) -> Cursor {
+        match self {
+            Item::Record(record) => Cursor::of_work(&record.work),
+            Item::Deleted(deleted_work) => Cursor::of_deleted_work(deleted_work),
+        }
+    }
+}
+
+/// A page of a list of items, and the token to request the next one with. The token is empty on
+/// the last page of a list that spans several, and absent if the list fits in one page.
+pub(crate) struct Page {
+    pub(crate) items: Vec<Item>,
+    pub(crate) resumption_token: Option<String>,
+}
+
+/// Data answering each of the OAI-PMH verbs
+pub(crate) enum Payload {
+    Identify,
+    ListMetadataFormats,
+    ListSets(Vec<Publisher>),
+    ListIdentifiers(Page),
+    ListRecords(Page),
+    GetRecord(Item),
+}
+
+impl Payload {
+    fn verb(&self) -> &'static str {
+        match self {
+            Payload::Identify => "Identify",
+            Payload::ListMetadataFormats => "ListMetadataFormats",
+            Payload::ListSets(_) => "ListSets",
+            Payload::ListIdentifiers(_) => "ListIdentifiers",
+            Payload::ListRecords(_) => "ListRecords",
+            Payload::GetRecord(_) => "GetRecord",
+        }
+    }
+}
+
+/// An OAI-PMH response document
+pub(crate) struct OaiResponse(Vec<u8>);
+
+impl OaiResponse {
+    const XML_MIME_TYPE: &'static str = "text/xml; charset=utf-8";
+
+    /// Write the response to a request, echoing back its arguments unless they were illegal
+    pub(crate) fn new(
+        repository: &Repository,
+        response_date: DateTime<Utc>,
+        arguments: &[(String, String)],
+        outcome: &Result<Payload, OaiError>,
+    ) -> ThothResult<Self> {
+        let mut buffer = XML_DECLARATION.as_bytes().to_vec();
+        let mut writer = EmitterConfig::new()
+            .write_document_declaration(false)
+            .perform_indent(true)
+            .create_writer(&mut buffer);
+        let mut ns_map: HashMap<String, String> = HashMap::new();
+        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+        ns_map.insert("".to_string(), OAI_NS.to_string());
+        ns_map.insert(
+            "xsi".to_string(),
+            "http://www.w3.org/2001/XMLSchema-instance".to_string(),
+        );
+        attr_map.insert("xsi:schemaLocation", OAI_SCHEMA_LOCATION);
+
+        write_full_element_block("OAI-PMH", Some(ns_map), Some(attr_map), &mut writer, |w| {
+            write_attributed_element(
+                "responseDate",
+                &[],
+                &response_date.format(DATESTAMP_FORMAT).to_string(),
+                w,
+            )?;
+            let attributes: Vec<(&str, &str)> = match outcome {
+                Err(e) if e.is_illegal_request() => vec![],
+                _ => arguments
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str()))
+                    .collect(),
+            };
+            write_attributed_element("request", &attributes, repository.base_url(), w)?;
+            match outcome {
+                Ok(payload) => write_element_block(payload.verb(), w, |w| {
+                    write_payload(repository, payload, w)
+                }),
+                Err(e) => write_attributed_element("error", &[("code", e.code())], &e.message(), w),
+            }
+        })?;
+        Ok(OaiResponse(buffer))
+    }
+}
+
+fn write_payload<W: Write>(
+    repository: &Repository,
+    payload: &Payload,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    match payload {
+        Payload::Identify => {
+            write_attributed_element("repositoryName", &[], "Thoth", w)?;
+            write_attributed_element("baseURL", &[], repository.base_url(), w)?;
+            write_attributed_element("protocolVersion", &[], "2.0", w)?;
+            write_attributed_element("adminEmail", &[], ADMIN_EMAIL, w)?;
+            write_attributed_element("earliestDatestamp", &[], EARLIEST_DATESTAMP, w)?;
+            // Deletions are listed for as long as Thoth keeps a record of them, with no guarantee
+            write_attributed_element("deletedRecord", &[], "transient", w)?;
+            write_attributed_element("granularity", &[], "YYYY-MM-DDThh:mm:ssZ", w)
+        }
+        Payload::ListMetadataFormats => {
+            for format in METADATA_FORMATS {
+                write_element_block("metadataFormat", w, |w| {
+                    write_attributed_element("metadataPrefix", &[], format.prefix, w)?;
+                    write_attributed_element("schema", &[], format.schema, w)?;
+                    write_attributed_element("metadataNamespace", &[], format.namespace, w)
+                })?;
+            }
+            Ok(())
+        }
+        Payload::ListSets(publishers) => {
+            for publisher in publishers {
+                write_set(
+                    &Set::Publisher(publisher.publisher_id),
+                    &publisher.publisher_name,
+                    w,
+                )?;
+                for imprint in &publisher.imprints {
+                    write_set(&Set::Imprint(imprint.imprint_id), &imprint.imprint_name, w)?;
+                }
+            }
+            Ok(())
+        }
+        Payload::ListIdentifiers(page) => {
+            for item in &page.items {
+                write_header(repository, item, w)?;
+            }
+            write_resumption_token(page, w)
+        }
+        Payload::ListRecords(page) => {
+            for item in &page.items {
+                write_record(repository, item, w)?;
+            }
+            write_resumption_token(page, w)
+        }
+        Payload::GetRecord(item) => write_record(repository, item, w),
+    }
+}
+
+fn write_set<W: Write>(set: &Set, name: &str, w: &mut EventWriter<W>) -> ThothResult<()> {
+    write_element_block("set", w, |w| {
+        write_attributed_element("setSpec", &[], &set.spec(), w)?;
+        write_attributed_element("setName", &[], name, w)
+    })
+}
+
+fn write_header<W: Write>(
+    repository: &Repository,
+    item: &Item,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    let (attributes, sets): (&[(&str, &str)], Vec<Set>) = match item {
+        Item::Record(record) => (&[], Set::of_imprint(&record.work.imprint)),
+        Item::Deleted(deleted_work) => {
+            (&[("status", "deleted")], Set::of_deleted_work(deleted_work))
+        }
+    };
+    let cursor = item.cursor();
+    write_start_element("header", attributes, w)?;
+    write_attributed_element(
+        "identifier",
+        &[],
+        &repository.item_identifier(&cursor.work_id),
+        w,
+    )?;
+    write_attributed_element(
+        "datestamp",
+        &[],
+        &cursor.datestamp().format(DATESTAMP_FORMAT).to_string(),
+        w,
+    )?;
+    for set in sets.iter() {
+        write_attributed_element("setSpec", &[], &set.spec(), w)?;
+    }
+    w.write(WriterEvent::end_element()).map_err(|e| e.into())
+}
+
+/// Write an item's header and, unless it was deleted, its metadata
+fn write_record<W: Write>(
+    repository: &Repository,
+    item: &Item,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    write_element_block("record", w, |w| {
+        write_header(repository, item, w)?;
+        match item {
+            Item::Record(record) => {
+                write_element_block("metadata", w, |w| write_embedded(&record.metadata, w))
+            }
+            Item::Deleted(_) => Ok(()),
+        }
+    })
+}
+
+fn write_resumption_token<W: Write>(page: &Page, w: &mut EventWriter<W>) -> ThothResult<()> {
+    match &page.resumption_token {
+        Some(token) => write_attributed_element("resumptionToken", &[], token, w),
+        None => Ok(()),
+    }
+}
+
+/// Copy the root element of a standalone XML document, with its namespaces, into the response
+fn write_embedded<W: Write>(document: &[u8], w: &mut EventWriter<W>) -> ThothResult<()> {
+    for event in ParserConfig::new().create_reader(document) {
+        let event = event.map_err(|e| ThothError::InternalError(e.to_string()))?;
+        match event {
+            // The response is indented as a whole
+            ReaderEvent::StartDocument { .. } | ReaderEvent::Whitespace(_) => {}
+            event => {
+                if let Some(event) = event.as_writer_event() {
+                    w.write(event)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Responder for OaiResponse {
+    type Error = ThothError;
+    type Future = Ready<ThothResult<HttpResponse>>;
+
+    fn respond_to(self, _: &HttpRequest) -> Self::Future {
+        ready(Ok(HttpResponse::build(StatusCode::OK)
+            .content_type(Self::XML_MIME_TYPE)
+            .body(self.0)))
+    }
+}
+
+impl Apiv2Schema for OaiResponse {}
+
+impl OperationModifier for OaiResponse {
+    fn update_response(op: &mut DefaultOperationRaw) {
+        let status: StatusCode = StatusCode::OK;
+        op.responses.insert(
+            status.as_str().into(),
+            Either::Right(Response {
+                description: status.canonical_reason().map(ToString::to_string),
+                schema: None,
+                ..Default::default()
+            }),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oai::model::{MetadataFormat, OaiRequest};
+    use chrono::TimeZone;
+    use std::str::FromStr;
+    use thoth_api::model::Timestamp;
+    use thoth_client::{
+        PublisherImprints, WorkImprint, WorkImprintPublisher, WorkStatus, WorkType,
+    };
+    use uuid::Uuid;
+    use xml::reader::EventReader;
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: None,
+            publication_date: None,
+            license: None,
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: None,
+            long_abstract: None,
+            general_note: None,
+            place: None,
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: None,
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: None,
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Timestamp::from_str("2021-06-01T12:30:00.123456Z").unwrap(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![],
+            contributions: vec![],
+            languages: vec![],
+            publications: vec![],
+            subjects: vec![],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    fn response(arguments: &[(&str, &str)], outcome: &Result<Payload, OaiError>) -> String {
+        let arguments: Vec<(String, String)> = arguments
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        let response = OaiResponse::new(
+            &Repository::new("https://export.thoth.pub"),
+            Utc.ymd(2021, 6, 2).and_hms(8, 0, 0),
+            &arguments,
+            outcome,
+        )
+        .unwrap();
+        String::from_utf8(response.0).unwrap()
+    }
+
+    fn record() -> Record {
+        let work = test_work();
+        let metadata = MetadataFormat::find("oai_dc")
+            .unwrap()
+            .disseminate(&work)
+            .unwrap();
+        Record { work, metadata }
+    }
+
+    #[test]
+    fn test_identify() {
+        let output = response(&[("verb", "Identify")], &Ok(Payload::Identify));
+        assert!(output.contains(r#"<OAI-PMH xmlns="http://www.openarchives.org/OAI/2.0/""#));
+        assert!(output.contains(r#"  <responseDate>2021-06-02T08:00:00Z</responseDate>"#));
+        assert!(
+            output.contains(r#"  <request verb="Identify">https://export.thoth.pub/oai</request>"#)
+        );
+        assert!(output.contains(r#"    <baseURL>https://export.thoth.pub/oai</baseURL>"#));
+        assert!(output.contains(r#"    <protocolVersion>2.0</protocolVersion>"#));
+        assert!(output.contains(r#"    <deletedRecord>transient</deletedRecord>"#));
+    }
+
+    #[test]
+    fn test_errors() {
+        let arguments = [("verb", "Identify"), ("verb", "Identify")];
+        let outcome = OaiRequest::parse(
+            &arguments
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect::<Vec<(String, String)>>(),
+        )
+        .map(|_| Payload::Identify);
+        let output = response(&arguments, &outcome);
+        // Illegal arguments are not echoed back
+        assert!(output.contains(r#"  <request>https://export.thoth.pub/oai</request>"#));
+        assert!(output.contains(r#"  <error code="badArgument">verb is repeated</error>"#));
+
+        let output = response(
+            &[("verb", "ListRecords"), ("metadataPrefix", "oai_dc")],
+            &Err(OaiError::NoRecordsMatch),
+        );
+        assert!(output.contains(r#"verb="ListRecords""#));
+        assert!(output.contains(r#"metadataPrefix="oai_dc""#));
+        assert!(output.contains(r#"<error code="noRecordsMatch">"#));
+    }
+
+    #[test]
+    fn test_list_sets() {
+        let work = test_work();
+        let publisher = Publisher {
+            publisher_id: work.imprint.publisher.publisher_id,
+            publisher_name: "OA Editions".to_string(),
+            imprints: vec![PublisherImprints {
+                imprint_id: work.imprint.imprint_id,
+                imprint_name: "OA Editions Imprint".to_string(),
+            }],
+        };
+        let output = response(
+            &[("verb", "ListSets")],
+            &Ok(Payload::ListSets(vec![publisher])),
+        );
+        assert!(output.contains(
+            r#"      <setSpec>publisher_00000000-0000-0000-cccc-000000000001</setSpec>"#
+        ));
+        assert!(output.contains(r#"      <setName>OA Editions</setName>"#));
+        assert!(output
+            .contains(r#"      <setSpec>imprint_00000000-0000-0000-bbbb-000000000001</setSpec>"#));
+        assert!(output.contains(r#"      <setName>OA Editions Imprint</setName>"#));
+    }
+
+    #[test]
+    fn test_list_identifiers() {
+        let page = Page {
+            items: vec![Item::Record(Box::new(record()))],
+            resumption_token: Some(String::new()),
+        };
+        let output = response(
+            &[("verb", "ListIdentifiers"), ("resumptionToken", "token")],
+            &Ok(Payload::ListIdentifiers(page)),
+        );
+        assert!(output.contains(
+            r#"      <identifier>oai:export.thoth.pub:00000000-0000-0000-aaaa-000000000001</identifier>"#
+        ));
+        // Datestamps are truncated to the second
+        assert!(output.contains(r#"      <datestamp>2021-06-01T12:30:00Z</datestamp>"#));
+        assert!(output.contains(
+            r#"      <setSpec>publisher_00000000-0000-0000-cccc-000000000001</setSpec>"#
+        ));
+        assert!(!output.contains("<metadata>"));
+        assert!(output.contains(r#"    <resumptionToken></resumptionToken>"#));
+    }
+
+    #[test]
+    fn test_list_deleted_records() {
+        let deleted_work = DeletedWork {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000002").unwrap(),
+            publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+            publication_ids: vec![],
+            deleted_at: Timestamp::from_str("2021-06-01T13:00:00.5Z").unwrap(),
+        };
+        let page = Page {
+            items: vec![
+                Item::Record(Box::new(record())),
+                Item::Deleted(deleted_work),
+            ],
+            resumption_token: None,
+        };
+        let output = response(
+            &[("verb", "ListRecords"), ("metadataPrefix", "oai_dc")],
+            &Ok(Payload::ListRecords(page)),
+        );
+        assert!(output.contains(r#"      <header>"#));
+        assert!(output.contains(
+            r#"      <header status="deleted">
+        <identifier>oai:export.thoth.pub:00000000-0000-0000-aaaa-000000000002</identifier>
+        <datestamp>2021-06-01T13:00:00Z</datestamp>
+        <setSpec>publisher_00000000-0000-0000-cccc-000000000001</setSpec>
+      </header>
+    </record>"#
+        ));
+        // Only the available record has metadata
+        assert_eq!(output.matches("<metadata>").count(), 1);
+    }
+
+    #[test]
+    fn test_get_record() {
+        let output = response(
+            &[
+                ("verb", "GetRecord"),
+                ("identifier", "oai:export.thoth.pub:1"),
+                ("metadataPrefix", "oai_dc"),
+            ],
+            &Ok(Payload::GetRecord(Item::Record(Box::new(record())))),
+        );
+        assert!(!output.contains("<resumptionToken"));
+        // The record's metadata keeps its own namespaces within the response
+        let mut title = None;
+        let mut parents = vec![];
+        for event in EventReader::from_str(&output) {
+            match event.unwrap() {
+                ReaderEvent::StartElement { name, .. } => {
+                    if name.local_name == "title" {
+                        title = Some((name.namespace.clone(), parents.clone()));
+                    }
+                    parents.push(name.local_name);
+                }
+                ReaderEvent::EndElement { .. } => {
+                    parents.pop();
+                }
+                _ => {}
+            }
+        }
+        let (namespace, parents) = title.expect("Record has no title");
+        assert_eq!(
+            namespace.as_deref(),
+            Some("http://purl.org/dc/elements/1.1/")
+        );
+        assert_eq!(
+            parents,
+            vec!["OAI-PMH", "GetRecord", "record", "metadata", "dc"]
+        );
+        assert!(output.contains(r#"        <dc:title>Book Title: Book Subtitle</dc:title>"#));
+    }
+}
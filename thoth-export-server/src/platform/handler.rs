@@ -5,15 +5,15 @@ use paperclip::actix::{
 };
 
 use super::model::Platform;
-use crate::data::{find_platform, ALL_PLATFORMS};
+use crate::data::Registry;
 
 #[api_v2_operation(
     summary = "List supported platforms",
     description = "Full list of platforms supported by Thoth's outputs",
     tags(Platforms)
 )]
-pub(crate) async fn get_all() -> Json<Vec<Platform<'static>>> {
-    Json(ALL_PLATFORMS.clone())
+pub(crate) async fn get_all(registry: web::Data<Registry>) -> Json<Vec<Platform>> {
+    Json(registry.platforms().to_vec())
 }
 
 #[api_v2_operation(
@@ -23,6 +23,10 @@ pub(crate) async fn get_all() -> Json<Vec<Platform<'static>>> {
 )]
 pub(crate) async fn get_one(
     web::Path(platform_id): web::Path<String>,
-) -> Result<Json<Platform<'static>>, Error> {
-    find_platform(platform_id).map(Json).map_err(|e| e.into())
+    registry: web::Data<Registry>,
+) -> Result<Json<Platform>, Error> {
+    registry
+        .find_platform(platform_id)
+        .map(Json)
+        .map_err(|e| e.into())
 }
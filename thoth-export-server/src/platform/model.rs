@@ -3,8 +3,8 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Serialize, Deserialize, Apiv2Schema)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct Platform<'a> {
-    pub(crate) id: &'a str,
-    pub(crate) name: &'a str,
-    pub(crate) accepts: Vec<&'a str>,
+pub(crate) struct Platform {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) accepts: Vec<String>,
 }
@@ -1,16 +1,39 @@
-use actix_web::{http::StatusCode, HttpRequest, Responder};
+use actix_web::dev::HttpResponseBuilder;
+use actix_web::http::header::{
+    HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, LINK, VARY,
+};
+use actix_web::http::{Method, StatusCode};
+use actix_web::web::Bytes;
+use actix_web::{HttpRequest, Responder, ResponseError};
+use chrono::{DateTime, Utc};
 use csv::QuoteStyle;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
 use paperclip::actix::web::HttpResponse;
 use paperclip::actix::OperationModifier;
 use paperclip::util::{ready, Ready};
 use paperclip::v2::models::{DefaultOperationRaw, Either, Response};
 use paperclip::v2::schema::Apiv2Schema;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::{Cursor, Write};
+use std::pin::Pin;
 use std::str::FromStr;
-use thoth_client::Work;
+use thoth_api::model::Timestamp;
+use thoth_client::{DeletedWork, Work, WorksSummary};
 use thoth_errors::{ThothError, ThothResult};
 
-use crate::csv::{CsvSpecification, CsvThoth, KbartOclc};
-use crate::xml::{Onix21EbscoHost, Onix3Jstor, Onix3Oapen, Onix3ProjectMuse, XmlSpecification};
+use crate::citation::{BibtexThoth, CitationSpecification, CitationStreamWriter, RisThoth};
+use crate::csv::{CsvSpecification, CsvStreamWriter, CsvThoth, KbartNiso, KbartOclc};
+use crate::json::{CslJsonThoth, DataCiteThothJson, JsonLdSchemaOrg, JsonSpecification};
+use crate::marc21::{Marc21Specification, Marc21StreamWriter, Marc21Thoth};
+use crate::specification::model::{BundleEntry, BundleManifest, SkippedWork, WorkValidation};
+use crate::xml::{
+    CrossrefThoth, DataCiteThoth, Marc21XmlThoth, ModsThoth, OaiDcThoth, Onix21EbscoHost,
+    Onix3GoogleBooks, Onix3Jstor, Onix3Oapen, Onix3ProjectMuse, Onix3ProquestEbrary, Onix3Thoth,
+    XmlSpecification, XmlStreamWriter,
+};
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
 
 pub(crate) trait AsRecord {}
 impl AsRecord for Vec<Work> {}
@@ -24,15 +47,117 @@ pub(crate) enum MetadataSpecification {
     Onix3ProjectMuse(Onix3ProjectMuse),
     Onix3Oapen(Onix3Oapen),
     Onix3Jstor(Onix3Jstor),
+    Onix3Thoth(Onix3Thoth),
+    Onix3GoogleBooks(Onix3GoogleBooks),
+    Onix3ProquestEbrary(Onix3ProquestEbrary),
     Onix21EbscoHost(Onix21EbscoHost),
     CsvThoth(CsvThoth),
     KbartOclc(KbartOclc),
+    KbartNiso(KbartNiso),
+    Marc21XmlThoth(Marc21XmlThoth),
+    Marc21Thoth(Marc21Thoth),
+    CrossrefThoth(CrossrefThoth),
+    DataCiteThoth(DataCiteThoth),
+    DataCiteThothJson(DataCiteThothJson),
+    BibtexThoth(BibtexThoth),
+    RisThoth(RisThoth),
+    CslJsonThoth(CslJsonThoth),
+    OaiDcThoth(OaiDcThoth),
+    ModsThoth(ModsThoth),
+    JsonLdSchemaOrg(JsonLdSchemaOrg),
 }
 
 pub(crate) struct MetadataRecord<T: AsRecord> {
     id: String,
     data: T,
     specification: MetadataSpecification,
+    deleted_works: Vec<DeletedWork>,
+}
+
+/// Works deleted from Thoth since a record's last update, as well as publications deleted from
+/// works that still exist, grouped by work
+#[derive(Clone, Default)]
+pub(crate) struct Deletions {
+    pub(crate) works: Vec<DeletedWork>,
+    pub(crate) publications: Vec<DeletedWork>,
+}
+
+/// A metadata record's output, along with the validators used to answer conditional requests
+#[derive(Clone)]
+pub(crate) struct GeneratedRecord {
+    pub(crate) body: Bytes,
+    pub(crate) content_type: &'static str,
+    pub(crate) content_disposition: String,
+    pub(crate) etag: String,
+    pub(crate) last_modified: Option<DateTime<Utc>>,
+}
+
+/// A metadata record written one page of works at a time, for records too large to generate in
+/// one go. Its validators are derived from a summary of its works obtained before streaming.
+pub(crate) struct StreamedRecord {
+    content_type: &'static str,
+    content_disposition: String,
+    etag: String,
+    last_modified: Option<DateTime<Utc>>,
+    body: Pin<Box<dyn Stream<Item = ThothResult<Bytes>>>>,
+}
+
+/// Response to a request for a metadata record whose works may span several pages
+pub(crate) enum RecordResponse {
+    Generated(GeneratedRecord),
+    Streamed(StreamedRecord),
+}
+
+/// A work's record in the representation negotiated with the client, or the reason that none of
+/// the representations requested is available, along with links to every available representation
+pub(crate) struct NegotiatedRecord {
+    record: ThothResult<GeneratedRecord>,
+    links: String,
+}
+
+/// Fields that a specification cannot output a work without. Writers check them before
+/// generating each work, and validation lists them, so both report the same fields.
+pub(crate) trait RequiredFields {
+    /// Identifier of the specification, as reported in errors
+    const SPECIFICATION_ID: &'static str;
+
+    /// Every field that the specification requires and the work lacks, in the order that
+    /// generation reports them
+    fn missing_fields(_work: &Work) -> Vec<&'static str> {
+        Vec::new()
+    }
+
+    /// Reject a work that lacks any required field, reporting the first of them
+    fn check_required_fields(work: &Work) -> ThothResult<()> {
+        match Self::missing_fields(work).first() {
+            Some(field) => Err(ThothError::IncompleteMetadataRecord(
+                Self::SPECIFICATION_ID.to_string(),
+                field.to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Writes a metadata record incrementally, one page of works at a time
+pub(crate) trait RecordWriter {
+    /// Output for a page of works, preceded on the first page by the document's header
+    fn write_page(&mut self, works: &[Work]) -> ThothResult<Vec<u8>>;
+
+    /// Output closing the document, including any notifications of deleted works
+    fn finish(&mut self, deleted_works: &[DeletedWork]) -> ThothResult<Vec<u8>>;
+
+    /// Output marking a document that could not be completed, written before the connection is
+    /// dropped, where the format has a way to express it
+    fn abort(&mut self, _error: &ThothError) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+/// A ZIP archive of several metadata records, along with a manifest describing them
+pub(crate) struct MetadataBundle<T: AsRecord> {
+    id: String,
+    records: Vec<MetadataRecord<T>>,
 }
 
 impl<T> MetadataRecord<T>
@@ -42,16 +167,38 @@ where
     const XML_MIME_TYPE: &'static str = "text/xml; charset=utf-8";
     const CSV_MIME_TYPE: &'static str = "text/csv; charset=utf-8";
     const TXT_MIME_TYPE: &'static str = "text/plain; charset=utf-8";
+    const MARC_MIME_TYPE: &'static str = "application/marc";
+    const JSON_MIME_TYPE: &'static str = "application/json; charset=utf-8";
+    const BIBTEX_MIME_TYPE: &'static str = "application/x-bibtex; charset=utf-8";
+    const RIS_MIME_TYPE: &'static str = "application/x-research-info-systems; charset=utf-8";
+    const CSL_JSON_MIME_TYPE: &'static str = "application/vnd.citationstyles.csl+json";
+    const JSONLD_MIME_TYPE: &'static str = "application/ld+json";
     const XML_EXTENSION: &'static str = ".xml";
     const CSV_EXTENSION: &'static str = ".csv";
     const TXT_EXTENSION: &'static str = ".txt";
+    const MARC_EXTENSION: &'static str = ".mrc";
+    const JSON_EXTENSION: &'static str = ".json";
+    const BIBTEX_EXTENSION: &'static str = ".bib";
+    const RIS_EXTENSION: &'static str = ".ris";
+    const JSONLD_EXTENSION: &'static str = ".jsonld";
 
     pub(crate) fn new(id: String, specification: MetadataSpecification, data: T) -> Self {
         MetadataRecord {
             id,
             data,
             specification,
+            deleted_works: vec![],
+        }
+    }
+
+    /// Notify recipients of works deleted from Thoth, where the specification supports it, and of
+    /// deleted publications where the specification sends a product per publication
+    pub(crate) fn with_deletions(mut self, deletions: Deletions) -> Self {
+        self.deleted_works = deletions.works;
+        if self.specification.notifies_deleted_publications() {
+            self.deleted_works.extend(deletions.publications);
         }
+        self
     }
 
     fn content_type(&self) -> &'static str {
@@ -59,9 +206,24 @@ where
             MetadataSpecification::Onix3ProjectMuse(_) => Self::XML_MIME_TYPE,
             MetadataSpecification::Onix3Oapen(_) => Self::XML_MIME_TYPE,
             MetadataSpecification::Onix3Jstor(_) => Self::XML_MIME_TYPE,
+            MetadataSpecification::Onix3Thoth(_) => Self::XML_MIME_TYPE,
+            MetadataSpecification::Onix3GoogleBooks(_) => Self::XML_MIME_TYPE,
+            MetadataSpecification::Onix3ProquestEbrary(_) => Self::XML_MIME_TYPE,
             MetadataSpecification::Onix21EbscoHost(_) => Self::XML_MIME_TYPE,
             MetadataSpecification::CsvThoth(_) => Self::CSV_MIME_TYPE,
             MetadataSpecification::KbartOclc(_) => Self::TXT_MIME_TYPE,
+            MetadataSpecification::KbartNiso(_) => Self::TXT_MIME_TYPE,
+            MetadataSpecification::Marc21XmlThoth(_) => Self::XML_MIME_TYPE,
+            MetadataSpecification::Marc21Thoth(_) => Self::MARC_MIME_TYPE,
+            MetadataSpecification::CrossrefThoth(_) => Self::XML_MIME_TYPE,
+            MetadataSpecification::DataCiteThoth(_) => Self::XML_MIME_TYPE,
+            MetadataSpecification::DataCiteThothJson(_) => Self::JSON_MIME_TYPE,
+            MetadataSpecification::BibtexThoth(_) => Self::BIBTEX_MIME_TYPE,
+            MetadataSpecification::RisThoth(_) => Self::RIS_MIME_TYPE,
+            MetadataSpecification::CslJsonThoth(_) => Self::CSL_JSON_MIME_TYPE,
+            MetadataSpecification::OaiDcThoth(_) => Self::XML_MIME_TYPE,
+            MetadataSpecification::ModsThoth(_) => Self::XML_MIME_TYPE,
+            MetadataSpecification::JsonLdSchemaOrg(_) => Self::JSONLD_MIME_TYPE,
         }
     }
 
@@ -70,9 +232,24 @@ where
             MetadataSpecification::Onix3ProjectMuse(_) => self.xml_file_name(),
             MetadataSpecification::Onix3Oapen(_) => self.xml_file_name(),
             MetadataSpecification::Onix3Jstor(_) => self.xml_file_name(),
+            MetadataSpecification::Onix3Thoth(_) => self.xml_file_name(),
+            MetadataSpecification::Onix3GoogleBooks(_) => self.xml_file_name(),
+            MetadataSpecification::Onix3ProquestEbrary(_) => self.xml_file_name(),
             MetadataSpecification::Onix21EbscoHost(_) => self.xml_file_name(),
             MetadataSpecification::CsvThoth(_) => self.csv_file_name(),
             MetadataSpecification::KbartOclc(_) => self.txt_file_name(),
+            MetadataSpecification::KbartNiso(_) => self.txt_file_name(),
+            MetadataSpecification::Marc21XmlThoth(_) => self.xml_file_name(),
+            MetadataSpecification::Marc21Thoth(_) => self.marc_file_name(),
+            MetadataSpecification::CrossrefThoth(_) => self.xml_file_name(),
+            MetadataSpecification::DataCiteThoth(_) => self.xml_file_name(),
+            MetadataSpecification::DataCiteThothJson(_) => self.json_file_name(),
+            MetadataSpecification::BibtexThoth(_) => self.bibtex_file_name(),
+            MetadataSpecification::RisThoth(_) => self.ris_file_name(),
+            MetadataSpecification::CslJsonThoth(_) => self.json_file_name(),
+            MetadataSpecification::OaiDcThoth(_) => self.xml_file_name(),
+            MetadataSpecification::ModsThoth(_) => self.xml_file_name(),
+            MetadataSpecification::JsonLdSchemaOrg(_) => self.jsonld_file_name(),
         }
     }
 
@@ -88,6 +265,26 @@ where
         self.format_file_name(Self::TXT_EXTENSION)
     }
 
+    fn marc_file_name(&self) -> String {
+        self.format_file_name(Self::MARC_EXTENSION)
+    }
+
+    fn json_file_name(&self) -> String {
+        self.format_file_name(Self::JSON_EXTENSION)
+    }
+
+    fn bibtex_file_name(&self) -> String {
+        self.format_file_name(Self::BIBTEX_EXTENSION)
+    }
+
+    fn ris_file_name(&self) -> String {
+        self.format_file_name(Self::RIS_EXTENSION)
+    }
+
+    fn jsonld_file_name(&self) -> String {
+        self.format_file_name(Self::JSONLD_EXTENSION)
+    }
+
     fn format_file_name(&self, extension: &'static str) -> String {
         format!(
             "{}__{}{}",
@@ -103,43 +300,409 @@ where
 }
 
 impl MetadataRecord<Vec<Work>> {
-    fn generate(&self) -> ThothResult<String> {
-        match &self.specification {
-            MetadataSpecification::Onix3ProjectMuse(onix3_project_muse) => {
-                onix3_project_muse.generate(&self.data, None)
-            }
-            MetadataSpecification::Onix3Oapen(onix3_oapen) => {
-                onix3_oapen.generate(&self.data, None)
-            }
-            MetadataSpecification::Onix3Jstor(onix3_jstor) => {
-                onix3_jstor.generate(&self.data, None)
-            }
-            MetadataSpecification::Onix21EbscoHost(onix21_ebsco_host) => {
-                onix21_ebsco_host.generate(&self.data, Some(DOCTYPE_ONIX21_REF))
-            }
-            MetadataSpecification::CsvThoth(csv_thoth) => {
-                csv_thoth.generate(&self.data, QuoteStyle::Always, DELIMITER_COMMA)
+    fn generate(&self) -> ThothResult<Vec<u8>> {
+        self.specification.generate(&self.data, &self.deleted_works)
+    }
+
+    /// Time of the last update to any of the works in this record, their child records and their
+    /// chapters, or of the deletion of any of the works it notifies
+    fn last_modified(&self) -> Option<DateTime<Utc>> {
+        self.data
+            .iter()
+            .flat_map(|work| {
+                std::iter::once(&work.updated_at_with_relations).chain(
+                    work.relations
+                        .iter()
+                        .map(|relation| &relation.related_work.updated_at_with_relations),
+                )
+            })
+            .chain(
+                self.deleted_works
+                    .iter()
+                    .map(|deleted_work| &deleted_work.deleted_at),
+            )
+            .map(Timestamp::to_datetime)
+            .max()
+    }
+
+    /// Opaque validator that changes whenever the record's works, or their last update, change
+    fn etag(&self, last_modified: Option<DateTime<Utc>>) -> String {
+        let mut hasher = DefaultHasher::new();
+        self.specification.to_string().hash(&mut hasher);
+        self.id.hash(&mut hasher);
+        last_modified.hash(&mut hasher);
+        for work in &self.data {
+            work.work_id.hash(&mut hasher);
+        }
+        for deleted_work in &self.deleted_works {
+            deleted_work.work_id.hash(&mut hasher);
+        }
+        format!("\"{:016x}\"", hasher.finish())
+    }
+
+    /// Generate this record's output, along with the headers needed to validate it later
+    pub(crate) fn to_generated(&self) -> ThothResult<GeneratedRecord> {
+        let last_modified = self.last_modified();
+        self.generate().map(|body| GeneratedRecord {
+            body: body.into(),
+            content_type: self.content_type(),
+            content_disposition: self.content_disposition(),
+            etag: self.etag(last_modified),
+            last_modified,
+        })
+    }
+
+    /// Check each work against this record's specification, discarding the generated output.
+    ///
+    /// Every missing field that the specification requires is listed. Only works that have them
+    /// all are then generated on their own, to recover any other error that multi-work records
+    /// would discard when skipping the work.
+    pub(crate) fn validate(&self) -> Vec<WorkValidation> {
+        self.data
+            .iter()
+            .map(|work| {
+                let mut errors = self.specification.missing_fields(work);
+                if errors.is_empty() {
+                    if let Err(error) = self.specification.generate(std::slice::from_ref(work), &[])
+                    {
+                        errors.push(match error {
+                            ThothError::IncompleteMetadataRecord(_, reason) => reason,
+                            _ => error.to_string(),
+                        });
+                    }
+                }
+                WorkValidation {
+                    work_id: work.work_id,
+                    title: work.full_title.clone(),
+                    valid: errors.is_empty(),
+                    errors,
+                }
+            })
+            .collect()
+    }
+
+    /// List the works that are left out of this record, and why.
+    pub(crate) fn skipped_works(&self) -> Vec<SkippedWork> {
+        skipped_works(self.validate())
+    }
+
+    pub(crate) fn total_works(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Validators of a streamed record, whose works are only known through a summary of them.
+    /// The number of works stands in for their IDs, since removing one changes it.
+    fn streamed_validators(
+        &self,
+        summary: &WorksSummary,
+        updated_since: &Option<Timestamp>,
+    ) -> (String, Option<DateTime<Utc>>) {
+        let last_modified = summary
+            .last_updated
+            .iter()
+            .chain(
+                self.deleted_works
+                    .iter()
+                    .map(|deleted_work| &deleted_work.deleted_at),
+            )
+            .map(Timestamp::to_datetime)
+            .max();
+        let mut hasher = DefaultHasher::new();
+        self.specification.to_string().hash(&mut hasher);
+        self.id.hash(&mut hasher);
+        updated_since
+            .as_ref()
+            .map(Timestamp::to_datetime)
+            .hash(&mut hasher);
+        last_modified.hash(&mut hasher);
+        summary.work_count.hash(&mut hasher);
+        for deleted_work in &self.deleted_works {
+            deleted_work.work_id.hash(&mut hasher);
+        }
+        (format!("\"{:016x}\"", hasher.finish()), last_modified)
+    }
+
+    /// Write this record, which holds the first page of its works, followed by each page that
+    /// `pages` yields, sending each one to the client as soon as it is written.
+    ///
+    /// If a page cannot be fetched or written, the writer's marker of an incomplete document is
+    /// sent and the response is then aborted, so that clients never mistake it for a whole one.
+    pub(crate) fn into_stream<S>(
+        self,
+        mut writer: Box<dyn RecordWriter>,
+        pages: S,
+        summary: &WorksSummary,
+        updated_since: &Option<Timestamp>,
+    ) -> ThothResult<StreamedRecord>
+    where
+        S: Stream<Item = ThothResult<Vec<Work>>> + 'static,
+    {
+        // The first page is written before responding, so that its errors are still reported
+        let first_page = writer.write_page(&self.data)?;
+        let content_type = self.content_type();
+        let content_disposition = self.content_disposition();
+        let (etag, last_modified) = self.streamed_validators(summary, updated_since);
+        let state = StreamState::Writing(Box::pin(pages), writer, self.deleted_works);
+        let next_pages = stream::unfold(state, |state| async move {
+            match state {
+                StreamState::Writing(mut pages, mut writer, deleted_works) => {
+                    let output = match pages.next().await {
+                        Some(Ok(works)) => writer.write_page(&works),
+                        Some(Err(e)) => Err(e),
+                        None => {
+                            return Some((writer.finish(&deleted_works), StreamState::Done));
+                        }
+                    };
+                    match output {
+                        Ok(output) => Some((
+                            Ok(output),
+                            StreamState::Writing(pages, writer, deleted_works),
+                        )),
+                        Err(e) => Some((Ok(writer.abort(&e)), StreamState::Failed(e))),
+                    }
+                }
+                StreamState::Failed(e) => Some((Err(e), StreamState::Done)),
+                StreamState::Done => None,
             }
-            MetadataSpecification::KbartOclc(kbart_oclc) => {
-                kbart_oclc.generate(&self.data, QuoteStyle::Necessary, DELIMITER_TAB)
+        });
+        Ok(StreamedRecord {
+            content_type,
+            content_disposition,
+            etag,
+            last_modified,
+            body: Box::pin(
+                stream::once(async { Ok(first_page) })
+                    .chain(next_pages)
+                    .map_ok(Bytes::from),
+            ),
+        })
+    }
+}
+
+/// Progress of a streamed record: writing pages, about to abort after an error, or complete
+enum StreamState<S> {
+    Writing(Pin<Box<S>>, Box<dyn RecordWriter>, Vec<DeletedWork>),
+    Failed(ThothError),
+    Done,
+}
+
+impl Responder for MetadataRecord<Vec<Work>>
+where
+    actix_web::dev::Body: From<Vec<u8>>,
+{
+    type Error = ThothError;
+    type Future = Ready<ThothResult<HttpResponse>>;
+
+    fn respond_to(self, req: &HttpRequest) -> Self::Future {
+        match self.to_generated() {
+            Ok(record) => record.respond_to(req),
+            Err(e) => ready(Err(e)),
+        }
+    }
+}
+
+fn http_date(date: DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Whether the copy the client already holds of a record with the given validators is still
+/// current, as per its conditional headers. `If-None-Match` takes precedence over
+/// `If-Modified-Since`, as in RFC 7232.
+fn is_not_modified(req: &HttpRequest, etag: &str, last_modified: Option<DateTime<Utc>>) -> bool {
+    if req.method() != Method::GET && req.method() != Method::HEAD {
+        return false;
+    }
+    let headers = req.headers();
+    if let Some(if_none_match) = headers.get(IF_NONE_MATCH) {
+        return if_none_match
+            .to_str()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag);
+    }
+    let if_modified_since = headers
+        .get(IF_MODIFIED_SINCE)
+        .and_then(|since| since.to_str().ok())
+        .and_then(|since| DateTime::parse_from_rfc2822(since).ok());
+    match (last_modified, if_modified_since) {
+        (Some(last_modified), Some(since)) => last_modified.timestamp() <= since.timestamp(),
+        _ => false,
+    }
+}
+
+/// Start a response to a request for a record with the given validators, answering `304 Not
+/// Modified` if the client's copy is still current
+fn validated_response(
+    req: &HttpRequest,
+    etag: &str,
+    last_modified: Option<DateTime<Utc>>,
+) -> (HttpResponseBuilder, bool) {
+    let not_modified = is_not_modified(req, etag, last_modified);
+    let mut response = HttpResponse::build(if not_modified {
+        StatusCode::NOT_MODIFIED
+    } else {
+        StatusCode::OK
+    });
+    response.header(ETAG, etag);
+    if let Some(last_modified) = last_modified {
+        response.header(LAST_MODIFIED, http_date(last_modified));
+    }
+    (response, not_modified)
+}
+
+impl GeneratedRecord {
+    #[cfg(test)]
+    fn is_not_modified(&self, req: &HttpRequest) -> bool {
+        is_not_modified(req, &self.etag, self.last_modified)
+    }
+
+    fn into_response(self, req: &HttpRequest) -> HttpResponse {
+        let (mut response, not_modified) = validated_response(req, &self.etag, self.last_modified);
+        if not_modified {
+            return response.finish();
+        }
+        response
+            .content_type(self.content_type)
+            .header("Content-Disposition", self.content_disposition)
+            .body(self.body)
+    }
+}
+
+impl NegotiatedRecord {
+    pub(crate) fn new(record: ThothResult<GeneratedRecord>, links: String) -> Self {
+        NegotiatedRecord { record, links }
+    }
+}
+
+impl Responder for GeneratedRecord {
+    type Error = ThothError;
+    type Future = Ready<ThothResult<HttpResponse>>;
+
+    fn respond_to(self, req: &HttpRequest) -> Self::Future {
+        ready(Ok(self.into_response(req)))
+    }
+}
+
+impl Responder for NegotiatedRecord {
+    type Error = ThothError;
+    type Future = Ready<ThothResult<HttpResponse>>;
+
+    fn respond_to(self, req: &HttpRequest) -> Self::Future {
+        let mut response = match self.record {
+            Ok(record) => record.into_response(req),
+            Err(e) => e.error_response(),
+        };
+        // The same URL answers with different representations depending on the Accept header
+        response
+            .headers_mut()
+            .insert(VARY, HeaderValue::from_static("Accept"));
+        match HeaderValue::from_str(&self.links) {
+            Ok(links) => {
+                response.headers_mut().insert(LINK, links);
+                ready(Ok(response))
             }
+            Err(e) => ready(Err(ThothError::InternalError(e.to_string()))),
         }
     }
 }
 
-impl Responder for MetadataRecord<Vec<Work>>
+impl Responder for StreamedRecord {
+    type Error = ThothError;
+    type Future = Ready<ThothResult<HttpResponse>>;
+
+    /// The remaining pages are only fetched if the client's copy is out of date
+    fn respond_to(self, req: &HttpRequest) -> Self::Future {
+        let (mut response, not_modified) = validated_response(req, &self.etag, self.last_modified);
+        if not_modified {
+            return ready(Ok(response.finish()));
+        }
+        ready(Ok(response
+            .content_type(self.content_type)
+            .header("Content-Disposition", self.content_disposition)
+            .streaming(self.body)))
+    }
+}
+
+impl Responder for RecordResponse {
+    type Error = ThothError;
+    type Future = Ready<ThothResult<HttpResponse>>;
+
+    fn respond_to(self, req: &HttpRequest) -> Self::Future {
+        match self {
+            RecordResponse::Generated(record) => record.respond_to(req),
+            RecordResponse::Streamed(record) => record.respond_to(req),
+        }
+    }
+}
+
+impl MetadataBundle<Vec<Work>> {
+    const ZIP_MIME_TYPE: &'static str = "application/zip";
+    const ZIP_EXTENSION: &'static str = ".zip";
+    const MANIFEST_FILE_NAME: &'static str = "manifest.json";
+
+    pub(crate) fn new(id: String, records: Vec<MetadataRecord<Vec<Work>>>) -> Self {
+        MetadataBundle { id, records }
+    }
+
+    fn file_name(&self) -> String {
+        format!("bundle__{}{}", self.id, Self::ZIP_EXTENSION)
+    }
+
+    fn content_disposition(&self) -> String {
+        format!("attachment; filename=\"{}\"", self.file_name())
+    }
+
+    /// Write each record that can be generated to the archive, and list all of them in the
+    /// manifest, together with the works they leave out or the reason they are missing.
+    fn generate(&self) -> ThothResult<Vec<u8>> {
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut entries: Vec<BundleEntry> = Vec::with_capacity(self.records.len());
+        for record in &self.records {
+            let validation = record.validate();
+            let (file_name, error) = match record.generate() {
+                Ok(output) => {
+                    zip.start_file(record.file_name(), options)?;
+                    zip.write_all(&output)?;
+                    (Some(record.file_name()), None)
+                }
+                Err(error) => (None, Some(error.to_string())),
+            };
+            entries.push(BundleEntry {
+                specification_id: record.specification.to_string(),
+                file_name,
+                total_works: record.total_works(),
+                skipped_works: skipped_works(validation),
+                error,
+            });
+        }
+        let manifest = BundleManifest {
+            id: self.id.clone(),
+            generated_at: Utc::now(),
+            records: entries,
+        };
+        zip.start_file(Self::MANIFEST_FILE_NAME, options)?;
+        zip.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+        zip.finish()
+            .map(|cursor| cursor.into_inner())
+            .map_err(|e| e.into())
+    }
+}
+
+impl Responder for MetadataBundle<Vec<Work>>
 where
-    actix_web::dev::Body: From<String>,
+    actix_web::dev::Body: From<Vec<u8>>,
 {
     type Error = ThothError;
     type Future = Ready<ThothResult<HttpResponse>>;
 
     fn respond_to(self, _: &HttpRequest) -> Self::Future {
         match self.generate() {
-            Ok(record) => ready(Ok(HttpResponse::build(StatusCode::OK)
-                .content_type(self.content_type())
+            Ok(bundle) => ready(Ok(HttpResponse::build(StatusCode::OK)
+                .content_type(Self::ZIP_MIME_TYPE)
                 .header("Content-Disposition", self.content_disposition())
-                .body(record))),
+                .body(bundle))),
             Err(e) => ready(Err(e)),
         }
     }
@@ -147,6 +710,14 @@ where
 
 impl<T: AsRecord> Apiv2Schema for MetadataRecord<T> {}
 
+impl<T: AsRecord> Apiv2Schema for MetadataBundle<T> {}
+
+impl Apiv2Schema for GeneratedRecord {}
+
+impl Apiv2Schema for RecordResponse {}
+
+impl Apiv2Schema for NegotiatedRecord {}
+
 impl<T> OperationModifier for MetadataRecord<T>
 where
     T: AsRecord,
@@ -164,6 +735,254 @@ where
     }
 }
 
+impl<T> OperationModifier for MetadataBundle<T>
+where
+    T: AsRecord,
+{
+    fn update_response(op: &mut DefaultOperationRaw) {
+        MetadataRecord::<T>::update_response(op)
+    }
+}
+
+impl OperationModifier for GeneratedRecord {
+    fn update_response(op: &mut DefaultOperationRaw) {
+        MetadataRecord::<Vec<Work>>::update_response(op)
+    }
+}
+
+impl OperationModifier for RecordResponse {
+    fn update_response(op: &mut DefaultOperationRaw) {
+        MetadataRecord::<Vec<Work>>::update_response(op)
+    }
+}
+
+impl OperationModifier for NegotiatedRecord {
+    fn update_response(op: &mut DefaultOperationRaw) {
+        MetadataRecord::<Vec<Work>>::update_response(op);
+        let status: StatusCode = StatusCode::NOT_ACCEPTABLE;
+        op.responses.insert(
+            status.as_str().into(),
+            Either::Right(Response {
+                description: status.canonical_reason().map(ToString::to_string),
+                schema: None,
+                ..Default::default()
+            }),
+        );
+    }
+}
+
+/// Works that fail validation, which multi-work records leave out
+fn skipped_works(validation: Vec<WorkValidation>) -> Vec<SkippedWork> {
+    validation
+        .into_iter()
+        .filter(|validation| !validation.valid)
+        .map(|validation| SkippedWork {
+            work_id: validation.work_id,
+            title: validation.title,
+            reason: validation.errors.join("; "),
+        })
+        .collect()
+}
+
+impl MetadataSpecification {
+    pub(crate) fn generate(
+        &self,
+        works: &[Work],
+        deleted_works: &[DeletedWork],
+    ) -> ThothResult<Vec<u8>> {
+        match self {
+            MetadataSpecification::Onix3ProjectMuse(onix3_project_muse) => onix3_project_muse
+                .generate_with_deletions(works, deleted_works, None)
+                .map(String::into_bytes),
+            MetadataSpecification::Onix3Oapen(onix3_oapen) => onix3_oapen
+                .generate_with_deletions(works, deleted_works, None)
+                .map(String::into_bytes),
+            MetadataSpecification::Onix3Jstor(onix3_jstor) => onix3_jstor
+                .generate_with_deletions(works, deleted_works, None)
+                .map(String::into_bytes),
+            MetadataSpecification::Onix3Thoth(onix3_thoth) => onix3_thoth
+                .generate_with_deletions(works, deleted_works, None)
+                .map(String::into_bytes),
+            MetadataSpecification::Onix3GoogleBooks(onix3_google_books) => onix3_google_books
+                .generate_with_deletions(works, deleted_works, None)
+                .map(String::into_bytes),
+            MetadataSpecification::Onix3ProquestEbrary(onix3_proquest_ebrary) => {
+                onix3_proquest_ebrary
+                    .generate_with_deletions(works, deleted_works, None)
+                    .map(String::into_bytes)
+            }
+            MetadataSpecification::Onix21EbscoHost(onix21_ebsco_host) => onix21_ebsco_host
+                .generate(works, Some(DOCTYPE_ONIX21_REF))
+                .map(String::into_bytes),
+            MetadataSpecification::CsvThoth(csv_thoth) => csv_thoth
+                .generate(works, QuoteStyle::Always, DELIMITER_COMMA)
+                .map(String::into_bytes),
+            MetadataSpecification::KbartOclc(kbart_oclc) => kbart_oclc
+                .generate(works, QuoteStyle::Necessary, DELIMITER_TAB)
+                .map(String::into_bytes),
+            MetadataSpecification::KbartNiso(kbart_niso) => kbart_niso
+                .generate(works, QuoteStyle::Necessary, DELIMITER_TAB)
+                .map(String::into_bytes),
+            MetadataSpecification::Marc21XmlThoth(marc21xml_thoth) => marc21xml_thoth
+                .generate(works, None)
+                .map(String::into_bytes),
+            MetadataSpecification::Marc21Thoth(marc21_thoth) => marc21_thoth.generate(works),
+            MetadataSpecification::CrossrefThoth(crossref_thoth) => {
+                crossref_thoth.generate(works, None).map(String::into_bytes)
+            }
+            MetadataSpecification::DataCiteThoth(datacite_thoth) => {
+                datacite_thoth.generate(works, None).map(String::into_bytes)
+            }
+            MetadataSpecification::DataCiteThothJson(datacite_thoth_json) => {
+                datacite_thoth_json.generate(works).map(String::into_bytes)
+            }
+            MetadataSpecification::BibtexThoth(bibtex_thoth) => {
+                bibtex_thoth.generate(works).map(String::into_bytes)
+            }
+            MetadataSpecification::RisThoth(ris_thoth) => {
+                ris_thoth.generate(works).map(String::into_bytes)
+            }
+            MetadataSpecification::CslJsonThoth(csl_json_thoth) => {
+                csl_json_thoth.generate(works).map(String::into_bytes)
+            }
+            MetadataSpecification::OaiDcThoth(oai_dc_thoth) => {
+                oai_dc_thoth.generate(works, None).map(String::into_bytes)
+            }
+            MetadataSpecification::ModsThoth(mods_thoth) => {
+                mods_thoth.generate(works, None).map(String::into_bytes)
+            }
+            MetadataSpecification::JsonLdSchemaOrg(jsonld_schema_org) => {
+                jsonld_schema_org.generate(works).map(String::into_bytes)
+            }
+        }
+    }
+
+    /// List every field that this specification requires and the work lacks, without generating
+    /// its output. Generation stops at the first of these, so it can't report them all.
+    pub(crate) fn missing_fields(&self, work: &Work) -> Vec<String> {
+        let missing = match self {
+            MetadataSpecification::Onix3ProjectMuse(_) => Onix3ProjectMuse::missing_fields(work),
+            MetadataSpecification::Onix3Oapen(_) => Onix3Oapen::missing_fields(work),
+            MetadataSpecification::Onix3Jstor(_) => Onix3Jstor::missing_fields(work),
+            MetadataSpecification::Onix3Thoth(_) => Onix3Thoth::missing_fields(work),
+            MetadataSpecification::Onix3GoogleBooks(_) => Onix3GoogleBooks::missing_fields(work),
+            MetadataSpecification::Onix3ProquestEbrary(_) => {
+                Onix3ProquestEbrary::missing_fields(work)
+            }
+            MetadataSpecification::Onix21EbscoHost(_) => Onix21EbscoHost::missing_fields(work),
+            MetadataSpecification::CsvThoth(_) => CsvThoth::missing_fields(work),
+            MetadataSpecification::KbartOclc(_) => KbartOclc::missing_fields(work),
+            MetadataSpecification::KbartNiso(_) => KbartNiso::missing_fields(work),
+            MetadataSpecification::Marc21XmlThoth(_) => Marc21XmlThoth::missing_fields(work),
+            MetadataSpecification::Marc21Thoth(_) => Marc21Thoth::missing_fields(work),
+            MetadataSpecification::CrossrefThoth(_) => CrossrefThoth::missing_fields(work),
+            MetadataSpecification::DataCiteThoth(_) => DataCiteThoth::missing_fields(work),
+            MetadataSpecification::DataCiteThothJson(_) => DataCiteThothJson::missing_fields(work),
+            MetadataSpecification::BibtexThoth(_) => BibtexThoth::missing_fields(work),
+            MetadataSpecification::RisThoth(_) => RisThoth::missing_fields(work),
+            MetadataSpecification::CslJsonThoth(_) => CslJsonThoth::missing_fields(work),
+            MetadataSpecification::OaiDcThoth(_) => OaiDcThoth::missing_fields(work),
+            MetadataSpecification::ModsThoth(_) => ModsThoth::missing_fields(work),
+            MetadataSpecification::JsonLdSchemaOrg(_) => JsonLdSchemaOrg::missing_fields(work),
+        };
+        missing.into_iter().map(ToString::to_string).collect()
+    }
+
+    /// Path of the reference schema that this specification's output adheres to, relative to the
+    /// schemas directory, if it is an XML specification with a published schema
+    pub(crate) fn schema(&self) -> Option<&'static str> {
+        match self {
+            MetadataSpecification::Onix3ProjectMuse(_)
+            | MetadataSpecification::Onix3Oapen(_)
+            | MetadataSpecification::Onix3Jstor(_)
+            | MetadataSpecification::Onix3Thoth(_)
+            | MetadataSpecification::Onix3GoogleBooks(_)
+            | MetadataSpecification::Onix3ProquestEbrary(_) => {
+                Some("onix_3.0/ONIX_BookProduct_3.0_reference.xsd")
+            }
+            MetadataSpecification::Onix21EbscoHost(_) => {
+                Some("onix_2.1/ONIX_BookProduct_Release2.1_reference.xsd")
+            }
+            MetadataSpecification::Marc21XmlThoth(_) => Some("marc21xml/MARC21slim.xsd"),
+            MetadataSpecification::CrossrefThoth(_) => Some("crossref/crossref5.3.1.xsd"),
+            MetadataSpecification::DataCiteThoth(_) => Some("datacite/kernel-4.4/metadata.xsd"),
+            MetadataSpecification::OaiDcThoth(_) => Some("oai_dc/oai_dc.xsd"),
+            MetadataSpecification::ModsThoth(_) => Some("mods/mods-3-7.xsd"),
+            _ => None,
+        }
+    }
+
+    /// Whether this specification describes each publication as a separate product, and so must
+    /// notify recipients of publications deleted from works that still exist
+    pub(crate) fn notifies_deleted_publications(&self) -> bool {
+        matches!(
+            self,
+            MetadataSpecification::Onix3Thoth(_) | MetadataSpecification::Onix3ProquestEbrary(_)
+        )
+    }
+
+    /// Writer that outputs this specification one page of works at a time, if its documents can
+    /// be split into independent records
+    pub(crate) fn stream_writer(&self) -> Option<Box<dyn RecordWriter>> {
+        match self {
+            MetadataSpecification::Onix3ProjectMuse(_) => {
+                Some(Box::new(XmlStreamWriter::<Onix3ProjectMuse>::new(None)))
+            }
+            MetadataSpecification::Onix3Oapen(_) => {
+                Some(Box::new(XmlStreamWriter::<Onix3Oapen>::new(None)))
+            }
+            MetadataSpecification::Onix3Jstor(_) => {
+                Some(Box::new(XmlStreamWriter::<Onix3Jstor>::new(None)))
+            }
+            MetadataSpecification::Onix3Thoth(_) => {
+                Some(Box::new(XmlStreamWriter::<Onix3Thoth>::new(None)))
+            }
+            MetadataSpecification::Onix3GoogleBooks(_) => {
+                Some(Box::new(XmlStreamWriter::<Onix3GoogleBooks>::new(None)))
+            }
+            MetadataSpecification::Onix3ProquestEbrary(_) => {
+                Some(Box::new(XmlStreamWriter::<Onix3ProquestEbrary>::new(None)))
+            }
+            MetadataSpecification::Onix21EbscoHost(_) => {
+                Some(Box::new(XmlStreamWriter::<Onix21EbscoHost>::new(Some(
+                    DOCTYPE_ONIX21_REF,
+                ))))
+            }
+            MetadataSpecification::CsvThoth(_) => Some(Box::new(CsvStreamWriter::<CsvThoth>::new(
+                QuoteStyle::Always,
+                DELIMITER_COMMA,
+            ))),
+            MetadataSpecification::KbartOclc(_) => Some(Box::new(
+                CsvStreamWriter::<KbartOclc>::new(QuoteStyle::Necessary, DELIMITER_TAB),
+            )),
+            MetadataSpecification::Marc21XmlThoth(_) => {
+                Some(Box::new(XmlStreamWriter::<Marc21XmlThoth>::new(None)))
+            }
+            MetadataSpecification::Marc21Thoth(_) => {
+                Some(Box::new(Marc21StreamWriter::<Marc21Thoth>::new()))
+            }
+            MetadataSpecification::RisThoth(_) => {
+                Some(Box::new(CitationStreamWriter::<RisThoth>::new()))
+            }
+            // KBART NISO lists journals once, with all their issues; BibTeX disambiguates citation
+            // keys across the whole file; and the JSON specifications, Crossref, DataCite, MODS
+            // and OAI Dublin Core wrap every work in a single document or batch. These all need
+            // every work at once.
+            _ => None,
+        }
+    }
+
+    /// Apply the conventions of the platform that the record is destined for, where these vary
+    pub(crate) fn for_platform(self, platform_id: Option<&str>) -> ThothResult<Self> {
+        match (self, platform_id) {
+            (MetadataSpecification::KbartNiso(_), Some(platform_id)) => {
+                KbartNiso::for_platform(platform_id).map(MetadataSpecification::KbartNiso)
+            }
+            (specification, _) => Ok(specification),
+        }
+    }
+}
+
 impl FromStr for MetadataSpecification {
     type Err = ThothError;
 
@@ -174,11 +993,32 @@ impl FromStr for MetadataSpecification {
             }
             "onix_3.0::oapen" => Ok(MetadataSpecification::Onix3Oapen(Onix3Oapen {})),
             "onix_3.0::jstor" => Ok(MetadataSpecification::Onix3Jstor(Onix3Jstor {})),
+            "onix_3.0::thoth" => Ok(MetadataSpecification::Onix3Thoth(Onix3Thoth {})),
+            "onix_3.0::google_books" => {
+                Ok(MetadataSpecification::Onix3GoogleBooks(Onix3GoogleBooks {}))
+            }
+            "onix_3.0::proquest_ebrary" => Ok(MetadataSpecification::Onix3ProquestEbrary(
+                Onix3ProquestEbrary {},
+            )),
             "onix_2.1::ebsco_host" => {
                 Ok(MetadataSpecification::Onix21EbscoHost(Onix21EbscoHost {}))
             }
             "csv::thoth" => Ok(MetadataSpecification::CsvThoth(CsvThoth {})),
             "kbart::oclc" => Ok(MetadataSpecification::KbartOclc(KbartOclc {})),
+            "kbart::niso" => Ok(MetadataSpecification::KbartNiso(KbartNiso::default())),
+            "marc21xml::thoth" => Ok(MetadataSpecification::Marc21XmlThoth(Marc21XmlThoth {})),
+            "marc21::thoth" => Ok(MetadataSpecification::Marc21Thoth(Marc21Thoth {})),
+            "crossref::thoth" => Ok(MetadataSpecification::CrossrefThoth(CrossrefThoth {})),
+            "datacite::thoth" => Ok(MetadataSpecification::DataCiteThoth(DataCiteThoth {})),
+            "datacite::thoth_json" => Ok(MetadataSpecification::DataCiteThothJson(
+                DataCiteThothJson {},
+            )),
+            "bibtex::thoth" => Ok(MetadataSpecification::BibtexThoth(BibtexThoth {})),
+            "ris::thoth" => Ok(MetadataSpecification::RisThoth(RisThoth {})),
+            "csl_json::thoth" => Ok(MetadataSpecification::CslJsonThoth(CslJsonThoth {})),
+            "oai_dc::thoth" => Ok(MetadataSpecification::OaiDcThoth(OaiDcThoth {})),
+            "mods::thoth" => Ok(MetadataSpecification::ModsThoth(ModsThoth {})),
+            "jsonld::schema_org" => Ok(MetadataSpecification::JsonLdSchemaOrg(JsonLdSchemaOrg {})),
             _ => Err(ThothError::InvalidMetadataSpecification(input.to_string())),
         }
     }
@@ -190,9 +1030,26 @@ impl ToString for MetadataSpecification {
             MetadataSpecification::Onix3ProjectMuse(_) => "onix_3.0::project_muse".to_string(),
             MetadataSpecification::Onix3Oapen(_) => "onix_3.0::oapen".to_string(),
             MetadataSpecification::Onix3Jstor(_) => "onix_3.0::jstor".to_string(),
+            MetadataSpecification::Onix3Thoth(_) => "onix_3.0::thoth".to_string(),
+            MetadataSpecification::Onix3GoogleBooks(_) => "onix_3.0::google_books".to_string(),
+            MetadataSpecification::Onix3ProquestEbrary(_) => {
+                "onix_3.0::proquest_ebrary".to_string()
+            }
             MetadataSpecification::Onix21EbscoHost(_) => "onix_2.1::ebsco_host".to_string(),
             MetadataSpecification::CsvThoth(_) => "csv::thoth".to_string(),
             MetadataSpecification::KbartOclc(_) => "kbart::oclc".to_string(),
+            MetadataSpecification::KbartNiso(_) => "kbart::niso".to_string(),
+            MetadataSpecification::Marc21XmlThoth(_) => "marc21xml::thoth".to_string(),
+            MetadataSpecification::Marc21Thoth(_) => "marc21::thoth".to_string(),
+            MetadataSpecification::CrossrefThoth(_) => "crossref::thoth".to_string(),
+            MetadataSpecification::DataCiteThoth(_) => "datacite::thoth".to_string(),
+            MetadataSpecification::DataCiteThothJson(_) => "datacite::thoth_json".to_string(),
+            MetadataSpecification::BibtexThoth(_) => "bibtex::thoth".to_string(),
+            MetadataSpecification::RisThoth(_) => "ris::thoth".to_string(),
+            MetadataSpecification::CslJsonThoth(_) => "csl_json::thoth".to_string(),
+            MetadataSpecification::OaiDcThoth(_) => "oai_dc::thoth".to_string(),
+            MetadataSpecification::ModsThoth(_) => "mods::thoth".to_string(),
+            MetadataSpecification::JsonLdSchemaOrg(_) => "jsonld::schema_org".to_string(),
         }
     }
 }
@@ -200,11 +1057,18 @@ impl ToString for MetadataSpecification {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use actix_web::test::TestRequest;
+    use chrono::TimeZone;
+    use futures::executor::block_on;
+    use thoth_client::{WorkImprint, WorkImprintPublisher, WorkStatus, WorkType};
+    use uuid::Uuid;
 
     #[test]
     fn test_all_specifications_are_supported_metadata_specification() {
-        for s in crate::data::ALL_SPECIFICATIONS.iter() {
-            assert!(MetadataSpecification::from_str(s.id).is_ok())
+        let registry =
+            crate::data::Registry::new("http://localhost:8181", &Default::default()).unwrap();
+        for s in registry.specifications() {
+            assert!(MetadataSpecification::from_str(&s.id).is_ok())
         }
     }
 
@@ -248,6 +1112,33 @@ mod tests {
             to_test.file_name(),
             "onix_3.0__jstor__some_id.xml".to_string()
         );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::Onix3Thoth(Onix3Thoth {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "onix_3.0__thoth__some_id.xml".to_string()
+        );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::Onix3GoogleBooks(Onix3GoogleBooks {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "onix_3.0__google_books__some_id.xml".to_string()
+        );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::Onix3ProquestEbrary(Onix3ProquestEbrary {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "onix_3.0__proquest_ebrary__some_id.xml".to_string()
+        );
         let to_test = MetadataRecord::new(
             "some_id".to_string(),
             MetadataSpecification::Onix21EbscoHost(Onix21EbscoHost {}),
@@ -263,5 +1154,606 @@ mod tests {
             vec![],
         );
         assert_eq!(to_test.file_name(), "kbart__oclc__some_id.txt".to_string());
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::KbartNiso(KbartNiso::default()),
+            vec![],
+        );
+        assert_eq!(to_test.file_name(), "kbart__niso__some_id.txt".to_string());
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::Marc21XmlThoth(Marc21XmlThoth {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "marc21xml__thoth__some_id.xml".to_string()
+        );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::Marc21Thoth(Marc21Thoth {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "marc21__thoth__some_id.mrc".to_string()
+        );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::CrossrefThoth(CrossrefThoth {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "crossref__thoth__some_id.xml".to_string()
+        );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::DataCiteThoth(DataCiteThoth {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "datacite__thoth__some_id.xml".to_string()
+        );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::DataCiteThothJson(DataCiteThothJson {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "datacite__thoth_json__some_id.json".to_string()
+        );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::BibtexThoth(BibtexThoth {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "bibtex__thoth__some_id.bib".to_string()
+        );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::RisThoth(RisThoth {}),
+            vec![],
+        );
+        assert_eq!(to_test.file_name(), "ris__thoth__some_id.ris".to_string());
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::CslJsonThoth(CslJsonThoth {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "csl_json__thoth__some_id.json".to_string()
+        );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::OaiDcThoth(OaiDcThoth {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "oai_dc__thoth__some_id.xml".to_string()
+        );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::ModsThoth(ModsThoth {}),
+            vec![],
+        );
+        assert_eq!(to_test.file_name(), "mods__thoth__some_id.xml".to_string());
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::JsonLdSchemaOrg(JsonLdSchemaOrg {}),
+            vec![],
+        );
+        assert_eq!(
+            to_test.file_name(),
+            "jsonld__schema_org__some_id.jsonld".to_string()
+        );
+    }
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: None,
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: None,
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: None,
+            long_abstract: None,
+            general_note: None,
+            place: None,
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: None,
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![],
+            contributions: vec![],
+            languages: vec![],
+            publications: vec![],
+            subjects: vec![],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    fn incomplete_work() -> Work {
+        let mut incomplete_work = test_work();
+        incomplete_work.work_id = Uuid::from_str("00000000-0000-0000-AAAA-000000000002").unwrap();
+        incomplete_work.full_title = "Incomplete Title".to_string();
+        incomplete_work.landing_page = None;
+        incomplete_work
+    }
+
+    #[test]
+    fn test_record_skipped_works() {
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::KbartOclc(KbartOclc {}),
+            vec![test_work(), incomplete_work()],
+        );
+        assert_eq!(to_test.total_works(), 2);
+        let skipped_works = to_test.skipped_works();
+        assert_eq!(skipped_works.len(), 1);
+        assert_eq!(
+            skipped_works[0].work_id,
+            Uuid::from_str("00000000-0000-0000-AAAA-000000000002").unwrap()
+        );
+        assert_eq!(skipped_works[0].title, "Incomplete Title".to_string());
+        assert_eq!(skipped_works[0].reason, "Missing Landing Page".to_string());
+
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::KbartOclc(KbartOclc {}),
+            vec![test_work()],
+        );
+        assert!(to_test.skipped_works().is_empty());
+    }
+
+    #[test]
+    fn test_record_validate() {
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::KbartOclc(KbartOclc {}),
+            vec![test_work(), incomplete_work()],
+        );
+        let validation = to_test.validate();
+        assert_eq!(validation.len(), 2);
+        assert!(validation[0].valid);
+        assert!(validation[0].errors.is_empty());
+        assert!(!validation[1].valid);
+        assert_eq!(
+            validation[1].errors,
+            vec!["Missing Landing Page".to_string()]
+        );
+
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::BibtexThoth(BibtexThoth {}),
+            vec![incomplete_work()],
+        );
+        assert!(to_test.validate()[0].valid);
+
+        // Every missing field is reported, not just the first one found
+        let mut work = incomplete_work();
+        work.publication_date = None;
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::KbartOclc(KbartOclc {}),
+            vec![work.clone()],
+        );
+        assert_eq!(
+            to_test.validate()[0].errors,
+            vec![
+                "Missing Landing Page".to_string(),
+                "Missing Publication Date".to_string()
+            ]
+        );
+        assert_eq!(
+            to_test.skipped_works()[0].reason,
+            "Missing Landing Page; Missing Publication Date".to_string()
+        );
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::CrossrefThoth(CrossrefThoth {}),
+            vec![work],
+        );
+        assert_eq!(
+            to_test.validate()[0].errors,
+            vec![
+                "Missing DOI".to_string(),
+                "Missing Publication Date".to_string(),
+                "Missing Landing Page".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_fields_match_generation() {
+        // The first missing field is the error that generation itself reports
+        let mut work = incomplete_work();
+        work.publication_date = None;
+        work.doi = None;
+        work.publications.clear();
+        work.contributions.clear();
+        for specification in [
+            "onix_3.0::project_muse",
+            "onix_3.0::oapen",
+            "onix_3.0::jstor",
+            "onix_3.0::thoth",
+            "onix_3.0::google_books",
+            "onix_3.0::proquest_ebrary",
+            "onix_2.1::ebsco_host",
+            "kbart::oclc",
+            "kbart::niso",
+            "marc21::thoth",
+            "marc21xml::thoth",
+            "crossref::thoth",
+            "datacite::thoth",
+            "datacite::thoth_json",
+            "bibtex::thoth",
+        ]
+        .iter()
+        {
+            let specification = MetadataSpecification::from_str(specification).unwrap();
+            let missing = specification.missing_fields(&work);
+            assert!(!missing.is_empty(), "{}", specification.to_string());
+            match specification.generate(std::slice::from_ref(&work), &[]) {
+                Err(ThothError::IncompleteMetadataRecord(_, reason)) => {
+                    assert_eq!(reason, missing[0], "{}", specification.to_string())
+                }
+                other => panic!("{}: {:?}", specification.to_string(), other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_bundle_file_name() {
+        let to_test = MetadataBundle::new("some_id".to_string(), vec![]);
+        assert_eq!(to_test.file_name(), "bundle__some_id.zip".to_string());
+    }
+
+    #[test]
+    fn test_bundle_generate() {
+        let to_test = MetadataBundle::new(
+            "some_id".to_string(),
+            vec![
+                MetadataRecord::new(
+                    "some_id".to_string(),
+                    MetadataSpecification::KbartOclc(KbartOclc {}),
+                    vec![test_work(), incomplete_work()],
+                ),
+                MetadataRecord::new(
+                    "some_id".to_string(),
+                    MetadataSpecification::Onix3Jstor(Onix3Jstor {}),
+                    vec![test_work()],
+                ),
+            ],
+        );
+        let bundle = to_test.generate().unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bundle)).unwrap();
+        // Records that cannot be generated are left out of the archive
+        assert_eq!(archive.len(), 2);
+        assert!(archive.by_name("kbart__oclc__some_id.txt").is_ok());
+        assert!(archive.by_name("onix_3.0__jstor__some_id.xml").is_err());
+
+        let manifest: serde_json::Value =
+            serde_json::from_reader(archive.by_name("manifest.json").unwrap()).unwrap();
+        assert_eq!(manifest["id"], "some_id");
+        assert!(manifest["generatedAt"].is_string());
+        let records = manifest["records"].as_array().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["specificationId"], "kbart::oclc");
+        assert_eq!(records[0]["fileName"], "kbart__oclc__some_id.txt");
+        assert_eq!(records[0]["totalWorks"], 2);
+        assert_eq!(
+            records[0]["skippedWorks"][0]["workId"],
+            "00000000-0000-0000-aaaa-000000000002"
+        );
+        assert_eq!(
+            records[0]["skippedWorks"][0]["reason"],
+            "Missing Landing Page"
+        );
+        assert!(records[0]["error"].is_null());
+        assert_eq!(records[1]["specificationId"], "onix_3.0::jstor");
+        assert!(records[1]["fileName"].is_null());
+        assert_eq!(records[1]["totalWorks"], 1);
+        assert!(records[1]["error"].is_string());
+    }
+
+    #[test]
+    fn test_record_validators() {
+        let mut work = test_work();
+        work.updated_at_with_relations = Timestamp::from_str("2021-06-01T12:30:00Z").unwrap();
+        let mut deleted_work = incomplete_work();
+        deleted_work.updated_at_with_relations =
+            Timestamp::from_str("2021-06-02T08:00:00Z").unwrap();
+        let to_test = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::KbartOclc(KbartOclc {}),
+            vec![work.clone()],
+        );
+        let generated = to_test.to_generated().unwrap();
+        assert_eq!(
+            generated.last_modified.map(http_date),
+            Some("Tue, 01 Jun 2021 12:30:00 GMT".to_string())
+        );
+        // The same data yields the same validator
+        assert_eq!(generated.etag, to_test.to_generated().unwrap().etag);
+
+        // Adding a more recently updated work changes both
+        let updated = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::KbartOclc(KbartOclc {}),
+            vec![work, deleted_work],
+        );
+        assert_eq!(
+            updated.last_modified(),
+            Some(TimeZone::ymd(&Utc, 2021, 6, 2).and_hms(8, 0, 0))
+        );
+        assert_ne!(generated.etag, updated.etag(updated.last_modified()));
+    }
+
+    #[test]
+    fn test_record_deleted_publications() {
+        let work_id = Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap();
+        let publication_id = Uuid::from_str("00000000-0000-0000-BBBB-000000000002").unwrap();
+        let deleted_work_id = Uuid::from_str("00000000-0000-0000-AAAA-000000000003").unwrap();
+        let publisher_id = Uuid::from_str("00000000-0000-0000-DDDD-000000000001").unwrap();
+        // A deleted work, and a publication deleted from a work that still exists
+        let deletions = Deletions {
+            works: vec![DeletedWork {
+                work_id: deleted_work_id,
+                publisher_id,
+                publication_ids: vec![],
+                deleted_at: Timestamp::from_str("2021-06-01T08:00:00Z").unwrap(),
+            }],
+            publications: vec![DeletedWork {
+                work_id,
+                publisher_id,
+                publication_ids: vec![publication_id],
+                deleted_at: Timestamp::from_str("2021-06-02T08:00:00Z").unwrap(),
+            }],
+        };
+        // Specifications with a single product per work must not delete the whole work
+        for specification in [
+            MetadataSpecification::Onix3ProjectMuse(Onix3ProjectMuse {}),
+            MetadataSpecification::Onix3Oapen(Onix3Oapen {}),
+            MetadataSpecification::Onix3Jstor(Onix3Jstor {}),
+            MetadataSpecification::Onix3GoogleBooks(Onix3GoogleBooks {}),
+        ] {
+            let record = MetadataRecord::new("some_id".to_string(), specification, vec![])
+                .with_deletions(deletions.clone());
+            let output = String::from_utf8(record.generate().unwrap()).unwrap();
+            assert_eq!(
+                output
+                    .matches("<NotificationType>05</NotificationType>")
+                    .count(),
+                1
+            );
+            assert!(output.contains(&format!("urn:uuid:{}", deleted_work_id)));
+            assert!(!output.contains(&format!("urn:uuid:{}", work_id)));
+        }
+        // Specifications with a product per publication delete just that publication
+        let record = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::Onix3Thoth(Onix3Thoth {}),
+            vec![],
+        )
+        .with_deletions(deletions);
+        let output = String::from_utf8(record.generate().unwrap()).unwrap();
+        assert_eq!(
+            output
+                .matches("<NotificationType>05</NotificationType>")
+                .count(),
+            1
+        );
+        assert!(output.contains(&format!("urn:uuid:{}", publication_id)));
+        assert!(!output.contains(&format!("urn:uuid:{}", work_id)));
+    }
+
+    #[test]
+    fn test_record_not_modified() {
+        let mut work = test_work();
+        work.updated_at_with_relations = Timestamp::from_str("2021-06-01T12:30:00Z").unwrap();
+        let generated = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::KbartOclc(KbartOclc {}),
+            vec![work],
+        )
+        .to_generated()
+        .unwrap();
+
+        let req = TestRequest::default().to_http_request();
+        assert!(!generated.is_not_modified(&req));
+        let req = TestRequest::default()
+            .header(IF_NONE_MATCH, generated.etag.clone())
+            .to_http_request();
+        assert!(generated.is_not_modified(&req));
+        let req = TestRequest::default()
+            .header(IF_NONE_MATCH, format!("\"other\", W/{}", generated.etag))
+            .to_http_request();
+        assert!(generated.is_not_modified(&req));
+        let req = TestRequest::default()
+            .header(IF_MODIFIED_SINCE, "Tue, 01 Jun 2021 12:30:00 GMT")
+            .to_http_request();
+        assert!(generated.is_not_modified(&req));
+        let req = TestRequest::default()
+            .header(IF_MODIFIED_SINCE, "Tue, 01 Jun 2021 12:29:59 GMT")
+            .to_http_request();
+        assert!(!generated.is_not_modified(&req));
+        // If-None-Match takes precedence over If-Modified-Since
+        let req = TestRequest::default()
+            .header(IF_NONE_MATCH, "\"other\"")
+            .header(IF_MODIFIED_SINCE, "Tue, 01 Jun 2021 12:30:00 GMT")
+            .to_http_request();
+        assert!(!generated.is_not_modified(&req));
+        // Conditional headers only apply to safe requests
+        let req = TestRequest::post()
+            .header(IF_NONE_MATCH, generated.etag.clone())
+            .to_http_request();
+        assert!(!generated.is_not_modified(&req));
+    }
+
+    #[test]
+    fn test_streamed_record_validators() {
+        let summary = WorksSummary {
+            work_count: 250,
+            last_updated: Some(Timestamp::from_str("2021-06-01T12:30:00Z").unwrap()),
+        };
+        let streamed = |summary: &WorksSummary| {
+            MetadataRecord::new(
+                "some_id".to_string(),
+                MetadataSpecification::KbartOclc(KbartOclc {}),
+                vec![test_work()],
+            )
+            .into_stream(
+                MetadataSpecification::KbartOclc(KbartOclc {})
+                    .stream_writer()
+                    .unwrap(),
+                stream::empty(),
+                summary,
+                &None,
+            )
+            .unwrap()
+        };
+        let record = streamed(&summary);
+        assert_eq!(
+            record.last_modified.map(http_date),
+            Some("Tue, 01 Jun 2021 12:30:00 GMT".to_string())
+        );
+        assert_eq!(record.etag, streamed(&summary).etag);
+        // Removing a work changes the validator, even if the last update does not
+        let removed = WorksSummary {
+            work_count: 249,
+            ..summary.clone()
+        };
+        assert_ne!(record.etag, streamed(&removed).etag);
+
+        let req = TestRequest::default()
+            .header(IF_NONE_MATCH, record.etag.clone())
+            .to_http_request();
+        let response = block_on(streamed(&summary).respond_to(&req)).unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers().get(ETAG).unwrap(), record.etag.as_str());
+        let req = TestRequest::default().to_http_request();
+        let response = block_on(streamed(&summary).respond_to(&req)).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(LAST_MODIFIED).unwrap(),
+            "Tue, 01 Jun 2021 12:30:00 GMT"
+        );
+    }
+
+    #[test]
+    fn test_streamed_record_aborts_on_error() {
+        let summary = WorksSummary {
+            work_count: 250,
+            last_updated: None,
+        };
+        let pages = stream::iter(vec![
+            Ok(vec![test_work()]),
+            Err(ThothError::InternalError("Page unavailable".to_string())),
+            Ok(vec![test_work()]),
+        ]);
+        let record = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::Onix3Thoth(Onix3Thoth {}),
+            vec![test_work()],
+        )
+        .into_stream(
+            MetadataSpecification::Onix3Thoth(Onix3Thoth {})
+                .stream_writer()
+                .unwrap(),
+            pages,
+            &summary,
+            &None,
+        )
+        .unwrap();
+        let chunks: Vec<ThothResult<Bytes>> = block_on(record.body.collect());
+        // The first two pages, the marker of an incomplete document, then the error
+        assert_eq!(chunks.len(), 4);
+        let marker = String::from_utf8(chunks[2].as_ref().unwrap().to_vec()).unwrap();
+        assert!(marker.contains("<!-- Export aborted: Internal error: Page unavailable -->"));
+        assert!(chunks[3].is_err());
+        // The document is never closed
+        let output: Vec<u8> = chunks
+            .iter()
+            .filter_map(|chunk| chunk.as_ref().ok())
+            .flat_map(|chunk| chunk.to_vec())
+            .collect();
+        assert!(!String::from_utf8(output)
+            .unwrap()
+            .contains("</ONIXMessage>"));
+    }
+
+    #[test]
+    fn test_negotiated_record_headers() {
+        let links =
+            r#"<https://export.thoth.pub/works/1?format=csv>; rel="alternate"; type="text/csv""#;
+        let generated = MetadataRecord::new(
+            "some_id".to_string(),
+            MetadataSpecification::KbartOclc(KbartOclc {}),
+            vec![test_work()],
+        )
+        .to_generated()
+        .unwrap();
+        let req = TestRequest::default().to_http_request();
+
+        let response =
+            block_on(NegotiatedRecord::new(Ok(generated), links.to_string()).respond_to(&req))
+                .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(VARY).unwrap(), "Accept");
+        assert_eq!(response.headers().get(LINK).unwrap(), links);
+
+        let response = block_on(
+            NegotiatedRecord::new(
+                Err(ThothError::NotAcceptable("text/html".to_string())),
+                links.to_string(),
+            )
+            .respond_to(&req),
+        )
+        .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+        assert_eq!(response.headers().get(LINK).unwrap(), links);
     }
 }
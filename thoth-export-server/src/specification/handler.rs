@@ -1,22 +1,128 @@
 use actix_web::Error;
+use futures::stream::{self, Stream};
 use paperclip::actix::{
     api_v2_operation,
     web::{self, Json},
 };
-use thoth_client::{ThothClient, Work};
+use std::future::Future;
+use thoth_api::model::Timestamp;
+use thoth_client::{DeletedWork, ThothClient, Work, WORKS_PAGE_SIZE};
+use thoth_errors::{ThothError, ThothResult};
 use uuid::Uuid;
 
-use super::model::Specification;
-use crate::data::{find_specification, ALL_SPECIFICATIONS};
-use crate::record::MetadataRecord;
+use super::model::{
+    BundleQuery, PublisherReport, Specification, SpecificationQuery, ValidationReport, WorkList,
+};
+use crate::cache::RecordCache;
+use crate::data::Registry;
+use crate::record::{
+    Deletions, GeneratedRecord, MetadataBundle, MetadataRecord, MetadataSpecification,
+    RecordResponse,
+};
+use crate::xml::SchemaValidator;
+
+fn new_record(
+    id: String,
+    specification_id: &str,
+    platform: Option<&str>,
+    data: Vec<Work>,
+) -> ThothResult<MetadataRecord<Vec<Work>>> {
+    specification_id
+        .parse::<MetadataSpecification>()
+        .and_then(|specification| specification.for_platform(platform))
+        .map(|specification| MetadataRecord::new(id, specification, data))
+}
+
+/// Deletions to notify in a publisher's record of the works updated since a given time.
+///
+/// Publications deleted from works that remain are kept apart from deleted works, grouped by the
+/// work they belonged to, since only specifications with a product per publication notify them.
+async fn deletions(
+    thoth_client: &ThothClient,
+    publisher_id: Uuid,
+    updated_since: &Option<Timestamp>,
+) -> ThothResult<Deletions> {
+    let since = match updated_since {
+        Some(since) => since,
+        None => return Ok(Deletions::default()),
+    };
+    let deleted_works = thoth_client
+        .get_deleted_works(Some(vec![publisher_id]), Some(since.clone()))
+        .await?;
+    let deleted_publications = thoth_client
+        .get_deleted_publications(Some(vec![publisher_id]), Some(since.clone()))
+        .await?;
+    let mut publications: Vec<DeletedWork> = vec![];
+    for deleted_publication in deleted_publications {
+        match publications
+            .iter_mut()
+            .find(|work| work.work_id == deleted_publication.work_id)
+        {
+            // Deleted publications are listed in order of deletion
+            Some(work) => {
+                work.publication_ids
+                    .push(deleted_publication.publication_id);
+                work.deleted_at = deleted_publication.deleted_at;
+            }
+            None => publications.push(DeletedWork {
+                work_id: deleted_publication.work_id,
+                publisher_id,
+                publication_ids: vec![deleted_publication.publication_id],
+                deleted_at: deleted_publication.deleted_at,
+            }),
+        }
+    }
+    Ok(Deletions {
+        works: deleted_works,
+        publications,
+    })
+}
+
+/// Serve a record from the cache if it was generated recently enough, otherwise generate it from
+/// freshly queried data and cache the result
+async fn cached_record(
+    cache: &RecordCache,
+    key: String,
+    record: impl Future<Output = ThothResult<MetadataRecord<Vec<Work>>>>,
+) -> Result<GeneratedRecord, Error> {
+    if let Some(generated) = cache.get(&key) {
+        return Ok(generated);
+    }
+    let generated = record.await?.to_generated()?;
+    cache.insert(key, generated.clone());
+    Ok(generated)
+}
+
+/// Check a record against its specification's reference schema, if the query asks for it
+fn validated(
+    generated: GeneratedRecord,
+    specification_id: &str,
+    query: &SpecificationQuery,
+    schemas: &SchemaValidator,
+) -> ThothResult<GeneratedRecord> {
+    if !query.validates() {
+        return Ok(generated);
+    }
+    let schema = specification_id
+        .parse::<MetadataSpecification>()?
+        .schema()
+        .ok_or_else(|| {
+            ThothError::SchemaValidationError(
+                specification_id.to_string(),
+                "No reference schema is available".to_string(),
+            )
+        })?;
+    schemas.validate(specification_id, schema, &generated.body)?;
+    Ok(generated)
+}
 
 #[api_v2_operation(
     summary = "List supported specifications",
     description = "Full list of metadata specifications that can be output by Thoth",
     tags(Specifications)
 )]
-pub(crate) async fn get_all() -> Json<Vec<Specification<'static>>> {
-    Json(ALL_SPECIFICATIONS.clone())
+pub(crate) async fn get_all(registry: web::Data<Registry>) -> Json<Vec<Specification>> {
+    Json(registry.specifications().to_vec())
 }
 
 #[api_v2_operation(
@@ -26,8 +132,10 @@ pub(crate) async fn get_all() -> Json<Vec<Specification<'static>>> {
 )]
 pub(crate) async fn get_one(
     web::Path(specification_id): web::Path<String>,
-) -> Result<Json<Specification<'static>>, Error> {
-    find_specification(specification_id)
+    registry: web::Data<Registry>,
+) -> Result<Json<Specification>, Error> {
+    registry
+        .find_specification(specification_id)
         .map(Json)
         .map_err(|e| e.into())
 }
@@ -40,36 +148,336 @@ pub(crate) async fn get_one(
 )]
 pub(crate) async fn by_work(
     web::Path((specification_id, work_id)): web::Path<(String, Uuid)>,
+    web::Query(query): web::Query<SpecificationQuery>,
+    thoth_client: web::Data<ThothClient>,
+    cache: web::Data<RecordCache>,
+    schemas: web::Data<SchemaValidator>,
+) -> Result<GeneratedRecord, Error> {
+    let key = RecordCache::key(&specification_id, &format!("work/{}", work_id), &query);
+    let generated = cached_record(&cache, key, async {
+        thoth_client.get_work(work_id).await.and_then(|data| {
+            new_record(
+                work_id.to_string(),
+                &specification_id,
+                query.platform.as_deref(),
+                vec![data],
+            )
+        })
+    })
+    .await?;
+    validated(generated, &specification_id, &query, &schemas).map_err(|e| e.into())
+}
+
+#[api_v2_operation(
+    summary = "Get a publisher's metadata record",
+    description = "Obtain a metadata record that adheres to a particular specification for all of a given publisher's works. Records spanning more than one page of works are streamed as each page is fetched, where the specification allows it.",
+    produces = "text/xml, text/csv",
+    tags(Specifications)
+)]
+pub(crate) async fn by_publisher(
+    web::Path((specification_id, publisher_id)): web::Path<(String, Uuid)>,
+    web::Query(query): web::Query<SpecificationQuery>,
     thoth_client: web::Data<ThothClient>,
-) -> Result<MetadataRecord<Vec<Work>>, Error> {
+    cache: web::Data<RecordCache>,
+    schemas: web::Data<SchemaValidator>,
+) -> Result<RecordResponse, Error> {
+    let updated_since = query.updated_since()?;
+    let key = RecordCache::key(
+        &specification_id,
+        &format!("publisher/{}", publisher_id),
+        &query,
+    );
+    if let Some(generated) = cache.get(&key) {
+        let generated = validated(generated, &specification_id, &query, &schemas)?;
+        return Ok(RecordResponse::Generated(generated));
+    }
+    let specification = specification_id
+        .parse::<MetadataSpecification>()
+        .and_then(|specification| specification.for_platform(query.platform.as_deref()))?;
+    let deletions = deletions(&thoth_client, publisher_id, &updated_since).await?;
+    // Validation needs the whole document, so validated records are never streamed
+    let writer = match specification.stream_writer() {
+        Some(writer) if !query.validates() => writer,
+        _ => {
+            let data = thoth_client
+                .get_works(Some(vec![publisher_id]), updated_since)
+                .await?;
+            let generated = MetadataRecord::new(publisher_id.to_string(), specification, data)
+                .with_deletions(deletions)
+                .to_generated()?;
+            cache.insert(key, generated.clone());
+            let generated = validated(generated, &specification_id, &query, &schemas)?;
+            return Ok(RecordResponse::Generated(generated));
+        }
+    };
+    let first_page = thoth_client
+        .get_works_page(
+            Some(vec![publisher_id]),
+            updated_since.clone(),
+            WORKS_PAGE_SIZE,
+            0,
+        )
+        .await?;
+    let is_last_page = (first_page.len() as i64) < WORKS_PAGE_SIZE;
+    let record = MetadataRecord::new(publisher_id.to_string(), specification, first_page)
+        .with_deletions(deletions);
+    if is_last_page {
+        let generated = record.to_generated()?;
+        cache.insert(key, generated.clone());
+        let generated = validated(generated, &specification_id, &query, &schemas)?;
+        return Ok(RecordResponse::Generated(generated));
+    }
+    // Validators of the whole record must be sent before its remaining pages are fetched
+    let summary = thoth_client
+        .get_works_summary(Some(vec![publisher_id]), updated_since.clone())
+        .await?;
+    let pages = next_pages(thoth_client, publisher_id, updated_since.clone());
+    Ok(RecordResponse::Streamed(record.into_stream(
+        writer,
+        pages,
+        &summary,
+        &updated_since,
+    )?))
+}
+
+/// Pages of a publisher's works following the first one, each fetched once the previous one has
+/// been written out
+fn next_pages(
+    thoth_client: web::Data<ThothClient>,
+    publisher_id: Uuid,
+    updated_since: Option<Timestamp>,
+) -> impl Stream<Item = ThothResult<Vec<Work>>> {
+    stream::unfold(Some(WORKS_PAGE_SIZE), move |offset| {
+        let thoth_client = thoth_client.clone();
+        let updated_since = updated_since.clone();
+        async move {
+            let offset = offset?;
+            let page = thoth_client
+                .get_works_page(
+                    Some(vec![publisher_id]),
+                    updated_since,
+                    WORKS_PAGE_SIZE,
+                    offset,
+                )
+                .await;
+            let next_offset = match &page {
+                Ok(works) if works.len() as i64 == WORKS_PAGE_SIZE => {
+                    Some(offset + WORKS_PAGE_SIZE)
+                }
+                _ => None,
+            };
+            Some((page, next_offset))
+        }
+    })
+}
+
+#[api_v2_operation(
+    summary = "Get a bundle of a publisher's metadata records",
+    description = "Obtain a ZIP archive with a metadata record for each of the given specifications for all of a given publisher's works, along with a manifest listing the works left out of each record",
+    produces = "application/zip",
+    tags(Specifications)
+)]
+pub(crate) async fn by_publisher_bundle(
+    web::Path(publisher_id): web::Path<Uuid>,
+    web::Query(query): web::Query<BundleQuery>,
+    thoth_client: web::Data<ThothClient>,
+) -> Result<MetadataBundle<Vec<Work>>, Error> {
+    // Reject unknown specifications before querying any data
+    let specifications = query
+        .specification_ids()
+        .into_iter()
+        .map(|specification_id| specification_id.parse::<MetadataSpecification>())
+        .collect::<ThothResult<Vec<MetadataSpecification>>>()?;
+    let updated_since = query.updated_since()?;
+    let deletions = deletions(&thoth_client, publisher_id, &updated_since).await?;
+    let data = thoth_client
+        .get_works(Some(vec![publisher_id]), updated_since)
+        .await?;
+    let records = specifications
+        .into_iter()
+        .map(|specification| {
+            MetadataRecord::new(publisher_id.to_string(), specification, data.clone())
+                .with_deletions(deletions.clone())
+        })
+        .collect();
+    Ok(MetadataBundle::new(publisher_id.to_string(), records))
+}
+
+#[api_v2_operation(
+    summary = "Report on a publisher's metadata record",
+    description = "List the works that are left out of a publisher's metadata record, and the reason each was skipped",
+    tags(Specifications)
+)]
+pub(crate) async fn by_publisher_report(
+    web::Path((specification_id, publisher_id)): web::Path<(String, Uuid)>,
+    web::Query(query): web::Query<SpecificationQuery>,
+    thoth_client: web::Data<ThothClient>,
+) -> Result<Json<PublisherReport>, Error> {
+    let updated_since = query.updated_since()?;
     thoth_client
-        .get_work(work_id)
+        .get_works(Some(vec![publisher_id]), updated_since)
         .await
         .and_then(|data| {
-            specification_id.parse().map(|specification| {
-                MetadataRecord::new(work_id.to_string(), specification, vec![data])
+            new_record(
+                publisher_id.to_string(),
+                &specification_id,
+                query.platform.as_deref(),
+                data,
+            )
+        })
+        .map(|record| {
+            Json(PublisherReport {
+                specification_id,
+                publisher_id,
+                total_works: record.total_works(),
+                skipped_works: record.skipped_works(),
             })
         })
         .map_err(|e| e.into())
 }
 
 #[api_v2_operation(
-    summary = "Get a publisher's metadata record",
-    description = "Obtain a metadata record that adheres to a particular specification for all of a given publisher's works",
-    produces = "text/xml, text/csv",
+    summary = "Validate a work against a specification",
+    description = "List the missing or invalid fields that prevent a given work from being output in a particular specification",
     tags(Specifications)
 )]
-pub(crate) async fn by_publisher(
+pub(crate) async fn validate_by_work(
+    web::Path((specification_id, work_id)): web::Path<(String, Uuid)>,
+    web::Query(query): web::Query<SpecificationQuery>,
+    thoth_client: web::Data<ThothClient>,
+) -> Result<Json<ValidationReport>, Error> {
+    thoth_client
+        .get_work(work_id)
+        .await
+        .and_then(|data| {
+            new_record(
+                work_id.to_string(),
+                &specification_id,
+                query.platform.as_deref(),
+                vec![data],
+            )
+        })
+        .map(|record| Json(ValidationReport::new(specification_id, record.validate())))
+        .map_err(|e| e.into())
+}
+
+#[api_v2_operation(
+    summary = "Validate a publisher's works against a specification",
+    description = "List the missing or invalid fields that prevent each of a given publisher's works from being output in a particular specification",
+    tags(Specifications)
+)]
+pub(crate) async fn validate_by_publisher(
     web::Path((specification_id, publisher_id)): web::Path<(String, Uuid)>,
+    web::Query(query): web::Query<SpecificationQuery>,
     thoth_client: web::Data<ThothClient>,
-) -> Result<MetadataRecord<Vec<Work>>, Error> {
+) -> Result<Json<ValidationReport>, Error> {
+    let updated_since = query.updated_since()?;
     thoth_client
-        .get_works(Some(vec![publisher_id]))
+        .get_works(Some(vec![publisher_id]), updated_since)
         .await
         .and_then(|data| {
-            specification_id.parse().map(|specification| {
-                MetadataRecord::new(publisher_id.to_string(), specification, data)
+            new_record(
+                publisher_id.to_string(),
+                &specification_id,
+                query.platform.as_deref(),
+                data,
+            )
+        })
+        .map(|record| Json(ValidationReport::new(specification_id, record.validate())))
+        .map_err(|e| e.into())
+}
+
+#[api_v2_operation(
+    summary = "Get an imprint's metadata record",
+    description = "Obtain a metadata record that adheres to a particular specification for all of a given imprint's works",
+    produces = "text/xml, text/csv",
+    tags(Specifications)
+)]
+pub(crate) async fn by_imprint(
+    web::Path((specification_id, imprint_id)): web::Path<(String, Uuid)>,
+    web::Query(query): web::Query<SpecificationQuery>,
+    thoth_client: web::Data<ThothClient>,
+    cache: web::Data<RecordCache>,
+    schemas: web::Data<SchemaValidator>,
+) -> Result<GeneratedRecord, Error> {
+    let key = RecordCache::key(
+        &specification_id,
+        &format!("imprint/{}", imprint_id),
+        &query,
+    );
+    let generated = cached_record(&cache, key, async {
+        thoth_client
+            .get_imprint_works(imprint_id)
+            .await
+            .and_then(|data| {
+                new_record(
+                    imprint_id.to_string(),
+                    &specification_id,
+                    query.platform.as_deref(),
+                    data,
+                )
             })
+    })
+    .await?;
+    validated(generated, &specification_id, &query, &schemas).map_err(|e| e.into())
+}
+
+#[api_v2_operation(
+    summary = "Get a series' metadata record",
+    description = "Obtain a metadata record that adheres to a particular specification for all works issued in a given series",
+    produces = "text/xml, text/csv",
+    tags(Specifications)
+)]
+pub(crate) async fn by_series(
+    web::Path((specification_id, series_id)): web::Path<(String, Uuid)>,
+    web::Query(query): web::Query<SpecificationQuery>,
+    thoth_client: web::Data<ThothClient>,
+    cache: web::Data<RecordCache>,
+    schemas: web::Data<SchemaValidator>,
+) -> Result<GeneratedRecord, Error> {
+    let key = RecordCache::key(&specification_id, &format!("series/{}", series_id), &query);
+    let generated = cached_record(&cache, key, async {
+        thoth_client
+            .get_series_works(series_id)
+            .await
+            .and_then(|data| {
+                new_record(
+                    series_id.to_string(),
+                    &specification_id,
+                    query.platform.as_deref(),
+                    data,
+                )
+            })
+    })
+    .await?;
+    validated(generated, &specification_id, &query, &schemas).map_err(|e| e.into())
+}
+
+#[api_v2_operation(
+    summary = "Get a metadata record for a list of works",
+    description = "Obtain a metadata record that adheres to a particular specification for the given works (at most 100 at once)",
+    produces = "text/xml, text/csv",
+    tags(Specifications)
+)]
+pub(crate) async fn by_work_list(
+    web::Path(specification_id): web::Path<String>,
+    web::Query(query): web::Query<SpecificationQuery>,
+    web::Json(body): web::Json<WorkList>,
+    thoth_client: web::Data<ThothClient>,
+    schemas: web::Data<SchemaValidator>,
+) -> Result<GeneratedRecord, Error> {
+    thoth_client
+        .get_works_by_id(body.work_ids)
+        .await
+        .and_then(|data| {
+            new_record(
+                "works".to_string(),
+                &specification_id,
+                query.platform.as_deref(),
+                data,
+            )
         })
+        .and_then(|record| record.to_generated())
+        .and_then(|generated| validated(generated, &specification_id, &query, &schemas))
         .map_err(|e| e.into())
 }
@@ -1,7 +1,10 @@
 mod handler;
 pub(crate) mod model;
 
-use self::handler::{by_publisher, by_work, get_all, get_one};
+use self::handler::{
+    by_imprint, by_publisher, by_publisher_bundle, by_publisher_report, by_series, by_work,
+    by_work_list, get_all, get_one, validate_by_publisher, validate_by_work,
+};
 use paperclip::actix::web;
 
 pub(crate) fn route(cfg: &mut web::ServiceConfig) {
@@ -9,12 +12,39 @@ pub(crate) fn route(cfg: &mut web::ServiceConfig) {
         web::scope("/specifications")
             .service(web::resource("/").route(web::get().to(get_all)))
             .service(web::resource("/{specification_id}").route(web::get().to(get_one)))
+            .service(
+                web::resource("/publisher/{publisher_id}/bundle")
+                    .route(web::get().to(by_publisher_bundle)),
+            )
             .service(
                 web::resource("/{specification_id}/work/{work_id}").route(web::get().to(by_work)),
             )
+            .service(
+                web::resource("/{specification_id}/work/{work_id}/validate")
+                    .route(web::get().to(validate_by_work)),
+            )
             .service(
                 web::resource("/{specification_id}/publisher/{publisher_id}")
                     .route(web::get().to(by_publisher)),
+            )
+            .service(
+                web::resource("/{specification_id}/publisher/{publisher_id}/report")
+                    .route(web::get().to(by_publisher_report)),
+            )
+            .service(
+                web::resource("/{specification_id}/publisher/{publisher_id}/validate")
+                    .route(web::get().to(validate_by_publisher)),
+            )
+            .service(
+                web::resource("/{specification_id}/imprint/{imprint_id}")
+                    .route(web::get().to(by_imprint)),
+            )
+            .service(
+                web::resource("/{specification_id}/series/{series_id}")
+                    .route(web::get().to(by_series)),
+            )
+            .service(
+                web::resource("/{specification_id}/works").route(web::post().to(by_work_list)),
             ),
     );
 }
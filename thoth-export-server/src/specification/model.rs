@@ -1,11 +1,132 @@
+use chrono::{DateTime, Utc};
 use paperclip::actix::Apiv2Schema;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use thoth_api::model::Timestamp;
+use thoth_errors::ThothResult;
+use uuid::Uuid;
 
 #[derive(Clone, Serialize, Deserialize, Apiv2Schema)]
 #[serde(rename_all = "camelCase")]
-pub(crate) struct Specification<'a> {
-    pub(crate) id: &'a str,
-    pub(crate) name: &'a str,
-    pub(crate) format: &'a str,
-    pub(crate) accepted_by: Vec<&'a str>,
+pub(crate) struct Specification {
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) format: String,
+    pub(crate) accepted_by: Vec<String>,
+}
+
+#[derive(Default, Deserialize, Apiv2Schema)]
+pub(crate) struct SpecificationQuery {
+    /// ID of the platform that the record is destined for, if the specification varies by platform
+    pub(crate) platform: Option<String>,
+    /// RFC 3339 time: if set, publisher records only include works updated after it, along with
+    /// deletions of works removed since then, where the specification supports them
+    pub(crate) since: Option<String>,
+    /// If true, the record is checked against the specification's reference schema, and an
+    /// error listing the violations is returned instead if it does not adhere to it
+    pub(crate) validate: Option<bool>,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+pub(crate) struct BundleQuery {
+    /// Comma-separated list of the IDs of the specifications to include in the bundle
+    pub(crate) specifications: String,
+    /// RFC 3339 time: if set, records only include works updated after it, along with
+    /// deletions of works removed since then, where the specification supports them
+    pub(crate) since: Option<String>,
+}
+
+#[derive(Deserialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkList {
+    pub(crate) work_ids: Vec<Uuid>,
+}
+
+impl SpecificationQuery {
+    pub(crate) fn updated_since(&self) -> ThothResult<Option<Timestamp>> {
+        self.since.as_deref().map(Timestamp::from_str).transpose()
+    }
+
+    pub(crate) fn validates(&self) -> bool {
+        self.validate.unwrap_or(false)
+    }
+}
+
+impl BundleQuery {
+    pub(crate) fn specification_ids(&self) -> Vec<&str> {
+        self.specifications
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .collect()
+    }
+
+    pub(crate) fn updated_since(&self) -> ThothResult<Option<Timestamp>> {
+        self.since.as_deref().map(Timestamp::from_str).transpose()
+    }
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SkippedWork {
+    pub(crate) work_id: Uuid,
+    pub(crate) title: String,
+    pub(crate) reason: String,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PublisherReport {
+    pub(crate) specification_id: String,
+    pub(crate) publisher_id: Uuid,
+    pub(crate) total_works: usize,
+    pub(crate) skipped_works: Vec<SkippedWork>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WorkValidation {
+    pub(crate) work_id: Uuid,
+    pub(crate) title: String,
+    pub(crate) valid: bool,
+    /// Missing or invalid fields that prevent the work from being output in the specification
+    pub(crate) errors: Vec<String>,
+}
+
+#[derive(Serialize, Apiv2Schema)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ValidationReport {
+    pub(crate) specification_id: String,
+    pub(crate) valid: bool,
+    pub(crate) works: Vec<WorkValidation>,
+}
+
+impl ValidationReport {
+    pub(crate) fn new(specification_id: String, works: Vec<WorkValidation>) -> Self {
+        ValidationReport {
+            specification_id,
+            valid: works.iter().all(|work| work.valid),
+            works,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BundleEntry {
+    pub(crate) specification_id: String,
+    /// Name of the record within the bundle, unless it could not be generated
+    pub(crate) file_name: Option<String>,
+    pub(crate) total_works: usize,
+    pub(crate) skipped_works: Vec<SkippedWork>,
+    /// Reason the record could not be generated, if it is missing from the bundle
+    pub(crate) error: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct BundleManifest {
+    pub(crate) id: String,
+    pub(crate) generated_at: DateTime<Utc>,
+    pub(crate) records: Vec<BundleEntry>,
 }
@@ -0,0 +1,65 @@
+use actix_web::http::header::ACCEPT;
+use actix_web::{Error, HttpRequest};
+use paperclip::actix::{api_v2_operation, web};
+use std::str::FromStr;
+use thoth_client::ThothClient;
+use thoth_errors::ThothError;
+use uuid::Uuid;
+
+use super::model::{Representation, WorkQuery, REPRESENTATIONS};
+use crate::cache::RecordCache;
+use crate::data::Registry;
+use crate::record::{MetadataRecord, MetadataSpecification, NegotiatedRecord};
+use crate::specification::model::SpecificationQuery;
+
+#[api_v2_operation(
+    summary = "Get a work's metadata",
+    description = "Obtain a work's metadata in the format requested in the Accept header, or in the format query parameter, which takes precedence: JSON-LD (application/ld+json, the default), ONIX 3.0 (application/onix+xml), MARC 21 XML (application/marc+xml), BibTeX (application/x-bibtex) or CSV (text/csv). Every format available is listed in the Link header.",
+    produces = "application/ld+json, application/onix+xml, application/marc+xml, application/x-bibtex, text/csv",
+    tags(Works)
+)]
+pub(crate) async fn get_one(
+    req: HttpRequest,
+    web::Path(work_id): web::Path<Uuid>,
+    web::Query(query): web::Query<WorkQuery>,
+    thoth_client: web::Data<ThothClient>,
+    registry: web::Data<Registry>,
+    cache: web::Data<RecordCache>,
+) -> Result<NegotiatedRecord, Error> {
+    let work_url = registry.work_url(&work_id);
+    let links = REPRESENTATIONS
+        .iter()
+        .map(|representation| representation.link(&work_url))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let accept = req
+        .headers()
+        .get(ACCEPT)
+        .map(|accept| accept.to_str().unwrap_or_default());
+    let representation = match Representation::negotiate(query.format.as_deref(), accept) {
+        Some(representation) => representation,
+        None => {
+            let requested = query.format.as_deref().or(accept).unwrap_or_default();
+            return Ok(NegotiatedRecord::new(
+                Err(ThothError::NotAcceptable(requested.to_string())),
+                links,
+            ));
+        }
+    };
+
+    // Shared with the specification's own endpoint for the same work
+    let key = RecordCache::key(
+        representation.specification_id,
+        &format!("work/{}", work_id),
+        &SpecificationQuery::default(),
+    );
+    if let Some(generated) = cache.get(&key) {
+        return Ok(NegotiatedRecord::new(Ok(generated), links));
+    }
+    let specification = MetadataSpecification::from_str(representation.specification_id)?;
+    let data = thoth_client.get_work(work_id).await?;
+    let generated =
+        MetadataRecord::new(work_id.to_string(), specification, vec![data]).to_generated()?;
+    cache.insert(key, generated.clone());
+    Ok(NegotiatedRecord::new(Ok(generated), links))
+}
@@ -0,0 +1,11 @@
+mod handler;
+pub(crate) mod model;
+
+use self::handler::get_one;
+use paperclip::actix::web;
+
+pub(crate) fn route(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/works").service(web::resource("/{work_id}").route(web::get().to(get_one))),
+    );
+}
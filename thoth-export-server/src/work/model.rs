@@ -0,0 +1,214 @@
+use paperclip::actix::Apiv2Schema;
+use serde::Deserialize;
+
+#[derive(Deserialize, Apiv2Schema)]
+pub(crate) struct WorkQuery {
+    /// ID or media type of the format to return, overriding the `Accept` header
+    pub(crate) format: Option<String>,
+}
+
+/// A format in which a work's metadata is served at its canonical URL
+pub(crate) struct Representation {
+    pub(crate) format_id: &'static str,
+    pub(crate) media_type: &'static str,
+    pub(crate) specification_id: &'static str,
+}
+
+/// Representations available at a work's canonical URL, in order of preference when the client
+/// accepts more than one of them equally
+pub(crate) const REPRESENTATIONS: &[Representation] = &[
+    Representation {
+        format_id: "jsonld",
+        media_type: "application/ld+json",
+        specification_id: "jsonld::schema_org",
+    },
+    Representation {
+        format_id: "onix_3.0",
+        media_type: "application/onix+xml",
+        specification_id: "onix_3.0::thoth",
+    },
+    Representation {
+        format_id: "marc21xml",
+        media_type: "application/marc+xml",
+        specification_id: "marc21xml::thoth",
+    },
+    Representation {
+        format_id: "bibtex",
+        media_type: "application/x-bibtex",
+        specification_id: "bibtex::thoth",
+    },
+    Representation {
+        format_id: "csv",
+        media_type: "text/csv",
+        specification_id: "csv::thoth",
+    },
+];
+
+/// A media range from an `Accept` header, e.g. `text/*;q=0.5`
+struct MediaRange {
+    range: String,
+    quality: f32,
+}
+
+impl MediaRange {
+    fn parse(value: &str) -> Option<Self> {
+        let mut parts = value.split(';').map(str::trim);
+        let range = parts.next().filter(|range| range.contains('/'))?;
+        let quality = parts
+            .filter_map(|param| param.strip_prefix("q="))
+            .find_map(|q| q.parse::<f32>().ok())
+            .unwrap_or(1.0);
+        Some(MediaRange {
+            range: range.to_ascii_lowercase(),
+            quality,
+        })
+    }
+
+    fn matches(&self, media_type: &str) -> bool {
+        match self.range.as_str() {
+            "*/*" => true,
+            range => match range.strip_suffix('*') {
+                Some(prefix) => media_type.starts_with(prefix),
+                None => range == media_type,
+            },
+        }
+    }
+
+    /// Ranges naming a media type outrank those with a wildcard subtype, which in turn outrank `*/*`
+    fn specificity(&self) -> usize {
+        match self.range.as_str() {
+            "*/*" => 0,
+            range if range.ends_with("/*") => 1,
+            _ => 2,
+        }
+    }
+}
+
+impl Representation {
+    /// Representation named by the `format` parameter if given, otherwise the one the client
+    /// prefers in its `Accept` header. With neither, the first representation is served.
+    pub(crate) fn negotiate(format: Option<&str>, accept: Option<&str>) -> Option<&'static Self> {
+        if let Some(format) = format {
+            return REPRESENTATIONS.iter().find(|representation| {
+                representation.format_id == format
+                    || representation.media_type.eq_ignore_ascii_case(format)
+            });
+        }
+        let ranges: Vec<MediaRange> = accept
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(MediaRange::parse)
+            .collect();
+        if ranges.is_empty() {
+            return REPRESENTATIONS.first();
+        }
+        let mut preferred = None;
+        let mut preferred_quality = 0.0;
+        for representation in REPRESENTATIONS {
+            let quality = representation.quality(&ranges);
+            if quality > preferred_quality {
+                preferred = Some(representation);
+                preferred_quality = quality;
+            }
+        }
+        preferred
+    }
+
+    /// Quality the client gives this representation: that of the most specific range matching it
+    fn quality(&self, ranges: &[MediaRange]) -> f32 {
+        ranges
+            .iter()
+            .filter(|range| range.matches(self.media_type))
+            .max_by_key(|range| range.specificity())
+            .map(|range| range.quality)
+            .unwrap_or(0.0)
+    }
+
+    /// `Link` header entry pointing to this representation of the work at `work_url`
+    pub(crate) fn link(&self, work_url: &str) -> String {
+        format!(
+            r#"<{}?format={}>; rel="alternate"; type="{}""#,
+            work_url, self.format_id, self.media_type
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::record::MetadataSpecification;
+    use std::str::FromStr;
+
+    fn negotiated(format: Option<&str>, accept: Option<&str>) -> Option<&'static str> {
+        Representation::negotiate(format, accept).map(|representation| representation.format_id)
+    }
+
+    #[test]
+    fn test_representations_use_known_specifications() {
+        for representation in REPRESENTATIONS {
+            assert!(MetadataSpecification::from_str(representation.specification_id).is_ok());
+            assert!(representation
+                .specification_id
+                .starts_with(representation.format_id));
+        }
+    }
+
+    #[test]
+    fn test_negotiate_accept() {
+        assert_eq!(
+            negotiated(None, Some("application/x-bibtex")),
+            Some("bibtex")
+        );
+        assert_eq!(
+            negotiated(None, Some("text/html, application/marc+xml;q=0.9")),
+            Some("marc21xml")
+        );
+        assert_eq!(
+            negotiated(None, Some("text/csv;q=0.5, application/ld+json")),
+            Some("jsonld")
+        );
+        assert_eq!(
+            negotiated(None, Some("application/*;q=0.8, text/csv;q=0.9")),
+            Some("csv")
+        );
+        assert_eq!(
+            negotiated(None, Some("APPLICATION/ONIX+XML; charset=utf-8")),
+            Some("onix_3.0")
+        );
+    }
+
+    #[test]
+    fn test_negotiate_wildcards() {
+        assert_eq!(negotiated(None, None), Some("jsonld"));
+        assert_eq!(negotiated(None, Some("*/*")), Some("jsonld"));
+        assert_eq!(negotiated(None, Some("text/*")), Some("csv"));
+        assert_eq!(
+            negotiated(None, Some("*/*, application/ld+json;q=0")),
+            Some("onix_3.0")
+        );
+    }
+
+    #[test]
+    fn test_negotiate_unacceptable() {
+        assert_eq!(negotiated(None, Some("text/html")), None);
+        assert_eq!(negotiated(None, Some("application/ld+json;q=0")), None);
+        assert_eq!(negotiated(Some("pdf"), Some("*/*")), None);
+    }
+
+    #[test]
+    fn test_negotiate_format_overrides_accept() {
+        assert_eq!(negotiated(Some("bibtex"), Some("text/csv")), Some("bibtex"));
+        assert_eq!(
+            negotiated(Some("application/marc+xml"), Some("text/html")),
+            Some("marc21xml")
+        );
+    }
+
+    #[test]
+    fn test_link() {
+        assert_eq!(
+            REPRESENTATIONS[3].link("https://export.thoth.pub/works/1"),
+            r#"<https://export.thoth.pub/works/1?format=bibtex>; rel="alternate"; type="application/x-bibtex""#
+        );
+    }
+}
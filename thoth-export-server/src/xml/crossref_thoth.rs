@@ -0,0 +1,990 @@
+use chrono::Utc;
+use std::collections::HashMap;
+use std::io::Write;
+use thoth_api::model::{DOI_DOMAIN, ORCID_DOMAIN, ROR_DOMAIN};
+use thoth_client::{
+    ContributionType, PublicationType, Work, WorkContributions, WorkFundings, WorkIssues,
+    WorkPublications, WorkRelations, WorkType,
+};
+use xml::writer::{EventWriter, XmlEvent};
+
+use super::{write_element_block, XmlSpecification};
+use crate::record::RequiredFields;
+use crate::xml::{write_full_element_block, XmlElementBlock};
+use thoth_errors::{ThothError, ThothResult};
+
+pub struct CrossrefThoth {}
+
+const CROSSREF_NS: &str = "http://www.crossref.org/schema/5.3.1";
+const CROSSREF_SCHEMA_LOCATION: &str =
+    "http://www.crossref.org/schema/5.3.1 https://www.crossref.org/schemas/crossref5.3.1.xsd";
+
+impl XmlSpecification for CrossrefThoth {
+    fn handle_event<W: Write>(w: &mut EventWriter<W>, works: &[Work]) -> ThothResult<()> {
+        let mut ns_map: HashMap<String, String> = HashMap::new();
+        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+
+        ns_map.insert("".to_string(), CROSSREF_NS.to_string());
+        ns_map.insert(
+            "xsi".to_string(),
+            "http://www.w3.org/2001/XMLSchema-instance".to_string(),
+        );
+        ns_map.insert(
+            "jats".to_string(),
+            "http://www.ncbi.nlm.nih.gov/JATS1".to_string(),
+        );
+        ns_map.insert(
+            "fr".to_string(),
+            "http://www.crossref.org/fundref.xsd".to_string(),
+        );
+        ns_map.insert(
+            "ai".to_string(),
+            "http://www.crossref.org/AccessIndicators.xsd".to_string(),
+        );
+        attr_map.insert("version", "5.3.1");
+        attr_map.insert("xsi:schemaLocation", CROSSREF_SCHEMA_LOCATION);
+
+        // The registrant is taken from the first work, as the header can only name one
+        let registrant = match works.first() {
+            Some(work) => work.imprint.publisher.publisher_name.clone(),
+            None => {
+                return Err(ThothError::IncompleteMetadataRecord(
+                    "crossref::thoth".to_string(),
+                    "Not enough data".to_string(),
+                ))
+            }
+        };
+        // Crossref requires a unique batch ID, and a timestamp that increases with each deposit
+        let timestamp = Utc::now().format("%Y%m%d%H%M%S%3f").to_string();
+
+        write_full_element_block("doi_batch", Some(ns_map), Some(attr_map), w, |w| {
+            write_element_block("head", w, |w| {
+                write_element_block("doi_batch_id", w, |w| {
+                    w.write(XmlEvent::Characters(&format!("thoth_{}", timestamp)))
+                        .map_err(|e| e.into())
+                })?;
+                write_element_block("timestamp", w, |w| {
+                    w.write(XmlEvent::Characters(&timestamp))
+                        .map_err(|e| e.into())
+                })?;
+                write_element_block("depositor", w, |w| {
+                    write_element_block("depositor_name", w, |w| {
+                        w.write(XmlEvent::Characters("Thoth")).map_err(|e| e.into())
+                    })?;
+                    write_element_block("email_address", w, |w| {
+                        w.write(XmlEvent::Characters("info@thoth.pub"))
+                            .map_err(|e| e.into())
+                    })
+                })?;
+                write_element_block("registrant", w, |w| {
+                    w.write(XmlEvent::Characters(&registrant))
+                        .map_err(|e| e.into())
+                })
+            })?;
+
+            write_element_block("body", w, |w| match works.len() {
+                1 => XmlElementBlock::<CrossrefThoth>::xml_element(works.first().unwrap(), w),
+                _ => {
+                    for work in works.iter() {
+                        XmlElementBlock::<CrossrefThoth>::xml_element(work, w).ok();
+                    }
+                    Ok(())
+                }
+            })
+        })
+    }
+}
+
+impl RequiredFields for CrossrefThoth {
+    const SPECIFICATION_ID: &'static str = "crossref::thoth";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        // Chapters are deposited as content items of their parent book
+        if work.work_type == WorkType::BOOK_CHAPTER {
+            missing.push("Chapters must be deposited as part of their parent book");
+            return missing;
+        }
+        if work.doi.is_none() {
+            missing.push("Missing DOI");
+        }
+        if work.publication_date.is_none() {
+            missing.push("Missing Publication Date");
+        }
+        if work.landing_page.is_none() {
+            missing.push("Missing Landing Page");
+        }
+        missing
+    }
+}
+
+impl XmlElementBlock<CrossrefThoth> for Work {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        CrossrefThoth::check_required_fields(self)?;
+        let book_type = match &self.work_type {
+            WorkType::MONOGRAPH | WorkType::TEXTBOOK => "monograph",
+            WorkType::EDITED_BOOK => "edited_book",
+            WorkType::BOOK_SET | WorkType::JOURNAL_ISSUE => "other",
+            // Chapters are deposited as content items of their parent book
+            WorkType::BOOK_CHAPTER => {
+                return Err(ThothError::IncompleteMetadataRecord(
+                    "crossref::thoth".to_string(),
+                    "Chapters must be deposited as part of their parent book".to_string(),
+                ))
+            }
+            WorkType::Other(_) => unreachable!(),
+        };
+        let doi = self.doi.as_ref().ok_or_else(|| {
+            ThothError::IncompleteMetadataRecord(
+                "crossref::thoth".to_string(),
+                "Missing DOI".to_string(),
+            )
+        })?;
+        let publication_date = self.publication_date.ok_or_else(|| {
+            ThothError::IncompleteMetadataRecord(
+                "crossref::thoth".to_string(),
+                "Missing Publication Date".to_string(),
+            )
+        })?;
+        let landing_page = self.landing_page.as_ref().ok_or_else(|| {
+            ThothError::IncompleteMetadataRecord(
+                "crossref::thoth".to_string(),
+                "Missing Landing Page".to_string(),
+            )
+        })?;
+
+        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+        attr_map.insert("book_type", book_type);
+        write_full_element_block("book", None, Some(attr_map), w, |w| {
+            // Books in a series must be deposited with their series metadata
+            let (metadata_element, issue) = match self.issues.first() {
+                Some(issue) => ("book_series_metadata", Some(issue)),
+                None => ("book_metadata", None),
+            };
+            write_element_block(metadata_element, w, |w| {
+                if let Some(issue) = issue {
+                    XmlElementBlock::<CrossrefThoth>::xml_element(issue, w)?;
+                }
+                write_contributors(&self.contributions, w)?;
+                write_titles(&self.title, &self.subtitle, w)?;
+                if let Some(labstract) =
+                    self.long_abstract.as_ref().or(self.short_abstract.as_ref())
+                {
+                    write_element_block("jats:abstract", w, |w| {
+                        write_element_block("jats:p", w, |w| {
+                            w.write(XmlEvent::Characters(labstract))
+                                .map_err(|e| e.into())
+                        })
+                    })?;
+                }
+                if let Some(issue) = issue {
+                    write_element_block("volume", w, |w| {
+                        w.write(XmlEvent::Characters(&issue.issue_ordinal.to_string()))
+                            .map_err(|e| e.into())
+                    })?;
+                }
+                write_element_block("edition_number", w, |w| {
+                    w.write(XmlEvent::Characters(&self.edition.to_string()))
+                        .map_err(|e| e.into())
+                })?;
+                let mut attr_map: HashMap<&str, &str> = HashMap::new();
+                attr_map.insert("media_type", "online");
+                write_full_element_block("publication_date", None, Some(attr_map), w, |w| {
+                    write_element_block("month", w, |w| {
+                        w.write(XmlEvent::Characters(
+                            &publication_date.format("%m").to_string(),
+                        ))
+                        .map_err(|e| e.into())
+                    })?;
+                    write_element_block("day", w, |w| {
+                        w.write(XmlEvent::Characters(
+                            &publication_date.format("%d").to_string(),
+                        ))
+                        .map_err(|e| e.into())
+                    })?;
+                    write_element_block("year", w, |w| {
+                        w.write(XmlEvent::Characters(
+                            &publication_date.format("%Y").to_string(),
+                        ))
+                        .map_err(|e| e.into())
+                    })
+                })?;
+                let publications: Vec<&WorkPublications> = self
+                    .publications
+                    .iter()
+                    .filter(|p| p.isbn.is_some())
+                    .collect();
+                if publications.is_empty() {
+                    let mut attr_map: HashMap<&str, &str> = HashMap::new();
+                    attr_map.insert(
+                        "reason",
+                        if issue.is_some() {
+                            "simple_series"
+                        } else {
+                            "monograph"
+                        },
+                    );
+                    write_full_element_block("noisbn", None, Some(attr_map), w, |_w| Ok(()))?;
+                } else {
+                    for publication in publications {
+                        XmlElementBlock::<CrossrefThoth>::xml_element(publication, w)?;
+                    }
+                }
+                write_element_block("publisher", w, |w| {
+                    write_element_block("publisher_name", w, |w| {
+                        w.write(XmlEvent::Characters(&self.imprint.publisher.publisher_name))
+                            .map_err(|e| e.into())
+                    })?;
+                    if let Some(place) = &self.place {
+                        write_element_block("publisher_place", w, |w| {
+                            w.write(XmlEvent::Characters(place)).map_err(|e| e.into())
+                        })?;
+                    }
+                    Ok(())
+                })?;
+                if !self.fundings.is_empty() {
+                    let mut attr_map: HashMap<&str, &str> = HashMap::new();
+                    attr_map.insert("name", "fundref");
+                    write_full_element_block("fr:program", None, Some(attr_map), w, |w| {
+                        for funding in &self.fundings {
+                            XmlElementBlock::<CrossrefThoth>::xml_element(funding, w)?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                if let Some(license) = &self.license {
+                    let mut attr_map: HashMap<&str, &str> = HashMap::new();
+                    attr_map.insert("name", "AccessIndicators");
+                    write_full_element_block("ai:program", None, Some(attr_map), w, |w| {
+                        // vor Version of record
+                        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+                        attr_map.insert("applies_to", "vor");
+                        write_full_element_block("ai:license_ref", None, Some(attr_map), w, |w| {
+                            w.write(XmlEvent::Characters(license)).map_err(|e| e.into())
+                        })
+                    })?;
+                }
+                write_doi_data(&doi.to_string(), landing_page, w)
+            })?;
+            let mut relations: Vec<&WorkRelations> = self.relations.iter().collect();
+            relations.sort_by_key(|r| r.relation_ordinal);
+            for relation in relations {
+                XmlElementBlock::<CrossrefThoth>::xml_element(relation, w)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl XmlElementBlock<CrossrefThoth> for WorkRelations {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let chapter = &self.related_work;
+        // A content item cannot be deposited without its own DOI and landing page
+        let (doi, landing_page) = match (&chapter.doi, &chapter.landing_page) {
+            (Some(doi), Some(landing_page)) => (doi, landing_page),
+            _ => return Ok(()),
+        };
+        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+        attr_map.insert("component_type", "chapter");
+        write_full_element_block("content_item", None, Some(attr_map), w, |w| {
+            let contributions: Vec<WorkContributions> = chapter
+                .contributions
+                .iter()
+                .cloned()
+                .map(WorkContributions::from)
+                .collect();
+            write_contributors(&contributions, w)?;
+            write_titles(&chapter.title, &chapter.subtitle, w)?;
+            write_element_block("component_number", w, |w| {
+                w.write(XmlEvent::Characters(&self.relation_ordinal.to_string()))
+                    .map_err(|e| e.into())
+            })?;
+            if let Some(first_page) = &chapter.first_page {
+                write_element_block("pages", w, |w| {
+                    write_element_block("first_page", w, |w| {
+                        w.write(XmlEvent::Characters(first_page))
+                            .map_err(|e| e.into())
+                    })?;
+                    if let Some(last_page) = &chapter.last_page {
+                        write_element_block("last_page", w, |w| {
+                            w.write(XmlEvent::Characters(last_page))
+                                .map_err(|e| e.into())
+                        })?;
+                    }
+                    Ok(())
+                })?;
+            }
+            write_doi_data(&doi.to_string(), landing_page, w)
+        })
+    }
+}
+
+impl XmlElementBlock<CrossrefThoth> for WorkIssues {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_element_block("series_metadata", w, |w| {
+            write_element_block("titles", w, |w| {
+                write_element_block("title", w, |w| {
+                    w.write(XmlEvent::Characters(&self.series.series_name))
+                        .map_err(|e| e.into())
+                })
+            })?;
+            let mut attr_map: HashMap<&str, &str> = HashMap::new();
+            attr_map.insert("media_type", "electronic");
+            write_full_element_block("issn", None, Some(attr_map), w, |w| {
+                w.write(XmlEvent::Characters(&self.series.issn_digital))
+                    .map_err(|e| e.into())
+            })?;
+            let mut attr_map: HashMap<&str, &str> = HashMap::new();
+            attr_map.insert("media_type", "print");
+            write_full_element_block("issn", None, Some(attr_map), w, |w| {
+                w.write(XmlEvent::Characters(&self.series.issn_print))
+                    .map_err(|e| e.into())
+            })
+        })
+    }
+}
+
+impl XmlElementBlock<CrossrefThoth> for WorkPublications {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        if let Some(isbn) = &self.isbn {
+            let media_type = match &self.publication_type {
+                PublicationType::PAPERBACK | PublicationType::HARDBACK => "print",
+                PublicationType::PDF
+                | PublicationType::HTML
+                | PublicationType::XML
+                | PublicationType::EPUB
+                | PublicationType::MOBI => "electronic",
+                PublicationType::Other(_) => unreachable!(),
+            };
+            let mut attr_map: HashMap<&str, &str> = HashMap::new();
+            attr_map.insert("media_type", media_type);
+            write_full_element_block("isbn", None, Some(attr_map), w, |w| {
+                w.write(XmlEvent::Characters(&isbn.to_string()))
+                    .map_err(|e| e.into())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl XmlElementBlock<CrossrefThoth> for WorkFundings {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+        attr_map.insert("name", "fundgroup");
+        write_full_element_block("fr:assertion", None, Some(attr_map), w, |w| {
+            let mut attr_map: HashMap<&str, &str> = HashMap::new();
+            attr_map.insert("name", "funder_name");
+            write_full_element_block("fr:assertion", None, Some(attr_map), w, |w| {
+                w.write(XmlEvent::Characters(&self.institution.institution_name))?;
+                // Funder Registry DOIs are preferred, and ROR IDs accepted in their absence
+                let identifier = match (&self.institution.institution_doi, &self.institution.ror) {
+                    (Some(doi), _) => Some(("funder_identifier", format!("{}{}", DOI_DOMAIN, doi))),
+                    (None, Some(ror)) => Some(("ror", format!("{}{}", ROR_DOMAIN, ror))),
+                    (None, None) => None,
+                };
+                if let Some((name, value)) = &identifier {
+                    let mut attr_map: HashMap<&str, &str> = HashMap::new();
+                    attr_map.insert("name", name);
+                    write_full_element_block("fr:assertion", None, Some(attr_map), w, |w| {
+                        w.write(XmlEvent::Characters(value)).map_err(|e| e.into())
+                    })?;
+                }
+                Ok(())
+            })?;
+            if let Some(grant_number) = &self.grant_number {
+                let mut attr_map: HashMap<&str, &str> = HashMap::new();
+                attr_map.insert("name", "award_number");
+                write_full_element_block("fr:assertion", None, Some(attr_map), w, |w| {
+                    w.write(XmlEvent::Characters(grant_number))
+                        .map_err(|e| e.into())
+                })?;
+            }
+            Ok(())
+        })
+    }
+}
+
+fn write_contributors<W: Write>(
+    contributions: &[WorkContributions],
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    let mut contributions: Vec<&WorkContributions> = contributions
+        .iter()
+        .filter(|c| contributor_role(&c.contribution_type).is_some())
+        .collect();
+    if contributions.is_empty() {
+        return Ok(());
+    }
+    contributions.sort_by_key(|c| c.contribution_ordinal);
+    write_element_block("contributors", w, |w| {
+        for (position, contribution) in contributions.iter().enumerate() {
+            write_person_name(contribution, position == 0, w)?;
+        }
+        Ok(())
+    })
+}
+
+fn write_titles<W: Write>(
+    title: &str,
+    subtitle: &Option<String>,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    write_element_block("titles", w, |w| {
+        write_element_block("title", w, |w| {
+            w.write(XmlEvent::Characters(title)).map_err(|e| e.into())
+        })?;
+        if let Some(subtitle) = subtitle {
+            write_element_block("subtitle", w, |w| {
+                w.write(XmlEvent::Characters(subtitle))
+                    .map_err(|e| e.into())
+            })?;
+        }
+        Ok(())
+    })
+}
+
+fn write_doi_data<W: Write>(doi: &str, resource: &str, w: &mut EventWriter<W>) -> ThothResult<()> {
+    write_element_block("doi_data", w, |w| {
+        write_element_block("doi", w, |w| {
+            w.write(XmlEvent::Characters(doi)).map_err(|e| e.into())
+        })?;
+        write_element_block("resource", w, |w| {
+            w.write(XmlEvent::Characters(resource))
+                .map_err(|e| e.into())
+        })
+    })
+}
+
+fn write_person_name<W: Write>(
+    contribution: &WorkContributions,
+    first: bool,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    // Attributes are written in a fixed order, rather than via `write_full_element_block`,
+    // so that `sequence` always precedes `contributor_role`
+    w.write(
+        XmlEvent::start_element("person_name")
+            .attr("sequence", if first { "first" } else { "additional" })
+            .attr(
+                "contributor_role",
+                contributor_role(&contribution.contribution_type).unwrap(),
+            ),
+    )?;
+    if let Some(first_name) = &contribution.first_name {
+        write_element_block("given_name", w, |w| {
+            w.write(XmlEvent::Characters(first_name))
+                .map_err(|e| e.into())
+        })?;
+    }
+    write_element_block("surname", w, |w| {
+        w.write(XmlEvent::Characters(&contribution.last_name))
+            .map_err(|e| e.into())
+    })?;
+    if !contribution.affiliations.is_empty() {
+        let mut affiliations = contribution.affiliations.clone();
+        affiliations.sort_by_key(|a| a.affiliation_ordinal);
+        write_element_block("affiliations", w, |w| {
+            for affiliation in &affiliations {
+                write_element_block("institution", w, |w| {
+                    write_element_block("institution_name", w, |w| {
+                        w.write(XmlEvent::Characters(
+                            &affiliation.institution.institution_name,
+                        ))
+                        .map_err(|e| e.into())
+                    })?;
+                    if let Some(ror) = &affiliation.institution.ror {
+                        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+                        attr_map.insert("type", "ror");
+                        write_full_element_block("institution_id", None, Some(attr_map), w, |w| {
+                            w.write(XmlEvent::Characters(&format!("{}{}", ROR_DOMAIN, ror)))
+                                .map_err(|e| e.into())
+                        })?;
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        })?;
+    }
+    if let Some(orcid) = &contribution.contributor.orcid {
+        write_element_block("ORCID", w, |w| {
+            w.write(XmlEvent::Characters(&format!("{}{}", ORCID_DOMAIN, orcid)))
+                .map_err(|e| e.into())
+        })?;
+    }
+    w.write(XmlEvent::end_element()).map_err(|e| e.into())
+}
+
+fn contributor_role(contribution_type: &ContributionType) -> Option<&'static str> {
+    // Crossref only accepts a restricted set of contributor roles
+    match contribution_type {
+        ContributionType::AUTHOR => Some("author"),
+        ContributionType::EDITOR => Some("editor"),
+        ContributionType::TRANSLATOR => Some("translator"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Testing note: XML nodes cannot be guaranteed to be output in the same order every time
+    // We therefore rely on `assert!(contains)` rather than `assert_eq!`
+    use super::*;
+    use std::str::FromStr;
+    use thoth_api::model::Doi;
+    use thoth_api::model::Isbn;
+    use thoth_api::model::Orcid;
+    use thoth_api::model::Ror;
+    use thoth_client::{
+        SeriesType, WorkContributionsAffiliations, WorkContributionsAffiliationsInstitution,
+        WorkContributionsContributor, WorkFundingsInstitution, WorkImprint, WorkImprintPublisher,
+        WorkIssuesSeries, WorkRelationsRelatedWork, WorkRelationsRelatedWorkContributions,
+        WorkRelationsRelatedWorkContributionsContributor, WorkStatus,
+    };
+    use uuid::Uuid;
+
+    fn generate_test_output(input: &impl XmlElementBlock<CrossrefThoth>) -> String {
+        // Helper function based on `XmlSpecification::generate`
+        let mut buffer = Vec::new();
+        let mut writer = xml::writer::EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(&mut buffer);
+        let wrapped_output = XmlElementBlock::<CrossrefThoth>::xml_element(input, &mut writer)
+            .map(|_| buffer)
+            .and_then(|xml| {
+                String::from_utf8(xml)
+                    .map_err(|_| ThothError::InternalError("Could not parse XML".to_string()))
+            });
+        assert!(wrapped_output.is_ok());
+        wrapped_output.unwrap()
+    }
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            copyright_holder: "Author 1; Author 2".to_string(),
+            short_abstract: None,
+            long_abstract: Some("Lorem ipsum dolor sit amet".to_string()),
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: Some(334),
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![],
+            contributions: vec![
+                WorkContributions {
+                    contribution_type: ContributionType::EDITOR,
+                    first_name: None,
+                    last_name: "Editor".to_string(),
+                    full_name: "Editor".to_string(),
+                    main_contribution: true,
+                    biography: None,
+                    contribution_ordinal: 2,
+                    contributor: WorkContributionsContributor { orcid: None },
+                    affiliations: vec![],
+                },
+                WorkContributions {
+                    contribution_type: ContributionType::AUTHOR,
+                    first_name: Some("Author".to_string()),
+                    last_name: "1".to_string(),
+                    full_name: "Author 1".to_string(),
+                    main_contribution: true,
+                    biography: None,
+                    contribution_ordinal: 1,
+                    contributor: WorkContributionsContributor {
+                        orcid: Some(
+                            Orcid::from_str("https://orcid.org/0000-0002-0000-0001").unwrap(),
+                        ),
+                    },
+                    affiliations: vec![WorkContributionsAffiliations {
+                        position: None,
+                        affiliation_ordinal: 1,
+                        institution: WorkContributionsAffiliationsInstitution {
+                            institution_name: "University of Life".to_string(),
+                            ror: Some(Ror::from_str("https://ror.org/0abcdef12").unwrap()),
+                        },
+                    }],
+                },
+                WorkContributions {
+                    contribution_type: ContributionType::ILUSTRATOR,
+                    first_name: Some("Illustrator".to_string()),
+                    last_name: "1".to_string(),
+                    full_name: "Illustrator 1".to_string(),
+                    main_contribution: false,
+                    biography: None,
+                    contribution_ordinal: 3,
+                    contributor: WorkContributionsContributor { orcid: None },
+                    affiliations: vec![],
+                },
+            ],
+            languages: vec![],
+            publications: vec![
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-DDDD-000000000004").unwrap(),
+                    publication_type: PublicationType::PDF,
+                    isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                    prices: vec![],
+                    locations: vec![],
+                },
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-DDDD-000000000005").unwrap(),
+                    publication_type: PublicationType::PAPERBACK,
+                    isbn: Some(Isbn::from_str("978-1-4028-9462-6").unwrap()),
+                    prices: vec![],
+                    locations: vec![],
+                },
+            ],
+            subjects: vec![],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    #[test]
+    fn test_crossref_thoth_publications() {
+        let mut test_publication = WorkPublications {
+            publication_id: Uuid::from_str("00000000-0000-0000-DDDD-000000000004").unwrap(),
+            publication_type: PublicationType::EPUB,
+            isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+            prices: vec![],
+            locations: vec![],
+        };
+
+        // Test standard output
+        let output = generate_test_output(&test_publication);
+        assert!(output.contains(r#"<isbn media_type="electronic">978-3-16-148410-0</isbn>"#));
+
+        // Print publications are marked as such
+        test_publication.publication_type = PublicationType::HARDBACK;
+        let output = generate_test_output(&test_publication);
+        assert!(output.contains(r#"<isbn media_type="print">978-3-16-148410-0</isbn>"#));
+
+        // No ISBN supplied: nothing is output
+        test_publication.isbn = None;
+        let output = generate_test_output(&test_publication);
+        assert_eq!(output, "".to_string());
+    }
+
+    #[test]
+    fn test_crossref_thoth_issues() {
+        let test_issue = WorkIssues {
+            issue_ordinal: 1,
+            series: WorkIssuesSeries {
+                series_type: SeriesType::BOOK_SERIES,
+                series_name: "Name of series".to_string(),
+                issn_print: "1234-5678".to_string(),
+                issn_digital: "8765-4321".to_string(),
+                series_url: None,
+            },
+        };
+
+        // Test standard output
+        let output = generate_test_output(&test_issue);
+        assert!(output.contains(r#"<series_metadata>"#));
+        assert!(output.contains(r#"  <titles>"#));
+        assert!(output.contains(r#"    <title>Name of series</title>"#));
+        assert!(output.contains(r#"  <issn media_type="electronic">8765-4321</issn>"#));
+        assert!(output.contains(r#"  <issn media_type="print">1234-5678</issn>"#));
+    }
+
+    #[test]
+    fn test_crossref_thoth_fundings() {
+        let mut test_funding = WorkFundings {
+            program: Some("Name of program".to_string()),
+            project_name: Some("Name of project".to_string()),
+            project_shortname: None,
+            grant_number: Some("Number of grant".to_string()),
+            jurisdiction: None,
+            institution: WorkFundingsInstitution {
+                institution_name: "Name of institution".to_string(),
+                institution_doi: Some(Doi::from_str("https://doi.org/10.13039/100000001").unwrap()),
+                ror: Some(Ror::from_str("https://ror.org/0abcdef12").unwrap()),
+                country_code: None,
+            },
+        };
+
+        // Test standard output
+        let output = generate_test_output(&test_funding);
+        assert!(output.contains(r#"<fr:assertion name="fundgroup">"#));
+        assert!(output.contains(r#"<fr:assertion name="funder_name">Name of institution"#));
+        assert!(output.contains(
+            r#"<fr:assertion name="funder_identifier">https://doi.org/10.13039/100000001</fr:assertion>"#
+        ));
+        assert!(
+            output.contains(r#"<fr:assertion name="award_number">Number of grant</fr:assertion>"#)
+        );
+        // Funder DOI takes precedence over ROR ID
+        assert!(!output.contains(r#"<fr:assertion name="ror">"#));
+
+        // Remove some values to test non-output of optional blocks
+        test_funding.institution.institution_doi = None;
+        test_funding.grant_number = None;
+        let output = generate_test_output(&test_funding);
+        assert!(!output.contains(r#"<fr:assertion name="funder_identifier">"#));
+        assert!(
+            output.contains(r#"<fr:assertion name="ror">https://ror.org/0abcdef12</fr:assertion>"#)
+        );
+        assert!(!output.contains(r#"<fr:assertion name="award_number">"#));
+
+        // No identifiers at all
+        test_funding.institution.ror = None;
+        let output = generate_test_output(&test_funding);
+        assert!(!output.contains(r#"<fr:assertion name="ror">"#));
+    }
+
+    #[test]
+    fn test_crossref_thoth_works() {
+        let mut test_work = test_work();
+
+        // Test standard output
+        let output = generate_test_output(&test_work);
+        assert!(output.contains(r#"<book book_type="monograph">"#));
+        assert!(output.contains(r#"  <book_metadata>"#));
+        assert!(output.contains(r#"    <contributors>"#));
+        // Contributors are output in order, and only if their role is accepted by Crossref
+        assert!(
+            output.contains(r#"      <person_name sequence="first" contributor_role="author">"#)
+        );
+        assert!(output.contains(r#"        <given_name>Author</given_name>"#));
+        assert!(output.contains(r#"        <surname>1</surname>"#));
+        assert!(output.contains(r#"        <affiliations>"#));
+        assert!(output.contains(r#"          <institution>"#));
+        assert!(output
+            .contains(r#"            <institution_name>University of Life</institution_name>"#));
+        assert!(output.contains(
+            r#"            <institution_id type="ror">https://ror.org/0abcdef12</institution_id>"#
+        ));
+        assert!(output.contains(r#"        <ORCID>https://orcid.org/0000-0002-0000-0001</ORCID>"#));
+        assert!(output
+            .contains(r#"      <person_name sequence="additional" contributor_role="editor">"#));
+        assert!(output.contains(r#"        <surname>Editor</surname>"#));
+        // Illustrators are not an accepted contributor role
+        assert!(!output.contains(r#"Illustrator"#));
+        assert!(
+            output.find(r#"sequence="first""#).unwrap()
+                < output.find(r#"sequence="additional""#).unwrap()
+        );
+        assert!(output.contains(r#"    <titles>"#));
+        assert!(output.contains(r#"      <title>Book Title</title>"#));
+        assert!(output.contains(r#"      <subtitle>Book Subtitle</subtitle>"#));
+        assert!(output.contains(r#"    <jats:abstract>"#));
+        assert!(output.contains(r#"      <jats:p>Lorem ipsum dolor sit amet</jats:p>"#));
+        assert!(output.contains(r#"    <edition_number>1</edition_number>"#));
+        assert!(output.contains(r#"    <publication_date media_type="online">"#));
+        assert!(output.contains(r#"      <month>12</month>"#));
+        assert!(output.contains(r#"      <day>31</day>"#));
+        assert!(output.contains(r#"      <year>1999</year>"#));
+        assert!(output.contains(r#"    <isbn media_type="electronic">978-3-16-148410-0</isbn>"#));
+        assert!(output.contains(r#"    <isbn media_type="print">978-1-4028-9462-6</isbn>"#));
+        assert!(output.contains(r#"    <publisher>"#));
+        assert!(output.contains(r#"      <publisher_name>OA Editions</publisher_name>"#));
+        assert!(output.contains(r#"      <publisher_place>León, Spain</publisher_place>"#));
+        assert!(output.contains(r#"    <ai:program name="AccessIndicators">"#));
+        assert!(output.contains(
+            r#"      <ai:license_ref applies_to="vor">https://creativecommons.org/licenses/by/4.0/</ai:license_ref>"#
+        ));
+        assert!(output.contains(r#"    <doi_data>"#));
+        assert!(output.contains(r#"      <doi>10.00001/BOOK.0001</doi>"#));
+        assert!(output.contains(r#"      <resource>https://www.book.com</resource>"#));
+        // No fundings supplied
+        assert!(!output.contains(r#"<fr:program"#));
+        // No ISBN-less publications
+        assert!(!output.contains(r#"<noisbn"#));
+
+        // Change the work to an edited book in a series, without ISBNs
+        test_work.work_type = WorkType::EDITED_BOOK;
+        test_work.issues = vec![WorkIssues {
+            issue_ordinal: 3,
+            series: WorkIssuesSeries {
+                series_type: SeriesType::BOOK_SERIES,
+                series_name: "Name of series".to_string(),
+                issn_print: "1234-5678".to_string(),
+                issn_digital: "8765-4321".to_string(),
+                series_url: None,
+            },
+        }];
+        test_work.publications.clear();
+        test_work.fundings = vec![WorkFundings {
+            program: None,
+            project_name: None,
+            project_shortname: None,
+            grant_number: None,
+            jurisdiction: None,
+            institution: WorkFundingsInstitution {
+                institution_name: "Name of institution".to_string(),
+                institution_doi: None,
+                ror: None,
+                country_code: None,
+            },
+        }];
+        test_work.subtitle = None;
+        test_work.license = None;
+        test_work.long_abstract = None;
+        test_work.place = None;
+        let output = generate_test_output(&test_work);
+        assert!(output.contains(r#"<book book_type="edited_book">"#));
+        assert!(output.contains(r#"  <book_series_metadata>"#));
+        assert!(!output.contains(r#"<book_metadata>"#));
+        assert!(output.contains(r#"    <series_metadata>"#));
+        assert!(output.contains(r#"    <volume>3</volume>"#));
+        assert!(output.contains(r#"    <noisbn reason="simple_series" />"#));
+        assert!(output.contains(r#"    <fr:program name="fundref">"#));
+        assert!(!output.contains(r#"<subtitle>"#));
+        assert!(!output.contains(r#"<ai:program"#));
+        assert!(!output.contains(r#"<jats:abstract>"#));
+        assert!(!output.contains(r#"<publisher_place>"#));
+
+        // Missing mandatory data and chapters fail to generate
+        // Can't use helper function for this as it assumes Ok rather than Err
+        let expect_error = |work: &Work, message: &str| {
+            let mut buffer = Vec::new();
+            let mut writer = xml::writer::EmitterConfig::new()
+                .perform_indent(true)
+                .create_writer(&mut buffer);
+            let wrapped_output = XmlElementBlock::<CrossrefThoth>::xml_element(work, &mut writer);
+            assert_eq!(
+                wrapped_output.unwrap_err().to_string(),
+                format!("Could not generate crossref::thoth: {}", message)
+            );
+        };
+        let mut test_work_chapter = test_work.clone();
+        test_work_chapter.work_type = WorkType::BOOK_CHAPTER;
+        expect_error(
+            &test_work_chapter,
+            "Chapters must be deposited as part of their parent book",
+        );
+        test_work.landing_page = None;
+        expect_error(&test_work, "Missing Landing Page");
+        test_work.publication_date = None;
+        expect_error(&test_work, "Missing Publication Date");
+        test_work.doi = None;
+        expect_error(&test_work, "Missing DOI");
+    }
+
+    #[test]
+    fn test_crossref_thoth_content_items() {
+        let mut relation = WorkRelations {
+            relation_ordinal: 2,
+            related_work: WorkRelationsRelatedWork {
+                work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000002").unwrap(),
+                work_status: WorkStatus::ACTIVE,
+                full_title: "Chapter Title: Chapter Subtitle".to_string(),
+                title: "Chapter Title".to_string(),
+                subtitle: Some("Chapter Subtitle".to_string()),
+                work_type: WorkType::BOOK_CHAPTER,
+                edition: 1,
+                doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001.02").unwrap()),
+                publication_date: None,
+                license: None,
+                copyright_holder: "Author 1".to_string(),
+                short_abstract: None,
+                long_abstract: None,
+                page_count: Some(20),
+                first_page: Some("11".to_string()),
+                last_page: Some("30".to_string()),
+                landing_page: Some("https://www.book.com/chapter".to_string()),
+                updated_at_with_relations: Default::default(),
+                contributions: vec![WorkRelationsRelatedWorkContributions {
+                    contribution_type: ContributionType::AUTHOR,
+                    first_name: Some("Author".to_string()),
+                    last_name: "1".to_string(),
+                    full_name: "Author 1".to_string(),
+                    main_contribution: true,
+                    biography: None,
+                    contribution_ordinal: 1,
+                    contributor: WorkRelationsRelatedWorkContributionsContributor { orcid: None },
+                    affiliations: vec![],
+                }],
+            },
+        };
+        let output = generate_test_output(&relation);
+        assert!(output.contains(r#"<content_item component_type="chapter">"#));
+        assert!(output.contains(r#"    <person_name sequence="first" contributor_role="author">"#));
+        assert!(output.contains(r#"      <surname>1</surname>"#));
+        assert!(output.contains(r#"    <title>Chapter Title</title>"#));
+        assert!(output.contains(r#"    <subtitle>Chapter Subtitle</subtitle>"#));
+        assert!(output.contains(r#"  <component_number>2</component_number>"#));
+        assert!(output.contains(
+            "  <pages>\n    <first_page>11</first_page>\n    <last_page>30</last_page>\n  </pages>"
+        ));
+        assert!(output.contains(r#"    <doi>10.00001/BOOK.0001.02</doi>"#));
+        assert!(output.contains(r#"    <resource>https://www.book.com/chapter</resource>"#));
+
+        // Content items follow their book's metadata, in order
+        let mut test_work = test_work();
+        let mut first_relation = relation.clone();
+        first_relation.relation_ordinal = 1;
+        first_relation.related_work.doi =
+            Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001.01").unwrap());
+        test_work.relations = vec![relation.clone(), first_relation];
+        let output = generate_test_output(&test_work);
+        assert_eq!(output.matches("<content_item ").count(), 2);
+        assert!(output.contains("  </book_metadata>\n  <content_item component_type=\"chapter\">"));
+        assert!(output.find("BOOK.0001.01").unwrap() < output.find("BOOK.0001.02").unwrap());
+
+        // Chapters without a DOI or a landing page cannot be deposited
+        relation.related_work.landing_page = None;
+        assert_eq!(generate_test_output(&relation), "");
+    }
+
+    #[test]
+    fn test_crossref_thoth_generate() {
+        let output = CrossrefThoth {}.generate(&[test_work()], None).unwrap();
+        assert!(output.contains(r#"<doi_batch"#));
+        assert!(output.contains(r#"xmlns="http://www.crossref.org/schema/5.3.1""#));
+        assert!(output.contains(r#"xmlns:fr="http://www.crossref.org/fundref.xsd""#));
+        assert!(output.contains(r#"version="5.3.1""#));
+        assert!(output.contains(r#"  <head>"#));
+        assert!(output.contains(r#"      <depositor_name>Thoth</depositor_name>"#));
+        assert!(output.contains(r#"    <registrant>OA Editions</registrant>"#));
+        assert!(output.contains(r#"  <body>"#));
+        assert!(output.contains(r#"    <book book_type="monograph">"#));
+
+        assert_eq!(
+            CrossrefThoth {}
+                .generate(&[], None)
+                .unwrap_err()
+                .to_string(),
+            "Could not generate crossref::thoth: Not enough data".to_string()
+        );
+    }
+}
@@ -0,0 +1,552 @@
+use std::collections::HashMap;
+use std::io::Write;
+use thoth_client::Work;
+use xml::writer::{EventWriter, XmlEvent};
+
+use super::{write_attributed_element, write_element_block, write_start_element, XmlSpecification};
+use crate::datacite::{
+    self, DataCiteFundingReference, DataCiteName, DataCiteRecord, DataCiteSubject,
+    DATACITE_SCHEMA_VERSION,
+};
+use crate::record::RequiredFields;
+use crate::xml::{write_full_element_block, XmlElementBlock};
+use thoth_errors::{ThothError, ThothResult};
+
+pub struct DataCiteThoth {}
+
+impl XmlSpecification for DataCiteThoth {
+    fn handle_event<W: Write>(w: &mut EventWriter<W>, works: &[Work]) -> ThothResult<()> {
+        match works.len() {
+            0 => Err(ThothError::IncompleteMetadataRecord(
+                "datacite::thoth".to_string(),
+                "Not enough data".to_string(),
+            )),
+            1 => XmlElementBlock::<DataCiteThoth>::xml_element(works.first().unwrap(), w),
+            // A DataCite XML document describes a single resource, so several are wrapped
+            // in a `resources` element
+            _ => write_element_block("resources", w, |w| {
+                for work in works.iter() {
+                    XmlElementBlock::<DataCiteThoth>::xml_element(work, w).ok();
+                }
+                Ok(())
+            }),
+        }
+    }
+}
+
+impl RequiredFields for DataCiteThoth {
+    const SPECIFICATION_ID: &'static str = "datacite::thoth";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        datacite::missing_fields(work)
+    }
+}
+
+impl XmlElementBlock<DataCiteThoth> for Work {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        DataCiteThoth::check_required_fields(self)?;
+        let record = DataCiteRecord::from_work(self, "datacite::thoth")?;
+        XmlElementBlock::<DataCiteThoth>::xml_element(&record, w)
+    }
+}
+
+impl XmlElementBlock<DataCiteThoth> for DataCiteRecord {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let mut ns_map: HashMap<String, String> = HashMap::new();
+        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+        let schema_location = format!(
+            "{} http://schema.datacite.org/meta/kernel-4.4/metadata.xsd",
+            DATACITE_SCHEMA_VERSION
+        );
+
+        ns_map.insert("".to_string(), DATACITE_SCHEMA_VERSION.to_string());
+        ns_map.insert(
+            "xsi".to_string(),
+            "http://www.w3.org/2001/XMLSchema-instance".to_string(),
+        );
+        attr_map.insert("xsi:schemaLocation", &schema_location);
+
+        write_full_element_block("resource", Some(ns_map), Some(attr_map), w, |w| {
+            write_attributed_element("identifier", &[("identifierType", "DOI")], &self.doi, w)?;
+            write_element_block("creators", w, |w| {
+                for creator in &self.creators {
+                    write_name("creator", creator, w)?;
+                }
+                Ok(())
+            })?;
+            write_element_block("titles", w, |w| {
+                for title in &self.titles {
+                    match title.title_type {
+                        Some(title_type) => write_attributed_element(
+                            "title",
+                            &[("titleType", title_type)],
+                            &title.title,
+                            w,
+                        )?,
+                        None => write_attributed_element("title", &[], &title.title, w)?,
+                    }
+                }
+                Ok(())
+            })?;
+            write_attributed_element("publisher", &[], &self.publisher, w)?;
+            write_attributed_element("publicationYear", &[], &self.publication_year, w)?;
+            write_attributed_element(
+                "resourceType",
+                &[("resourceTypeGeneral", self.types.resource_type_general)],
+                self.types.resource_type,
+                w,
+            )?;
+            if !self.subjects.is_empty() {
+                write_element_block("subjects", w, |w| {
+                    for subject in &self.subjects {
+                        XmlElementBlock::<DataCiteThoth>::xml_element(subject, w)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            if !self.contributors.is_empty() {
+                write_element_block("contributors", w, |w| {
+                    for contributor in &self.contributors {
+                        write_name("contributor", contributor, w)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            write_element_block("dates", w, |w| {
+                for date in &self.dates {
+                    write_attributed_element(
+                        "date",
+                        &[("dateType", date.date_type)],
+                        &date.date,
+                        w,
+                    )?;
+                }
+                Ok(())
+            })?;
+            if !self.alternate_identifiers.is_empty() {
+                write_element_block("alternateIdentifiers", w, |w| {
+                    for identifier in &self.alternate_identifiers {
+                        write_attributed_element(
+                            "alternateIdentifier",
+                            &[(
+                                "alternateIdentifierType",
+                                identifier.alternate_identifier_type,
+                            )],
+                            &identifier.alternate_identifier,
+                            w,
+                        )?;
+                    }
+                    Ok(())
+                })?;
+            }
+            if !self.rights_list.is_empty() {
+                write_element_block("rightsList", w, |w| {
+                    for rights in &self.rights_list {
+                        write_attributed_element(
+                            "rights",
+                            &[("rightsURI", &rights.rights_uri)],
+                            &rights.rights,
+                            w,
+                        )?;
+                    }
+                    Ok(())
+                })?;
+            }
+            if !self.descriptions.is_empty() {
+                write_element_block("descriptions", w, |w| {
+                    for description in &self.descriptions {
+                        write_attributed_element(
+                            "description",
+                            &[("descriptionType", description.description_type)],
+                            &description.description,
+                            w,
+                        )?;
+                    }
+                    Ok(())
+                })?;
+            }
+            if !self.funding_references.is_empty() {
+                write_element_block("fundingReferences", w, |w| {
+                    for funding_reference in &self.funding_references {
+                        XmlElementBlock::<DataCiteThoth>::xml_element(funding_reference, w)?;
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl XmlElementBlock<DataCiteThoth> for DataCiteSubject {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let mut attributes = vec![];
+        if let Some(subject_scheme) = self.subject_scheme {
+            attributes.push(("subjectScheme", subject_scheme));
+        }
+        if let Some(scheme_uri) = self.scheme_uri {
+            attributes.push(("schemeURI", scheme_uri));
+        }
+        if let Some(classification_code) = &self.classification_code {
+            attributes.push(("classificationCode", classification_code));
+        }
+        write_attributed_element("subject", &attributes, &self.subject, w)
+    }
+}
+
+impl XmlElementBlock<DataCiteThoth> for DataCiteFundingReference {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_element_block("fundingReference", w, |w| {
+            write_attributed_element("funderName", &[], &self.funder_name, w)?;
+            if let (Some(funder_identifier), Some(funder_identifier_type)) =
+                (&self.funder_identifier, self.funder_identifier_type)
+            {
+                write_attributed_element(
+                    "funderIdentifier",
+                    &[("funderIdentifierType", funder_identifier_type)],
+                    funder_identifier,
+                    w,
+                )?;
+            }
+            if let Some(award_number) = &self.award_number {
+                write_attributed_element("awardNumber", &[], award_number, w)?;
+            }
+            if let Some(award_title) = &self.award_title {
+                write_attributed_element("awardTitle", &[], award_title, w)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Write a `creator` or `contributor` element, whose child elements are prefixed accordingly
+fn write_name<W: Write>(
+    element: &str,
+    name: &DataCiteName,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    let mut attributes = vec![];
+    if let Some(contributor_type) = name.contributor_type {
+        attributes.push(("contributorType", contributor_type));
+    }
+    write_start_element(element, &attributes, w)?;
+    write_attributed_element(
+        &format!("{}Name", element),
+        &[("nameType", name.name_type)],
+        &name.name,
+        w,
+    )?;
+    if let Some(given_name) = &name.given_name {
+        write_attributed_element("givenName", &[], given_name, w)?;
+    }
+    write_attributed_element("familyName", &[], &name.family_name, w)?;
+    for name_identifier in &name.name_identifiers {
+        write_attributed_element(
+            "nameIdentifier",
+            &[
+                (
+                    "nameIdentifierScheme",
+                    name_identifier.name_identifier_scheme,
+                ),
+                ("schemeURI", name_identifier.scheme_uri),
+            ],
+            &name_identifier.name_identifier,
+            w,
+        )?;
+    }
+    for affiliation in &name.affiliation {
+        let mut attributes = vec![];
+        if let Some(affiliation_identifier) = &affiliation.affiliation_identifier {
+            attributes.push(("affiliationIdentifier", affiliation_identifier.as_str()));
+        }
+        if let Some(affiliation_identifier_scheme) = affiliation.affiliation_identifier_scheme {
+            attributes.push(("affiliationIdentifierScheme", affiliation_identifier_scheme));
+        }
+        if let Some(scheme_uri) = affiliation.scheme_uri {
+            attributes.push(("schemeURI", scheme_uri));
+        }
+        write_attributed_element("affiliation", &attributes, &affiliation.name, w)?;
+    }
+    w.write(XmlEvent::end_element()).map_err(|e| e.into())
+}
+
+// Attributes are written in a fixed order, rather than via `write_full_element_block`,
+// so that the output is stable for elements carrying several of them
+#[cfg(test)]
+mod tests {
+    // Testing note: XML nodes cannot be guaranteed to be output in the same order every time
+    // We therefore rely on `assert!(contains)` rather than `assert_eq!`
+    use super::*;
+    use std::str::FromStr;
+    use thoth_api::model::{Doi, Isbn, Orcid, Ror};
+    use thoth_client::{
+        ContributionType, PublicationType, SubjectType, WorkContributions,
+        WorkContributionsAffiliations, WorkContributionsAffiliationsInstitution,
+        WorkContributionsContributor, WorkFundings, WorkFundingsInstitution, WorkImprint,
+        WorkImprintPublisher, WorkPublications, WorkStatus, WorkSubjects, WorkType,
+    };
+    use uuid::Uuid;
+
+    fn generate_test_output(input: &impl XmlElementBlock<DataCiteThoth>) -> String {
+        // Helper function based on `XmlSpecification::generate`
+        let mut buffer = Vec::new();
+        let mut writer = xml::writer::EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(&mut buffer);
+        let wrapped_output = XmlElementBlock::<DataCiteThoth>::xml_element(input, &mut writer)
+            .map(|_| buffer)
+            .and_then(|xml| {
+                String::from_utf8(xml)
+                    .map_err(|_| ThothError::InternalError("Could not parse XML".to_string()))
+            });
+        assert!(wrapped_output.is_ok());
+        wrapped_output.unwrap()
+    }
+
+    #[test]
+    fn test_datacite_thoth_subjects() {
+        let mut test_subject = DataCiteSubject {
+            subject: "AAB".to_string(),
+            subject_scheme: Some("Thema"),
+            scheme_uri: Some("https://ns.editeur.org/thema/"),
+            classification_code: Some("AAB".to_string()),
+        };
+        let output = generate_test_output(&test_subject);
+        assert!(output.contains(
+            r#"<subject subjectScheme="Thema" schemeURI="https://ns.editeur.org/thema/" classificationCode="AAB">AAB</subject>"#
+        ));
+
+        test_subject.subject = "keyword1".to_string();
+        test_subject.subject_scheme = None;
+        test_subject.scheme_uri = None;
+        test_subject.classification_code = None;
+        let output = generate_test_output(&test_subject);
+        assert!(output.contains(r#"<subject>keyword1</subject>"#));
+    }
+
+    #[test]
+    fn test_datacite_thoth_funding_references() {
+        let mut test_funding_reference = DataCiteFundingReference {
+            funder_name: "Name of institution".to_string(),
+            funder_identifier: Some("https://doi.org/10.13039/100000001".to_string()),
+            funder_identifier_type: Some("Crossref Funder ID"),
+            award_number: Some("Number of grant".to_string()),
+            award_title: Some("Name of project".to_string()),
+        };
+        let output = generate_test_output(&test_funding_reference);
+        assert!(output.contains(r#"<fundingReference>"#));
+        assert!(output.contains(r#"  <funderName>Name of institution</funderName>"#));
+        assert!(output.contains(
+            r#"  <funderIdentifier funderIdentifierType="Crossref Funder ID">https://doi.org/10.13039/100000001</funderIdentifier>"#
+        ));
+        assert!(output.contains(r#"  <awardNumber>Number of grant</awardNumber>"#));
+        assert!(output.contains(r#"  <awardTitle>Name of project</awardTitle>"#));
+
+        test_funding_reference.funder_identifier = None;
+        test_funding_reference.funder_identifier_type = None;
+        test_funding_reference.award_number = None;
+        test_funding_reference.award_title = None;
+        let output = generate_test_output(&test_funding_reference);
+        assert!(output.contains(r#"  <funderName>Name of institution</funderName>"#));
+        assert!(!output.contains(r#"<funderIdentifier"#));
+        assert!(!output.contains(r#"<awardNumber>"#));
+        assert!(!output.contains(r#"<awardTitle>"#));
+    }
+
+    #[test]
+    fn test_datacite_thoth_works() {
+        let mut test_work = Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: None,
+            long_abstract: Some("Lorem ipsum dolor sit amet".to_string()),
+            general_note: None,
+            place: None,
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: None,
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![],
+            contributions: vec![
+                WorkContributions {
+                    contribution_type: ContributionType::AUTHOR,
+                    first_name: Some("Author".to_string()),
+                    last_name: "1".to_string(),
+                    full_name: "Author 1".to_string(),
+                    main_contribution: true,
+                    biography: None,
+                    contribution_ordinal: 1,
+                    contributor: WorkContributionsContributor {
+                        orcid: Some(
+                            Orcid::from_str("https://orcid.org/0000-0002-0000-0001").unwrap(),
+                        ),
+                    },
+                    affiliations: vec![WorkContributionsAffiliations {
+                        position: None,
+                        affiliation_ordinal: 1,
+                        institution: WorkContributionsAffiliationsInstitution {
+                            institution_name: "University of Life".to_string(),
+                            ror: Some(Ror::from_str("https://ror.org/0abcdef12").unwrap()),
+                        },
+                    }],
+                },
+                WorkContributions {
+                    contribution_type: ContributionType::EDITOR,
+                    first_name: Some("Editor".to_string()),
+                    last_name: "1".to_string(),
+                    full_name: "Editor 1".to_string(),
+                    main_contribution: false,
+                    biography: None,
+                    contribution_ordinal: 2,
+                    contributor: WorkContributionsContributor { orcid: None },
+                    affiliations: vec![],
+                },
+            ],
+            languages: vec![],
+            publications: vec![WorkPublications {
+                publication_id: Uuid::from_str("00000000-0000-0000-DDDD-000000000004").unwrap(),
+                publication_type: PublicationType::PDF,
+                isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                prices: vec![],
+                locations: vec![],
+            }],
+            subjects: vec![WorkSubjects {
+                subject_code: "AAB".to_string(),
+                subject_type: SubjectType::THEMA,
+                subject_ordinal: 1,
+            }],
+            fundings: vec![WorkFundings {
+                program: None,
+                project_name: None,
+                project_shortname: None,
+                grant_number: None,
+                jurisdiction: None,
+                institution: WorkFundingsInstitution {
+                    institution_name: "Name of institution".to_string(),
+                    institution_doi: None,
+                    ror: None,
+                    country_code: None,
+                },
+            }],
+            relations: vec![],
+            parents: vec![],
+        };
+
+        // Test standard output
+        let output = generate_test_output(&test_work);
+        assert!(output.contains(r#"<resource"#));
+        assert!(output.contains(r#"xmlns="http://datacite.org/schema/kernel-4""#));
+        assert!(output.contains(
+            r#"xsi:schemaLocation="http://datacite.org/schema/kernel-4 http://schema.datacite.org/meta/kernel-4.4/metadata.xsd""#
+        ));
+        assert!(output
+            .contains(r#"  <identifier identifierType="DOI">10.00001/BOOK.0001</identifier>"#));
+        assert!(output.contains(r#"  <creators>"#));
+        assert!(output.contains(r#"    <creator>"#));
+        assert!(
+            output.contains(r#"      <creatorName nameType="Personal">1, Author</creatorName>"#)
+        );
+        assert!(output.contains(r#"      <givenName>Author</givenName>"#));
+        assert!(output.contains(r#"      <familyName>1</familyName>"#));
+        assert!(output.contains(
+            r#"      <nameIdentifier nameIdentifierScheme="ORCID" schemeURI="https://orcid.org">https://orcid.org/0000-0002-0000-0001</nameIdentifier>"#
+        ));
+        assert!(output.contains(
+            r#"      <affiliation affiliationIdentifier="https://ror.org/0abcdef12" affiliationIdentifierScheme="ROR" schemeURI="https://ror.org">University of Life</affiliation>"#
+        ));
+        assert!(output.contains(r#"  <titles>"#));
+        assert!(output.contains(r#"    <title>Book Title</title>"#));
+        assert!(output.contains(r#"    <title titleType="Subtitle">Book Subtitle</title>"#));
+        assert!(output.contains(r#"  <publisher>OA Editions</publisher>"#));
+        assert!(output.contains(r#"  <publicationYear>1999</publicationYear>"#));
+        assert!(output
+            .contains(r#"  <resourceType resourceTypeGeneral="Book">Monograph</resourceType>"#));
+        assert!(output.contains(r#"  <subjects>"#));
+        assert!(output.contains(
+            r#"    <subject subjectScheme="Thema" schemeURI="https://ns.editeur.org/thema/" classificationCode="AAB">AAB</subject>"#
+        ));
+        assert!(output.contains(r#"  <contributors>"#));
+        assert!(output.contains(r#"    <contributor contributorType="Editor">"#));
+        assert!(output
+            .contains(r#"      <contributorName nameType="Personal">1, Editor</contributorName>"#));
+        assert!(output.contains(r#"    <date dateType="Issued">1999-12-31</date>"#));
+        assert!(output.contains(
+            r#"    <alternateIdentifier alternateIdentifierType="ISBN">978-3-16-148410-0</alternateIdentifier>"#
+        ));
+        assert!(output.contains(
+            r#"    <rights rightsURI="https://creativecommons.org/licenses/by/4.0/">https://creativecommons.org/licenses/by/4.0/</rights>"#
+        ));
+        assert!(output.contains(
+            r#"    <description descriptionType="Abstract">Lorem ipsum dolor sit amet</description>"#
+        ));
+        assert!(output.contains(r#"  <fundingReferences>"#));
+        assert!(output.contains(r#"      <funderName>Name of institution</funderName>"#));
+
+        // Remove some values to test non-output of optional blocks
+        test_work.subtitle = None;
+        test_work.contributions.pop();
+        test_work.publications.clear();
+        test_work.subjects.clear();
+        test_work.fundings.clear();
+        test_work.license = None;
+        test_work.long_abstract = None;
+        let output = generate_test_output(&test_work);
+        assert!(!output.contains(r#"titleType="Subtitle""#));
+        assert!(!output.contains(r#"<contributors>"#));
+        assert!(!output.contains(r#"<alternateIdentifiers>"#));
+        assert!(!output.contains(r#"<subjects>"#));
+        assert!(!output.contains(r#"<fundingReferences>"#));
+        assert!(!output.contains(r#"<rightsList>"#));
+        assert!(!output.contains(r#"<descriptions>"#));
+
+        // Remove DOI: record fails to generate
+        test_work.doi = None;
+        // Can't use helper function for this as it assumes Ok rather than Err
+        let mut buffer = Vec::new();
+        let mut writer = xml::writer::EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(&mut buffer);
+        let wrapped_output = XmlElementBlock::<DataCiteThoth>::xml_element(&test_work, &mut writer);
+        assert_eq!(
+            wrapped_output.unwrap_err().to_string(),
+            "Could not generate datacite::thoth: Missing DOI".to_string()
+        );
+    }
+}
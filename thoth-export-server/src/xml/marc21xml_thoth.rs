@@ -0,0 +1,326 @@
+use std::collections::HashMap;
+use std::io::Write;
+use thoth_client::{DeletedWork, Work};
+use xml::writer::{EventWriter, XmlEvent};
+
+use super::{
+    handle_stream_event, write_element_block, write_start_element, XmlSpecification,
+    XmlStreamSpecification,
+};
+use crate::marc21::{self, MarcControlField, MarcDataField, MarcRecord, MarcSubfield};
+use crate::record::RequiredFields;
+use crate::xml::{write_full_element_block, XmlElementBlock};
+use thoth_errors::ThothResult;
+
+pub struct Marc21XmlThoth {}
+
+impl XmlSpecification for Marc21XmlThoth {
+    fn handle_event<W: Write>(w: &mut EventWriter<W>, works: &[Work]) -> ThothResult<()> {
+        handle_stream_event::<Self, W>(w, works, &[])
+    }
+}
+
+impl XmlStreamSpecification for Marc21XmlThoth {
+    fn write_header<W: Write>(w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_start_element(
+            "collection",
+            &[("xmlns", "http://www.loc.gov/MARC21/slim")],
+            w,
+        )
+    }
+
+    fn write_work<W: Write>(w: &mut EventWriter<W>, work: &Work) -> ThothResult<()> {
+        XmlElementBlock::<Marc21XmlThoth>::xml_element(work, w)
+    }
+
+    /// MARC records cannot express deletions, so this only closes the collection
+    fn write_footer<W: Write>(w: &mut EventWriter<W>, _: &[DeletedWork]) -> ThothResult<()> {
+        w.write(XmlEvent::end_element()).map_err(|e| e.into())
+    }
+}
+
+impl RequiredFields for Marc21XmlThoth {
+    const SPECIFICATION_ID: &'static str = "marc21xml::thoth";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        marc21::missing_fields(work)
+    }
+}
+
+impl XmlElementBlock<Marc21XmlThoth> for Work {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        Marc21XmlThoth::check_required_fields(self)?;
+        let record = MarcRecord::from_work(self, "marc21xml::thoth")?;
+        XmlElementBlock::<Marc21XmlThoth>::xml_element(&record, w)
+    }
+}
+
+impl XmlElementBlock<Marc21XmlThoth> for MarcRecord {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_element_block("record", w, |w| {
+            // Record length and base address are only meaningful in ISO 2709
+            write_element_block("leader", w, |w| {
+                w.write(XmlEvent::Characters(&self.leader(0, 0)))
+                    .map_err(|e| e.into())
+            })?;
+            for control_field in &self.control_fields {
+                XmlElementBlock::<Marc21XmlThoth>::xml_element(control_field, w)?;
+            }
+            for data_field in &self.data_fields {
+                XmlElementBlock::<Marc21XmlThoth>::xml_element(data_field, w)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl XmlElementBlock<Marc21XmlThoth> for MarcControlField {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+        attr_map.insert("tag", self.tag);
+        write_full_element_block("controlfield", None, Some(attr_map), w, |w| {
+            w.write(XmlEvent::Characters(&self.value))
+                .map_err(|e| e.into())
+        })
+    }
+}
+
+impl XmlElementBlock<Marc21XmlThoth> for MarcDataField {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        // Attributes are written in a fixed order, rather than via `write_full_element_block`,
+        // so that `tag`, `ind1` and `ind2` always appear in the same sequence
+        let ind1 = self.ind1.to_string();
+        let ind2 = self.ind2.to_string();
+        w.write(
+            XmlEvent::start_element("datafield")
+                .attr("tag", self.tag)
+                .attr("ind1", &ind1)
+                .attr("ind2", &ind2),
+        )?;
+        for subfield in &self.subfields {
+            XmlElementBlock::<Marc21XmlThoth>::xml_element(subfield, w)?;
+        }
+        w.write(XmlEvent::end_element()).map_err(|e| e.into())
+    }
+}
+
+impl XmlElementBlock<Marc21XmlThoth> for MarcSubfield {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let code = self.code.to_string();
+        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+        attr_map.insert("code", &code);
+        write_full_element_block("subfield", None, Some(attr_map), w, |w| {
+            w.write(XmlEvent::Characters(&self.value))
+                .map_err(|e| e.into())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Testing note: XML nodes cannot be guaranteed to be output in the same order every time
+    // We therefore rely on `assert!(contains)` rather than `assert_eq!`
+    use super::*;
+    use std::str::FromStr;
+    use thoth_api::model::Doi;
+    use thoth_api::model::Isbn;
+    use thoth_client::{
+        ContributionType, PublicationType, WorkContributions, WorkContributionsContributor,
+        WorkImprint, WorkImprintPublisher, WorkPublications, WorkStatus, WorkType,
+    };
+    use thoth_errors::ThothError;
+    use uuid::Uuid;
+
+    fn generate_test_output(input: &impl XmlElementBlock<Marc21XmlThoth>) -> String {
+        // Helper function based on `XmlSpecification::generate`
+        let mut buffer = Vec::new();
+        let mut writer = xml::writer::EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(&mut buffer);
+        let wrapped_output = XmlElementBlock::<Marc21XmlThoth>::xml_element(input, &mut writer)
+            .map(|_| buffer)
+            .and_then(|xml| {
+                String::from_utf8(xml)
+                    .map_err(|_| ThothError::InternalError("Could not parse XML".to_string()))
+            });
+        assert!(wrapped_output.is_ok());
+        wrapped_output.unwrap()
+    }
+
+    #[test]
+    fn test_marc21xml_thoth_fields() {
+        let test_control_field = MarcControlField {
+            tag: "001",
+            value: "00000000-0000-0000-aaaa-000000000001".to_string(),
+        };
+        let output = generate_test_output(&test_control_field);
+        assert!(output.contains(
+            r#"<controlfield tag="001">00000000-0000-0000-aaaa-000000000001</controlfield>"#
+        ));
+
+        let test_data_field = MarcDataField {
+            tag: "100",
+            ind1: '1',
+            ind2: ' ',
+            subfields: vec![
+                MarcSubfield {
+                    code: 'a',
+                    value: "First, Author".to_string(),
+                },
+                MarcSubfield {
+                    code: 'e',
+                    value: "author".to_string(),
+                },
+            ],
+        };
+        let output = generate_test_output(&test_data_field);
+        assert!(output.contains(r#"<datafield tag="100" ind1="1" ind2=" ">"#));
+        assert!(output.contains(r#"  <subfield code="a">First, Author</subfield>"#));
+        assert!(output.contains(r#"  <subfield code="e">author</subfield>"#));
+        assert!(output.contains(r#"</datafield>"#));
+    }
+
+    #[test]
+    fn test_marc21xml_thoth_works() {
+        let mut test_work = Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: None,
+            long_abstract: Some("Lorem ipsum dolor sit amet".to_string()),
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: Some(334),
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![],
+            contributions: vec![WorkContributions {
+                contribution_type: ContributionType::AUTHOR,
+                first_name: Some("Author".to_string()),
+                last_name: "1".to_string(),
+                full_name: "Author 1".to_string(),
+                main_contribution: true,
+                biography: None,
+                contribution_ordinal: 1,
+                contributor: WorkContributionsContributor { orcid: None },
+                affiliations: vec![],
+            }],
+            languages: vec![],
+            publications: vec![WorkPublications {
+                publication_id: Uuid::from_str("00000000-0000-0000-DDDD-000000000004").unwrap(),
+                publication_type: PublicationType::PDF,
+                isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                prices: vec![],
+                locations: vec![],
+            }],
+            subjects: vec![],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        };
+
+        // Test standard output
+        let output = generate_test_output(&test_work);
+        assert!(output.contains(r#"<record>"#));
+        assert!(output.contains(r#"  <leader>00000nam a2200000 c 4500</leader>"#));
+        assert!(output.contains(
+            r#"  <controlfield tag="001">00000000-0000-0000-aaaa-000000000001</controlfield>"#
+        ));
+        assert!(output.contains(r#"  <controlfield tag="007">cr |n|||||||||</controlfield>"#));
+        assert!(output.contains(r#"  <datafield tag="020" ind1=" " ind2=" ">"#));
+        assert!(output.contains(r#"    <subfield code="a">9783161484100</subfield>"#));
+        assert!(output.contains(r#"    <subfield code="q">PDF</subfield>"#));
+        assert!(output.contains(r#"  <datafield tag="024" ind1="7" ind2=" ">"#));
+        assert!(output.contains(r#"    <subfield code="a">10.00001/BOOK.0001</subfield>"#));
+        assert!(output.contains(r#"    <subfield code="2">doi</subfield>"#));
+        assert!(output.contains(r#"  <datafield tag="100" ind1="1" ind2=" ">"#));
+        assert!(output.contains(r#"    <subfield code="a">1, Author</subfield>"#));
+        assert!(output.contains(r#"    <subfield code="4">aut</subfield>"#));
+        assert!(output.contains(r#"  <datafield tag="245" ind1="1" ind2="0">"#));
+        assert!(output.contains(r#"    <subfield code="a">Book Title</subfield>"#));
+        assert!(output.contains(r#"    <subfield code="b">Book Subtitle</subfield>"#));
+        assert!(output.contains(r#"  <datafield tag="264" ind1=" " ind2="1">"#));
+        assert!(output.contains(r#"    <subfield code="b">OA Editions</subfield>"#));
+        assert!(output.contains(r#"    <subfield code="c">1999</subfield>"#));
+        assert!(output.contains(r#"  <datafield tag="520" ind1="3" ind2=" ">"#));
+        assert!(output.contains(r#"    <subfield code="a">Lorem ipsum dolor sit amet</subfield>"#));
+        assert!(output.contains(r#"  <datafield tag="540" ind1=" " ind2=" ">"#));
+        assert!(output.contains(r#"    <subfield code="f">CC BY 4.0</subfield>"#));
+        assert!(output.contains(
+            r#"    <subfield code="u">https://creativecommons.org/licenses/by/4.0/</subfield>"#
+        ));
+        assert!(output.contains(r#"  <datafield tag="856" ind1="4" ind2="0">"#));
+        assert!(output
+            .contains(r#"    <subfield code="u">https://doi.org/10.00001/BOOK.0001</subfield>"#));
+        assert!(output.contains(r#"    <subfield code="u">https://www.book.com</subfield>"#));
+
+        // Remove some values to test non-output of optional blocks
+        test_work.doi = None;
+        test_work.license = None;
+        test_work.long_abstract = None;
+        test_work.contributions.clear();
+        let output = generate_test_output(&test_work);
+        // No DOI supplied
+        assert!(!output.contains(r#"  <datafield tag="024" ind1="7" ind2=" ">"#));
+        assert!(!output
+            .contains(r#"    <subfield code="u">https://doi.org/10.00001/BOOK.0001</subfield>"#));
+        // No licence supplied
+        assert!(!output.contains(r#"  <datafield tag="540" ind1=" " ind2=" ">"#));
+        // No abstract supplied
+        assert!(!output.contains(r#"  <datafield tag="520" ind1="3" ind2=" ">"#));
+        // No author: no main entry, and title is not an added entry
+        assert!(!output.contains(r#"  <datafield tag="100" ind1="1" ind2=" ">"#));
+        assert!(output.contains(r#"  <datafield tag="245" ind1="0" ind2="0">"#));
+
+        // Remove publication date: record fails to generate
+        test_work.publication_date = None;
+        // Can't use helper function for this as it assumes Ok rather than Err
+        let mut buffer = Vec::new();
+        let mut writer = xml::writer::EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(&mut buffer);
+        let wrapped_output =
+            XmlElementBlock::<Marc21XmlThoth>::xml_element(&test_work, &mut writer);
+        assert_eq!(
+            wrapped_output.unwrap_err().to_string(),
+            "Could not generate marc21xml::thoth: Missing Publication Date".to_string()
+        );
+    }
+}
@@ -1,7 +1,8 @@
-use crate::record::XML_DECLARATION;
+use crate::record::{RecordWriter, RequiredFields, XML_DECLARATION};
 use std::collections::HashMap;
 use std::io::Write;
-use thoth_client::Work;
+use std::marker::PhantomData;
+use thoth_client::{DeletedWork, PublicationType, Work, WorkStatus};
 use thoth_errors::{ThothError, ThothResult};
 use xml::writer::events::StartElementBuilder;
 use xml::writer::{EmitterConfig, EventWriter, XmlEvent};
@@ -42,8 +43,101 @@ pub(crate) fn write_full_element_block<W: Write, F: Fn(&mut EventWriter<W>) -> T
     w.write(event).map_err(|e| e.into())
 }
 
+/// Write a start tag with its attributes in the given order
+pub(crate) fn write_start_element<W: Write>(
+    element: &str,
+    attributes: &[(&str, &str)],
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    let mut event_builder = XmlEvent::start_element(element);
+    for (name, value) in attributes {
+        event_builder = event_builder.attr(*name, value);
+    }
+    w.write(event_builder).map_err(|e| e.into())
+}
+
+/// Write a text-only element with its attributes in the given order
+pub(crate) fn write_attributed_element<W: Write>(
+    element: &str,
+    attributes: &[(&str, &str)],
+    value: &str,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    write_start_element(element, attributes, w)?;
+    w.write(XmlEvent::Characters(value))?;
+    w.write(XmlEvent::end_element()).map_err(|e| e.into())
+}
+
+/// ONIX 3.0 notification type (List 1) for a product of the given status
+pub(crate) fn onix3_notification_type(work_status: &WorkStatus) -> &'static str {
+    match work_status {
+        // 02 Advance notification (confirmed)
+        WorkStatus::FORTHCOMING | WorkStatus::POSTPONED_INDEFINITELY | WorkStatus::CANCELLED => {
+            "02"
+        }
+        // 03 Notification confirmed on publication
+        _ => "03",
+    }
+}
+
+/// ONIX 3.0 product availability (List 65) for a product of the given status, if it cannot be
+/// supplied. Products which can be supplied use the availability of each specification.
+pub(crate) fn onix3_unavailability(work_status: &WorkStatus) -> Option<&'static str> {
+    match work_status {
+        WorkStatus::CANCELLED => Some("01"),
+        WorkStatus::POSTPONED_INDEFINITELY => Some("09"),
+        WorkStatus::FORTHCOMING => Some("10"),
+        WorkStatus::OUT_OF_STOCK_INDEFINITELY | WorkStatus::INACTIVE => Some("40"),
+        WorkStatus::NO_LONGER_OUR_PRODUCT => Some("43"),
+        WorkStatus::WITHDRAWN_FROM_SALE => Some("46"),
+        WorkStatus::REMAINDERED => Some("47"),
+        WorkStatus::RECALLED => Some("49"),
+        WorkStatus::OUT_OF_PRINT => Some("51"),
+        _ => None,
+    }
+}
+
+/// Full text URL of the canonical location of the work's first located publication of a type
+pub(crate) fn full_text_url(work: &Work, publication_type: PublicationType) -> Option<&String> {
+    work.publications
+        .iter()
+        .find(|p| p.publication_type.eq(&publication_type) && !p.locations.is_empty())
+        .and_then(|p| p.locations.iter().find(|l| l.canonical))
+        .and_then(|l| l.full_text_url.as_ref())
+}
+
+/// Write an ONIX 3.0 product deleting a record previously sent to the recipient
+pub(crate) fn write_onix3_deletion<W: Write>(
+    record_reference: &str,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    write_element_block("Product", w, |w| {
+        write_attributed_element("RecordReference", &[], record_reference, w)?;
+        // 05 Delete
+        write_attributed_element("NotificationType", &[], "05", w)?;
+        // 01 Publisher
+        write_attributed_element("RecordSourceType", &[], "01", w)?;
+        write_element_block("ProductIdentifier", w, |w| {
+            // 01 Proprietary
+            write_attributed_element("ProductIDType", &[], "01", w)?;
+            write_attributed_element("IDValue", &[], record_reference, w)
+        })
+    })
+}
+
 pub(crate) trait XmlSpecification {
     fn generate(&self, works: &[Work], doctype: Option<&str>) -> ThothResult<String> {
+        self.generate_with_deletions(works, &[], doctype)
+    }
+
+    /// Generate a document which also notifies the recipient of works deleted from Thoth.
+    /// Deletions are ignored by specifications that cannot express them.
+    fn generate_with_deletions(
+        &self,
+        works: &[Work],
+        deleted_works: &[DeletedWork],
+        doctype: Option<&str>,
+    ) -> ThothResult<String> {
         let mut buffer = format!("{}{}", XML_DECLARATION, doctype.unwrap_or_default())
             .as_bytes()
             .to_vec();
@@ -51,7 +145,7 @@ pub(crate) trait XmlSpecification {
             .write_document_declaration(false)
             .perform_indent(true)
             .create_writer(&mut buffer);
-        Self::handle_event(&mut writer, works)
+        Self::handle_event_with_deletions(&mut writer, works, deleted_works)
             .map(|_| buffer)
             .and_then(|onix| {
                 String::from_utf8(onix)
@@ -60,6 +154,14 @@ pub(crate) trait XmlSpecification {
     }
 
     fn handle_event<W: Write>(w: &mut EventWriter<W>, works: &[Work]) -> ThothResult<()>;
+
+    fn handle_event_with_deletions<W: Write>(
+        w: &mut EventWriter<W>,
+        works: &[Work],
+        _deleted_works: &[DeletedWork],
+    ) -> ThothResult<()> {
+        Self::handle_event(w, works)
+    }
 }
 
 pub(crate) trait XmlElement<T: XmlSpecification> {
@@ -79,11 +181,475 @@ pub(crate) trait XmlElementBlock<T: XmlSpecification> {
     fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()>;
 }
 
+/// Specifications whose documents consist of a header, a sequence of independent work records
+/// and a footer, and can therefore be written one page of works at a time
+pub(crate) trait XmlStreamSpecification: XmlSpecification + RequiredFields {
+    /// Write the document's root start element and header
+    fn write_header<W: Write>(w: &mut EventWriter<W>) -> ThothResult<()>;
+
+    fn write_work<W: Write>(w: &mut EventWriter<W>, work: &Work) -> ThothResult<()>;
+
+    /// Write any notifications of deleted works, and close the root element
+    fn write_footer<W: Write>(
+        w: &mut EventWriter<W>,
+        deleted_works: &[DeletedWork],
+    ) -> ThothResult<()>;
+}
+
+/// Write a whole document of a specification that can also be streamed
+pub(crate) fn handle_stream_event<T: XmlStreamSpecification, W: Write>(
+    w: &mut EventWriter<W>,
+    works: &[Work],
+    deleted_works: &[DeletedWork],
+) -> ThothResult<()> {
+    T::write_header(w)?;
+    match works.len() {
+        0 if deleted_works.is_empty() => Err(ThothError::IncompleteMetadataRecord(
+            T::SPECIFICATION_ID.to_string(),
+            "Not enough data".to_string(),
+        )),
+        1 => T::write_work(w, works.first().unwrap()),
+        _ => {
+            for work in works.iter() {
+                T::write_work(w, work).ok();
+            }
+            Ok(())
+        }
+    }?;
+    T::write_footer(w, deleted_works)
+}
+
+/// Writes a document of a streamable specification one page of works at a time
+pub(crate) struct XmlStreamWriter<T: XmlStreamSpecification> {
+    writer: EventWriter<Vec<u8>>,
+    started: bool,
+    specification: PhantomData<T>,
+}
+
+impl<T: XmlStreamSpecification> XmlStreamWriter<T> {
+    pub(crate) fn new(doctype: Option<&str>) -> Self {
+        let buffer = format!("{}{}", XML_DECLARATION, doctype.unwrap_or_default()).into_bytes();
+        XmlStreamWriter {
+            writer: EmitterConfig::new()
+                .write_document_declaration(false)
+                .perform_indent(true)
+                .create_writer(buffer),
+            started: false,
+            specification: PhantomData,
+        }
+    }
+
+    fn start(&mut self) -> ThothResult<()> {
+        if !self.started {
+            T::write_header(&mut self.writer)?;
+            self.started = true;
+        }
+        Ok(())
+    }
+
+    fn take_output(&mut self) -> Vec<u8> {
+        std::mem::take(self.writer.inner_mut())
+    }
+}
+
+impl<T: XmlStreamSpecification> RecordWriter for XmlStreamWriter<T> {
+    fn write_page(&mut self, works: &[Work]) -> ThothResult<Vec<u8>> {
+        self.start()?;
+        for work in works {
+            // As in multi-work documents generated in one go, works that fail are left out
+            T::write_work(&mut self.writer, work).ok();
+        }
+        Ok(self.take_output())
+    }
+
+    fn finish(&mut self, deleted_works: &[DeletedWork]) -> ThothResult<Vec<u8>> {
+        self.start()?;
+        T::write_footer(&mut self.writer, deleted_works)?;
+        Ok(self.take_output())
+    }
+
+    fn abort(&mut self, error: &ThothError) -> Vec<u8> {
+        // Comments may not contain a double hyphen
+        let comment = format!(" Export aborted: {} ", error).replace("--", "- -");
+        self.writer.write(XmlEvent::comment(&comment)).ok();
+        self.take_output()
+    }
+}
+
 mod onix3_project_muse;
 pub(crate) use onix3_project_muse::Onix3ProjectMuse;
 mod onix3_oapen;
 pub(crate) use onix3_oapen::Onix3Oapen;
 mod onix3_jstor;
 pub(crate) use onix3_jstor::Onix3Jstor;
+mod onix3_thoth;
+pub(crate) use onix3_thoth::Onix3Thoth;
+mod onix3_google_books;
+pub(crate) use onix3_google_books::Onix3GoogleBooks;
+mod onix3_proquest_ebrary;
+pub(crate) use onix3_proquest_ebrary::Onix3ProquestEbrary;
 mod onix21_ebsco_host;
 pub(crate) use onix21_ebsco_host::Onix21EbscoHost;
+mod marc21xml_thoth;
+pub(crate) use marc21xml_thoth::Marc21XmlThoth;
+mod crossref_thoth;
+pub(crate) use crossref_thoth::CrossrefThoth;
+mod datacite_thoth;
+pub(crate) use datacite_thoth::DataCiteThoth;
+mod oai_dc_thoth;
+pub(crate) use oai_dc_thoth::OaiDcThoth;
+mod mods_thoth;
+pub(crate) use mods_thoth::ModsThoth;
+mod schema;
+pub(crate) use schema::{SchemaValidator, SCHEMAS_DIR};
+
+#[cfg(test)]
+mod tests {
+    // Validation against reference schemas requires `xmllint`, and every schema to be vendored
+    // (see `schemas/fetch.sh`).
+    use super::*;
+    use crate::record::DOCTYPE_ONIX21_REF;
+    use std::str::FromStr;
+    use thoth_api::model::{Doi, Isbn, Orcid};
+    use thoth_client::{
+        ContributionType, CurrencyCode, LanguageCode, LanguageRelation, LocationPlatform,
+        PublicationType, SeriesType, SubjectType, WorkContributions, WorkContributionsContributor,
+        WorkImprint, WorkImprintPublisher, WorkIssues, WorkIssuesSeries, WorkLanguages,
+        WorkPublications, WorkPublicationsLocations, WorkPublicationsPrices, WorkStatus,
+        WorkSubjects, WorkType,
+    };
+    use uuid::Uuid;
+    use xml::reader::{EventReader, XmlEvent as ReaderEvent};
+
+    const ONIX3_SCHEMA: &str = "onix_3.0/ONIX_BookProduct_3.0_reference.xsd";
+    const ONIX3_NAMESPACE: &str = "http://ns.editeur.org/onix/3.0/reference";
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: Some("Lorem ipsum".to_string()),
+            long_abstract: Some("Lorem ipsum dolor sit amet".to_string()),
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: Some(156.0),
+            width_cm: Some(15.6),
+            width_in: Some(6.14),
+            height_mm: Some(234.0),
+            height_cm: Some(23.4),
+            height_in: Some(9.21),
+            page_count: Some(334),
+            page_breakdown: Some("x+334".to_string()),
+            first_page: None,
+            last_page: None,
+            image_count: Some(15),
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: Some("1. Chapter 1".to_string()),
+            lccn: None,
+            oclc: None,
+            cover_url: Some("https://www.book.com/cover".to_string()),
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: Some("https://www.oaeditions.com".to_string()),
+                },
+            },
+            issues: vec![WorkIssues {
+                issue_ordinal: 3,
+                series: WorkIssuesSeries {
+                    series_type: SeriesType::BOOK_SERIES,
+                    series_name: "Name of series".to_string(),
+                    issn_print: "1234-5678".to_string(),
+                    issn_digital: "8765-4321".to_string(),
+                    series_url: None,
+                },
+            }],
+            contributions: vec![WorkContributions {
+                contribution_type: ContributionType::AUTHOR,
+                first_name: Some("Author".to_string()),
+                last_name: "1".to_string(),
+                full_name: "Author 1".to_string(),
+                main_contribution: true,
+                biography: Some("Author 1 was born".to_string()),
+                contribution_ordinal: 1,
+                contributor: WorkContributionsContributor {
+                    orcid: Some(Orcid::from_str("https://orcid.org/0000-0002-0000-0001").unwrap()),
+                },
+                affiliations: vec![],
+            }],
+            languages: vec![WorkLanguages {
+                language_code: LanguageCode::ENG,
+                language_relation: LanguageRelation::ORIGINAL,
+                main_language: true,
+            }],
+            publications: vec![
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000002").unwrap(),
+                    publication_type: PublicationType::PAPERBACK,
+                    isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                    prices: vec![WorkPublicationsPrices {
+                        currency_code: CurrencyCode::GBP,
+                        unit_price: 22.0,
+                    }],
+                    locations: vec![],
+                },
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000003").unwrap(),
+                    publication_type: PublicationType::PDF,
+                    isbn: Some(Isbn::from_str("978-1-56619-909-4").unwrap()),
+                    prices: vec![],
+                    locations: vec![WorkPublicationsLocations {
+                        landing_page: Some("https://www.book.com/pdf_landing".to_string()),
+                        full_text_url: Some("https://www.book.com/pdf_fulltext".to_string()),
+                        location_platform: LocationPlatform::OTHER,
+                        canonical: true,
+                    }],
+                },
+            ],
+            subjects: vec![
+                WorkSubjects {
+                    subject_code: "AAA000000".to_string(),
+                    subject_type: SubjectType::BISAC,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "AAB".to_string(),
+                    subject_type: SubjectType::THEMA,
+                    subject_ordinal: 2,
+                },
+                WorkSubjects {
+                    subject_code: "keyword1".to_string(),
+                    subject_type: SubjectType::KEYWORD,
+                    subject_ordinal: 3,
+                },
+            ],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    fn generate_test_output<T: XmlSpecification>(
+        specification: &T,
+        doctype: Option<&str>,
+    ) -> String {
+        let output = specification.generate(&[test_work()], doctype);
+        assert!(output.is_ok(), "{:?}", output);
+        output.unwrap()
+    }
+
+    fn stream_test_output<T: XmlStreamSpecification>(doctype: Option<&str>) -> String {
+        let mut writer = XmlStreamWriter::<T>::new(doctype);
+        let mut output = writer.write_page(&[test_work()]).unwrap();
+        output.extend(writer.write_page(&[test_work()]).unwrap());
+        output.extend(writer.finish(&[]).unwrap());
+        String::from_utf8(output).unwrap()
+    }
+
+    // Documents generated at different times only differ in the date they were sent
+    fn without_sent_date(output: &str) -> Vec<&str> {
+        output
+            .lines()
+            .filter(|line| !line.contains("<SentDate"))
+            .collect()
+    }
+
+    fn assert_well_formed(output: &str, root_element: &str, namespace: Option<&str>) {
+        let mut root = None;
+        for event in EventReader::from_str(output) {
+            match event {
+                Ok(ReaderEvent::StartElement { name, .. }) if root.is_none() => root = Some(name),
+                Ok(_) => {}
+                Err(e) => panic!("Output is not well-formed XML: {}", e),
+            }
+        }
+        let root = root.expect("Output has no root element");
+        assert_eq!(root.local_name, root_element);
+        assert_eq!(root.namespace.as_deref(), namespace);
+    }
+
+    fn assert_valid(output: &str, schema: &str) {
+        if let Err(e) =
+            SchemaValidator::new(SCHEMAS_DIR).validate("test", schema, output.as_bytes())
+        {
+            panic!("{}", e);
+        }
+    }
+
+    #[test]
+    fn test_xml_specifications_are_well_formed() {
+        for output in [
+            generate_test_output(&Onix3ProjectMuse {}, None),
+            generate_test_output(&Onix3Oapen {}, None),
+            generate_test_output(&Onix3Jstor {}, None),
+            generate_test_output(&Onix3Thoth {}, None),
+            generate_test_output(&Onix3GoogleBooks {}, None),
+            generate_test_output(&Onix3ProquestEbrary {}, None),
+        ]
+        .iter()
+        {
+            assert_well_formed(output, "ONIXMessage", Some(ONIX3_NAMESPACE));
+        }
+        assert_well_formed(
+            &generate_test_output(&Onix21EbscoHost {}, Some(DOCTYPE_ONIX21_REF)),
+            "ONIXMessage",
+            None,
+        );
+        assert_well_formed(
+            &generate_test_output(&Marc21XmlThoth {}, None),
+            "collection",
+            Some("http://www.loc.gov/MARC21/slim"),
+        );
+        assert_well_formed(
+            &generate_test_output(&CrossrefThoth {}, None),
+            "doi_batch",
+            Some("http://www.crossref.org/schema/5.3.1"),
+        );
+        assert_well_formed(
+            &generate_test_output(&DataCiteThoth {}, None),
+            "resource",
+            Some("http://datacite.org/schema/kernel-4"),
+        );
+        assert_well_formed(
+            &generate_test_output(&OaiDcThoth {}, None),
+            "dc",
+            Some("http://www.openarchives.org/OAI/2.0/oai_dc/"),
+        );
+        assert_well_formed(
+            &generate_test_output(&ModsThoth {}, None),
+            "mods",
+            Some("http://www.loc.gov/mods/v3"),
+        );
+    }
+
+    #[test]
+    fn test_streamed_onix_matches_generated() {
+        let works = [test_work(), test_work()];
+        assert_eq!(
+            without_sent_date(&stream_test_output::<Onix3Thoth>(None)),
+            without_sent_date(&Onix3Thoth {}.generate(&works, None).unwrap())
+        );
+        // ONIX 2.1 does not list a product's websites in a fixed order
+        let streamed = stream_test_output::<Onix21EbscoHost>(Some(DOCTYPE_ONIX21_REF));
+        let generated = Onix21EbscoHost {}
+            .generate(&works, Some(DOCTYPE_ONIX21_REF))
+            .unwrap();
+        assert!(streamed.starts_with(&format!("{}{}", XML_DECLARATION, DOCTYPE_ONIX21_REF)));
+        let mut streamed = without_sent_date(&streamed);
+        let mut generated = without_sent_date(&generated);
+        streamed.sort_unstable();
+        generated.sort_unstable();
+        assert_eq!(streamed, generated);
+    }
+
+    #[test]
+    fn test_streamed_marc21xml_matches_generated() {
+        let streamed = stream_test_output::<Marc21XmlThoth>(None);
+        assert_eq!(
+            streamed,
+            Marc21XmlThoth {}
+                .generate(&[test_work(), test_work()], None)
+                .unwrap()
+        );
+        assert_valid(&streamed, "marc21xml/MARC21slim.xsd");
+    }
+
+    #[test]
+    fn test_streamed_xml_abort_marker() {
+        let mut writer = XmlStreamWriter::<Onix3Thoth>::new(None);
+        writer.write_page(&[test_work()]).unwrap();
+        let marker = writer.abort(&ThothError::InternalError(
+            "Page -- unavailable".to_string(),
+        ));
+        assert_eq!(
+            String::from_utf8(marker).unwrap().trim(),
+            "<!-- Export aborted: Internal error: Page - - unavailable -->"
+        );
+    }
+
+    #[test]
+    fn test_onix3_specifications_validate_against_schema() {
+        assert_valid(
+            &generate_test_output(&Onix3ProjectMuse {}, None),
+            ONIX3_SCHEMA,
+        );
+        assert_valid(&generate_test_output(&Onix3Oapen {}, None), ONIX3_SCHEMA);
+        assert_valid(&generate_test_output(&Onix3Jstor {}, None), ONIX3_SCHEMA);
+        assert_valid(&generate_test_output(&Onix3Thoth {}, None), ONIX3_SCHEMA);
+        assert_valid(
+            &generate_test_output(&Onix3GoogleBooks {}, None),
+            ONIX3_SCHEMA,
+        );
+        assert_valid(
+            &generate_test_output(&Onix3ProquestEbrary {}, None),
+            ONIX3_SCHEMA,
+        );
+    }
+
+    #[test]
+    fn test_onix21_specifications_validate_against_schema() {
+        assert_valid(
+            &generate_test_output(&Onix21EbscoHost {}, Some(DOCTYPE_ONIX21_REF)),
+            "onix_2.1/ONIX_BookProduct_Release2.1_reference.xsd",
+        );
+    }
+
+    #[test]
+    fn test_marc21xml_specifications_validate_against_schema() {
+        assert_valid(
+            &generate_test_output(&Marc21XmlThoth {}, None),
+            "marc21xml/MARC21slim.xsd",
+        );
+    }
+
+    #[test]
+    fn test_crossref_specifications_validate_against_schema() {
+        assert_valid(
+            &generate_test_output(&CrossrefThoth {}, None),
+            "crossref/crossref5.3.1.xsd",
+        );
+    }
+
+    #[test]
+    fn test_datacite_specifications_validate_against_schema() {
+        assert_valid(
+            &generate_test_output(&DataCiteThoth {}, None),
+            "datacite/kernel-4.4/metadata.xsd",
+        );
+    }
+
+    #[test]
+    fn test_oai_dc_specifications_validate_against_schema() {
+        assert_valid(
+            &generate_test_output(&OaiDcThoth {}, None),
+            "oai_dc/oai_dc.xsd",
+        );
+    }
+
+    #[test]
+    fn test_mods_specifications_validate_against_schema() {
+        assert_valid(
+            &generate_test_output(&ModsThoth {}, None),
+            "mods/mods-3-7.xsd",
+        );
+    }
+}
@@ -0,0 +1,592 @@
+use std::collections::HashMap;
+use std::io::Write;
+use thoth_api::model::ORCID_DOMAIN;
+use thoth_client::{
+    LanguageRelation, SubjectType, Work, WorkContributions, WorkIssues, WorkLanguages, WorkType,
+};
+use xml::writer::{EventWriter, XmlEvent};
+
+use super::{write_attributed_element, write_element_block, write_start_element, XmlSpecification};
+use crate::marc21::relator;
+use crate::record::RequiredFields;
+use crate::xml::{write_full_element_block, XmlElementBlock};
+use thoth_errors::{ThothError, ThothResult};
+
+pub struct ModsThoth {}
+
+const MODS_NS: &str = "http://www.loc.gov/mods/v3";
+const MODS_SCHEMA_LOCATION: &str =
+    "http://www.loc.gov/mods/v3 http://www.loc.gov/standards/mods/v3/mods-3-7.xsd";
+
+fn mods_ns_map() -> HashMap<String, String> {
+    let mut ns_map: HashMap<String, String> = HashMap::new();
+    ns_map.insert("".to_string(), MODS_NS.to_string());
+    ns_map.insert(
+        "xsi".to_string(),
+        "http://www.w3.org/2001/XMLSchema-instance".to_string(),
+    );
+    ns_map
+}
+
+impl XmlSpecification for ModsThoth {
+    fn handle_event<W: Write>(w: &mut EventWriter<W>, works: &[Work]) -> ThothResult<()> {
+        match works.len() {
+            0 => Err(ThothError::IncompleteMetadataRecord(
+                "mods::thoth".to_string(),
+                "Not enough data".to_string(),
+            )),
+            1 => XmlElementBlock::<ModsThoth>::xml_element(works.first().unwrap(), w),
+            _ => {
+                let mut attr_map: HashMap<&str, &str> = HashMap::new();
+                attr_map.insert("xsi:schemaLocation", MODS_SCHEMA_LOCATION);
+                write_full_element_block(
+                    "modsCollection",
+                    Some(mods_ns_map()),
+                    Some(attr_map),
+                    w,
+                    |w| {
+                        for work in works.iter() {
+                            XmlElementBlock::<ModsThoth>::xml_element(work, w).ok();
+                        }
+                        Ok(())
+                    },
+                )
+            }
+        }
+    }
+}
+
+impl RequiredFields for ModsThoth {
+    const SPECIFICATION_ID: &'static str = "mods::thoth";
+}
+
+impl XmlElementBlock<ModsThoth> for Work {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+        attr_map.insert("version", "3.7");
+        attr_map.insert("xsi:schemaLocation", MODS_SCHEMA_LOCATION);
+
+        let mut contributions: Vec<&WorkContributions> = self.contributions.iter().collect();
+        contributions.sort_by_key(|c| c.contribution_ordinal);
+
+        write_full_element_block("mods", Some(mods_ns_map()), Some(attr_map), w, |w| {
+            write_element_block("titleInfo", w, |w| {
+                write_attributed_element("title", &[], &self.title, w)?;
+                if let Some(subtitle) = &self.subtitle {
+                    write_attributed_element("subTitle", &[], subtitle, w)?;
+                }
+                Ok(())
+            })?;
+            for contribution in &contributions {
+                XmlElementBlock::<ModsThoth>::xml_element(*contribution, w)?;
+            }
+            write_attributed_element("typeOfResource", &[], "text", w)?;
+            match self.work_type {
+                WorkType::MONOGRAPH
+                | WorkType::EDITED_BOOK
+                | WorkType::TEXTBOOK
+                | WorkType::BOOK_SET => {
+                    write_attributed_element("genre", &[("authority", "marcgt")], "book", w)?
+                }
+                WorkType::JOURNAL_ISSUE => {
+                    write_attributed_element("genre", &[("authority", "marcgt")], "periodical", w)?
+                }
+                // There is no MARC genre term for a chapter; its parent book is the "host" item
+                WorkType::BOOK_CHAPTER => {}
+                WorkType::Other(_) => unreachable!(),
+            }
+            write_start_element("originInfo", &[("eventType", "publication")], w)?;
+            if let Some(place) = &self.place {
+                write_element_block("place", w, |w| {
+                    write_attributed_element("placeTerm", &[("type", "text")], place, w)
+                })?;
+            }
+            write_attributed_element("publisher", &[], &self.imprint.publisher.publisher_name, w)?;
+            if let Some(date) = self.publication_date {
+                write_attributed_element(
+                    "dateIssued",
+                    &[("encoding", "w3cdtf"), ("keyDate", "yes")],
+                    &date.format("%Y-%m-%d").to_string(),
+                    w,
+                )?;
+            }
+            if self.edition > 1 {
+                write_attributed_element("edition", &[], &self.edition.to_string(), w)?;
+            }
+            w.write(XmlEvent::end_element())?;
+            // Languages the work was translated from belong to the original, described below
+            for language in self
+                .languages
+                .iter()
+                .filter(|l| l.language_relation != LanguageRelation::TRANSLATED_FROM)
+            {
+                XmlElementBlock::<ModsThoth>::xml_element(language, w)?;
+            }
+            if let Some(page_count) = self.page_count {
+                write_element_block("physicalDescription", w, |w| {
+                    write_attributed_element(
+                        "extent",
+                        &[("unit", "pages")],
+                        &page_count.to_string(),
+                        w,
+                    )
+                })?;
+            }
+            if let Some(labstract) = self.long_abstract.as_ref().or(self.short_abstract.as_ref()) {
+                write_attributed_element("abstract", &[], labstract, w)?;
+            }
+            let mut subjects: Vec<_> = self.subjects.iter().collect();
+            subjects.sort_by_key(|s| s.subject_ordinal);
+            for subject in subjects {
+                match subject.subject_type {
+                    SubjectType::KEYWORD | SubjectType::CUSTOM => {
+                        write_element_block("subject", w, |w| {
+                            write_attributed_element("topic", &[], &subject.subject_code, w)
+                        })?
+                    }
+                    SubjectType::BIC
+                    | SubjectType::BISAC
+                    | SubjectType::THEMA
+                    | SubjectType::LCC => write_attributed_element(
+                        "classification",
+                        &[("authority", classification_authority(&subject.subject_type))],
+                        &subject.subject_code,
+                        w,
+                    )?,
+                    SubjectType::Other(_) => unreachable!(),
+                }
+            }
+            for issue in &self.issues {
+                XmlElementBlock::<ModsThoth>::xml_element(issue, w)?;
+            }
+            let original_languages: Vec<&WorkLanguages> = self
+                .languages
+                .iter()
+                .filter(|l| l.language_relation == LanguageRelation::TRANSLATED_FROM)
+                .collect();
+            if !original_languages.is_empty() {
+                write_start_element("relatedItem", &[("type", "original")], w)?;
+                for language in original_languages {
+                    XmlElementBlock::<ModsThoth>::xml_element(language, w)?;
+                }
+                w.write(XmlEvent::end_element())?;
+            }
+            if let Some(doi) = &self.doi {
+                write_attributed_element("identifier", &[("type", "doi")], &doi.to_string(), w)?;
+            }
+            for isbn in self.publications.iter().filter_map(|p| p.isbn.as_ref()) {
+                write_attributed_element("identifier", &[("type", "isbn")], &isbn.to_string(), w)?;
+            }
+            write_attributed_element(
+                "identifier",
+                &[("type", "uri")],
+                &format!("urn:uuid:{}", self.work_id),
+                w,
+            )?;
+            if let Some(landing_page) = &self.landing_page {
+                write_element_block("location", w, |w| {
+                    write_attributed_element(
+                        "url",
+                        &[("usage", "primary display")],
+                        landing_page,
+                        w,
+                    )
+                })?;
+            }
+            if let Some(license) = &self.license {
+                write_attributed_element(
+                    "accessCondition",
+                    &[("type", "use and reproduction")],
+                    license,
+                    w,
+                )?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl XmlElementBlock<ModsThoth> for WorkContributions {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let (role_term, role_code) = relator(&self.contribution_type);
+        write_start_element("name", &[("type", "personal")], w)?;
+        match &self.first_name {
+            Some(first_name) => {
+                write_attributed_element("namePart", &[("type", "given")], first_name, w)?;
+                write_attributed_element("namePart", &[("type", "family")], &self.last_name, w)?;
+            }
+            None => write_attributed_element("namePart", &[], &self.full_name, w)?,
+        }
+        write_attributed_element("displayForm", &[], &self.full_name, w)?;
+        let mut affiliations: Vec<_> = self.affiliations.iter().collect();
+        affiliations.sort_by_key(|a| a.affiliation_ordinal);
+        for affiliation in affiliations {
+            write_attributed_element(
+                "affiliation",
+                &[],
+                &affiliation.institution.institution_name,
+                w,
+            )?;
+        }
+        write_element_block("role", w, |w| {
+            write_attributed_element(
+                "roleTerm",
+                &[("type", "text"), ("authority", "marcrelator")],
+                role_term,
+                w,
+            )?;
+            write_attributed_element(
+                "roleTerm",
+                &[("type", "code"), ("authority", "marcrelator")],
+                role_code,
+                w,
+            )
+        })?;
+        if let Some(orcid) = &self.contributor.orcid {
+            write_attributed_element(
+                "nameIdentifier",
+                &[("type", "orcid")],
+                &format!("{}{}", ORCID_DOMAIN, orcid),
+                w,
+            )?;
+        }
+        w.write(XmlEvent::end_element()).map_err(|e| e.into())
+    }
+}
+
+impl XmlElementBlock<ModsThoth> for WorkLanguages {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let attributes: &[(&str, &str)] = match self.main_language {
+            true => &[("usage", "primary")],
+            false => &[],
+        };
+        write_start_element("language", attributes, w)?;
+        write_attributed_element(
+            "languageTerm",
+            &[("type", "code"), ("authority", "iso639-2b")],
+            &self.language_code.to_string().to_lowercase(),
+            w,
+        )?;
+        w.write(XmlEvent::end_element()).map_err(|e| e.into())
+    }
+}
+
+impl XmlElementBlock<ModsThoth> for WorkIssues {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_start_element("relatedItem", &[("type", "series")], w)?;
+        write_element_block("titleInfo", w, |w| {
+            write_attributed_element("title", &[], &self.series.series_name, w)
+        })?;
+        write_attributed_element(
+            "identifier",
+            &[("type", "issn")],
+            &self.series.issn_print,
+            w,
+        )?;
+        write_attributed_element(
+            "identifier",
+            &[("type", "issn")],
+            &self.series.issn_digital,
+            w,
+        )?;
+        write_element_block("part", w, |w| {
+            write_start_element("detail", &[("type", "volume")], w)?;
+            write_attributed_element("number", &[], &self.issue_ordinal.to_string(), w)?;
+            w.write(XmlEvent::end_element()).map_err(|e| e.into())
+        })?;
+        w.write(XmlEvent::end_element()).map_err(|e| e.into())
+    }
+}
+
+fn classification_authority(subject_type: &SubjectType) -> &'static str {
+    match subject_type {
+        SubjectType::BIC => "bicssc",
+        SubjectType::BISAC => "bisacsh",
+        SubjectType::THEMA => "thema",
+        SubjectType::LCC => "lcc",
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Testing note: XML nodes cannot be guaranteed to be output in the same order every time
+    // We therefore rely on `assert!(contains)` rather than `assert_eq!`
+    use super::*;
+    use std::str::FromStr;
+    use thoth_api::model::{Doi, Isbn, Orcid};
+    use thoth_client::{
+        ContributionType, LanguageCode, PublicationType, SeriesType, WorkContributionsAffiliations,
+        WorkContributionsAffiliationsInstitution, WorkContributionsContributor, WorkImprint,
+        WorkImprintPublisher, WorkIssuesSeries, WorkPublications, WorkStatus, WorkSubjects,
+    };
+    use uuid::Uuid;
+
+    fn generate_test_output(input: &impl XmlElementBlock<ModsThoth>) -> String {
+        // Helper function based on `XmlSpecification::generate`
+        let mut buffer = Vec::new();
+        let mut writer = xml::writer::EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(&mut buffer);
+        let wrapped_output = XmlElementBlock::<ModsThoth>::xml_element(input, &mut writer)
+            .map(|_| buffer)
+            .and_then(|xml| {
+                String::from_utf8(xml)
+                    .map_err(|_| ThothError::InternalError("Could not parse XML".to_string()))
+            });
+        assert!(wrapped_output.is_ok());
+        wrapped_output.unwrap()
+    }
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 2,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: None,
+            long_abstract: Some("Lorem ipsum dolor sit amet".to_string()),
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: Some(334),
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![WorkIssues {
+                issue_ordinal: 3,
+                series: WorkIssuesSeries {
+                    series_type: SeriesType::BOOK_SERIES,
+                    series_name: "Name of series".to_string(),
+                    issn_print: "1234-5678".to_string(),
+                    issn_digital: "8765-4321".to_string(),
+                    series_url: None,
+                },
+            }],
+            contributions: vec![WorkContributions {
+                contribution_type: ContributionType::AUTHOR,
+                first_name: Some("Author".to_string()),
+                last_name: "1".to_string(),
+                full_name: "Author 1".to_string(),
+                main_contribution: true,
+                biography: None,
+                contribution_ordinal: 1,
+                contributor: WorkContributionsContributor {
+                    orcid: Some(Orcid::from_str("https://orcid.org/0000-0002-0000-0001").unwrap()),
+                },
+                affiliations: vec![WorkContributionsAffiliations {
+                    position: None,
+                    affiliation_ordinal: 1,
+                    institution: WorkContributionsAffiliationsInstitution {
+                        institution_name: "University of Life".to_string(),
+                        ror: None,
+                    },
+                }],
+            }],
+            languages: vec![
+                WorkLanguages {
+                    language_code: LanguageCode::ENG,
+                    language_relation: LanguageRelation::TRANSLATED_INTO,
+                    main_language: true,
+                },
+                WorkLanguages {
+                    language_code: LanguageCode::SPA,
+                    language_relation: LanguageRelation::TRANSLATED_FROM,
+                    main_language: false,
+                },
+            ],
+            publications: vec![WorkPublications {
+                publication_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000002").unwrap(),
+                publication_type: PublicationType::PAPERBACK,
+                isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                prices: vec![],
+                locations: vec![],
+            }],
+            subjects: vec![
+                WorkSubjects {
+                    subject_code: "AAA000000".to_string(),
+                    subject_type: SubjectType::BISAC,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "keyword1".to_string(),
+                    subject_type: SubjectType::KEYWORD,
+                    subject_ordinal: 2,
+                },
+            ],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    #[test]
+    fn test_mods_thoth_contributions() {
+        let mut test_contribution = test_work().contributions.pop().unwrap();
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"<name type="personal">"#));
+        assert!(output.contains(r#"  <namePart type="given">Author</namePart>"#));
+        assert!(output.contains(r#"  <namePart type="family">1</namePart>"#));
+        assert!(output.contains(r#"  <displayForm>Author 1</displayForm>"#));
+        assert!(output.contains(r#"  <affiliation>University of Life</affiliation>"#));
+        assert!(output
+            .contains(r#"    <roleTerm type="text" authority="marcrelator">author</roleTerm>"#));
+        assert!(
+            output.contains(r#"    <roleTerm type="code" authority="marcrelator">aut</roleTerm>"#)
+        );
+        assert!(output.contains(
+            r#"  <nameIdentifier type="orcid">https://orcid.org/0000-0002-0000-0001</nameIdentifier>"#
+        ));
+
+        // Name is not split if there is no given name
+        test_contribution.contribution_type = ContributionType::TRANSLATOR;
+        test_contribution.first_name = None;
+        test_contribution.contributor.orcid = None;
+        test_contribution.affiliations.clear();
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <namePart>Author 1</namePart>"#));
+        assert!(!output.contains(r#"<namePart type="#));
+        assert!(
+            output.contains(r#"    <roleTerm type="code" authority="marcrelator">trl</roleTerm>"#)
+        );
+        assert!(!output.contains(r#"<affiliation>"#));
+        assert!(!output.contains(r#"<nameIdentifier"#));
+    }
+
+    #[test]
+    fn test_mods_thoth_issues() {
+        let output = generate_test_output(&test_work().issues[0]);
+        assert_eq!(
+            output,
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<relatedItem type="series">
+  <titleInfo>
+    <title>Name of series</title>
+  </titleInfo>
+  <identifier type="issn">1234-5678</identifier>
+  <identifier type="issn">8765-4321</identifier>
+  <part>
+    <detail type="volume">
+      <number>3</number>
+    </detail>
+  </part>
+</relatedItem>"#
+        );
+    }
+
+    #[test]
+    fn test_mods_thoth_work() {
+        let output = generate_test_output(&test_work());
+        assert!(output.contains(r#"xmlns="http://www.loc.gov/mods/v3""#));
+        assert!(output.contains(r#"version="3.7""#));
+        assert!(output.contains("  <titleInfo>\n    <title>Book Title</title>\n    <subTitle>Book Subtitle</subTitle>\n  </titleInfo>"));
+        assert!(output.contains(r#"  <typeOfResource>text</typeOfResource>"#));
+        assert!(output.contains(r#"  <genre authority="marcgt">book</genre>"#));
+        assert!(output.contains(r#"  <originInfo eventType="publication">"#));
+        assert!(output.contains(r#"      <placeTerm type="text">León, Spain</placeTerm>"#));
+        assert!(output.contains(r#"    <publisher>OA Editions</publisher>"#));
+        assert!(output.contains(
+            r#"    <dateIssued encoding="w3cdtf" keyDate="yes">1999-12-31</dateIssued>"#
+        ));
+        assert!(output.contains(r#"    <edition>2</edition>"#));
+        // The language translated from is described as that of the original
+        assert!(output.contains("  <language usage=\"primary\">\n    <languageTerm type=\"code\" authority=\"iso639-2b\">eng</languageTerm>\n  </language>"));
+        assert!(output.contains("  <relatedItem type=\"original\">\n    <language>\n      <languageTerm type=\"code\" authority=\"iso639-2b\">spa</languageTerm>\n    </language>\n  </relatedItem>"));
+        assert!(output.contains(r#"    <extent unit="pages">334</extent>"#));
+        assert!(output.contains(r#"  <abstract>Lorem ipsum dolor sit amet</abstract>"#));
+        assert!(
+            output.contains(r#"  <classification authority="bisacsh">AAA000000</classification>"#)
+        );
+        assert!(output.contains("  <subject>\n    <topic>keyword1</topic>\n  </subject>"));
+        assert!(output.contains(r#"  <relatedItem type="series">"#));
+        assert!(output.contains(r#"  <identifier type="doi">10.00001/BOOK.0001</identifier>"#));
+        assert!(output.contains(r#"  <identifier type="isbn">978-3-16-148410-0</identifier>"#));
+        assert!(output.contains(
+            r#"  <identifier type="uri">urn:uuid:00000000-0000-0000-aaaa-000000000001</identifier>"#
+        ));
+        assert!(output.contains(r#"    <url usage="primary display">https://www.book.com</url>"#));
+        assert!(output.contains(r#"  <accessCondition type="use and reproduction">https://creativecommons.org/licenses/by/4.0/</accessCondition>"#));
+
+        // Remove all optional fields
+        let mut test_work = test_work();
+        test_work.work_type = WorkType::BOOK_CHAPTER;
+        test_work.subtitle = None;
+        test_work.edition = 1;
+        test_work.doi = None;
+        test_work.publication_date = None;
+        test_work.license = None;
+        test_work.long_abstract = None;
+        test_work.place = None;
+        test_work.page_count = None;
+        test_work.landing_page = None;
+        test_work.issues.clear();
+        test_work.languages.clear();
+        test_work.publications.clear();
+        test_work.subjects.clear();
+        let output = generate_test_output(&test_work);
+        assert!(!output.contains(r#"<subTitle>"#));
+        assert!(!output.contains(r#"<genre"#));
+        assert!(!output.contains(r#"<place>"#));
+        assert!(!output.contains(r#"<dateIssued"#));
+        assert!(!output.contains(r#"<edition>"#));
+        assert!(!output.contains(r#"<language"#));
+        assert!(!output.contains(r#"<physicalDescription>"#));
+        assert!(!output.contains(r#"<abstract>"#));
+        assert!(!output.contains(r#"<subject>"#));
+        assert!(!output.contains(r#"<classification"#));
+        assert!(!output.contains(r#"<relatedItem"#));
+        assert!(!output.contains(r#"<identifier type="doi">"#));
+        assert!(!output.contains(r#"<identifier type="isbn">"#));
+        assert!(!output.contains(r#"<location>"#));
+        assert!(!output.contains(r#"<accessCondition"#));
+        assert!(output.contains(r#"    <publisher>OA Editions</publisher>"#));
+    }
+
+    #[test]
+    fn test_mods_thoth_works() {
+        let output = ModsThoth {}
+            .generate(&[test_work(), test_work()], None)
+            .unwrap();
+        assert!(output.contains("<modsCollection"));
+        assert_eq!(output.matches("<mods ").count(), 2);
+
+        assert_eq!(
+            ModsThoth {}.generate(&[], None).unwrap_err().to_string(),
+            "Could not generate mods::thoth: Not enough data".to_string()
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+use std::io::Write;
+use thoth_api::model::DOI_DOMAIN;
+use thoth_client::{
+    ContributionType, LanguageRelation, SubjectType, Work, WorkContributions, WorkType,
+};
+use xml::writer::EventWriter;
+
+use super::{write_attributed_element, write_element_block, XmlSpecification};
+use crate::record::RequiredFields;
+use crate::xml::{write_full_element_block, XmlElementBlock};
+use thoth_errors::{ThothError, ThothResult};
+
+pub struct OaiDcThoth {}
+
+const OAI_DC_NS: &str = "http://www.openarchives.org/OAI/2.0/oai_dc/";
+const OAI_DC_SCHEMA_LOCATION: &str =
+    "http://www.openarchives.org/OAI/2.0/oai_dc/ http://www.openarchives.org/OAI/2.0/oai_dc.xsd";
+
+impl XmlSpecification for OaiDcThoth {
+    fn handle_event<W: Write>(w: &mut EventWriter<W>, works: &[Work]) -> ThothResult<()> {
+        match works.len() {
+            0 => Err(ThothError::IncompleteMetadataRecord(
+                "oai_dc::thoth".to_string(),
+                "Not enough data".to_string(),
+            )),
+            1 => XmlElementBlock::<OaiDcThoth>::xml_element(works.first().unwrap(), w),
+            // An oai_dc document describes a single resource, so several are wrapped
+            // in a `records` element
+            _ => write_element_block("records", w, |w| {
+                for work in works.iter() {
+                    XmlElementBlock::<OaiDcThoth>::xml_element(work, w).ok();
+                }
+                Ok(())
+            }),
+        }
+    }
+}
+
+impl RequiredFields for OaiDcThoth {
+    const SPECIFICATION_ID: &'static str = "oai_dc::thoth";
+}
+
+impl XmlElementBlock<OaiDcThoth> for Work {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let mut ns_map: HashMap<String, String> = HashMap::new();
+        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+
+        ns_map.insert("oai_dc".to_string(), OAI_DC_NS.to_string());
+        ns_map.insert(
+            "dc".to_string(),
+            "http://purl.org/dc/elements/1.1/".to_string(),
+        );
+        ns_map.insert(
+            "xsi".to_string(),
+            "http://www.w3.org/2001/XMLSchema-instance".to_string(),
+        );
+        attr_map.insert("xsi:schemaLocation", OAI_DC_SCHEMA_LOCATION);
+
+        let mut contributions: Vec<&WorkContributions> = self.contributions.iter().collect();
+        contributions.sort_by_key(|c| c.contribution_ordinal);
+
+        write_full_element_block("oai_dc:dc", Some(ns_map), Some(attr_map), w, |w| {
+            write_attributed_element("dc:title", &[], &self.full_title, w)?;
+            // Dublin Core has no notion of roles: authors are creators, everyone else contributes
+            for contribution in contributions
+                .iter()
+                .filter(|c| c.contribution_type == ContributionType::AUTHOR)
+            {
+                write_attributed_element("dc:creator", &[], &name(contribution), w)?;
+            }
+            for subject in self.subjects.iter().filter(|s| {
+                s.subject_type == SubjectType::KEYWORD || s.subject_type == SubjectType::CUSTOM
+            }) {
+                write_attributed_element("dc:subject", &[], &subject.subject_code, w)?;
+            }
+            if let Some(labstract) = self.long_abstract.as_ref().or(self.short_abstract.as_ref()) {
+                write_attributed_element("dc:description", &[], labstract, w)?;
+            }
+            write_attributed_element(
+                "dc:publisher",
+                &[],
+                &self.imprint.publisher.publisher_name,
+                w,
+            )?;
+            for contribution in contributions
+                .iter()
+                .filter(|c| c.contribution_type != ContributionType::AUTHOR)
+            {
+                write_attributed_element("dc:contributor", &[], &name(contribution), w)?;
+            }
+            if let Some(date) = self.publication_date {
+                write_attributed_element("dc:date", &[], &date.format("%Y-%m-%d").to_string(), w)?;
+            }
+            // DCMI Type Vocabulary, followed by a more specific free-text type
+            write_attributed_element("dc:type", &[], "Text", w)?;
+            write_attributed_element(
+                "dc:type",
+                &[],
+                match self.work_type {
+                    WorkType::BOOK_CHAPTER => "Book chapter",
+                    WorkType::JOURNAL_ISSUE => "Journal issue",
+                    WorkType::MONOGRAPH
+                    | WorkType::EDITED_BOOK
+                    | WorkType::TEXTBOOK
+                    | WorkType::BOOK_SET => "Book",
+                    WorkType::Other(_) => unreachable!(),
+                },
+                w,
+            )?;
+            write_attributed_element(
+                "dc:identifier",
+                &[],
+                &format!("urn:uuid:{}", self.work_id),
+                w,
+            )?;
+            if let Some(doi) = &self.doi {
+                write_attributed_element(
+                    "dc:identifier",
+                    &[],
+                    &format!("{}{}", DOI_DOMAIN, doi),
+                    w,
+                )?;
+            }
+            for isbn in self.publications.iter().filter_map(|p| p.isbn.as_ref()) {
+                write_attributed_element("dc:identifier", &[], &format!("urn:isbn:{}", isbn), w)?;
+            }
+            if let Some(landing_page) = &self.landing_page {
+                write_attributed_element("dc:identifier", &[], landing_page, w)?;
+            }
+            // The language of the resource itself, so not any language it was translated from
+            for language in self
+                .languages
+                .iter()
+                .filter(|l| l.language_relation != LanguageRelation::TRANSLATED_FROM)
+            {
+                write_attributed_element(
+                    "dc:language",
+                    &[],
+                    &language.language_code.to_string().to_lowercase(),
+                    w,
+                )?;
+            }
+            for issue in &self.issues {
+                write_attributed_element("dc:relation", &[], &issue.series.series_name, w)?;
+                for issn in &[&issue.series.issn_print, &issue.series.issn_digital] {
+                    write_attributed_element("dc:relation", &[], &format!("urn:issn:{}", issn), w)?;
+                }
+            }
+            if let Some(license) = &self.license {
+                write_attributed_element("dc:rights", &[], license, w)?;
+            }
+            write_attributed_element("dc:rights", &[], &self.copyright_holder, w)
+        })
+    }
+}
+
+fn name(contribution: &WorkContributions) -> String {
+    match &contribution.first_name {
+        Some(first_name) => format!("{}, {}", contribution.last_name, first_name),
+        None => contribution.full_name.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Testing note: XML nodes cannot be guaranteed to be output in the same order every time
+    // We therefore rely on `assert!(contains)` rather than `assert_eq!`
+    use super::*;
+    use std::str::FromStr;
+    use thoth_api::model::{Doi, Isbn};
+    use thoth_client::{
+        LanguageCode, PublicationType, SeriesType, WorkContributionsContributor, WorkImprint,
+        WorkImprintPublisher, WorkIssues, WorkIssuesSeries, WorkLanguages, WorkPublications,
+        WorkStatus, WorkSubjects,
+    };
+    use uuid::Uuid;
+
+    fn generate_test_output(input: &impl XmlElementBlock<OaiDcThoth>) -> String {
+        // Helper function based on `XmlSpecification::generate`
+        let mut buffer = Vec::new();
+        let mut writer = xml::writer::EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(&mut buffer);
+        let wrapped_output = XmlElementBlock::<OaiDcThoth>::xml_element(input, &mut writer)
+            .map(|_| buffer)
+            .and_then(|xml| {
+                String::from_utf8(xml)
+                    .map_err(|_| ThothError::InternalError("Could not parse XML".to_string()))
+            });
+        assert!(wrapped_output.is_ok());
+        wrapped_output.unwrap()
+    }
+
+    fn test_contribution(
+        contribution_type: ContributionType,
+        first_name: Option<&str>,
+        last_name: &str,
+        contribution_ordinal: i64,
+    ) -> WorkContributions {
+        WorkContributions {
+            contribution_type,
+            first_name: first_name.map(|n| n.to_string()),
+            last_name: last_name.to_string(),
+            full_name: format!("{} {}", first_name.unwrap_or("Mx"), last_name),
+            main_contribution: true,
+            biography: None,
+            contribution_ordinal,
+            contributor: WorkContributionsContributor { orcid: None },
+            affiliations: vec![],
+        }
+    }
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: Some("Lorem ipsum".to_string()),
+            long_abstract: Some("Lorem ipsum dolor sit amet".to_string()),
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: None,
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: None,
+            lccn: None,
+            oclc: None,
+            cover_url: None,
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![WorkIssues {
+                issue_ordinal: 3,
+                series: WorkIssuesSeries {
+                    series_type: SeriesType::BOOK_SERIES,
+                    series_name: "Name of series".to_string(),
+                    issn_print: "1234-5678".to_string(),
+                    issn_digital: "8765-4321".to_string(),
+                    series_url: None,
+                },
+            }],
+            contributions: vec![
+                test_contribution(ContributionType::TRANSLATOR, Some("Translator"), "1", 3),
+                test_contribution(ContributionType::AUTHOR, None, "Author 2", 2),
+                test_contribution(ContributionType::AUTHOR, Some("Author"), "1", 1),
+            ],
+            languages: vec![
+                WorkLanguages {
+                    language_code: LanguageCode::ENG,
+                    language_relation: LanguageRelation::TRANSLATED_INTO,
+                    main_language: true,
+                },
+                WorkLanguages {
+                    language_code: LanguageCode::SPA,
+                    language_relation: LanguageRelation::TRANSLATED_FROM,
+                    main_language: false,
+                },
+            ],
+            publications: vec![WorkPublications {
+                publication_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000002").unwrap(),
+                publication_type: PublicationType::PAPERBACK,
+                isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                prices: vec![],
+                locations: vec![],
+            }],
+            subjects: vec![
+                WorkSubjects {
+                    subject_code: "AAA".to_string(),
+                    subject_type: SubjectType::BIC,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "keyword1".to_string(),
+                    subject_type: SubjectType::KEYWORD,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "Custom subject".to_string(),
+                    subject_type: SubjectType::CUSTOM,
+                    subject_ordinal: 1,
+                },
+            ],
+            fundings: vec![],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    #[test]
+    fn test_oai_dc_thoth_work() {
+        let output = generate_test_output(&test_work());
+        assert!(output.contains(r#"<oai_dc:dc"#));
+        assert!(output.contains(r#"xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/""#));
+        assert!(output.contains(r#"xmlns:dc="http://purl.org/dc/elements/1.1/""#));
+        assert!(output.contains(r#"  <dc:title>Book Title: Book Subtitle</dc:title>"#));
+        // Creators are listed in order, and only authors are creators
+        assert!(output.contains(
+            "  <dc:creator>1, Author</dc:creator>\n  <dc:creator>Mx Author 2</dc:creator>"
+        ));
+        assert!(output.contains(r#"  <dc:contributor>1, Translator</dc:contributor>"#));
+        assert!(!output.contains(r#"<dc:creator>1, Translator</dc:creator>"#));
+        // Classification codes are not meaningful as free-text subjects
+        assert!(output.contains(r#"  <dc:subject>keyword1</dc:subject>"#));
+        assert!(output.contains(r#"  <dc:subject>Custom subject</dc:subject>"#));
+        assert!(!output.contains(r#"  <dc:subject>AAA</dc:subject>"#));
+        // Long abstract takes precedence over short abstract
+        assert!(output.contains(r#"  <dc:description>Lorem ipsum dolor sit amet</dc:description>"#));
+        assert!(output.contains(r#"  <dc:publisher>OA Editions</dc:publisher>"#));
+        assert!(output.contains(r#"  <dc:date>1999-12-31</dc:date>"#));
+        assert!(output.contains("  <dc:type>Text</dc:type>\n  <dc:type>Book</dc:type>"));
+        assert!(output.contains(
+            r#"  <dc:identifier>urn:uuid:00000000-0000-0000-aaaa-000000000001</dc:identifier>"#
+        ));
+        assert!(output
+            .contains(r#"  <dc:identifier>https://doi.org/10.00001/BOOK.0001</dc:identifier>"#));
+        assert!(output.contains(r#"  <dc:identifier>urn:isbn:978-3-16-148410-0</dc:identifier>"#));
+        assert!(output.contains(r#"  <dc:identifier>https://www.book.com</dc:identifier>"#));
+        // Only the language of the work itself is given
+        assert!(output.contains(r#"  <dc:language>eng</dc:language>"#));
+        assert!(!output.contains(r#"  <dc:language>spa</dc:language>"#));
+        assert!(output.contains(r#"  <dc:relation>Name of series</dc:relation>"#));
+        assert!(output.contains(r#"  <dc:relation>urn:issn:1234-5678</dc:relation>"#));
+        assert!(output.contains(r#"  <dc:relation>urn:issn:8765-4321</dc:relation>"#));
+        assert!(output
+            .contains(r#"  <dc:rights>https://creativecommons.org/licenses/by/4.0/</dc:rights>"#));
+        assert!(output.contains(r#"  <dc:rights>Author 1</dc:rights>"#));
+
+        // Remove all optional fields
+        let mut test_work = test_work();
+        test_work.work_type = WorkType::BOOK_CHAPTER;
+        test_work.doi = None;
+        test_work.publication_date = None;
+        test_work.license = None;
+        test_work.short_abstract = None;
+        test_work.long_abstract = None;
+        test_work.landing_page = None;
+        test_work.issues.clear();
+        test_work.contributions.clear();
+        test_work.languages.clear();
+        test_work.publications.clear();
+        test_work.subjects.clear();
+        let output = generate_test_output(&test_work);
+        assert!(output.contains("  <dc:type>Text</dc:type>\n  <dc:type>Book chapter</dc:type>"));
+        assert!(!output.contains(r#"<dc:creator>"#));
+        assert!(!output.contains(r#"<dc:contributor>"#));
+        assert!(!output.contains(r#"<dc:subject>"#));
+        assert!(!output.contains(r#"<dc:description>"#));
+        assert!(!output.contains(r#"<dc:date>"#));
+        assert!(!output.contains(r#"<dc:language>"#));
+        assert!(!output.contains(r#"<dc:relation>"#));
+        assert!(!output.contains(r#"<dc:identifier>https://"#));
+        assert!(!output.contains(r#"<dc:identifier>urn:isbn:"#));
+        // The work ID and copyright holder are always present
+        assert!(output.contains(
+            r#"  <dc:identifier>urn:uuid:00000000-0000-0000-aaaa-000000000001</dc:identifier>"#
+        ));
+        assert!(output.contains(r#"  <dc:rights>Author 1</dc:rights>"#));
+    }
+
+    #[test]
+    fn test_oai_dc_thoth_works() {
+        let output = OaiDcThoth {}
+            .generate(&[test_work(), test_work()], None)
+            .unwrap();
+        assert!(output.contains("<records>"));
+        assert_eq!(output.matches("<oai_dc:dc").count(), 2);
+
+        assert_eq!(
+            OaiDcThoth {}.generate(&[], None).unwrap_err().to_string(),
+            "Could not generate oai_dc::thoth: Not enough data".to_string()
+        );
+    }
+}
@@ -2,71 +2,82 @@ use chrono::Utc;
 use std::collections::HashMap;
 use std::io::Write;
 use thoth_client::{
-    ContributionType, CurrencyCode, LanguageRelation, PublicationType, SubjectType, Work,
-    WorkContributions, WorkIssues, WorkLanguages, WorkPublications, WorkStatus, WorkSubjects,
+    ContributionType, CurrencyCode, DeletedWork, LanguageRelation, PublicationType, SubjectType,
+    Work, WorkContributions, WorkIssues, WorkLanguages, WorkPublications, WorkStatus, WorkSubjects,
 };
 use xml::writer::{EventWriter, XmlEvent};
 
-use super::{write_element_block, XmlElement, XmlSpecification};
-use crate::xml::{write_full_element_block, XmlElementBlock};
+use super::{
+    handle_stream_event, write_element_block, write_start_element, XmlElement, XmlSpecification,
+    XmlStreamSpecification,
+};
+use crate::record::RequiredFields;
+use crate::xml::{full_text_url, XmlElementBlock};
 use thoth_errors::{ThothError, ThothResult};
 
 pub struct Onix21EbscoHost {}
 
 impl XmlSpecification for Onix21EbscoHost {
     fn handle_event<W: Write>(w: &mut EventWriter<W>, works: &[Work]) -> ThothResult<()> {
-        write_full_element_block("ONIXMessage", None, None, w, |w| {
-            write_element_block("Header", w, |w| {
-                write_element_block("FromCompany", w, |w| {
-                    w.write(XmlEvent::Characters("Thoth")).map_err(|e| e.into())
-                })?;
-                write_element_block("FromEmail", w, |w| {
-                    w.write(XmlEvent::Characters("info@thoth.pub"))
-                        .map_err(|e| e.into())
-                })?;
-                write_element_block("SentDate", w, |w| {
-                    w.write(XmlEvent::Characters(
-                        &Utc::today().format("%Y%m%d").to_string(),
-                    ))
+        handle_stream_event::<Self, W>(w, works, &[])
+    }
+}
+
+impl XmlStreamSpecification for Onix21EbscoHost {
+    fn write_header<W: Write>(w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_start_element("ONIXMessage", &[], w)?;
+        write_element_block("Header", w, |w| {
+            write_element_block("FromCompany", w, |w| {
+                w.write(XmlEvent::Characters("Thoth")).map_err(|e| e.into())
+            })?;
+            write_element_block("FromEmail", w, |w| {
+                w.write(XmlEvent::Characters("info@thoth.pub"))
                     .map_err(|e| e.into())
-                })
             })?;
-
-            match works.len() {
-                0 => Err(ThothError::IncompleteMetadataRecord(
-                    "onix_2.1::ebsco_host".to_string(),
-                    "Not enough data".to_string(),
-                )),
-                1 => XmlElementBlock::<Onix21EbscoHost>::xml_element(works.first().unwrap(), w),
-                _ => {
-                    for work in works.iter() {
-                        XmlElementBlock::<Onix21EbscoHost>::xml_element(work, w).ok();
-                    }
-                    Ok(())
-                }
-            }
+            write_element_block("SentDate", w, |w| {
+                w.write(XmlEvent::Characters(
+                    &Utc::today().format("%Y%m%d").to_string(),
+                ))
+                .map_err(|e| e.into())
+            })
         })
     }
+
+    fn write_work<W: Write>(w: &mut EventWriter<W>, work: &Work) -> ThothResult<()> {
+        XmlElementBlock::<Onix21EbscoHost>::xml_element(work, w)
+    }
+
+    fn write_footer<W: Write>(
+        w: &mut EventWriter<W>,
+        _deleted_works: &[DeletedWork],
+    ) -> ThothResult<()> {
+        w.write(XmlEvent::end_element()).map_err(|e| e.into())
+    }
+}
+
+impl RequiredFields for Onix21EbscoHost {
+    const SPECIFICATION_ID: &'static str = "onix_2.1::ebsco_host";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        // We only submit PDFs and EPUBs to EBSCO Host, so don't
+        // generate ONIX for works which do not have either
+        if full_text_url(work, PublicationType::PDF).is_none()
+            && full_text_url(work, PublicationType::EPUB).is_none()
+        {
+            missing.push("No PDF or EPUB URL");
+        }
+        missing
+    }
 }
 
 impl XmlElementBlock<Onix21EbscoHost> for Work {
     fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        Onix21EbscoHost::check_required_fields(self)?;
         let work_id = format!("urn:uuid:{}", self.work_id.to_string());
         let (main_isbn, isbns) = get_publications_data(&self.publications);
-        // We only submit PDFs and EPUBs to EBSCO Host, so don't
-        // generate ONIX for works which do not have either
-        let pdf_url = self
-            .publications
-            .iter()
-            .find(|p| p.publication_type.eq(&PublicationType::PDF) && !p.locations.is_empty())
-            .and_then(|p| p.locations.iter().find(|l| l.canonical))
-            .and_then(|l| l.full_text_url.as_ref());
-        let epub_url = self
-            .publications
-            .iter()
-            .find(|p| p.publication_type.eq(&PublicationType::EPUB) && !p.locations.is_empty())
-            .and_then(|p| p.locations.iter().find(|l| l.canonical))
-            .and_then(|l| l.full_text_url.as_ref());
+        let pdf_url = full_text_url(self, PublicationType::PDF);
+        let epub_url = full_text_url(self, PublicationType::EPUB);
         if pdf_url.is_some() || epub_url.is_some() {
             write_element_block("Product", w, |w| {
                 write_element_block("RecordReference", w, |w| {
@@ -872,6 +883,8 @@ mod tests {
             height_in: None,
             page_count: Some(334),
             page_breakdown: None,
+            first_page: None,
+            last_page: None,
             image_count: None,
             table_count: None,
             audio_count: None,
@@ -882,9 +895,14 @@ mod tests {
             oclc: None,
             cover_url: Some("https://www.book.com/cover".to_string()),
             cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
             imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
                 imprint_name: "OA Editions Imprint".to_string(),
                 publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
                     publisher_name: "OA Editions".to_string(),
                     publisher_url: Some("https://www.publisher.com".to_string()),
                 },
@@ -933,6 +951,8 @@ mod tests {
             ],
             subjects: vec![],
             fundings: vec![],
+            relations: vec![],
+            parents: vec![],
         };
 
         // Test standard output
@@ -0,0 +1,1192 @@
+use chrono::Utc;
+use std::collections::HashMap;
+use std::io::Write;
+use thoth_client::{
+    ContributionType, DeletedWork, LanguageRelation, PublicationType, SubjectType, Work,
+    WorkContributions, WorkLanguages, WorkPublications, WorkStatus,
+};
+use xml::writer::{EventWriter, XmlEvent};
+
+use super::{
+    handle_stream_event, write_element_block, write_start_element, XmlElement, XmlSpecification,
+    XmlStreamSpecification,
+};
+use crate::record::RequiredFields;
+use crate::xml::{
+    full_text_url, onix3_notification_type, onix3_unavailability, write_full_element_block,
+    write_onix3_deletion, XmlElementBlock,
+};
+use thoth_errors::{ThothError, ThothResult};
+
+pub struct Onix3GoogleBooks {}
+
+impl XmlSpecification for Onix3GoogleBooks {
+    fn handle_event<W: Write>(w: &mut EventWriter<W>, works: &[Work]) -> ThothResult<()> {
+        Self::handle_event_with_deletions(w, works, &[])
+    }
+
+    fn handle_event_with_deletions<W: Write>(
+        w: &mut EventWriter<W>,
+        works: &[Work],
+        deleted_works: &[DeletedWork],
+    ) -> ThothResult<()> {
+        handle_stream_event::<Self, W>(w, works, deleted_works)
+    }
+}
+
+impl XmlStreamSpecification for Onix3GoogleBooks {
+    fn write_header<W: Write>(w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_start_element(
+            "ONIXMessage",
+            &[
+                ("release", "3.0"),
+                ("xmlns", "http://ns.editeur.org/onix/3.0/reference"),
+            ],
+            w,
+        )?;
+        write_element_block("Header", w, |w| {
+            write_element_block("Sender", w, |w| {
+                write_element_block("SenderName", w, |w| {
+                    w.write(XmlEvent::Characters("Thoth")).map_err(|e| e.into())
+                })?;
+                write_element_block("EmailAddress", w, |w| {
+                    w.write(XmlEvent::Characters("info@thoth.pub"))
+                        .map_err(|e| e.into())
+                })
+            })?;
+            write_element_block("SentDateTime", w, |w| {
+                w.write(XmlEvent::Characters(
+                    &Utc::now().format("%Y%m%dT%H%M%S").to_string(),
+                ))
+                .map_err(|e| e.into())
+            })
+        })
+    }
+
+    fn write_work<W: Write>(w: &mut EventWriter<W>, work: &Work) -> ThothResult<()> {
+        XmlElementBlock::<Onix3GoogleBooks>::xml_element(work, w)
+    }
+
+    fn write_footer<W: Write>(
+        w: &mut EventWriter<W>,
+        deleted_works: &[DeletedWork],
+    ) -> ThothResult<()> {
+        for deleted_work in deleted_works {
+            XmlElementBlock::<Onix3GoogleBooks>::xml_element(deleted_work, w)?;
+        }
+        w.write(XmlEvent::end_element()).map_err(|e| e.into())
+    }
+}
+
+impl RequiredFields for Onix3GoogleBooks {
+    const SPECIFICATION_ID: &'static str = "onix_3.0::google_books";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        // Google Books identifies products by ISBN, and the product described is the PDF
+        if get_publications_data(&work.publications).0.is_none() {
+            missing.push("Missing PDF ISBN");
+        }
+        // Only open access titles are listed free of charge; others need the PDF's prices
+        if work.license.is_none()
+            && !work
+                .publications
+                .iter()
+                .any(|p| p.publication_type.eq(&PublicationType::PDF) && !p.prices.is_empty())
+        {
+            missing.push("Missing Prices");
+        }
+        // We can only generate the document if there's a PDF
+        if full_text_url(work, PublicationType::PDF).is_none() {
+            missing.push("Missing PDF URL");
+        }
+        missing
+    }
+}
+
+impl XmlElementBlock<Onix3GoogleBooks> for Work {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        Onix3GoogleBooks::check_required_fields(self)?;
+        let work_id = format!("urn:uuid:{}", self.work_id);
+        let (main_isbn, isbns) = get_publications_data(&self.publications);
+        let main_isbn = main_isbn.unwrap_or_default();
+        let prices: Vec<(String, String)> = if self.license.is_some() {
+            vec![("0.00".to_string(), "USD".to_string())]
+        } else {
+            self.publications
+                .iter()
+                .filter(|p| p.publication_type.eq(&PublicationType::PDF))
+                .flat_map(|p| &p.prices)
+                .map(|p| {
+                    (
+                        format!("{:.2}", p.unit_price),
+                        format!("{:?}", p.currency_code),
+                    )
+                })
+                .collect()
+        };
+        if let Some(pdf_url) = full_text_url(self, PublicationType::PDF) {
+            write_element_block("Product", w, |w| {
+                write_element_block("RecordReference", w, |w| {
+                    w.write(XmlEvent::Characters(&work_id))
+                        .map_err(|e| e.into())
+                })?;
+                write_element_block("NotificationType", w, |w| {
+                    w.write(XmlEvent::Characters(onix3_notification_type(
+                        &self.work_status,
+                    )))
+                    .map_err(|e| e.into())
+                })?;
+                // 01 Publisher
+                write_element_block("RecordSourceType", w, |w| {
+                    w.write(XmlEvent::Characters("01")).map_err(|e| e.into())
+                })?;
+                write_element_block("ProductIdentifier", w, |w| {
+                    // 01 Proprietary
+                    write_element_block("ProductIDType", w, |w| {
+                        w.write(XmlEvent::Characters("01")).map_err(|e| e.into())
+                    })?;
+                    write_element_block("IDValue", w, |w| {
+                        w.write(XmlEvent::Characters(&work_id))
+                            .map_err(|e| e.into())
+                    })
+                })?;
+                write_element_block("ProductIdentifier", w, |w| {
+                    // 15 ISBN-13
+                    write_element_block("ProductIDType", w, |w| {
+                        w.write(XmlEvent::Characters("15")).map_err(|e| e.into())
+                    })?;
+                    write_element_block("IDValue", w, |w| {
+                        w.write(XmlEvent::Characters(&main_isbn))
+                            .map_err(|e| e.into())
+                    })
+                })?;
+                if let Some(doi) = &self.doi {
+                    write_element_block("ProductIdentifier", w, |w| {
+                        write_element_block("ProductIDType", w, |w| {
+                            w.write(XmlEvent::Characters("06")).map_err(|e| e.into())
+                        })?;
+                        write_element_block("IDValue", w, |w| {
+                            w.write(XmlEvent::Characters(&doi.to_string()))
+                                .map_err(|e| e.into())
+                        })
+                    })?;
+                }
+                write_element_block("DescriptiveDetail", w, |w| {
+                    // 00 Single-component retail product
+                    write_element_block("ProductComposition", w, |w| {
+                        w.write(XmlEvent::Characters("00")).map_err(|e| e.into())
+                    })?;
+                    // EB Digital download and online
+                    write_element_block("ProductForm", w, |w| {
+                        w.write(XmlEvent::Characters("EB")).map_err(|e| e.into())
+                    })?;
+                    // E107 PDF
+                    write_element_block("ProductFormDetail", w, |w| {
+                        w.write(XmlEvent::Characters("E107")).map_err(|e| e.into())
+                    })?;
+                    // 10 Text (eye-readable)
+                    write_element_block("PrimaryContentType", w, |w| {
+                        w.write(XmlEvent::Characters("10")).map_err(|e| e.into())
+                    })?;
+                    // 00 None
+                    write_element_block("EpubTechnicalProtection", w, |w| {
+                        w.write(XmlEvent::Characters("00")).map_err(|e| e.into())
+                    })?;
+                    // 02 Print, 03 Copy/paste, 04 Share
+                    for usage_type in ["02", "03", "04"] {
+                        write_element_block("EpubUsageConstraint", w, |w| {
+                            write_element_block("EpubUsageType", w, |w| {
+                                w.write(XmlEvent::Characters(usage_type))
+                                    .map_err(|e| e.into())
+                            })?;
+                            // 01 Permitted unlimited
+                            write_element_block("EpubUsageStatus", w, |w| {
+                                w.write(XmlEvent::Characters("01")).map_err(|e| e.into())
+                            })
+                        })?;
+                    }
+                    if let Some(license) = &self.license {
+                        write_element_block("EpubLicense", w, |w| {
+                            write_element_block("EpubLicenseName", w, |w| {
+                                w.write(XmlEvent::Characters("Creative Commons License"))
+                                    .map_err(|e| e.into())
+                            })?;
+                            write_element_block("EpubLicenseExpression", w, |w| {
+                                write_element_block("EpubLicenseExpressionType", w, |w| {
+                                    w.write(XmlEvent::Characters("02")).map_err(|e| e.into())
+                                })?;
+                                write_element_block("EpubLicenseExpressionLink", w, |w| {
+                                    w.write(XmlEvent::Characters(license)).map_err(|e| e.into())
+                                })
+                            })
+                        })?;
+                    }
+                    write_element_block("TitleDetail", w, |w| {
+                        // 01 Distinctive title (book)
+                        write_element_block("TitleType", w, |w| {
+                            w.write(XmlEvent::Characters("01")).map_err(|e| e.into())
+                        })?;
+                        write_element_block("TitleElement", w, |w| {
+                            // 01 Product
+                            write_element_block("TitleElementLevel", w, |w| {
+                                w.write(XmlEvent::Characters("01")).map_err(|e| e.into())
+                            })?;
+                            if let Some(subtitle) = &self.subtitle {
+                                write_element_block("TitleText", w, |w| {
+                                    w.write(XmlEvent::Characters(&self.title))
+                                        .map_err(|e| e.into())
+                                })?;
+                                write_element_block("Subtitle", w, |w| {
+                                    w.write(XmlEvent::Characters(subtitle))
+                                        .map_err(|e| e.into())
+                                })
+                            } else {
+                                write_element_block("TitleText", w, |w| {
+                                    w.write(XmlEvent::Characters(&self.full_title))
+                                        .map_err(|e| e.into())
+                                })
+                            }
+                        })
+                    })?;
+                    for contribution in &self.contributions {
+                        XmlElementBlock::<Onix3GoogleBooks>::xml_element(contribution, w).ok();
+                    }
+                    for language in &self.languages {
+                        XmlElementBlock::<Onix3GoogleBooks>::xml_element(language, w).ok();
+                    }
+                    if let Some(page_count) = self.page_count {
+                        write_element_block("Extent", w, |w| {
+                            // 00 Main content
+                            write_element_block("ExtentType", w, |w| {
+                                w.write(XmlEvent::Characters("00")).map_err(|e| e.into())
+                            })?;
+                            write_element_block("ExtentValue", w, |w| {
+                                w.write(XmlEvent::Characters(&page_count.to_string()))
+                                    .map_err(|e| e.into())
+                            })?;
+                            // 03 Pages
+                            write_element_block("ExtentUnit", w, |w| {
+                                w.write(XmlEvent::Characters("03")).map_err(|e| e.into())
+                            })
+                        })?;
+                    }
+                    for subject in &self.subjects {
+                        write_element_block("Subject", w, |w| {
+                            XmlElement::<Onix3GoogleBooks>::xml_element(&subject.subject_type, w)?;
+                            write_element_block("SubjectCode", w, |w| {
+                                w.write(XmlEvent::Characters(&subject.subject_code))
+                                    .map_err(|e| e.into())
+                            })
+                        })?;
+                    }
+                    Ok(())
+                })?;
+                write_element_block("CollateralDetail", w, |w| {
+                    if let Some(labstract) = &self.long_abstract {
+                        write_element_block("TextContent", w, |w| {
+                            let mut lang_fmt: HashMap<&str, &str> = HashMap::new();
+                            lang_fmt.insert("language", "eng");
+                            // 03 Description ("30 Abstract" not implemented in OAPEN)
+                            write_element_block("TextType", w, |w| {
+                                w.write(XmlEvent::Characters("03")).map_err(|e| e.into())
+                            })?;
+                            // 00 Unrestricted
+                            write_element_block("ContentAudience", w, |w| {
+                                w.write(XmlEvent::Characters("00")).map_err(|e| e.into())
+                            })?;
+                            write_full_element_block("Text", None, Some(lang_fmt), w, |w| {
+                                w.write(XmlEvent::Characters(labstract))
+                                    .map_err(|e| e.into())
+                            })
+                        })?;
+                    }
+                    if let Some(toc) = &self.toc {
+                        write_element_block("TextContent", w, |w| {
+                            // 04 Table of contents
+                            write_element_block("TextType", w, |w| {
+                                w.write(XmlEvent::Characters("04")).map_err(|e| e.into())
+                            })?;
+                            // 00 Unrestricted
+                            write_element_block("ContentAudience", w, |w| {
+                                w.write(XmlEvent::Characters("00")).map_err(|e| e.into())
+                            })?;
+                            write_element_block("Text", w, |w| {
+                                w.write(XmlEvent::Characters(toc)).map_err(|e| e.into())
+                            })
+                        })?;
+                    }
+                    write_element_block("TextContent", w, |w| {
+                        // 20 Open access statement
+                        write_element_block("TextType", w, |w| {
+                            w.write(XmlEvent::Characters("20")).map_err(|e| e.into())
+                        })?;
+                        // 00 Unrestricted
+                        write_element_block("ContentAudience", w, |w| {
+                            w.write(XmlEvent::Characters("00")).map_err(|e| e.into())
+                        })?;
+                        write_element_block("Text", w, |w| {
+                            w.write(XmlEvent::Characters("Open Access"))
+                                .map_err(|e| e.into())
+                        })
+                    })?;
+                    if let Some(cover_url) = &self.cover_url {
+                        write_element_block("SupportingResource", w, |w| {
+                            // 01 Front cover
+                            write_element_block("ResourceContentType", w, |w| {
+                                w.write(XmlEvent::Characters("01")).map_err(|e| e.into())
+                            })?;
+                            // 00 Unrestricted
+                            write_element_block("ContentAudience", w, |w| {
+                                w.write(XmlEvent::Characters("00")).map_err(|e| e.into())
+                            })?;
+                            // 03 Image
+                            write_element_block("ResourceMode", w, |w| {
+                                w.write(XmlEvent::Characters("03")).map_err(|e| e.into())
+                            })?;
+                            write_element_block("ResourceVersion", w, |w| {
+                                // 02 Downloadable file
+                                write_element_block("ResourceForm", w, |w| {
+                                    w.write(XmlEvent::Characters("02")).map_err(|e| e.into())
+                                })?;
+                                write_element_block("ResourceLink", w, |w| {
+                                    w.write(XmlEvent::Characters(cover_url))
+                                        .map_err(|e| e.into())
+                                })
+                            })
+                        })?;
+                    }
+                    Ok(())
+                })?;
+                write_element_block("PublishingDetail", w, |w| {
+                    write_element_block("Imprint", w, |w| {
+                        write_element_block("ImprintName", w, |w| {
+                            w.write(XmlEvent::Characters(&self.imprint.imprint_name))
+                                .map_err(|e| e.into())
+                        })
+                    })?;
+                    write_element_block("Publisher", w, |w| {
+                        // 01 Publisher
+                        write_element_block("PublishingRole", w, |w| {
+                            w.write(XmlEvent::Characters("01")).map_err(|e| e.into())
+                        })?;
+                        write_element_block("PublisherName", w, |w| {
+                            w.write(XmlEvent::Characters(&self.imprint.publisher.publisher_name))
+                                .map_err(|e| e.into())
+                        })
+                    })?;
+                    if let Some(place) = &self.place {
+                        write_element_block("CityOfPublication", w, |w| {
+                            w.write(XmlEvent::Characters(place)).map_err(|e| e.into())
+                        })?;
+                    }
+                    XmlElement::<Onix3GoogleBooks>::xml_element(&self.work_status, w)?;
+                    if let Some(date) = self.publication_date {
+                        write_element_block("PublishingDate", w, |w| {
+                            let mut date_fmt: HashMap<&str, &str> = HashMap::new();
+                            date_fmt.insert("dateformat", "00"); // 00 YYYYMMDD
+
+                            write_element_block("PublishingDateRole", w, |w| {
+                                // 01 Publication date
+                                w.write(XmlEvent::Characters("01")).map_err(|e| e.into())
+                            })?;
+                            // dateformat="00" YYYYMMDD
+                            write_full_element_block("Date", None, Some(date_fmt), w, |w| {
+                                w.write(XmlEvent::Characters(&date.format("%Y%m%d").to_string()))
+                                    .map_err(|e| e.into())
+                            })
+                        })?;
+                    }
+                    write_element_block("SalesRights", w, |w| {
+                        // 01 For sale with exclusive rights in the specified countries or territories
+                        write_element_block("SalesRightsType", w, |w| {
+                            w.write(XmlEvent::Characters("01")).map_err(|e| e.into())
+                        })?;
+                        write_element_block("Territory", w, |w| {
+                            write_element_block("RegionsIncluded", w, |w| {
+                                w.write(XmlEvent::Characters("WORLD")).map_err(|e| e.into())
+                            })
+                        })
+                    })
+                })?;
+                if !isbns.is_empty() {
+                    write_element_block("RelatedMaterial", w, |w| {
+                        for isbn in &isbns {
+                            write_element_block("RelatedProduct", w, |w| {
+                                // 06 Alternative format
+                                write_element_block("ProductRelationCode", w, |w| {
+                                    w.write(XmlEvent::Characters("06")).map_err(|e| e.into())
+                                })?;
+                                write_element_block("ProductIdentifier", w, |w| {
+                                    // 15 ISBN-13
+                                    write_element_block("ProductIDType", w, |w| {
+                                        w.write(XmlEvent::Characters("15")).map_err(|e| e.into())
+                                    })?;
+                                    write_element_block("IDValue", w, |w| {
+                                        w.write(XmlEvent::Characters(isbn)).map_err(|e| e.into())
+                                    })
+                                })
+                            })?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                write_element_block("ProductSupply", w, |w| {
+                    // Google Books requires a single supply, zero-priced for open access titles
+                    let mut supplies: Vec<(&str, &str, &str)> = Vec::new();
+                    if let Some(landing_page) = &self.landing_page {
+                        supplies.push(("01", "Publisher's website: web shop", landing_page));
+                    }
+                    supplies.push(("29", "Publisher's website: download the title", pdf_url));
+                    write_element_block("SupplyDetail", w, |w| {
+                        write_element_block("Supplier", w, |w| {
+                            // 09 Publisher to end-customers
+                            write_element_block("SupplierRole", w, |w| {
+                                w.write(XmlEvent::Characters("09")).map_err(|e| e.into())
+                            })?;
+                            write_element_block("SupplierName", w, |w| {
+                                w.write(XmlEvent::Characters(
+                                    &self.imprint.publisher.publisher_name,
+                                ))
+                                .map_err(|e| e.into())
+                            })?;
+                            for (role, description, url) in &supplies {
+                                write_element_block("Website", w, |w| {
+                                    write_element_block("WebsiteRole", w, |w| {
+                                        w.write(XmlEvent::Characters(role)).map_err(|e| e.into())
+                                    })?;
+                                    write_element_block("WebsiteDescription", w, |w| {
+                                        w.write(XmlEvent::Characters(description))
+                                            .map_err(|e| e.into())
+                                    })?;
+                                    write_element_block("WebsiteLink", w, |w| {
+                                        w.write(XmlEvent::Characters(url)).map_err(|e| e.into())
+                                    })
+                                })?;
+                            }
+                            Ok(())
+                        })?;
+                        // 20 Available, unless the product cannot be supplied
+                        let availability = onix3_unavailability(&self.work_status).unwrap_or("20");
+                        write_element_block("ProductAvailability", w, |w| {
+                            w.write(XmlEvent::Characters(availability))
+                                .map_err(|e| e.into())
+                        })?;
+                        for (amount, currency) in &prices {
+                            write_element_block("Price", w, |w| {
+                                // 02 RRP including tax
+                                write_element_block("PriceType", w, |w| {
+                                    w.write(XmlEvent::Characters("02")).map_err(|e| e.into())
+                                })?;
+                                write_element_block("PriceAmount", w, |w| {
+                                    w.write(XmlEvent::Characters(amount)).map_err(|e| e.into())
+                                })?;
+                                write_element_block("CurrencyCode", w, |w| {
+                                    w.write(XmlEvent::Characters(currency))
+                                        .map_err(|e| e.into())
+                                })?;
+                                write_element_block("Territory", w, |w| {
+                                    write_element_block("RegionsIncluded", w, |w| {
+                                        w.write(XmlEvent::Characters("WORLD")).map_err(|e| e.into())
+                                    })
+                                })
+                            })?;
+                        }
+                        Ok(())
+                    })
+                })
+            })
+        } else {
+            Err(ThothError::IncompleteMetadataRecord(
+                "onix_3.0::google_books".to_string(),
+                "Missing PDF URL".to_string(),
+            ))
+        }
+    }
+}
+
+/// ISBN of the PDF, which is the product described, and those of its alternative formats
+fn get_publications_data(publications: &[WorkPublications]) -> (Option<String>, Vec<String>) {
+    let mut main_isbn = None;
+    let mut isbns: Vec<String> = Vec::new();
+
+    for publication in publications {
+        if let Some(isbn) = &publication.isbn.as_ref().map(|i| i.to_string()) {
+            if publication.publication_type.eq(&PublicationType::PDF) && main_isbn.is_none() {
+                main_isbn = Some(isbn.replace("-", ""));
+            } else {
+                isbns.push(isbn.replace("-", ""));
+            }
+        }
+    }
+
+    (main_isbn, isbns)
+}
+
+impl XmlElementBlock<Onix3GoogleBooks> for DeletedWork {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_onix3_deletion(&format!("urn:uuid:{}", self.work_id), w)
+    }
+}
+
+impl XmlElement<Onix3GoogleBooks> for WorkStatus {
+    const ELEMENT: &'static str = "PublishingStatus";
+
+    fn value(&self) -> &'static str {
+        match self {
+            WorkStatus::UNSPECIFIED => "00",
+            WorkStatus::CANCELLED => "01",
+            WorkStatus::FORTHCOMING => "02",
+            WorkStatus::POSTPONED_INDEFINITELY => "03",
+            WorkStatus::ACTIVE => "04",
+            WorkStatus::NO_LONGER_OUR_PRODUCT => "05",
+            WorkStatus::OUT_OF_STOCK_INDEFINITELY => "06",
+            WorkStatus::OUT_OF_PRINT => "07",
+            WorkStatus::INACTIVE => "08",
+            WorkStatus::UNKNOWN => "09",
+            WorkStatus::REMAINDERED => "10",
+            WorkStatus::WITHDRAWN_FROM_SALE => "11",
+            WorkStatus::RECALLED => "15",
+            WorkStatus::Other(_) => unreachable!(),
+        }
+    }
+}
+
+impl XmlElement<Onix3GoogleBooks> for SubjectType {
+    const ELEMENT: &'static str = "SubjectSchemeIdentifier";
+
+    fn value(&self) -> &'static str {
+        match self {
+            SubjectType::BIC => "12",
+            SubjectType::BISAC => "10",
+            SubjectType::KEYWORD => "20",
+            SubjectType::LCC => "04",
+            SubjectType::THEMA => "93",
+            SubjectType::CUSTOM => "B2",
+            SubjectType::Other(_) => unreachable!(),
+        }
+    }
+}
+
+impl XmlElement<Onix3GoogleBooks> for LanguageRelation {
+    const ELEMENT: &'static str = "LanguageRole";
+
+    fn value(&self) -> &'static str {
+        match self {
+            LanguageRelation::ORIGINAL => "01",
+            LanguageRelation::TRANSLATED_FROM => "02",
+            LanguageRelation::TRANSLATED_INTO => "01",
+            LanguageRelation::Other(_) => unreachable!(),
+        }
+    }
+}
+
+impl XmlElement<Onix3GoogleBooks> for ContributionType {
+    const ELEMENT: &'static str = "ContributorRole";
+
+    fn value(&self) -> &'static str {
+        match self {
+            ContributionType::AUTHOR => "A01",
+            ContributionType::EDITOR => "B01",
+            ContributionType::TRANSLATOR => "B06",
+            ContributionType::PHOTOGRAPHER => "A13",
+            ContributionType::ILUSTRATOR => "A12",
+            ContributionType::MUSIC_EDITOR => "B25",
+            ContributionType::FOREWORD_BY => "A23",
+            ContributionType::INTRODUCTION_BY => "A24",
+            ContributionType::AFTERWORD_BY => "A19",
+            ContributionType::PREFACE_BY => "A15",
+            ContributionType::Other(_) => unreachable!(),
+        }
+    }
+}
+
+impl XmlElementBlock<Onix3GoogleBooks> for WorkContributions {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_element_block("Contributor", w, |w| {
+            write_element_block("SequenceNumber", w, |w| {
+                w.write(XmlEvent::Characters(&self.contribution_ordinal.to_string()))
+                    .map_err(|e| e.into())
+            })?;
+            XmlElement::<Onix3GoogleBooks>::xml_element(&self.contribution_type, w)?;
+
+            if let Some(orcid) = &self.contributor.orcid {
+                write_element_block("NameIdentifier", w, |w| {
+                    write_element_block("NameIDType", w, |w| {
+                        w.write(XmlEvent::Characters("21")).map_err(|e| e.into())
+                    })?;
+                    write_element_block("IDValue", w, |w| {
+                        w.write(XmlEvent::Characters(&orcid.to_string()))
+                            .map_err(|e| e.into())
+                    })
+                })?;
+            }
+            if let Some(first_name) = &self.first_name {
+                write_element_block("NamesBeforeKey", w, |w| {
+                    w.write(XmlEvent::Characters(first_name))
+                        .map_err(|e| e.into())
+                })?;
+                write_element_block("KeyNames", w, |w| {
+                    w.write(XmlEvent::Characters(&self.last_name))
+                        .map_err(|e| e.into())
+                })?;
+            } else {
+                write_element_block("PersonName", w, |w| {
+                    w.write(XmlEvent::Characters(&self.full_name))
+                        .map_err(|e| e.into())
+                })?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl XmlElementBlock<Onix3GoogleBooks> for WorkLanguages {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_element_block("Language", w, |w| {
+            XmlElement::<Onix3GoogleBooks>::xml_element(&self.language_relation, w).ok();
+            // not worth implementing XmlElement for LanguageCode as all cases would
+            // need to be exhaustively matched and the codes are equivalent anyway
+            write_element_block("LanguageCode", w, |w| {
+                w.write(XmlEvent::Characters(
+                    &self.language_code.to_string().to_lowercase(),
+                ))
+                .map_err(|e| e.into())
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Testing note: XML nodes cannot be guaranteed to be output in the same order every time
+    // We therefore rely on `assert!(contains)` rather than `assert_eq!`
+    use super::*;
+    use std::str::FromStr;
+    use thoth_api::model::Doi;
+    use thoth_api::model::Isbn;
+    use thoth_api::model::Orcid;
+    use thoth_client::{
+        ContributionType, CurrencyCode, LanguageCode, LanguageRelation, LocationPlatform,
+        PublicationType, WorkContributionsContributor, WorkFundings, WorkImprint,
+        WorkImprintPublisher, WorkIssues, WorkIssuesSeries, WorkPublicationsLocations,
+        WorkPublicationsPrices, WorkStatus, WorkSubjects, WorkType,
+    };
+    use uuid::Uuid;
+
+    fn generate_test_output(input: &impl XmlElementBlock<Onix3GoogleBooks>) -> String {
+        // Helper function based on `XmlSpecification::generate`
+        let mut buffer = Vec::new();
+        let mut writer = xml::writer::EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(&mut buffer);
+        let wrapped_output = XmlElementBlock::<Onix3GoogleBooks>::xml_element(input, &mut writer)
+            .map(|_| buffer)
+            .and_then(|onix| {
+                String::from_utf8(onix)
+                    .map_err(|_| ThothError::InternalError("Could not parse XML".to_string()))
+            });
+        assert!(wrapped_output.is_ok());
+        wrapped_output.unwrap()
+    }
+
+    #[test]
+    fn test_onix3_google_books_contributions() {
+        let mut test_contribution = WorkContributions {
+            contribution_type: ContributionType::AUTHOR,
+            first_name: Some("Author".to_string()),
+            last_name: "1".to_string(),
+            full_name: "Author 1".to_string(),
+            main_contribution: true,
+            biography: None,
+            contribution_ordinal: 1,
+            contributor: WorkContributionsContributor {
+                orcid: Some(Orcid::from_str("https://orcid.org/0000-0002-0000-0001").unwrap()),
+            },
+            affiliations: vec![],
+        };
+
+        // Test standard output
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <SequenceNumber>1</SequenceNumber>"#));
+        assert!(output.contains(r#"  <ContributorRole>A01</ContributorRole>"#));
+        assert!(output.contains(r#"  <NameIdentifier>"#));
+        assert!(output.contains(r#"    <NameIDType>21</NameIDType>"#));
+        assert!(output.contains(r#"    <IDValue>0000-0002-0000-0001</IDValue>"#));
+        assert!(output.contains(r#"  </NameIdentifier>"#));
+        // Given name is output as NamesBeforeKey and family name as KeyNames
+        assert!(output.contains(r#"  <NamesBeforeKey>Author</NamesBeforeKey>"#));
+        assert!(output.contains(r#"  <KeyNames>1</KeyNames>"#));
+        // PersonName is not output when given name is supplied
+        assert!(!output.contains(r#"  <PersonName>Author 1</PersonName>"#));
+
+        // Change all possible values to test that output is updated
+        test_contribution.contribution_type = ContributionType::EDITOR;
+        test_contribution.contribution_ordinal = 2;
+        test_contribution.contributor.orcid = None;
+        test_contribution.first_name = None;
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <SequenceNumber>2</SequenceNumber>"#));
+        assert!(output.contains(r#"  <ContributorRole>B01</ContributorRole>"#));
+        // No ORCID supplied
+        assert!(!output.contains(r#"  <NameIdentifier>"#));
+        assert!(!output.contains(r#"    <NameIDType>21</NameIDType>"#));
+        assert!(!output.contains(r#"    <IDValue>0000-0002-0000-0001</IDValue>"#));
+        assert!(!output.contains(r#"  </NameIdentifier>"#));
+        // No given name supplied, so PersonName is output instead of KeyNames and NamesBeforeKey
+        assert!(!output.contains(r#"  <NamesBeforeKey>Author</NamesBeforeKey>"#));
+        assert!(!output.contains(r#"  <KeyNames>1</KeyNames>"#));
+        assert!(output.contains(r#"  <PersonName>Author 1</PersonName>"#));
+
+        // Test all remaining contributor roles
+        test_contribution.contribution_type = ContributionType::TRANSLATOR;
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <ContributorRole>B06</ContributorRole>"#));
+        test_contribution.contribution_type = ContributionType::PHOTOGRAPHER;
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <ContributorRole>A13</ContributorRole>"#));
+        test_contribution.contribution_type = ContributionType::ILUSTRATOR;
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <ContributorRole>A12</ContributorRole>"#));
+        test_contribution.contribution_type = ContributionType::MUSIC_EDITOR;
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <ContributorRole>B25</ContributorRole>"#));
+        test_contribution.contribution_type = ContributionType::FOREWORD_BY;
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <ContributorRole>A23</ContributorRole>"#));
+        test_contribution.contribution_type = ContributionType::INTRODUCTION_BY;
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <ContributorRole>A24</ContributorRole>"#));
+        test_contribution.contribution_type = ContributionType::AFTERWORD_BY;
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <ContributorRole>A19</ContributorRole>"#));
+        test_contribution.contribution_type = ContributionType::PREFACE_BY;
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <ContributorRole>A15</ContributorRole>"#));
+    }
+
+    #[test]
+    fn test_onix3_google_books_languages() {
+        let mut test_language = WorkLanguages {
+            language_code: LanguageCode::SPA,
+            language_relation: LanguageRelation::TRANSLATED_FROM,
+            main_language: true,
+        };
+
+        // Test standard output
+        let output = generate_test_output(&test_language);
+        assert!(output.contains(r#"  <LanguageRole>02</LanguageRole>"#));
+        assert!(output.contains(r#"  <LanguageCode>spa</LanguageCode>"#));
+
+        // Change all possible values to test that output is updated
+        test_language.language_code = LanguageCode::WEL;
+        for language_relation in [
+            LanguageRelation::ORIGINAL,
+            LanguageRelation::TRANSLATED_INTO,
+        ] {
+            test_language.language_relation = language_relation;
+            let output = generate_test_output(&test_language);
+            assert!(output.contains(r#"  <LanguageRole>01</LanguageRole>"#));
+            assert!(output.contains(r#"  <LanguageCode>wel</LanguageCode>"#));
+        }
+    }
+
+    #[test]
+    fn test_onix3_google_books_works() {
+        let mut test_work = Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            copyright_holder: "Author 1; Author 2".to_string(),
+            short_abstract: None,
+            long_abstract: Some("Lorem ipsum dolor sit amet".to_string()),
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: None,
+            width_cm: None,
+            width_in: None,
+            height_mm: None,
+            height_cm: None,
+            height_in: None,
+            page_count: Some(334),
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: None,
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: Some("1. Chapter 1".to_string()),
+            lccn: None,
+            oclc: None,
+            cover_url: Some("https://www.book.com/cover".to_string()),
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: None,
+                },
+            },
+            issues: vec![WorkIssues {
+                issue_ordinal: 1,
+                series: WorkIssuesSeries {
+                    series_type: thoth_client::SeriesType::JOURNAL,
+                    series_name: "Name of series".to_string(),
+                    issn_print: "1234-5678".to_string(),
+                    issn_digital: "8765-4321".to_string(),
+                    series_url: None,
+                },
+            }],
+            contributions: vec![],
+            languages: vec![],
+            publications: vec![
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-DDDD-000000000004").unwrap(),
+                    publication_type: PublicationType::PDF,
+                    isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                    prices: vec![],
+                    locations: vec![WorkPublicationsLocations {
+                        landing_page: Some("https://www.book.com/pdf_landing".to_string()),
+                        full_text_url: Some("https://www.book.com/pdf_fulltext".to_string()),
+                        location_platform: LocationPlatform::OTHER,
+                        canonical: true,
+                    }],
+                },
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-DDDD-000000000005").unwrap(),
+                    publication_type: PublicationType::PAPERBACK,
+                    isbn: Some(Isbn::from_str("978-1-4028-9462-6").unwrap()),
+                    prices: vec![],
+                    locations: vec![],
+                },
+            ],
+            subjects: vec![
+                WorkSubjects {
+                    subject_code: "AAB".to_string(),
+                    subject_type: SubjectType::BIC,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "AAA000000".to_string(),
+                    subject_type: SubjectType::BISAC,
+                    subject_ordinal: 2,
+                },
+                WorkSubjects {
+                    subject_code: "JA85".to_string(),
+                    subject_type: SubjectType::LCC,
+                    subject_ordinal: 3,
+                },
+                WorkSubjects {
+                    subject_code: "JWA".to_string(),
+                    subject_type: SubjectType::THEMA,
+                    subject_ordinal: 4,
+                },
+                WorkSubjects {
+                    subject_code: "keyword1".to_string(),
+                    subject_type: SubjectType::KEYWORD,
+                    subject_ordinal: 5,
+                },
+                WorkSubjects {
+                    subject_code: "custom1".to_string(),
+                    subject_type: SubjectType::CUSTOM,
+                    subject_ordinal: 6,
+                },
+            ],
+            fundings: vec![WorkFundings {
+                program: Some("Name of program".to_string()),
+                project_name: Some("Name of project".to_string()),
+                project_shortname: None,
+                grant_number: Some("Number of grant".to_string()),
+                jurisdiction: None,
+                institution: thoth_client::WorkFundingsInstitution {
+                    institution_name: "Name of institution".to_string(),
+                    institution_doi: None,
+                    ror: None,
+                    country_code: None,
+                },
+            }],
+            relations: vec![],
+            parents: vec![],
+        };
+
+        // Test standard output
+        let output = generate_test_output(&test_work);
+        assert!(output.contains(r#"<Product>"#));
+        assert!(output.contains(
+            r#"  <RecordReference>urn:uuid:00000000-0000-0000-aaaa-000000000001</RecordReference>"#
+        ));
+        assert!(output.contains(r#"  <NotificationType>03</NotificationType>"#));
+        assert!(output.contains(r#"  <RecordSourceType>01</RecordSourceType>"#));
+        assert!(output.contains(r#"  <ProductIdentifier>"#));
+        assert!(output.contains(r#"    <ProductIDType>01</ProductIDType>"#));
+        assert!(output
+            .contains(r#"    <IDValue>urn:uuid:00000000-0000-0000-aaaa-000000000001</IDValue>"#));
+        assert!(output.contains(r#"    <ProductIDType>15</ProductIDType>"#));
+        assert!(output.contains(r#"    <IDValue>9783161484100</IDValue>"#));
+        assert!(output.contains(r#"    <ProductIDType>06</ProductIDType>"#));
+        assert!(output.contains(r#"    <IDValue>10.00001/BOOK.0001</IDValue>"#));
+        assert!(output.contains(r#"  <DescriptiveDetail>"#));
+        assert!(output.contains(r#"    <ProductComposition>00</ProductComposition>"#));
+        assert!(output.contains(r#"    <ProductForm>EB</ProductForm>"#));
+        assert!(output.contains(r#"    <ProductFormDetail>E107</ProductFormDetail>"#));
+        assert!(output.contains(r#"    <PrimaryContentType>10</PrimaryContentType>"#));
+        assert!(output.contains(r#"    <EpubTechnicalProtection>00</EpubTechnicalProtection>"#));
+        assert!(output.contains(r#"    <EpubUsageConstraint>"#));
+        assert!(output.contains(r#"      <EpubUsageType>02</EpubUsageType>"#));
+        assert!(output.contains(r#"      <EpubUsageType>03</EpubUsageType>"#));
+        assert!(output.contains(r#"      <EpubUsageType>04</EpubUsageType>"#));
+        assert!(output.contains(r#"      <EpubUsageStatus>01</EpubUsageStatus>"#));
+        assert!(output.contains(r#"    <EpubLicense>"#));
+        assert!(
+            output.contains(r#"      <EpubLicenseName>Creative Commons License</EpubLicenseName>"#)
+        );
+        assert!(output.contains(r#"      <EpubLicenseExpression>"#));
+        assert!(
+            output.contains(r#"        <EpubLicenseExpressionType>02</EpubLicenseExpressionType>"#)
+        );
+        assert!(output.contains(r#"        <EpubLicenseExpressionLink>https://creativecommons.org/licenses/by/4.0/</EpubLicenseExpressionLink>"#));
+        assert!(output.contains(r#"    <TitleDetail>"#));
+        assert!(output.contains(r#"      <TitleType>01</TitleType>"#));
+        assert!(output.contains(r#"      <TitleElement>"#));
+        assert!(output.contains(r#"        <TitleElementLevel>01</TitleElementLevel>"#));
+        assert!(output.contains(r#"        <TitleText>Book Title</TitleText>"#));
+        assert!(output.contains(r#"        <Subtitle>Book Subtitle</Subtitle>"#));
+        assert!(output.contains(r#"    <Extent>"#));
+        assert!(output.contains(r#"      <ExtentType>00</ExtentType>"#));
+        assert!(output.contains(r#"      <ExtentValue>334</ExtentValue>"#));
+        assert!(output.contains(r#"      <ExtentUnit>03</ExtentUnit>"#));
+        assert!(output.contains(r#"    <Subject>"#));
+        assert!(output.contains(r#"      <SubjectSchemeIdentifier>12</SubjectSchemeIdentifier>"#));
+        assert!(output.contains(r#"      <SubjectCode>AAB</SubjectCode>"#));
+        assert!(output.contains(r#"      <SubjectSchemeIdentifier>10</SubjectSchemeIdentifier>"#));
+        assert!(output.contains(r#"      <SubjectCode>AAA000000</SubjectCode>"#));
+        assert!(output.contains(r#"      <SubjectSchemeIdentifier>04</SubjectSchemeIdentifier>"#));
+        assert!(output.contains(r#"      <SubjectCode>JA85</SubjectCode>"#));
+        assert!(output.contains(r#"      <SubjectSchemeIdentifier>93</SubjectSchemeIdentifier>"#));
+        assert!(output.contains(r#"      <SubjectCode>JWA</SubjectCode>"#));
+        assert!(output.contains(r#"      <SubjectSchemeIdentifier>20</SubjectSchemeIdentifier>"#));
+        assert!(output.contains(r#"      <SubjectCode>keyword1</SubjectCode>"#));
+        assert!(output.contains(r#"      <SubjectSchemeIdentifier>B2</SubjectSchemeIdentifier>"#));
+        assert!(output.contains(r#"      <SubjectCode>custom1</SubjectCode>"#));
+        assert!(output.contains(r#"  <CollateralDetail>"#));
+        assert!(output.contains(r#"    <TextContent>"#));
+        assert!(output.contains(r#"      <TextType>03</TextType>"#));
+        assert!(output.contains(r#"      <ContentAudience>00</ContentAudience>"#));
+        assert!(output.contains(r#"      <Text language="eng">Lorem ipsum dolor sit amet</Text>"#));
+        assert!(output.contains(r#"    <TextContent>"#));
+        assert!(output.contains(r#"      <TextType>04</TextType>"#));
+        assert!(output.contains(r#"      <Text>1. Chapter 1</Text>"#));
+        assert!(output.contains(r#"    <TextContent>"#));
+        assert!(output.contains(r#"      <TextType>20</TextType>"#));
+        assert!(output.contains(r#"      <Text>Open Access</Text>"#));
+        assert!(output.contains(r#"    <SupportingResource>"#));
+        assert!(output.contains(r#"      <ResourceContentType>01</ResourceContentType>"#));
+        assert!(output.contains(r#"      <ResourceMode>03</ResourceMode>"#));
+        assert!(output.contains(r#"        <ResourceForm>02</ResourceForm>"#));
+        assert!(
+            output.contains(r#"        <ResourceLink>https://www.book.com/cover</ResourceLink>"#)
+        );
+        assert!(output.contains(r#"  <PublishingDetail>"#));
+        assert!(output.contains(r#"    <Imprint>"#));
+        assert!(output.contains(r#"      <ImprintName>OA Editions Imprint</ImprintName>"#));
+        assert!(output.contains(r#"    <Publisher>"#));
+        assert!(output.contains(r#"      <PublishingRole>01</PublishingRole>"#));
+        assert!(output.contains(r#"      <PublisherName>OA Editions</PublisherName>"#));
+        assert!(output.contains(r#"    <CityOfPublication>León, Spain</CityOfPublication>"#));
+        assert!(output.contains(r#"    <PublishingStatus>04</PublishingStatus>"#));
+        assert!(output.contains(r#"    <PublishingDate>"#));
+        assert!(output.contains(r#"      <PublishingDateRole>01</PublishingDateRole>"#));
+        assert!(output.contains(r#"      <Date dateformat="00">19991231</Date>"#));
+        assert!(output.contains(r#"    <SalesRights>"#));
+        assert!(output.contains(r#"      <SalesRightsType>01</SalesRightsType>"#));
+        assert!(output.contains(r#"        <RegionsIncluded>WORLD</RegionsIncluded>"#));
+        assert!(output.contains(r#"    <RelatedProduct>"#));
+        assert!(output.contains(r#"      <ProductRelationCode>06</ProductRelationCode>"#));
+        assert!(output.contains(r#"      <ProductIdentifier>"#));
+        assert!(output.contains(r#"        <ProductIDType>15</ProductIDType>"#));
+        assert!(output.contains(r#"    <IDValue>9783161484100</IDValue>"#));
+        // The PDF's own ISBN is not listed as an alternative format
+        assert_eq!(output.matches("<RelatedProduct>").count(), 1);
+        assert!(output.contains(r#"        <IDValue>9781402894626</IDValue>"#));
+        assert!(!output.contains(r#"        <IDValue>9783161484100</IDValue>"#));
+        assert!(output.contains(r#"  <ProductSupply>"#));
+        assert!(output.contains(r#"    <SupplyDetail>"#));
+        assert!(output.contains(r#"      <Supplier>"#));
+        assert!(output.contains(r#"        <SupplierRole>09</SupplierRole>"#));
+        assert!(output.contains(r#"        <SupplierName>OA Editions</SupplierName>"#));
+        assert!(output.contains(r#"        <Website>"#));
+        assert!(output.contains(r#"          <WebsiteRole>01</WebsiteRole>"#));
+        assert!(output.contains(
+            r#"          <WebsiteDescription>Publisher's website: web shop</WebsiteDescription>"#
+        ));
+        assert!(output.contains(r#"          <WebsiteLink>https://www.book.com</WebsiteLink>"#));
+        assert!(output.contains(r#"          <WebsiteRole>29</WebsiteRole>"#));
+        assert!(output.contains(r#"          <WebsiteDescription>Publisher's website: download the title</WebsiteDescription>"#));
+        assert!(output
+            .contains(r#"          <WebsiteLink>https://www.book.com/pdf_fulltext</WebsiteLink>"#));
+        assert!(output.contains(r#"      <ProductAvailability>20</ProductAvailability>"#));
+        assert!(output.contains(r#"      <Price>"#));
+        assert!(output.contains(r#"        <PriceType>02</PriceType>"#));
+        assert!(output.contains(r#"        <PriceAmount>0.00</PriceAmount>"#));
+        assert!(output.contains(r#"        <CurrencyCode>USD</CurrencyCode>"#));
+        assert!(output.contains(r#"          <RegionsIncluded>WORLD</RegionsIncluded>"#));
+        // All websites are listed under a single supply
+        assert_eq!(output.matches("<SupplyDetail>").count(), 1);
+        assert!(!output.contains(r#"<UnpricedItemType>"#));
+
+        // Test that OAPEN-only blocks are not output in Google Books format
+        assert!(!output.contains(r#"    <Audience>"#));
+        assert!(!output.contains(r#"      <AudienceCodeType>01</AudienceCodeType>"#));
+        assert!(!output.contains(r#"      <AudienceCodeValue>06</AudienceCodeValue>"#));
+        assert!(!output.contains(r#"      <PublishingRole>16</PublishingRole>"#));
+        assert!(!output.contains(r#"      <PublisherName>Name of institution</PublisherName>"#));
+        assert!(!output.contains(r#"      <Funding>"#));
+        assert!(!output.contains(r#"        <FundingIdentifier>"#));
+        assert!(!output.contains(r#"          <FundingIDType>01</FundingIDType>"#));
+        assert!(!output.contains(r#"          <IDTypeName>programname</IDTypeName>"#));
+        assert!(!output.contains(r#"          <IDValue>Name of program</IDValue>"#));
+        assert!(!output.contains(r#"          <IDTypeName>projectname</IDTypeName>"#));
+        assert!(!output.contains(r#"          <IDValue>Name of project</IDValue>"#));
+        assert!(!output.contains(r#"          <IDTypeName>grantnumber</IDTypeName>"#));
+        assert!(!output.contains(r#"          <IDValue>Number of grant</IDValue>"#));
+        assert!(!output.contains(r#"    <Collection>"#));
+        assert!(!output.contains(r#"      <CollectionType>10</CollectionType>"#));
+        assert!(!output.contains(r#"          <TitleElementLevel>02</TitleElementLevel>"#));
+        assert!(!output.contains(r#"          <PartNumber>1</PartNumber>"#));
+        assert!(!output.contains(r#"          <TitleText>Name of series</TitleText>"#));
+
+        // Remove some values to test non-output of optional blocks
+        test_work.doi = None;
+        test_work.license = None;
+        test_work.publications[0].prices = vec![WorkPublicationsPrices {
+            currency_code: CurrencyCode::GBP,
+            unit_price: 12.5,
+        }];
+        test_work.subtitle = None;
+        test_work.page_count = None;
+        test_work.long_abstract = None;
+        test_work.toc = None;
+        test_work.place = None;
+        test_work.publication_date = None;
+        test_work.landing_page = None;
+        test_work.cover_url = None;
+        test_work.subjects.clear();
+        let output = generate_test_output(&test_work);
+        // No DOI supplied
+        assert!(!output.contains(r#"    <ProductIDType>06</ProductIDType>"#));
+        assert!(!output.contains(r#"    <IDValue>10.00001/BOOK.0001</IDValue>"#));
+        // No licence supplied
+        assert!(!output.contains(r#"    <EpubLicense>"#));
+        assert!(!output
+            .contains(r#"      <EpubLicenseName>Creative Commons License</EpubLicenseName>"#));
+        assert!(!output.contains(r#"      <EpubLicenseExpression>"#));
+        assert!(!output
+            .contains(r#"        <EpubLicenseExpressionType>02</EpubLicenseExpressionType>"#));
+        assert!(!output.contains(r#"        <EpubLicenseExpressionLink>https://creativecommons.org/licenses/by/4.0/</EpubLicenseExpressionLink>"#));
+        // Not open access: the PDF's own prices are output rather than a zero price
+        assert_eq!(output.matches("<Price>").count(), 1);
+        assert!(!output.contains(r#"        <PriceAmount>0.00</PriceAmount>"#));
+        assert!(output.contains(
+            "        <PriceAmount>12.50</PriceAmount>\n        <CurrencyCode>GBP</CurrencyCode>"
+        ));
+        // No subtitle supplied: work FullTitle is used instead of Title
+        assert!(!output.contains(r#"        <TitleText>Book Title</TitleText>"#));
+        assert!(!output.contains(r#"        <Subtitle>Book Subtitle</Subtitle>"#));
+        assert!(output.contains(r#"        <TitleText>Book Title: Book Subtitle</TitleText>"#));
+        // No page count supplied
+        assert!(!output.contains(r#"    <Extent>"#));
+        assert!(!output.contains(r#"      <ExtentType>00</ExtentType>"#));
+        assert!(!output.contains(r#"      <ExtentValue>334</ExtentValue>"#));
+        assert!(!output.contains(r#"      <ExtentUnit>03</ExtentUnit>"#));
+        // No long abstract supplied
+        assert!(!output.contains(r#"      <TextType>03</TextType>"#));
+        assert!(!output.contains(r#"      <Text language="eng">Lorem ipsum dolor sit amet</Text>"#));
+        // No TOC supplied
+        assert!(!output.contains(r#"      <TextType>04</TextType>"#));
+        assert!(!output.contains(r#"      <Text>1. Chapter 1</Text>"#));
+        // CollateralDetail block is still present as it always contains Open Access statement
+        assert!(output.contains(r#"  <CollateralDetail>"#));
+        assert!(output.contains(r#"    <TextContent>"#));
+        assert!(output.contains(r#"      <ContentAudience>00</ContentAudience>"#));
+        // No place supplied
+        assert!(!output.contains(r#"    <CityOfPublication>León, Spain</CityOfPublication>"#));
+        // No publication date supplied
+        assert!(!output.contains(r#"    <PublishingDate>"#));
+        assert!(!output.contains(r#"      <PublishingDateRole>01</PublishingDateRole>"#));
+        assert!(!output.contains(r#"      <Date dateformat="00">19991231</Date>"#));
+        // No cover URL supplied
+        assert!(!output.contains(r#"    <SupportingResource>"#));
+        // No landing page supplied: only one Website block, linking to PDF download
+        assert!(!output.contains(r#"          <WebsiteRole>01</WebsiteRole>"#));
+        assert!(!output.contains(
+            r#"          <WebsiteDescription>Publisher's website: web shop</WebsiteDescription>"#
+        ));
+        assert!(!output.contains(r#"          <WebsiteLink>https://www.book.com</WebsiteLink>"#));
+        // No subjects supplied
+        assert!(!output.contains(r#"    <Subject>"#));
+        assert!(!output.contains(r#"      <SubjectSchemeIdentifier>12</SubjectSchemeIdentifier>"#));
+        assert!(!output.contains(r#"      <SubjectCode>AAB</SubjectCode>"#));
+        assert!(!output.contains(r#"      <SubjectSchemeIdentifier>10</SubjectSchemeIdentifier>"#));
+        assert!(!output.contains(r#"      <SubjectCode>AAA000000</SubjectCode>"#));
+        assert!(!output.contains(r#"      <SubjectSchemeIdentifier>04</SubjectSchemeIdentifier>"#));
+        assert!(!output.contains(r#"      <SubjectCode>JA85</SubjectCode>"#));
+        assert!(!output.contains(r#"      <SubjectSchemeIdentifier>93</SubjectSchemeIdentifier>"#));
+        assert!(!output.contains(r#"      <SubjectCode>JWA</SubjectCode>"#));
+        assert!(!output.contains(r#"      <SubjectSchemeIdentifier>20</SubjectSchemeIdentifier>"#));
+        assert!(!output.contains(r#"      <SubjectCode>keyword1</SubjectCode>"#));
+        assert!(!output.contains(r#"      <SubjectSchemeIdentifier>B2</SubjectSchemeIdentifier>"#));
+        assert!(!output.contains(r#"      <SubjectCode>custom1</SubjectCode>"#));
+
+        // Remove the prices of a title which is not open access
+        // Result: error (only open access titles can be listed free of charge)
+        let mut test_work_unpriced = test_work.clone();
+        test_work_unpriced.publications[0].prices.clear();
+        let output = Onix3GoogleBooks {}.generate(&[test_work_unpriced], None);
+        assert_eq!(
+            output.unwrap_err().to_string(),
+            "Could not generate onix_3.0::google_books: Missing Prices".to_string()
+        );
+
+        // Remove the PDF's locations
+        // Result: error (can't generate Google Books ONIX without PDF URL)
+        let mut test_work_no_url = test_work.clone();
+        test_work_no_url.publications[0].locations.clear();
+        let output = Onix3GoogleBooks {}.generate(&[test_work_no_url], None);
+        assert_eq!(
+            output.unwrap_err().to_string(),
+            "Could not generate onix_3.0::google_books: Missing PDF URL".to_string()
+        );
+
+        // Replace the PDF with a paperback
+        // Result: error (the paperback's ISBN does not identify the PDF)
+        test_work.publications[0].publication_type = PublicationType::PAPERBACK;
+        let output = Onix3GoogleBooks {}.generate(&[test_work.clone()], None);
+        assert_eq!(
+            output.unwrap_err().to_string(),
+            "Could not generate onix_3.0::google_books: Missing PDF ISBN".to_string()
+        );
+
+        // Remove all publications
+        // Result: error (can't generate Google Books ONIX without ISBN)
+        test_work.publications.clear();
+        let output = Onix3GoogleBooks {}.generate(&[test_work], None);
+        assert_eq!(
+            output.unwrap_err().to_string(),
+            "Could not generate onix_3.0::google_books: Missing PDF ISBN".to_string()
+        );
+    }
+}
@@ -2,80 +2,112 @@ use chrono::Utc;
 use std::collections::HashMap;
 use std::io::Write;
 use thoth_client::{
-    ContributionType, LanguageRelation, PublicationType, SubjectType, Work, WorkContributions,
-    WorkFundings, WorkIssues, WorkLanguages, WorkPublications, WorkStatus, WorkSubjects,
+    ContributionType, DeletedWork, LanguageRelation, PublicationType, SubjectType, Work,
+    WorkContributions, WorkFundings, WorkIssues, WorkLanguages, WorkPublications, WorkStatus,
+    WorkSubjects,
 };
 use xml::writer::{EventWriter, XmlEvent};
 
-use super::{write_element_block, XmlElement, XmlSpecification};
-use crate::xml::{write_full_element_block, XmlElementBlock};
+use super::{
+    handle_stream_event, write_element_block, write_start_element, XmlElement, XmlSpecification,
+    XmlStreamSpecification,
+};
+use crate::record::RequiredFields;
+use crate::xml::{
+    full_text_url, onix3_notification_type, onix3_unavailability, write_full_element_block,
+    write_onix3_deletion, XmlElementBlock,
+};
 use thoth_errors::{ThothError, ThothResult};
 
 pub struct Onix3Oapen {}
 
 impl XmlSpecification for Onix3Oapen {
     fn handle_event<W: Write>(w: &mut EventWriter<W>, works: &[Work]) -> ThothResult<()> {
-        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+        Self::handle_event_with_deletions(w, works, &[])
+    }
 
-        attr_map.insert("release", "3.0");
-        attr_map.insert("xmlns", "http://ns.editeur.org/onix/3.0/reference");
+    fn handle_event_with_deletions<W: Write>(
+        w: &mut EventWriter<W>,
+        works: &[Work],
+        deleted_works: &[DeletedWork],
+    ) -> ThothResult<()> {
+        handle_stream_event::<Self, W>(w, works, deleted_works)
+    }
+}
 
-        write_full_element_block("ONIXMessage", None, Some(attr_map), w, |w| {
-            write_element_block("Header", w, |w| {
-                write_element_block("Sender", w, |w| {
-                    write_element_block("SenderName", w, |w| {
-                        w.write(XmlEvent::Characters("Thoth")).map_err(|e| e.into())
-                    })?;
-                    write_element_block("EmailAddress", w, |w| {
-                        w.write(XmlEvent::Characters("info@thoth.pub"))
-                            .map_err(|e| e.into())
-                    })
+impl XmlStreamSpecification for Onix3Oapen {
+    fn write_header<W: Write>(w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_start_element(
+            "ONIXMessage",
+            &[
+                ("release", "3.0"),
+                ("xmlns", "http://ns.editeur.org/onix/3.0/reference"),
+            ],
+            w,
+        )?;
+        write_element_block("Header", w, |w| {
+            write_element_block("Sender", w, |w| {
+                write_element_block("SenderName", w, |w| {
+                    w.write(XmlEvent::Characters("Thoth")).map_err(|e| e.into())
                 })?;
-                write_element_block("SentDateTime", w, |w| {
-                    w.write(XmlEvent::Characters(
-                        &Utc::now().format("%Y%m%d").to_string(),
-                    ))
-                    .map_err(|e| e.into())
+                write_element_block("EmailAddress", w, |w| {
+                    w.write(XmlEvent::Characters("info@thoth.pub"))
+                        .map_err(|e| e.into())
                 })
             })?;
-
-            match works.len() {
-                0 => Err(ThothError::IncompleteMetadataRecord(
-                    "onix_3.0::oapen".to_string(),
-                    "Not enough data".to_string(),
-                )),
-                1 => XmlElementBlock::<Onix3Oapen>::xml_element(works.first().unwrap(), w),
-                _ => {
-                    for work in works.iter() {
-                        XmlElementBlock::<Onix3Oapen>::xml_element(work, w).ok();
-                    }
-                    Ok(())
-                }
-            }
+            write_element_block("SentDateTime", w, |w| {
+                w.write(XmlEvent::Characters(
+                    &Utc::now().format("%Y%m%d").to_string(),
+                ))
+                .map_err(|e| e.into())
+            })
         })
     }
+
+    fn write_work<W: Write>(w: &mut EventWriter<W>, work: &Work) -> ThothResult<()> {
+        XmlElementBlock::<Onix3Oapen>::xml_element(work, w)
+    }
+
+    fn write_footer<W: Write>(
+        w: &mut EventWriter<W>,
+        deleted_works: &[DeletedWork],
+    ) -> ThothResult<()> {
+        for deleted_work in deleted_works {
+            XmlElementBlock::<Onix3Oapen>::xml_element(deleted_work, w)?;
+        }
+        w.write(XmlEvent::end_element()).map_err(|e| e.into())
+    }
+}
+
+impl RequiredFields for Onix3Oapen {
+    const SPECIFICATION_ID: &'static str = "onix_3.0::oapen";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        // We can only generate the document if there's a PDF
+        if full_text_url(work, PublicationType::PDF).is_none() {
+            missing.push("Missing PDF URL");
+        }
+        missing
+    }
 }
 
 impl XmlElementBlock<Onix3Oapen> for Work {
     fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        Onix3Oapen::check_required_fields(self)?;
         let work_id = format!("urn:uuid:{}", self.work_id.to_string());
         let (main_isbn, isbns) = get_publications_data(&self.publications);
-        // We can only generate the document if there's a PDF
-        if let Some(pdf_url) = self
-            .publications
-            .iter()
-            .find(|p| p.publication_type.eq(&PublicationType::PDF) && !p.locations.is_empty())
-            .and_then(|p| p.locations.iter().find(|l| l.canonical))
-            .and_then(|l| l.full_text_url.as_ref())
-        {
+        if let Some(pdf_url) = full_text_url(self, PublicationType::PDF) {
             write_element_block("Product", w, |w| {
                 write_element_block("RecordReference", w, |w| {
                     w.write(XmlEvent::Characters(&work_id))
                         .map_err(|e| e.into())
                 })?;
-                // 03 Notification confirmed on publication
                 write_element_block("NotificationType", w, |w| {
-                    w.write(XmlEvent::Characters("03")).map_err(|e| e.into())
+                    w.write(XmlEvent::Characters(onix3_notification_type(
+                        &self.work_status,
+                    )))
+                    .map_err(|e| e.into())
                 })?;
                 // 01 Publisher
                 write_element_block("RecordSourceType", w, |w| {
@@ -373,9 +405,12 @@ impl XmlElementBlock<Onix3Oapen> for Work {
                                     })
                                 })
                             })?;
-                            // 99 Contact supplier
+                            // 99 Contact supplier, unless the product cannot be supplied
+                            let availability =
+                                onix3_unavailability(&self.work_status).unwrap_or("99");
                             write_element_block("ProductAvailability", w, |w| {
-                                w.write(XmlEvent::Characters("99")).map_err(|e| e.into())
+                                w.write(XmlEvent::Characters(availability))
+                                    .map_err(|e| e.into())
                             })?;
                             // 04 Contact supplier
                             write_element_block("UnpricedItemType", w, |w| {
@@ -417,6 +452,12 @@ fn get_publications_data(publications: &[WorkPublications]) -> (String, Vec<Stri
     (main_isbn, isbns)
 }
 
+impl XmlElementBlock<Onix3Oapen> for DeletedWork {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_onix3_deletion(&format!("urn:uuid:{}", self.work_id), w)
+    }
+}
+
 impl XmlElement<Onix3Oapen> for WorkStatus {
     const ELEMENT: &'static str = "PublishingStatus";
 
@@ -969,6 +1010,8 @@ mod tests {
             height_in: None,
             page_count: Some(334),
             page_breakdown: None,
+            first_page: None,
+            last_page: None,
             image_count: None,
             table_count: None,
             audio_count: None,
@@ -979,9 +1022,14 @@ mod tests {
             oclc: None,
             cover_url: Some("https://www.book.com/cover".to_string()),
             cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
             imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
                 imprint_name: "OA Editions Imprint".to_string(),
                 publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
                     publisher_name: "OA Editions".to_string(),
                     publisher_url: None,
                 },
@@ -1003,6 +1051,8 @@ mod tests {
             }],
             subjects: vec![],
             fundings: vec![],
+            relations: vec![],
+            parents: vec![],
         };
 
         // Test standard output
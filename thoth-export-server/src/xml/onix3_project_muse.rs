@@ -2,81 +2,107 @@ use chrono::Utc;
 use std::collections::HashMap;
 use std::io::Write;
 use thoth_client::{
-    ContributionType, LanguageRelation, PublicationType, SubjectType, Work, WorkContributions,
-    WorkLanguages, WorkPublications, WorkStatus,
+    ContributionType, DeletedWork, LanguageRelation, PublicationType, SubjectType, Work,
+    WorkContributions, WorkLanguages, WorkPublications, WorkRelations, WorkStatus,
 };
 use xml::writer::{EventWriter, XmlEvent};
 
-use super::{write_element_block, XmlElement, XmlSpecification};
-use crate::xml::{write_full_element_block, XmlElementBlock};
+use super::{
+    handle_stream_event, write_element_block, write_start_element, XmlElement, XmlSpecification,
+    XmlStreamSpecification,
+};
+use crate::record::RequiredFields;
+use crate::xml::{
+    full_text_url, onix3_notification_type, onix3_unavailability, write_full_element_block,
+    write_onix3_deletion, XmlElementBlock,
+};
 use thoth_errors::{ThothError, ThothResult};
 
 pub struct Onix3ProjectMuse {}
 
 impl XmlSpecification for Onix3ProjectMuse {
     fn handle_event<W: Write>(w: &mut EventWriter<W>, works: &[Work]) -> ThothResult<()> {
-        let mut attr_map: HashMap<&str, &str> = HashMap::new();
+        Self::handle_event_with_deletions(w, works, &[])
+    }
 
-        attr_map.insert("release", "3.0");
-        attr_map.insert("xmlns", "http://ns.editeur.org/onix/3.0/reference");
+    fn handle_event_with_deletions<W: Write>(
+        w: &mut EventWriter<W>,
+        works: &[Work],
+        deleted_works: &[DeletedWork],
+    ) -> ThothResult<()> {
+        handle_stream_event::<Self, W>(w, works, deleted_works)
+    }
+}
 
-        write_full_element_block("ONIXMessage", None, Some(attr_map), w, |w| {
-            write_element_block("Header", w, |w| {
-                write_element_block("Sender", w, |w| {
-                    write_element_block("SenderName", w, |w| {
-                        w.write(XmlEvent::Characters("Thoth")).map_err(|e| e.into())
-                    })?;
-                    write_element_block("EmailAddress", w, |w| {
-                        w.write(XmlEvent::Characters("info@thoth.pub"))
-                            .map_err(|e| e.into())
-                    })
+impl XmlStreamSpecification for Onix3ProjectMuse {
+    fn write_header<W: Write>(w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_start_element(
+            "ONIXMessage",
+            &[
+                ("release", "3.0"),
+                ("xmlns", "http://ns.editeur.org/onix/3.0/reference"),
+            ],
+            w,
+        )?;
+        write_element_block("Header", w, |w| {
+            write_element_block("Sender", w, |w| {
+                write_element_block("SenderName", w, |w| {
+                    w.write(XmlEvent::Characters("Thoth")).map_err(|e| e.into())
                 })?;
-                write_element_block("SentDateTime", w, |w| {
-                    w.write(XmlEvent::Characters(
-                        &Utc::now().format("%Y%m%dT%H%M%S").to_string(),
-                    ))
-                    .map_err(|e| e.into())
+                write_element_block("EmailAddress", w, |w| {
+                    w.write(XmlEvent::Characters("info@thoth.pub"))
+                        .map_err(|e| e.into())
                 })
             })?;
-
-            match works.len() {
-                0 => Err(ThothError::IncompleteMetadataRecord(
-                    "onix_3.0::project_muse".to_string(),
-                    "Not enough data".to_string(),
-                )),
-                1 => XmlElementBlock::<Onix3ProjectMuse>::xml_element(works.first().unwrap(), w),
-                _ => {
-                    for work in works.iter() {
-                        XmlElementBlock::<Onix3ProjectMuse>::xml_element(work, w).ok();
-                    }
-                    Ok(())
-                }
-            }
+            write_element_block("SentDateTime", w, |w| {
+                w.write(XmlEvent::Characters(
+                    &Utc::now().format("%Y%m%dT%H%M%S").to_string(),
+                ))
+                .map_err(|e| e.into())
+            })
         })
     }
+
+    fn write_work<W: Write>(w: &mut EventWriter<W>, work: &Work) -> ThothResult<()> {
+        XmlElementBlock::<Onix3ProjectMuse>::xml_element(work, w)
+    }
+
+    fn write_footer<W: Write>(
+        w: &mut EventWriter<W>,
+        deleted_works: &[DeletedWork],
+    ) -> ThothResult<()> {
+        for deleted_work in deleted_works {
+            XmlElementBlock::<Onix3ProjectMuse>::xml_element(deleted_work, w)?;
+        }
+        w.write(XmlEvent::end_element()).map_err(|e| e.into())
+    }
 }
 
-impl XmlElementBlock<Onix3ProjectMuse> for Work {
-    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+impl RequiredFields for Onix3ProjectMuse {
+    const SPECIFICATION_ID: &'static str = "onix_3.0::project_muse";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        let mut missing = Vec::new();
         // Project MUSE can only ingest works which have at least one BIC or BISAC subject code
-        if !self
+        if !work
             .subjects
             .iter()
             .any(|s| s.subject_type.eq(&SubjectType::BISAC) || s.subject_type.eq(&SubjectType::BIC))
         {
-            Err(ThothError::IncompleteMetadataRecord(
-                "onix_3.0::project_muse".to_string(),
-                "No BIC or BISAC subject code".to_string(),
-            ))
+            missing.push("No BIC or BISAC subject code");
         }
         // We can only generate the document if there's a PDF
-        else if let Some(pdf_url) = self
-            .publications
-            .iter()
-            .find(|p| p.publication_type.eq(&PublicationType::PDF) && !p.locations.is_empty())
-            .and_then(|p| p.locations.iter().find(|l| l.canonical))
-            .and_then(|l| l.full_text_url.as_ref())
-        {
+        if full_text_url(work, PublicationType::PDF).is_none() {
+            missing.push("Missing PDF URL");
+        }
+        missing
+    }
+}
+
+impl XmlElementBlock<Onix3ProjectMuse> for Work {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        Onix3ProjectMuse::check_required_fields(self)?;
+        if let Some(pdf_url) = full_text_url(self, PublicationType::PDF) {
             let work_id = format!("urn:uuid:{}", self.work_id.to_string());
             let (main_isbn, isbns) = get_publications_data(&self.publications);
             write_element_block("Product", w, |w| {
@@ -84,9 +110,11 @@ impl XmlElementBlock<Onix3ProjectMuse> for Work {
                     w.write(XmlEvent::Characters(&work_id))
                         .map_err(|e| e.into())
                 })?;
-                // 03 Notification confirmed on publication
                 write_element_block("NotificationType", w, |w| {
-                    w.write(XmlEvent::Characters("03")).map_err(|e| e.into())
+                    w.write(XmlEvent::Characters(onix3_notification_type(
+                        &self.work_status,
+                    )))
+                    .map_err(|e| e.into())
                 })?;
                 // 01 Publisher
                 write_element_block("RecordSourceType", w, |w| {
@@ -271,6 +299,14 @@ impl XmlElementBlock<Onix3ProjectMuse> for Work {
                         })
                     })
                 })?;
+                if !self.relations.is_empty() {
+                    write_element_block("ContentDetail", w, |w| {
+                        for relation in &self.relations {
+                            XmlElementBlock::<Onix3ProjectMuse>::xml_element(relation, w)?;
+                        }
+                        Ok(())
+                    })?;
+                }
                 write_element_block("PublishingDetail", w, |w| {
                     write_element_block("Imprint", w, |w| {
                         write_element_block("ImprintName", w, |w| {
@@ -380,9 +416,12 @@ impl XmlElementBlock<Onix3ProjectMuse> for Work {
                                     })
                                 })
                             })?;
-                            // 99 Contact supplier
+                            // 99 Contact supplier, unless the product cannot be supplied
+                            let availability =
+                                onix3_unavailability(&self.work_status).unwrap_or("99");
                             write_element_block("ProductAvailability", w, |w| {
-                                w.write(XmlEvent::Characters("99")).map_err(|e| e.into())
+                                w.write(XmlEvent::Characters(availability))
+                                    .map_err(|e| e.into())
                             })?;
                             // 01 Free of charge
                             write_element_block("UnpricedItemType", w, |w| {
@@ -424,6 +463,12 @@ fn get_publications_data(publications: &[WorkPublications]) -> (String, Vec<Stri
     (main_isbn, isbns)
 }
 
+impl XmlElementBlock<Onix3ProjectMuse> for DeletedWork {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_onix3_deletion(&format!("urn:uuid:{}", self.work_id), w)
+    }
+}
+
 impl XmlElement<Onix3ProjectMuse> for WorkStatus {
     const ELEMENT: &'static str = "PublishingStatus";
 
@@ -536,6 +581,117 @@ impl XmlElementBlock<Onix3ProjectMuse> for WorkContributions {
     }
 }
 
+impl XmlElementBlock<Onix3ProjectMuse> for WorkRelations {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let chapter = &self.related_work;
+        write_element_block("ContentItem", w, |w| {
+            write_element_block("LevelSequenceNumber", w, |w| {
+                w.write(XmlEvent::Characters(&self.relation_ordinal.to_string()))
+                    .map_err(|e| e.into())
+            })?;
+            write_element_block("TextItem", w, |w| {
+                // 03 Body matter
+                write_element_block("TextItemType", w, |w| {
+                    w.write(XmlEvent::Characters("03")).map_err(|e| e.into())
+                })?;
+                if let Some(doi) = &chapter.doi {
+                    write_element_block("TextItemIdentifier", w, |w| {
+                        // 06 DOI
+                        write_element_block("TextItemIDType", w, |w| {
+                            w.write(XmlEvent::Characters("06")).map_err(|e| e.into())
+                        })?;
+                        write_element_block("IDValue", w, |w| {
+                            w.write(XmlEvent::Characters(&doi.to_string()))
+                                .map_err(|e| e.into())
+                        })
+                    })?;
+                }
+                if let Some(first_page) = &chapter.first_page {
+                    write_element_block("PageRun", w, |w| {
+                        write_element_block("FirstPageNumber", w, |w| {
+                            w.write(XmlEvent::Characters(first_page))
+                                .map_err(|e| e.into())
+                        })?;
+                        if let Some(last_page) = &chapter.last_page {
+                            write_element_block("LastPageNumber", w, |w| {
+                                w.write(XmlEvent::Characters(last_page))
+                                    .map_err(|e| e.into())
+                            })?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                if let Some(page_count) = chapter.page_count {
+                    write_element_block("NumberOfPages", w, |w| {
+                        w.write(XmlEvent::Characters(&page_count.to_string()))
+                            .map_err(|e| e.into())
+                    })?;
+                }
+                Ok(())
+            })?;
+            write_element_block("ComponentTypeName", w, |w| {
+                w.write(XmlEvent::Characters("Chapter"))
+                    .map_err(|e| e.into())
+            })?;
+            write_element_block("ComponentNumber", w, |w| {
+                w.write(XmlEvent::Characters(&self.relation_ordinal.to_string()))
+                    .map_err(|e| e.into())
+            })?;
+            write_element_block("TitleDetail", w, |w| {
+                // 01 Distinctive title
+                write_element_block("TitleType", w, |w| {
+                    w.write(XmlEvent::Characters("01")).map_err(|e| e.into())
+                })?;
+                write_element_block("TitleElement", w, |w| {
+                    // 04 Content item
+                    write_element_block("TitleElementLevel", w, |w| {
+                        w.write(XmlEvent::Characters("04")).map_err(|e| e.into())
+                    })?;
+                    if let Some(subtitle) = &chapter.subtitle {
+                        write_element_block("TitleText", w, |w| {
+                            w.write(XmlEvent::Characters(&chapter.title))
+                                .map_err(|e| e.into())
+                        })?;
+                        write_element_block("Subtitle", w, |w| {
+                            w.write(XmlEvent::Characters(subtitle))
+                                .map_err(|e| e.into())
+                        })
+                    } else {
+                        write_element_block("TitleText", w, |w| {
+                            w.write(XmlEvent::Characters(&chapter.full_title))
+                                .map_err(|e| e.into())
+                        })
+                    }
+                })
+            })?;
+            for contribution in &chapter.contributions {
+                XmlElementBlock::<Onix3ProjectMuse>::xml_element(
+                    &WorkContributions::from(contribution.clone()),
+                    w,
+                )
+                .ok();
+            }
+            if let Some(labstract) = &chapter.long_abstract {
+                write_element_block("TextContent", w, |w| {
+                    // 03 Description
+                    write_element_block("TextType", w, |w| {
+                        w.write(XmlEvent::Characters("03")).map_err(|e| e.into())
+                    })?;
+                    // 00 Unrestricted
+                    write_element_block("ContentAudience", w, |w| {
+                        w.write(XmlEvent::Characters("00")).map_err(|e| e.into())
+                    })?;
+                    write_element_block("Text", w, |w| {
+                        w.write(XmlEvent::Characters(labstract))
+                            .map_err(|e| e.into())
+                    })
+                })?;
+            }
+            Ok(())
+        })
+    }
+}
+
 impl XmlElementBlock<Onix3ProjectMuse> for WorkLanguages {
     fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
         write_element_block("Language", w, |w| {
@@ -564,7 +720,9 @@ mod tests {
     use thoth_client::{
         ContributionType, LanguageCode, LanguageRelation, LocationPlatform, PublicationType,
         WorkContributionsContributor, WorkFundings, WorkImprint, WorkImprintPublisher, WorkIssues,
-        WorkIssuesSeries, WorkPublicationsLocations, WorkStatus, WorkSubjects, WorkType,
+        WorkIssuesSeries, WorkPublicationsLocations, WorkRelationsRelatedWork,
+        WorkRelationsRelatedWorkContributions, WorkRelationsRelatedWorkContributionsContributor,
+        WorkStatus, WorkSubjects, WorkType,
     };
     use uuid::Uuid;
 
@@ -659,6 +817,74 @@ mod tests {
         assert!(output.contains(r#"  <ContributorRole>A15</ContributorRole>"#));
     }
 
+    #[test]
+    fn test_onix3_projectmuse_content_items() {
+        let mut relation = WorkRelations {
+            relation_ordinal: 1,
+            related_work: WorkRelationsRelatedWork {
+                work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000002").unwrap(),
+                work_status: WorkStatus::ACTIVE,
+                full_title: "Chapter Title: Chapter Subtitle".to_string(),
+                title: "Chapter Title".to_string(),
+                subtitle: Some("Chapter Subtitle".to_string()),
+                work_type: WorkType::BOOK_CHAPTER,
+                edition: 1,
+                doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001.01").unwrap()),
+                publication_date: None,
+                license: None,
+                copyright_holder: "Author 1".to_string(),
+                short_abstract: None,
+                long_abstract: Some("Chapter abstract".to_string()),
+                page_count: Some(10),
+                first_page: Some("1".to_string()),
+                last_page: Some("10".to_string()),
+                landing_page: None,
+                updated_at_with_relations: Default::default(),
+                contributions: vec![WorkRelationsRelatedWorkContributions {
+                    contribution_type: ContributionType::AUTHOR,
+                    first_name: Some("Author".to_string()),
+                    last_name: "1".to_string(),
+                    full_name: "Author 1".to_string(),
+                    main_contribution: true,
+                    biography: None,
+                    contribution_ordinal: 1,
+                    contributor: WorkRelationsRelatedWorkContributionsContributor { orcid: None },
+                    affiliations: vec![],
+                }],
+            },
+        };
+        let output = generate_test_output(&relation);
+        assert!(output.contains(r#"<ContentItem>"#));
+        assert!(output.contains(r#"  <LevelSequenceNumber>1</LevelSequenceNumber>"#));
+        assert!(output.contains(r#"    <TextItemType>03</TextItemType>"#));
+        assert!(output.contains(r#"      <TextItemIDType>06</TextItemIDType>"#));
+        assert!(output.contains(r#"      <IDValue>10.00001/BOOK.0001.01</IDValue>"#));
+        assert!(output.contains(r#"      <FirstPageNumber>1</FirstPageNumber>"#));
+        assert!(output.contains(r#"      <LastPageNumber>10</LastPageNumber>"#));
+        assert!(output.contains(r#"    <NumberOfPages>10</NumberOfPages>"#));
+        assert!(output.contains(r#"  <ComponentTypeName>Chapter</ComponentTypeName>"#));
+        assert!(output.contains(r#"  <ComponentNumber>1</ComponentNumber>"#));
+        assert!(output.contains(r#"      <TitleElementLevel>04</TitleElementLevel>"#));
+        assert!(output.contains(r#"      <TitleText>Chapter Title</TitleText>"#));
+        assert!(output.contains(r#"      <Subtitle>Chapter Subtitle</Subtitle>"#));
+        assert!(output.contains(r#"    <NamesBeforeKey>Author</NamesBeforeKey>"#));
+        assert!(output.contains(r#"    <Text>Chapter abstract</Text>"#));
+
+        // Remove optional elements
+        relation.related_work.subtitle = None;
+        relation.related_work.doi = None;
+        relation.related_work.first_page = None;
+        relation.related_work.page_count = None;
+        relation.related_work.long_abstract = None;
+        let output = generate_test_output(&relation);
+        assert!(output.contains(r#"      <TitleText>Chapter Title: Chapter Subtitle</TitleText>"#));
+        assert!(!output.contains(r#"<Subtitle>"#));
+        assert!(!output.contains(r#"<TextItemIdentifier>"#));
+        assert!(!output.contains(r#"<PageRun>"#));
+        assert!(!output.contains(r#"<NumberOfPages>"#));
+        assert!(!output.contains(r#"<TextContent>"#));
+    }
+
     #[test]
     fn test_onix3_projectmuse_languages() {
         let mut test_language = WorkLanguages {
@@ -711,6 +937,8 @@ mod tests {
             height_in: None,
             page_count: Some(334),
             page_breakdown: None,
+            first_page: None,
+            last_page: None,
             image_count: None,
             table_count: None,
             audio_count: None,
@@ -721,9 +949,14 @@ mod tests {
             oclc: None,
             cover_url: None,
             cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
             imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
                 imprint_name: "OA Editions Imprint".to_string(),
                 publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
                     publisher_name: "OA Editions".to_string(),
                     publisher_url: None,
                 },
@@ -797,6 +1030,8 @@ mod tests {
                     country_code: None,
                 },
             }],
+            relations: vec![],
+            parents: vec![],
         };
 
         // Test standard output
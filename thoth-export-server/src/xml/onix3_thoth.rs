@@ -0,0 +1,1406 @@
+use chrono::Utc;
+use std::io::Write;
+use thoth_api::model::DOI_DOMAIN;
+use thoth_client::{
+    ContributionType, DeletedWork, LanguageRelation, LocationPlatform, PublicationType,
+    SubjectType, Work, WorkContributions, WorkFundings, WorkIssues, WorkLanguages,
+    WorkPublications, WorkPublicationsLocations, WorkRelations, WorkStatus, WorkSubjects,
+};
+use xml::writer::{EventWriter, XmlEvent};
+
+use super::{
+    handle_stream_event, write_attributed_element, write_element_block, write_start_element,
+    XmlElement, XmlSpecification, XmlStreamSpecification,
+};
+use crate::record::RequiredFields;
+use crate::xml::{
+    onix3_notification_type, onix3_unavailability, write_onix3_deletion, XmlElementBlock,
+};
+use thoth_errors::ThothResult;
+
+pub struct Onix3Thoth {}
+
+impl XmlSpecification for Onix3Thoth {
+    fn handle_event<W: Write>(w: &mut EventWriter<W>, works: &[Work]) -> ThothResult<()> {
+        Self::handle_event_with_deletions(w, works, &[])
+    }
+
+    fn handle_event_with_deletions<W: Write>(
+        w: &mut EventWriter<W>,
+        works: &[Work],
+        deleted_works: &[DeletedWork],
+    ) -> ThothResult<()> {
+        handle_stream_event::<Self, W>(w, works, deleted_works)
+    }
+}
+
+impl XmlStreamSpecification for Onix3Thoth {
+    fn write_header<W: Write>(w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_start_element(
+            "ONIXMessage",
+            &[
+                ("release", "3.0"),
+                ("xmlns", "http://ns.editeur.org/onix/3.0/reference"),
+            ],
+            w,
+        )?;
+        write_element_block("Header", w, |w| {
+            write_element_block("Sender", w, |w| {
+                write_attributed_element("SenderName", &[], "Thoth", w)?;
+                write_attributed_element("EmailAddress", &[], "info@thoth.pub", w)
+            })?;
+            write_attributed_element(
+                "SentDateTime",
+                &[],
+                &Utc::now().format("%Y%m%dT%H%M%S").to_string(),
+                w,
+            )
+        })
+    }
+
+    fn write_work<W: Write>(w: &mut EventWriter<W>, work: &Work) -> ThothResult<()> {
+        XmlElementBlock::<Onix3Thoth>::xml_element(work, w)
+    }
+
+    fn write_footer<W: Write>(
+        w: &mut EventWriter<W>,
+        deleted_works: &[DeletedWork],
+    ) -> ThothResult<()> {
+        for deleted_work in deleted_works {
+            XmlElementBlock::<Onix3Thoth>::xml_element(deleted_work, w)?;
+        }
+        w.write(XmlEvent::end_element()).map_err(|e| e.into())
+    }
+}
+
+impl RequiredFields for Onix3Thoth {
+    const SPECIFICATION_ID: &'static str = "onix_3.0::thoth";
+
+    fn missing_fields(work: &Work) -> Vec<&'static str> {
+        let mut missing = Vec::new();
+        // Each publication is a separate product, so there must be at least one
+        if work.publications.is_empty() {
+            missing.push("No publications");
+        }
+        missing
+    }
+}
+
+impl XmlElementBlock<Onix3Thoth> for Work {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        Onix3Thoth::check_required_fields(self)?;
+        for publication in &self.publications {
+            write_product(self, publication, w)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_product<W: Write>(
+    work: &Work,
+    publication: &WorkPublications,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    let record_reference = format!("urn:uuid:{}", publication.publication_id);
+    write_element_block("Product", w, |w| {
+        write_attributed_element("RecordReference", &[], &record_reference, w)?;
+        write_attributed_element(
+            "NotificationType",
+            &[],
+            onix3_notification_type(&work.work_status),
+            w,
+        )?;
+        // 01 Publisher
+        write_attributed_element("RecordSourceType", &[], "01", w)?;
+        // 01 Proprietary
+        write_product_identifier("01", &record_reference, w)?;
+        if let Some(isbn) = &publication.isbn {
+            // 15 ISBN-13
+            write_product_identifier("15", &isbn.to_string().replace("-", ""), w)?;
+        }
+        if let Some(doi) = &work.doi {
+            // 06 DOI
+            write_product_identifier("06", &doi.to_string(), w)?;
+        }
+        write_descriptive_detail(work, publication, w)?;
+        write_collateral_detail(work, w)?;
+        write_content_detail(work, w)?;
+        write_publishing_detail(work, w)?;
+        write_related_material(work, publication, w)?;
+        write_product_supply(work, publication, w)
+    })
+}
+
+fn write_product_identifier<W: Write>(
+    id_type: &str,
+    id_value: &str,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    write_element_block("ProductIdentifier", w, |w| {
+        write_attributed_element("ProductIDType", &[], id_type, w)?;
+        write_attributed_element("IDValue", &[], id_value, w)
+    })
+}
+
+fn write_descriptive_detail<W: Write>(
+    work: &Work,
+    publication: &WorkPublications,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    write_element_block("DescriptiveDetail", w, |w| {
+        // 00 Single-component retail product
+        write_attributed_element("ProductComposition", &[], "00", w)?;
+        let (product_form, product_form_detail) = product_form(&publication.publication_type);
+        write_attributed_element("ProductForm", &[], product_form, w)?;
+        if let Some(product_form_detail) = product_form_detail {
+            write_attributed_element("ProductFormDetail", &[], product_form_detail, w)?;
+        }
+        // 10 Text (eye-readable)
+        write_attributed_element("PrimaryContentType", &[], "10", w)?;
+        // Dimensions only apply to physical products
+        if product_form_detail.is_none() {
+            // 01 Height, 02 Width
+            for (measure_type, measurement) in [("01", work.height_mm), ("02", work.width_mm)] {
+                if let Some(measurement) = measurement {
+                    write_element_block("Measure", w, |w| {
+                        write_attributed_element("MeasureType", &[], measure_type, w)?;
+                        write_attributed_element("Measurement", &[], &measurement.to_string(), w)?;
+                        write_attributed_element("MeasureUnitCode", &[], "mm", w)
+                    })?;
+                }
+            }
+        }
+        if let Some(license) = &work.license {
+            write_element_block("EpubLicense", w, |w| {
+                write_attributed_element("EpubLicenseName", &[], "Creative Commons License", w)?;
+                write_element_block("EpubLicenseExpression", w, |w| {
+                    // 02 Human readable
+                    write_attributed_element("EpubLicenseExpressionType", &[], "02", w)?;
+                    write_attributed_element("EpubLicenseExpressionLink", &[], license, w)
+                })
+            })?;
+        }
+        for issue in &work.issues {
+            XmlElementBlock::<Onix3Thoth>::xml_element(issue, w)?;
+        }
+        write_element_block("TitleDetail", w, |w| {
+            // 01 Distinctive title (book)
+            write_attributed_element("TitleType", &[], "01", w)?;
+            write_element_block("TitleElement", w, |w| {
+                // 01 Product
+                write_attributed_element("TitleElementLevel", &[], "01", w)?;
+                match &work.subtitle {
+                    Some(subtitle) => {
+                        write_attributed_element("TitleText", &[], &work.title, w)?;
+                        write_attributed_element("Subtitle", &[], subtitle, w)
+                    }
+                    None => write_attributed_element("TitleText", &[], &work.full_title, w),
+                }
+            })
+        })?;
+        let mut contributions: Vec<&WorkContributions> = work.contributions.iter().collect();
+        contributions.sort_by_key(|c| c.contribution_ordinal);
+        for contribution in contributions {
+            XmlElementBlock::<Onix3Thoth>::xml_element(contribution, w)?;
+        }
+        write_attributed_element("EditionNumber", &[], &work.edition.to_string(), w)?;
+        for language in &work.languages {
+            XmlElementBlock::<Onix3Thoth>::xml_element(language, w)?;
+        }
+        if let Some(page_count) = work.page_count {
+            write_element_block("Extent", w, |w| {
+                // 00 Main content
+                write_attributed_element("ExtentType", &[], "00", w)?;
+                write_attributed_element("ExtentValue", &[], &page_count.to_string(), w)?;
+                // 03 Pages
+                write_attributed_element("ExtentUnit", &[], "03", w)
+            })?;
+        }
+        if let Some(image_count) = work.image_count {
+            write_attributed_element("NumberOfIllustrations", &[], &image_count.to_string(), w)?;
+        }
+        let mut subjects: Vec<&WorkSubjects> = work.subjects.iter().collect();
+        subjects.sort_by_key(|s| s.subject_ordinal);
+        for subject in subjects {
+            XmlElementBlock::<Onix3Thoth>::xml_element(subject, w)?;
+        }
+        write_element_block("Audience", w, |w| {
+            // 01 ONIX audience codes
+            write_attributed_element("AudienceCodeType", &[], "01", w)?;
+            // 06 Professional and scholarly
+            write_attributed_element("AudienceCodeValue", &[], "06", w)
+        })
+    })
+}
+
+fn write_collateral_detail<W: Write>(work: &Work, w: &mut EventWriter<W>) -> ThothResult<()> {
+    // 03 Description, 02 Short description/annotation, 04 Table of contents
+    let texts: Vec<(&str, &String)> = [
+        ("03", &work.long_abstract),
+        ("02", &work.short_abstract),
+        ("04", &work.toc),
+    ]
+    .iter()
+    .filter_map(|(text_type, text)| text.as_ref().map(|t| (*text_type, t)))
+    .collect();
+    if texts.is_empty() && work.cover_url.is_none() {
+        return Ok(());
+    }
+    write_element_block("CollateralDetail", w, |w| {
+        for (text_type, text) in &texts {
+            write_element_block("TextContent", w, |w| {
+                write_attributed_element("TextType", &[], text_type, w)?;
+                // 00 Unrestricted
+                write_attributed_element("ContentAudience", &[], "00", w)?;
+                write_attributed_element("Text", &[], text, w)
+            })?;
+        }
+        if let Some(cover_url) = &work.cover_url {
+            write_element_block("SupportingResource", w, |w| {
+                // 01 Front cover
+                write_attributed_element("ResourceContentType", &[], "01", w)?;
+                // 00 Unrestricted
+                write_attributed_element("ContentAudience", &[], "00", w)?;
+                // 03 Image
+                write_attributed_element("ResourceMode", &[], "03", w)?;
+                write_element_block("ResourceVersion", w, |w| {
+                    // 02 Downloadable file
+                    write_attributed_element("ResourceForm", &[], "02", w)?;
+                    if let Some(cover_caption) = &work.cover_caption {
+                        write_element_block("ResourceVersionFeature", w, |w| {
+                            // 06 Caption
+                            write_attributed_element("ResourceVersionFeatureType", &[], "06", w)?;
+                            write_attributed_element("FeatureNote", &[], cover_caption, w)
+                        })?;
+                    }
+                    write_attributed_element("ResourceLink", &[], cover_url, w)
+                })
+            })?;
+        }
+        Ok(())
+    })
+}
+
+fn write_content_detail<W: Write>(work: &Work, w: &mut EventWriter<W>) -> ThothResult<()> {
+    if work.relations.is_empty() {
+        return Ok(());
+    }
+    let mut relations: Vec<&WorkRelations> = work.relations.iter().collect();
+    relations.sort_by_key(|r| r.relation_ordinal);
+    write_element_block("ContentDetail", w, |w| {
+        for relation in &relations {
+            XmlElementBlock::<Onix3Thoth>::xml_element(*relation, w)?;
+        }
+        Ok(())
+    })
+}
+
+fn write_publishing_detail<W: Write>(work: &Work, w: &mut EventWriter<W>) -> ThothResult<()> {
+    write_element_block("PublishingDetail", w, |w| {
+        write_element_block("Imprint", w, |w| {
+            write_attributed_element("ImprintName", &[], &work.imprint.imprint_name, w)
+        })?;
+        write_element_block("Publisher", w, |w| {
+            // 01 Publisher
+            write_attributed_element("PublishingRole", &[], "01", w)?;
+            write_attributed_element(
+                "PublisherName",
+                &[],
+                &work.imprint.publisher.publisher_name,
+                w,
+            )?;
+            if let Some(publisher_url) = &work.imprint.publisher.publisher_url {
+                write_element_block("Website", w, |w| {
+                    // 01 Publisher’s corporate website
+                    write_attributed_element("WebsiteRole", &[], "01", w)?;
+                    write_attributed_element("WebsiteLink", &[], publisher_url, w)
+                })?;
+            }
+            Ok(())
+        })?;
+        for funding in &work.fundings {
+            XmlElementBlock::<Onix3Thoth>::xml_element(funding, w)?;
+        }
+        if let Some(place) = &work.place {
+            write_attributed_element("CityOfPublication", &[], place, w)?;
+        }
+        XmlElement::<Onix3Thoth>::xml_element(&work.work_status, w)?;
+        if let Some(date) = work.publication_date {
+            write_element_block("PublishingDate", w, |w| {
+                // 01 Publication date
+                write_attributed_element("PublishingDateRole", &[], "01", w)?;
+                // 00 YYYYMMDD
+                write_attributed_element(
+                    "Date",
+                    &[("dateformat", "00")],
+                    &date.format("%Y%m%d").to_string(),
+                    w,
+                )
+            })?;
+        }
+        write_element_block("CopyrightStatement", w, |w| {
+            if let Some(date) = work.publication_date {
+                write_attributed_element("CopyrightYear", &[], &date.format("%Y").to_string(), w)?;
+            }
+            write_element_block("CopyrightOwner", w, |w| {
+                write_attributed_element("CorporateName", &[], &work.copyright_holder, w)
+            })
+        })
+    })
+}
+
+fn write_related_material<W: Write>(
+    work: &Work,
+    publication: &WorkPublications,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    let related: Vec<&WorkPublications> = work
+        .publications
+        .iter()
+        .filter(|p| p.publication_id != publication.publication_id)
+        .collect();
+    if related.is_empty() {
+        return Ok(());
+    }
+    write_element_block("RelatedMaterial", w, |w| {
+        for related_publication in &related {
+            write_element_block("RelatedProduct", w, |w| {
+                // 06 Alternative format
+                write_attributed_element("ProductRelationCode", &[], "06", w)?;
+                // 01 Proprietary, matching the related product's own record reference
+                write_product_identifier(
+                    "01",
+                    &format!("urn:uuid:{}", related_publication.publication_id),
+                    w,
+                )?;
+                if let Some(isbn) = &related_publication.isbn {
+                    // 15 ISBN-13
+                    write_product_identifier("15", &isbn.to_string().replace("-", ""), w)?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(())
+    })
+}
+
+fn write_product_supply<W: Write>(
+    work: &Work,
+    publication: &WorkPublications,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    let mut locations: Vec<&WorkPublicationsLocations> = publication.locations.iter().collect();
+    // Canonical location first
+    locations.sort_by_key(|l| !l.canonical);
+    write_element_block("ProductSupply", w, |w| {
+        // The publisher supplies the product at the listed prices, via the work's landing page
+        write_element_block("SupplyDetail", w, |w| {
+            write_element_block("Supplier", w, |w| {
+                // 09 Publisher to end-customers
+                write_attributed_element("SupplierRole", &[], "09", w)?;
+                write_attributed_element(
+                    "SupplierName",
+                    &[],
+                    &work.imprint.publisher.publisher_name,
+                    w,
+                )?;
+                if let Some(landing_page) = &work.landing_page {
+                    write_website("01", "Publisher's website: web shop", landing_page, w)?;
+                }
+                Ok(())
+            })?;
+            // 99 Contact supplier, unless the product cannot be supplied
+            write_attributed_element(
+                "ProductAvailability",
+                &[],
+                onix3_unavailability(&work.work_status).unwrap_or("99"),
+                w,
+            )?;
+            if publication.prices.is_empty() {
+                // 04 Contact supplier
+                write_attributed_element("UnpricedItemType", &[], "04", w)
+            } else {
+                for price in &publication.prices {
+                    write_element_block("Price", w, |w| {
+                        // 02 RRP including tax
+                        write_attributed_element("PriceType", &[], "02", w)?;
+                        write_attributed_element(
+                            "PriceAmount",
+                            &[],
+                            &format!("{:.2}", price.unit_price),
+                            w,
+                        )?;
+                        write_attributed_element(
+                            "CurrencyCode",
+                            &[],
+                            &format!("{:?}", price.currency_code),
+                            w,
+                        )
+                    })?;
+                }
+                Ok(())
+            }
+        })?;
+        for location in &locations {
+            write_element_block("SupplyDetail", w, |w| {
+                write_element_block("Supplier", w, |w| {
+                    match &location.location_platform {
+                        LocationPlatform::OTHER => {
+                            // 09 Publisher to end-customers
+                            write_attributed_element("SupplierRole", &[], "09", w)?;
+                            write_attributed_element(
+                                "SupplierName",
+                                &[],
+                                &work.imprint.publisher.publisher_name,
+                                w,
+                            )?;
+                        }
+                        platform => {
+                            // 11 Non-exclusive distributor to end-customers
+                            write_attributed_element("SupplierRole", &[], "11", w)?;
+                            write_attributed_element(
+                                "SupplierName",
+                                &[],
+                                platform_name(platform),
+                                w,
+                            )?;
+                        }
+                    }
+                    if let Some(landing_page) = &location.landing_page {
+                        write_website("01", "Web shop", landing_page, w)?;
+                    }
+                    if let Some(full_text_url) = &location.full_text_url {
+                        write_website("29", "Download the title", full_text_url, w)?;
+                    }
+                    Ok(())
+                })?;
+                // 99 Contact supplier, unless the product cannot be supplied
+                write_attributed_element(
+                    "ProductAvailability",
+                    &[],
+                    onix3_unavailability(&work.work_status).unwrap_or("99"),
+                    w,
+                )?;
+                // 04 Contact supplier
+                write_attributed_element("UnpricedItemType", &[], "04", w)
+            })?;
+        }
+        Ok(())
+    })
+}
+
+fn write_website<W: Write>(
+    role: &str,
+    description: &str,
+    link: &str,
+    w: &mut EventWriter<W>,
+) -> ThothResult<()> {
+    write_element_block("Website", w, |w| {
+        write_attributed_element("WebsiteRole", &[], role, w)?;
+        write_attributed_element("WebsiteDescription", &[], description, w)?;
+        write_attributed_element("WebsiteLink", &[], link, w)
+    })
+}
+
+/// ONIX product form and, for digital products, product form detail
+fn product_form(publication_type: &PublicationType) -> (&'static str, Option<&'static str>) {
+    match publication_type {
+        // BC Paperback / softback
+        PublicationType::PAPERBACK => ("BC", None),
+        // BB Hardback
+        PublicationType::HARDBACK => ("BB", None),
+        // EB Digital download and online, with the file format as detail
+        PublicationType::PDF => ("EB", Some("E107")),
+        PublicationType::HTML => ("EB", Some("E105")),
+        // There is no specific code for XML, so we use E100 Other
+        PublicationType::XML => ("EB", Some("E100")),
+        PublicationType::EPUB => ("EB", Some("E101")),
+        PublicationType::MOBI => ("EB", Some("E127")),
+        PublicationType::Other(_) => unreachable!(),
+    }
+}
+
+fn platform_name(location_platform: &LocationPlatform) -> &'static str {
+    match location_platform {
+        LocationPlatform::PROJECT_MUSE => "Project MUSE",
+        LocationPlatform::OAPEN => "OAPEN",
+        LocationPlatform::DOAB => "DOAB",
+        LocationPlatform::JSTOR => "JSTOR",
+        LocationPlatform::EBSCO_HOST => "EBSCO Host",
+        LocationPlatform::OCLC_KB => "OCLC KB",
+        LocationPlatform::PROQUEST_KB => "ProQuest KB",
+        LocationPlatform::PROQUEST_EXLIBRIS => "ProQuest ExLibris",
+        LocationPlatform::EBSCO_KB => "EBSCO KB",
+        LocationPlatform::JISC_KB => "JISC KB",
+        LocationPlatform::OTHER | LocationPlatform::Other(_) => unreachable!(),
+    }
+}
+
+impl XmlElementBlock<Onix3Thoth> for DeletedWork {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        // Each publication was sent as a separate product
+        for publication_id in &self.publication_ids {
+            write_onix3_deletion(&format!("urn:uuid:{}", publication_id), w)?;
+        }
+        Ok(())
+    }
+}
+
+impl XmlElement<Onix3Thoth> for WorkStatus {
+    const ELEMENT: &'static str = "PublishingStatus";
+
+    fn value(&self) -> &'static str {
+        match self {
+            WorkStatus::UNSPECIFIED => "00",
+            WorkStatus::CANCELLED => "01",
+            WorkStatus::FORTHCOMING => "02",
+            WorkStatus::POSTPONED_INDEFINITELY => "03",
+            WorkStatus::ACTIVE => "04",
+            WorkStatus::NO_LONGER_OUR_PRODUCT => "05",
+            WorkStatus::OUT_OF_STOCK_INDEFINITELY => "06",
+            WorkStatus::OUT_OF_PRINT => "07",
+            WorkStatus::INACTIVE => "08",
+            WorkStatus::UNKNOWN => "09",
+            WorkStatus::REMAINDERED => "10",
+            WorkStatus::WITHDRAWN_FROM_SALE => "11",
+            WorkStatus::RECALLED => "15",
+            WorkStatus::Other(_) => unreachable!(),
+        }
+    }
+}
+
+impl XmlElement<Onix3Thoth> for SubjectType {
+    const ELEMENT: &'static str = "SubjectSchemeIdentifier";
+
+    fn value(&self) -> &'static str {
+        match self {
+            SubjectType::BIC => "12",
+            SubjectType::BISAC => "10",
+            SubjectType::KEYWORD => "20",
+            SubjectType::LCC => "04",
+            SubjectType::THEMA => "93",
+            // 24 Proprietary subject scheme
+            SubjectType::CUSTOM => "24",
+            SubjectType::Other(_) => unreachable!(),
+        }
+    }
+}
+
+impl XmlElement<Onix3Thoth> for LanguageRelation {
+    const ELEMENT: &'static str = "LanguageRole";
+
+    fn value(&self) -> &'static str {
+        match self {
+            LanguageRelation::ORIGINAL => "01",
+            LanguageRelation::TRANSLATED_FROM => "02",
+            LanguageRelation::TRANSLATED_INTO => "01",
+            LanguageRelation::Other(_) => unreachable!(),
+        }
+    }
+}
+
+impl XmlElement<Onix3Thoth> for ContributionType {
+    const ELEMENT: &'static str = "ContributorRole";
+
+    fn value(&self) -> &'static str {
+        match self {
+            ContributionType::AUTHOR => "A01",
+            ContributionType::EDITOR | ContributionType::MUSIC_EDITOR => "B01",
+            ContributionType::TRANSLATOR => "B06",
+            ContributionType::PHOTOGRAPHER => "A13",
+            ContributionType::ILUSTRATOR => "A12",
+            ContributionType::FOREWORD_BY => "A23",
+            ContributionType::INTRODUCTION_BY => "A24",
+            ContributionType::AFTERWORD_BY => "A19",
+            ContributionType::PREFACE_BY => "A15",
+            ContributionType::Other(_) => unreachable!(),
+        }
+    }
+}
+
+impl XmlElementBlock<Onix3Thoth> for WorkRelations {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        let chapter = &self.related_work;
+        write_element_block("ContentItem", w, |w| {
+            let ordinal = self.relation_ordinal.to_string();
+            write_attributed_element("LevelSequenceNumber", &[], &ordinal, w)?;
+            write_element_block("TextItem", w, |w| {
+                // 03 Body matter
+                write_attributed_element("TextItemType", &[], "03", w)?;
+                if let Some(doi) = &chapter.doi {
+                    write_element_block("TextItemIdentifier", w, |w| {
+                        // 06 DOI
+                        write_attributed_element("TextItemIDType", &[], "06", w)?;
+                        write_attributed_element("IDValue", &[], &doi.to_string(), w)
+                    })?;
+                }
+                if let Some(first_page) = &chapter.first_page {
+                    write_element_block("PageRun", w, |w| {
+                        write_attributed_element("FirstPageNumber", &[], first_page, w)?;
+                        if let Some(last_page) = &chapter.last_page {
+                            write_attributed_element("LastPageNumber", &[], last_page, w)?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                if let Some(page_count) = chapter.page_count {
+                    write_attributed_element("NumberOfPages", &[], &page_count.to_string(), w)?;
+                }
+                Ok(())
+            })?;
+            write_attributed_element("ComponentTypeName", &[], "Chapter", w)?;
+            write_attributed_element("ComponentNumber", &[], &ordinal, w)?;
+            write_element_block("TitleDetail", w, |w| {
+                // 01 Distinctive title
+                write_attributed_element("TitleType", &[], "01", w)?;
+                write_element_block("TitleElement", w, |w| {
+                    // 04 Content item
+                    write_attributed_element("TitleElementLevel", &[], "04", w)?;
+                    match &chapter.subtitle {
+                        Some(subtitle) => {
+                            write_attributed_element("TitleText", &[], &chapter.title, w)?;
+                            write_attributed_element("Subtitle", &[], subtitle, w)
+                        }
+                        None => write_attributed_element("TitleText", &[], &chapter.full_title, w),
+                    }
+                })
+            })?;
+            let mut contributions: Vec<WorkContributions> = chapter
+                .contributions
+                .iter()
+                .cloned()
+                .map(WorkContributions::from)
+                .collect();
+            contributions.sort_by_key(|c| c.contribution_ordinal);
+            for contribution in &contributions {
+                XmlElementBlock::<Onix3Thoth>::xml_element(contribution, w)?;
+            }
+            // 03 Description, 02 Short description/annotation
+            for (text_type, text) in [
+                ("03", &chapter.long_abstract),
+                ("02", &chapter.short_abstract),
+            ]
+            .iter()
+            .filter_map(|(text_type, text)| text.as_ref().map(|t| (*text_type, t)))
+            {
+                write_element_block("TextContent", w, |w| {
+                    write_attributed_element("TextType", &[], text_type, w)?;
+                    // 00 Unrestricted
+                    write_attributed_element("ContentAudience", &[], "00", w)?;
+                    write_attributed_element("Text", &[], text, w)
+                })?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl XmlElementBlock<Onix3Thoth> for WorkContributions {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_element_block("Contributor", w, |w| {
+            write_attributed_element(
+                "SequenceNumber",
+                &[],
+                &self.contribution_ordinal.to_string(),
+                w,
+            )?;
+            XmlElement::<Onix3Thoth>::xml_element(&self.contribution_type, w)?;
+            if let Some(orcid) = &self.contributor.orcid {
+                write_element_block("NameIdentifier", w, |w| {
+                    // 21 ORCID
+                    write_attributed_element("NameIDType", &[], "21", w)?;
+                    write_attributed_element("IDValue", &[], &orcid.to_string(), w)
+                })?;
+            }
+            match &self.first_name {
+                Some(first_name) => {
+                    write_attributed_element("NamesBeforeKey", &[], first_name, w)?;
+                    write_attributed_element("KeyNames", &[], &self.last_name, w)?;
+                }
+                None => write_attributed_element("PersonName", &[], &self.full_name, w)?,
+            }
+            let mut affiliations: Vec<_> = self.affiliations.iter().collect();
+            affiliations.sort_by_key(|a| a.affiliation_ordinal);
+            for affiliation in affiliations {
+                write_element_block("ProfessionalAffiliation", w, |w| {
+                    if let Some(position) = &affiliation.position {
+                        write_attributed_element("ProfessionalPosition", &[], position, w)?;
+                    }
+                    write_attributed_element(
+                        "Affiliation",
+                        &[],
+                        &affiliation.institution.institution_name,
+                        w,
+                    )
+                })?;
+            }
+            if let Some(biography) = &self.biography {
+                write_attributed_element("BiographicalNote", &[], biography, w)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl XmlElementBlock<Onix3Thoth> for WorkLanguages {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_element_block("Language", w, |w| {
+            XmlElement::<Onix3Thoth>::xml_element(&self.language_relation, w)?;
+            write_attributed_element(
+                "LanguageCode",
+                &[],
+                &self.language_code.to_string().to_lowercase(),
+                w,
+            )
+        })
+    }
+}
+
+impl XmlElementBlock<Onix3Thoth> for WorkIssues {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_element_block("Collection", w, |w| {
+            // 10 Publisher collection (e.g. series)
+            write_attributed_element("CollectionType", &[], "10", w)?;
+            for issn in &[&self.series.issn_print, &self.series.issn_digital] {
+                write_element_block("CollectionIdentifier", w, |w| {
+                    // 02 ISSN
+                    write_attributed_element("CollectionIDType", &[], "02", w)?;
+                    write_attributed_element("IDValue", &[], &issn.replace("-", ""), w)
+                })?;
+            }
+            write_element_block("TitleDetail", w, |w| {
+                // 01 Cover title (serial)
+                write_attributed_element("TitleType", &[], "01", w)?;
+                write_element_block("TitleElement", w, |w| {
+                    // 02 Collection level
+                    write_attributed_element("TitleElementLevel", &[], "02", w)?;
+                    write_attributed_element(
+                        "PartNumber",
+                        &[],
+                        &self.issue_ordinal.to_string(),
+                        w,
+                    )?;
+                    write_attributed_element("TitleText", &[], &self.series.series_name, w)
+                })
+            })
+        })
+    }
+}
+
+impl XmlElementBlock<Onix3Thoth> for WorkFundings {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_element_block("Publisher", w, |w| {
+            // 16 Funding body
+            write_attributed_element("PublishingRole", &[], "16", w)?;
+            if let Some(doi) = &self.institution.institution_doi {
+                write_element_block("PublisherIdentifier", w, |w| {
+                    // 32 FundRef DOI
+                    write_attributed_element("PublisherIDType", &[], "32", w)?;
+                    write_attributed_element("IDValue", &[], &format!("{}{}", DOI_DOMAIN, doi), w)
+                })?;
+            }
+            if let Some(ror) = &self.institution.ror {
+                write_element_block("PublisherIdentifier", w, |w| {
+                    // 01 Proprietary
+                    write_attributed_element("PublisherIDType", &[], "01", w)?;
+                    write_attributed_element("IDTypeName", &[], "ROR", w)?;
+                    write_attributed_element("IDValue", &[], &ror.to_string(), w)
+                })?;
+            }
+            write_attributed_element("PublisherName", &[], &self.institution.institution_name, w)?;
+            let identifiers: Vec<(&str, &String)> = [
+                ("programname", &self.program),
+                ("projectname", &self.project_name),
+                ("projectshortname", &self.project_shortname),
+                ("grantnumber", &self.grant_number),
+                ("jurisdiction", &self.jurisdiction),
+            ]
+            .iter()
+            .filter_map(|(name, value)| value.as_ref().map(|v| (*name, v)))
+            .collect();
+            if !identifiers.is_empty() {
+                write_element_block("Funding", w, |w| {
+                    for (name, value) in &identifiers {
+                        write_element_block("FundingIdentifier", w, |w| {
+                            // 01 Proprietary
+                            write_attributed_element("FundingIDType", &[], "01", w)?;
+                            write_attributed_element("IDTypeName", &[], name, w)?;
+                            write_attributed_element("IDValue", &[], value, w)
+                        })?;
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl XmlElementBlock<Onix3Thoth> for WorkSubjects {
+    fn xml_element<W: Write>(&self, w: &mut EventWriter<W>) -> ThothResult<()> {
+        write_element_block("Subject", w, |w| {
+            XmlElement::<Onix3Thoth>::xml_element(&self.subject_type, w)?;
+            match self.subject_type {
+                SubjectType::KEYWORD => {
+                    write_attributed_element("SubjectHeadingText", &[], &self.subject_code, w)
+                }
+                SubjectType::CUSTOM => {
+                    write_attributed_element("SubjectSchemeName", &[], "Thoth custom", w)?;
+                    write_attributed_element("SubjectHeadingText", &[], &self.subject_code, w)
+                }
+                _ => write_attributed_element("SubjectCode", &[], &self.subject_code, w),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Testing note: XML nodes cannot be guaranteed to be output in the same order every time
+    // We therefore rely on `assert!(contains)` rather than `assert_eq!`
+    use super::*;
+    use std::str::FromStr;
+    use thoth_api::model::{Doi, Isbn, Orcid, Ror};
+    use thoth_client::{
+        CurrencyCode, LanguageCode, SeriesType, WorkContributionsAffiliations,
+        WorkContributionsAffiliationsInstitution, WorkContributionsContributor,
+        WorkFundingsInstitution, WorkImprint, WorkImprintPublisher, WorkIssuesSeries,
+        WorkPublicationsPrices, WorkRelationsRelatedWork, WorkRelationsRelatedWorkContributions,
+        WorkRelationsRelatedWorkContributionsContributor, WorkType,
+    };
+    use thoth_errors::ThothError;
+    use uuid::Uuid;
+
+    fn generate_test_output(input: &impl XmlElementBlock<Onix3Thoth>) -> String {
+        // Helper function based on `XmlSpecification::generate`
+        let mut buffer = Vec::new();
+        let mut writer = xml::writer::EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(&mut buffer);
+        let wrapped_output = XmlElementBlock::<Onix3Thoth>::xml_element(input, &mut writer)
+            .map(|_| buffer)
+            .and_then(|onix| {
+                String::from_utf8(onix)
+                    .map_err(|_| ThothError::InternalError("Could not parse XML".to_string()))
+            });
+        assert!(wrapped_output.is_ok());
+        wrapped_output.unwrap()
+    }
+
+    fn test_work() -> Work {
+        Work {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            work_status: WorkStatus::ACTIVE,
+            full_title: "Book Title: Book Subtitle".to_string(),
+            title: "Book Title".to_string(),
+            subtitle: Some("Book Subtitle".to_string()),
+            work_type: WorkType::MONOGRAPH,
+            edition: 1,
+            doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001").unwrap()),
+            publication_date: Some(chrono::NaiveDate::from_ymd(1999, 12, 31)),
+            license: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            copyright_holder: "Author 1".to_string(),
+            short_abstract: Some("Lorem ipsum".to_string()),
+            long_abstract: Some("Lorem ipsum dolor sit amet".to_string()),
+            general_note: None,
+            place: Some("León, Spain".to_string()),
+            width_mm: Some(156.0),
+            width_cm: None,
+            width_in: None,
+            height_mm: Some(234.0),
+            height_cm: None,
+            height_in: None,
+            page_count: Some(334),
+            page_breakdown: None,
+            first_page: None,
+            last_page: None,
+            image_count: Some(15),
+            table_count: None,
+            audio_count: None,
+            video_count: None,
+            landing_page: Some("https://www.book.com".to_string()),
+            toc: Some("1. Chapter 1".to_string()),
+            lccn: None,
+            oclc: None,
+            cover_url: Some("https://www.book.com/cover".to_string()),
+            cover_caption: None,
+            created_at: Default::default(),
+            updated_at: Default::default(),
+            updated_at_with_relations: Default::default(),
+            imprint: WorkImprint {
+                imprint_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000001").unwrap(),
+                imprint_name: "OA Editions Imprint".to_string(),
+                publisher: WorkImprintPublisher {
+                    publisher_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000001").unwrap(),
+                    publisher_name: "OA Editions".to_string(),
+                    publisher_url: Some("https://www.oaeditions.com".to_string()),
+                },
+            },
+            issues: vec![WorkIssues {
+                issue_ordinal: 3,
+                series: WorkIssuesSeries {
+                    series_type: SeriesType::BOOK_SERIES,
+                    series_name: "Name of series".to_string(),
+                    issn_print: "1234-5678".to_string(),
+                    issn_digital: "8765-4321".to_string(),
+                    series_url: None,
+                },
+            }],
+            contributions: vec![WorkContributions {
+                contribution_type: ContributionType::AUTHOR,
+                first_name: Some("Author".to_string()),
+                last_name: "1".to_string(),
+                full_name: "Author 1".to_string(),
+                main_contribution: true,
+                biography: Some("Author 1 was born".to_string()),
+                contribution_ordinal: 1,
+                contributor: WorkContributionsContributor {
+                    orcid: Some(Orcid::from_str("https://orcid.org/0000-0002-0000-0001").unwrap()),
+                },
+                affiliations: vec![WorkContributionsAffiliations {
+                    position: Some("Manager".to_string()),
+                    affiliation_ordinal: 1,
+                    institution: WorkContributionsAffiliationsInstitution {
+                        institution_name: "University of Life".to_string(),
+                        ror: None,
+                    },
+                }],
+            }],
+            languages: vec![WorkLanguages {
+                language_code: LanguageCode::SPA,
+                language_relation: LanguageRelation::ORIGINAL,
+                main_language: true,
+            }],
+            publications: vec![
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-BBBB-000000000002").unwrap(),
+                    publication_type: PublicationType::PAPERBACK,
+                    isbn: Some(Isbn::from_str("978-3-16-148410-0").unwrap()),
+                    prices: vec![
+                        WorkPublicationsPrices {
+                            currency_code: CurrencyCode::EUR,
+                            unit_price: 25.95,
+                        },
+                        WorkPublicationsPrices {
+                            currency_code: CurrencyCode::GBP,
+                            unit_price: 22.0,
+                        },
+                    ],
+                    locations: vec![],
+                },
+                WorkPublications {
+                    publication_id: Uuid::from_str("00000000-0000-0000-CCCC-000000000003").unwrap(),
+                    publication_type: PublicationType::PDF,
+                    isbn: None,
+                    prices: vec![],
+                    locations: vec![
+                        WorkPublicationsLocations {
+                            landing_page: Some("https://www.jstor.org/book".to_string()),
+                            full_text_url: None,
+                            location_platform: LocationPlatform::JSTOR,
+                            canonical: false,
+                        },
+                        WorkPublicationsLocations {
+                            landing_page: Some("https://www.book.com/pdf_landing".to_string()),
+                            full_text_url: Some("https://www.book.com/pdf_fulltext".to_string()),
+                            location_platform: LocationPlatform::OTHER,
+                            canonical: true,
+                        },
+                    ],
+                },
+            ],
+            subjects: vec![
+                WorkSubjects {
+                    subject_code: "AAA000000".to_string(),
+                    subject_type: SubjectType::BISAC,
+                    subject_ordinal: 1,
+                },
+                WorkSubjects {
+                    subject_code: "keyword1".to_string(),
+                    subject_type: SubjectType::KEYWORD,
+                    subject_ordinal: 2,
+                },
+                WorkSubjects {
+                    subject_code: "custom1".to_string(),
+                    subject_type: SubjectType::CUSTOM,
+                    subject_ordinal: 3,
+                },
+            ],
+            fundings: vec![WorkFundings {
+                program: Some("Name of program".to_string()),
+                project_name: None,
+                project_shortname: None,
+                grant_number: Some("Number of grant".to_string()),
+                jurisdiction: None,
+                institution: WorkFundingsInstitution {
+                    institution_name: "Name of institution".to_string(),
+                    institution_doi: Some(
+                        Doi::from_str("https://doi.org/10.13039/100014013").unwrap(),
+                    ),
+                    ror: Some(Ror::from_str("https://ror.org/0aaaaaa00").unwrap()),
+                    country_code: None,
+                },
+            }],
+            relations: vec![],
+            parents: vec![],
+        }
+    }
+
+    #[test]
+    fn test_onix3_thoth_contributions() {
+        let mut test_contribution = test_work().contributions.pop().unwrap();
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"<Contributor>"#));
+        assert!(output.contains(r#"  <SequenceNumber>1</SequenceNumber>"#));
+        assert!(output.contains(r#"  <ContributorRole>A01</ContributorRole>"#));
+        assert!(output.contains(r#"    <NameIDType>21</NameIDType>"#));
+        assert!(output.contains(r#"    <IDValue>0000-0002-0000-0001</IDValue>"#));
+        assert!(output.contains(r#"  <NamesBeforeKey>Author</NamesBeforeKey>"#));
+        assert!(output.contains(r#"  <KeyNames>1</KeyNames>"#));
+        assert!(output.contains(
+            "  <ProfessionalAffiliation>\n    <ProfessionalPosition>Manager</ProfessionalPosition>\n    <Affiliation>University of Life</Affiliation>\n  </ProfessionalAffiliation>"
+        ));
+        assert!(output.contains(r#"  <BiographicalNote>Author 1 was born</BiographicalNote>"#));
+
+        // Name is not split if there is no given name
+        test_contribution.contribution_type = ContributionType::MUSIC_EDITOR;
+        test_contribution.first_name = None;
+        test_contribution.biography = None;
+        test_contribution.contributor.orcid = None;
+        test_contribution.affiliations.clear();
+        let output = generate_test_output(&test_contribution);
+        assert!(output.contains(r#"  <ContributorRole>B01</ContributorRole>"#));
+        assert!(output.contains(r#"  <PersonName>Author 1</PersonName>"#));
+        assert!(!output.contains(r#"<NamesBeforeKey>"#));
+        assert!(!output.contains(r#"<NameIdentifier>"#));
+        assert!(!output.contains(r#"<ProfessionalAffiliation>"#));
+        assert!(!output.contains(r#"<BiographicalNote>"#));
+    }
+
+    #[test]
+    fn test_onix3_thoth_fundings() {
+        let mut test_funding = test_work().fundings.pop().unwrap();
+        let output = generate_test_output(&test_funding);
+        assert!(output.contains(r#"  <PublishingRole>16</PublishingRole>"#));
+        assert!(output.contains(
+            "    <PublisherIDType>32</PublisherIDType>\n    <IDValue>https://doi.org/10.13039/100014013</IDValue>"
+        ));
+        assert!(output.contains(
+            "    <PublisherIDType>01</PublisherIDType>\n    <IDTypeName>ROR</IDTypeName>\n    <IDValue>0aaaaaa00</IDValue>"
+        ));
+        assert!(output.contains(r#"  <PublisherName>Name of institution</PublisherName>"#));
+        // Funding identifiers are always output in the same order
+        assert!(output.contains(
+            "      <FundingIDType>01</FundingIDType>\n      <IDTypeName>programname</IDTypeName>\n      <IDValue>Name of program</IDValue>\n    </FundingIdentifier>\n    <FundingIdentifier>\n      <FundingIDType>01</FundingIDType>\n      <IDTypeName>grantnumber</IDTypeName>\n      <IDValue>Number of grant</IDValue>"
+        ));
+
+        // Funding block is omitted if there are no identifiers
+        test_funding.program = None;
+        test_funding.grant_number = None;
+        test_funding.institution.institution_doi = None;
+        test_funding.institution.ror = None;
+        let output = generate_test_output(&test_funding);
+        assert!(!output.contains(r#"<PublisherIdentifier>"#));
+        assert!(!output.contains(r#"<Funding>"#));
+    }
+
+    #[test]
+    fn test_onix3_thoth_subjects() {
+        let subjects = test_work().subjects;
+        let output = generate_test_output(&subjects[0]);
+        assert!(output.contains(r#"  <SubjectSchemeIdentifier>10</SubjectSchemeIdentifier>"#));
+        assert!(output.contains(r#"  <SubjectCode>AAA000000</SubjectCode>"#));
+        let output = generate_test_output(&subjects[1]);
+        assert!(output.contains(r#"  <SubjectSchemeIdentifier>20</SubjectSchemeIdentifier>"#));
+        assert!(output.contains(r#"  <SubjectHeadingText>keyword1</SubjectHeadingText>"#));
+        let output = generate_test_output(&subjects[2]);
+        assert!(output.contains(r#"  <SubjectSchemeIdentifier>24</SubjectSchemeIdentifier>"#));
+        assert!(output.contains(r#"  <SubjectSchemeName>Thoth custom</SubjectSchemeName>"#));
+        assert!(output.contains(r#"  <SubjectHeadingText>custom1</SubjectHeadingText>"#));
+    }
+
+    #[test]
+    fn test_onix3_thoth_work() {
+        let output = generate_test_output(&test_work());
+        // One product per publication
+        assert_eq!(output.matches("<Product>").count(), 2);
+        assert!(output.contains(
+            r#"  <RecordReference>urn:uuid:00000000-0000-0000-bbbb-000000000002</RecordReference>"#
+        ));
+        assert!(output.contains(
+            r#"  <RecordReference>urn:uuid:00000000-0000-0000-cccc-000000000003</RecordReference>"#
+        ));
+        assert!(output.contains(r#"  <NotificationType>03</NotificationType>"#));
+        assert!(output.contains(
+            "    <ProductIDType>15</ProductIDType>\n    <IDValue>9783161484100</IDValue>"
+        ));
+        assert!(output.contains(
+            "    <ProductIDType>06</ProductIDType>\n    <IDValue>10.00001/BOOK.0001</IDValue>"
+        ));
+        // Physical product
+        assert!(output.contains(r#"    <ProductForm>BC</ProductForm>"#));
+        assert!(output.contains(
+            "      <MeasureType>01</MeasureType>\n      <Measurement>234</Measurement>\n      <MeasureUnitCode>mm</MeasureUnitCode>"
+        ));
+        // Digital product
+        assert!(output.contains(
+            "    <ProductForm>EB</ProductForm>\n    <ProductFormDetail>E107</ProductFormDetail>"
+        ));
+        assert_eq!(output.matches("<Measure>").count(), 2);
+        assert!(output.contains(r#"      <EpubLicenseExpressionLink>https://creativecommons.org/licenses/by/4.0/</EpubLicenseExpressionLink>"#));
+        assert!(output.contains(r#"      <CollectionType>10</CollectionType>"#));
+        assert!(output.contains(r#"        <IDValue>12345678</IDValue>"#));
+        assert!(output.contains(r#"        <IDValue>87654321</IDValue>"#));
+        assert!(output.contains(r#"          <PartNumber>3</PartNumber>"#));
+        assert!(output.contains(r#"          <TitleText>Name of series</TitleText>"#));
+        assert!(output.contains(r#"        <TitleText>Book Title</TitleText>"#));
+        assert!(output.contains(r#"        <Subtitle>Book Subtitle</Subtitle>"#));
+        assert!(output.contains(r#"    <EditionNumber>1</EditionNumber>"#));
+        assert!(output.contains(
+            "      <LanguageRole>01</LanguageRole>\n      <LanguageCode>spa</LanguageCode>"
+        ));
+        assert!(output.contains(r#"      <ExtentValue>334</ExtentValue>"#));
+        assert!(output.contains(r#"    <NumberOfIllustrations>15</NumberOfIllustrations>"#));
+        assert!(output.contains(r#"      <AudienceCodeValue>06</AudienceCodeValue>"#));
+        assert!(output.contains(
+            "      <TextType>03</TextType>\n      <ContentAudience>00</ContentAudience>\n      <Text>Lorem ipsum dolor sit amet</Text>"
+        ));
+        assert!(output.contains(
+            "      <TextType>02</TextType>\n      <ContentAudience>00</ContentAudience>\n      <Text>Lorem ipsum</Text>"
+        ));
+        assert!(output.contains(
+            "      <TextType>04</TextType>\n      <ContentAudience>00</ContentAudience>\n      <Text>1. Chapter 1</Text>"
+        ));
+        assert!(
+            output.contains(r#"        <ResourceLink>https://www.book.com/cover</ResourceLink>"#)
+        );
+        assert!(output.contains(r#"      <ImprintName>OA Editions Imprint</ImprintName>"#));
+        assert!(output.contains(
+            "        <WebsiteRole>01</WebsiteRole>\n        <WebsiteLink>https://www.oaeditions.com</WebsiteLink>"
+        ));
+        assert!(output.contains(r#"      <PublishingRole>16</PublishingRole>"#));
+        assert!(output.contains(r#"    <CityOfPublication>León, Spain</CityOfPublication>"#));
+        assert!(output.contains(r#"    <PublishingStatus>04</PublishingStatus>"#));
+        assert!(output.contains(r#"      <Date dateformat="00">19991231</Date>"#));
+        assert!(output.contains(r#"      <CopyrightYear>1999</CopyrightYear>"#));
+        assert!(output.contains(r#"        <CorporateName>Author 1</CorporateName>"#));
+        // Each product is related to the other
+        assert_eq!(output.matches("<RelatedProduct>").count(), 2);
+        assert!(output.contains(
+            "      <ProductRelationCode>06</ProductRelationCode>\n      <ProductIdentifier>\n        <ProductIDType>01</ProductIDType>\n        <IDValue>urn:uuid:00000000-0000-0000-cccc-000000000003</IDValue>"
+        ));
+        // Prices per currency
+        assert!(output.contains(
+            "        <PriceType>02</PriceType>\n        <PriceAmount>25.95</PriceAmount>\n        <CurrencyCode>EUR</CurrencyCode>"
+        ));
+        assert!(output.contains(
+            "        <PriceType>02</PriceType>\n        <PriceAmount>22.00</PriceAmount>\n        <CurrencyCode>GBP</CurrencyCode>"
+        ));
+        // All locations, canonical first
+        assert_eq!(output.matches("<SupplyDetail>").count(), 4);
+        let canonical = output
+            .find("<WebsiteLink>https://www.book.com/pdf_landing</WebsiteLink>")
+            .unwrap();
+        let jstor = output.find("<SupplierName>JSTOR</SupplierName>").unwrap();
+        assert!(canonical < jstor);
+        assert!(output
+            .contains(r#"          <WebsiteLink>https://www.book.com/pdf_fulltext</WebsiteLink>"#));
+        assert!(output.contains(
+            "        <SupplierRole>11</SupplierRole>\n        <SupplierName>JSTOR</SupplierName>"
+        ));
+
+        // Remove all optional fields
+        let mut test_work = test_work();
+        test_work.publications.truncate(1);
+        test_work.publications[0].prices.clear();
+        test_work.publications[0].isbn = None;
+        test_work.doi = None;
+        test_work.license = None;
+        test_work.issues.clear();
+        test_work.subtitle = None;
+        test_work.short_abstract = None;
+        test_work.long_abstract = None;
+        test_work.toc = None;
+        test_work.cover_url = None;
+        test_work.fundings.clear();
+        test_work.landing_page = None;
+        let output = generate_test_output(&test_work);
+        assert!(!output.contains(r#"<ProductIDType>15</ProductIDType>"#));
+        assert!(!output.contains(r#"<ProductIDType>06</ProductIDType>"#));
+        assert!(!output.contains(r#"<EpubLicense>"#));
+        assert!(!output.contains(r#"<Collection>"#));
+        assert!(output.contains(r#"        <TitleText>Book Title: Book Subtitle</TitleText>"#));
+        assert!(!output.contains(r#"<CollateralDetail>"#));
+        assert!(!output.contains(r#"<PublishingRole>16</PublishingRole>"#));
+        assert!(!output.contains(r#"<RelatedMaterial>"#));
+        assert!(!output.contains(r#"<Price>"#));
+        assert!(output.contains(r#"      <UnpricedItemType>04</UnpricedItemType>"#));
+    }
+
+    #[test]
+    fn test_onix3_thoth_errors() {
+        let mut test_work = test_work();
+        test_work.publications.clear();
+        let output = Onix3Thoth {}.generate(&[test_work], None);
+        assert_eq!(
+            output,
+            Err(ThothError::IncompleteMetadataRecord(
+                "onix_3.0::thoth".to_string(),
+                "No publications".to_string()
+            ))
+        );
+        let output = Onix3Thoth {}.generate(&[], None);
+        assert_eq!(
+            output,
+            Err(ThothError::IncompleteMetadataRecord(
+                "onix_3.0::thoth".to_string(),
+                "Not enough data".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_onix3_thoth_work_status() {
+        let mut test_work = test_work();
+        test_work.work_status = WorkStatus::FORTHCOMING;
+        let output = generate_test_output(&test_work);
+        assert!(output.contains(r#"  <NotificationType>02</NotificationType>"#));
+        assert!(output.contains(r#"    <PublishingStatus>02</PublishingStatus>"#));
+        assert!(output.contains(r#"      <ProductAvailability>10</ProductAvailability>"#));
+        test_work.work_status = WorkStatus::CANCELLED;
+        let output = generate_test_output(&test_work);
+        assert!(output.contains(r#"  <NotificationType>02</NotificationType>"#));
+        assert!(output.contains(r#"    <PublishingStatus>01</PublishingStatus>"#));
+        assert!(output.contains(r#"      <ProductAvailability>01</ProductAvailability>"#));
+        test_work.work_status = WorkStatus::WITHDRAWN_FROM_SALE;
+        let output = generate_test_output(&test_work);
+        assert!(output.contains(r#"  <NotificationType>03</NotificationType>"#));
+        assert!(output.contains(r#"    <PublishingStatus>11</PublishingStatus>"#));
+        assert!(output.contains(r#"      <ProductAvailability>46</ProductAvailability>"#));
+        assert!(!output.contains(r#"<ProductAvailability>99</ProductAvailability>"#));
+        test_work.work_status = WorkStatus::OUT_OF_PRINT;
+        let output = generate_test_output(&test_work);
+        assert!(output.contains(r#"  <NotificationType>03</NotificationType>"#));
+        assert!(output.contains(r#"    <PublishingStatus>07</PublishingStatus>"#));
+        assert!(output.contains(r#"      <ProductAvailability>51</ProductAvailability>"#));
+    }
+
+    #[test]
+    fn test_onix3_thoth_content_items() {
+        let mut relation = WorkRelations {
+            relation_ordinal: 2,
+            related_work: WorkRelationsRelatedWork {
+                work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000002").unwrap(),
+                work_status: WorkStatus::ACTIVE,
+                full_title: "Chapter Title: Chapter Subtitle".to_string(),
+                title: "Chapter Title".to_string(),
+                subtitle: Some("Chapter Subtitle".to_string()),
+                work_type: WorkType::BOOK_CHAPTER,
+                edition: 1,
+                doi: Some(Doi::from_str("https://doi.org/10.00001/BOOK.0001.02").unwrap()),
+                publication_date: None,
+                license: None,
+                copyright_holder: "Author 1".to_string(),
+                short_abstract: None,
+                long_abstract: Some("Chapter abstract".to_string()),
+                page_count: Some(20),
+                first_page: Some("11".to_string()),
+                last_page: Some("30".to_string()),
+                landing_page: None,
+                updated_at_with_relations: Default::default(),
+                contributions: vec![WorkRelationsRelatedWorkContributions {
+                    contribution_type: ContributionType::AUTHOR,
+                    first_name: Some("Author".to_string()),
+                    last_name: "1".to_string(),
+                    full_name: "Author 1".to_string(),
+                    main_contribution: true,
+                    biography: None,
+                    contribution_ordinal: 1,
+                    contributor: WorkRelationsRelatedWorkContributionsContributor { orcid: None },
+                    affiliations: vec![],
+                }],
+            },
+        };
+        let output = generate_test_output(&relation);
+        assert!(output.contains(r#"<ContentItem>"#));
+        assert!(output.contains(r#"  <LevelSequenceNumber>2</LevelSequenceNumber>"#));
+        assert!(output.contains(r#"    <TextItemType>03</TextItemType>"#));
+        assert!(output.contains(
+            "      <TextItemIDType>06</TextItemIDType>\n      <IDValue>10.00001/BOOK.0001.02</IDValue>"
+        ));
+        assert!(output.contains(
+            "    <PageRun>\n      <FirstPageNumber>11</FirstPageNumber>\n      <LastPageNumber>30</LastPageNumber>\n    </PageRun>"
+        ));
+        assert!(output.contains(r#"    <NumberOfPages>20</NumberOfPages>"#));
+        assert!(output.contains(r#"  <ComponentTypeName>Chapter</ComponentTypeName>"#));
+        assert!(output.contains(r#"  <ComponentNumber>2</ComponentNumber>"#));
+        assert!(output.contains(r#"      <TitleElementLevel>04</TitleElementLevel>"#));
+        assert!(output.contains(r#"      <TitleText>Chapter Title</TitleText>"#));
+        assert!(output.contains(r#"      <Subtitle>Chapter Subtitle</Subtitle>"#));
+        assert!(output.contains(r#"    <KeyNames>1</KeyNames>"#));
+        assert!(output.contains(
+            "    <TextType>03</TextType>\n    <ContentAudience>00</ContentAudience>\n    <Text>Chapter abstract</Text>"
+        ));
+
+        // Optional elements are omitted
+        relation.related_work.subtitle = None;
+        relation.related_work.doi = None;
+        relation.related_work.first_page = None;
+        relation.related_work.page_count = None;
+        relation.related_work.long_abstract = None;
+        relation.related_work.contributions.clear();
+        let output = generate_test_output(&relation);
+        assert!(output.contains(r#"      <TitleText>Chapter Title: Chapter Subtitle</TitleText>"#));
+        assert!(!output.contains(r#"<TextItemIdentifier>"#));
+        assert!(!output.contains(r#"<PageRun>"#));
+        assert!(!output.contains(r#"<NumberOfPages>"#));
+        assert!(!output.contains(r#"<Contributor>"#));
+        assert!(!output.contains(r#"<TextContent>"#));
+
+        // Chapters are listed between the collateral and the publishing details of the book
+        let mut test_work = test_work();
+        let output = generate_test_output(&test_work);
+        assert!(!output.contains(r#"<ContentDetail>"#));
+        test_work.relations = vec![relation];
+        let output = generate_test_output(&test_work);
+        assert_eq!(output.matches("<ContentDetail>").count(), 2);
+        assert!(output.contains("  </CollateralDetail>\n  <ContentDetail>\n    <ContentItem>"));
+        assert!(output.contains("  </ContentDetail>\n  <PublishingDetail>"));
+    }
+
+    #[test]
+    fn test_onix3_thoth_deleted_work() {
+        let deleted_work = DeletedWork {
+            work_id: Uuid::from_str("00000000-0000-0000-AAAA-000000000001").unwrap(),
+            publisher_id: Uuid::from_str("00000000-0000-0000-DDDD-000000000001").unwrap(),
+            publication_ids: vec![
+                Uuid::from_str("00000000-0000-0000-BBBB-000000000002").unwrap(),
+                Uuid::from_str("00000000-0000-0000-CCCC-000000000003").unwrap(),
+            ],
+            deleted_at: Default::default(),
+        };
+        // One deletion per publication, as each was sent as a separate product
+        let output = generate_test_output(&deleted_work);
+        assert_eq!(output.matches("<Product>").count(), 2);
+        assert_eq!(
+            output
+                .matches("<NotificationType>05</NotificationType>")
+                .count(),
+            2
+        );
+        assert!(output.contains(
+            r#"  <RecordReference>urn:uuid:00000000-0000-0000-bbbb-000000000002</RecordReference>"#
+        ));
+        assert!(output.contains(
+            "    <ProductIDType>01</ProductIDType>\n    <IDValue>urn:uuid:00000000-0000-0000-cccc-000000000003</IDValue>"
+        ));
+        assert!(!output.contains(r#"<DescriptiveDetail>"#));
+
+        // A delta with no updated works still notifies the deletions
+        let output = Onix3Thoth {}
+            .generate_with_deletions(&[], &[deleted_work], None)
+            .unwrap();
+        assert_eq!(output.matches("<Product>").count(), 2);
+        assert!(Onix3Thoth {}.generate(&[], None).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use thoth_errors::{ThothError, ThothResult};
+
+/// Directory holding the reference schemas vendored with the crate: see `schemas/README.md`
+pub(crate) const SCHEMAS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/schemas");
+
+/// Validates XML output against the reference schema of its specification, using `xmllint`.
+///
+/// Schemas are read from a local directory, and validation never reaches the network, so any
+/// schema that a reference schema imports must be vendored alongside it.
+#[derive(Clone)]
+pub(crate) struct SchemaValidator {
+    dir: PathBuf,
+}
+
+impl SchemaValidator {
+    pub(crate) fn new<P: AsRef<Path>>(dir: P) -> Self {
+        SchemaValidator {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Check that an XML document of the given specification adheres to the schema found at
+    /// `schema`, relative to the schemas directory
+    pub(crate) fn validate(
+        &self,
+        specification_id: &str,
+        schema: &str,
+        output: &[u8],
+    ) -> ThothResult<()> {
+        let error = |reason: String| {
+            ThothError::SchemaValidationError(specification_id.to_string(), reason)
+        };
+        let schema = self.dir.join(schema);
+        if !schema.exists() {
+            return Err(error(format!("{} has not been vendored", schema.display())));
+        }
+        let mut xmllint = Command::new("xmllint")
+            .args(["--noout", "--nonet", "--schema"])
+            .arg(&schema)
+            .arg("-")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| error(format!("xmllint could not be run ({})", e)))?;
+        xmllint
+            .stdin
+            .take()
+            .expect("xmllint's standard input is piped")
+            .write_all(output)?;
+        let result = xmllint.wait_with_output()?;
+        if result.status.success() {
+            Ok(())
+        } else {
+            Err(error(
+                String::from_utf8_lossy(&result.stderr).trim().to_string(),
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_validator() {
+        let validator = SchemaValidator::new(SCHEMAS_DIR);
+        let valid = br#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:title xml:lang="en">Book Title</dc:title></oai_dc:dc>"#;
+        assert!(validator
+            .validate("oai_dc::thoth", "oai_dc/oai_dc.xsd", valid)
+            .is_ok());
+        let invalid = br#"<oai_dc:dc xmlns:oai_dc="http://www.openarchives.org/OAI/2.0/oai_dc/" xmlns:dc="http://purl.org/dc/elements/1.1/"><dc:heading>Book Title</dc:heading></oai_dc:dc>"#;
+        match validator.validate("oai_dc::thoth", "oai_dc/oai_dc.xsd", invalid) {
+            Err(ThothError::SchemaValidationError(specification_id, reason)) => {
+                assert_eq!(specification_id, "oai_dc::thoth");
+                assert!(reason.contains("heading"), "{}", reason);
+            }
+            other => panic!("{:?}", other),
+        }
+        match validator.validate("oai_dc::thoth", "missing/missing.xsd", valid) {
+            Err(ThothError::SchemaValidationError(_, reason)) => {
+                assert!(reason.ends_with("has not been vendored"), "{}", reason)
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+}